@@ -0,0 +1,235 @@
+// benches/flux_limiter.rs
+
+//! Hot-path benchmarks: contention, key cardinality, denials and cleanup
+
+// dependencies
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use flux_limiter::{
+    Clock, ClockError, FluxLimiter, FluxLimiterConfig, LocalFluxLimiter, SystemClock, Timestamp,
+};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const UNIQUE_KEYS: u64 = 100_000;
+
+// a clock that only moves when told to, so time reads cost one atomic load
+#[derive(Debug, Clone, Default)]
+struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    fn advance(&self, nanos: u64) {
+        self.0.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Result<Timestamp, ClockError> {
+        Ok(Timestamp::from_nanos(self.0.load(Ordering::Relaxed)))
+    }
+}
+
+// a generous limit, so the benchmarks measure the allow path unless stated otherwise
+fn generous_config() -> FluxLimiterConfig {
+    FluxLimiterConfig::new(1_000_000.0, 1_000.0)
+}
+
+// run `iters` checks of one shared key split over `threads` threads
+fn contended<C: Clock + Clone + 'static>(
+    limiter: &FluxLimiter<u64, C>,
+    threads: u64,
+    iters: u64,
+) -> Duration {
+    let per_thread = iters.div_ceil(threads);
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let limiter = limiter.clone();
+            thread::spawn(move || {
+                for _ in 0..per_thread {
+                    black_box(limiter.check_request(0).ok());
+                }
+            })
+        })
+        .collect();
+    handles.into_iter().for_each(|h| h.join().unwrap());
+    start.elapsed()
+}
+
+fn single_key_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_key_contention");
+    for threads in [1, 2, 4, 8] {
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(
+            BenchmarkId::new("system_clock", threads),
+            &threads,
+            |b, &threads| {
+                let limiter = FluxLimiter::with_config(generous_config(), SystemClock).unwrap();
+                b.iter_custom(|iters| contended(&limiter, threads, iters));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("manual_clock", threads),
+            &threads,
+            |b, &threads| {
+                let limiter =
+                    FluxLimiter::with_config(generous_config(), ManualClock::default()).unwrap();
+                b.iter_custom(|iters| contended(&limiter, threads, iters));
+            },
+        );
+    }
+    group.finish();
+}
+
+// an attacker hammering one exhausted key: every check is denied, and the
+// clock stands still as a cached or coarse clock would between ticks
+fn denial_storm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("denial_storm");
+    for threads in [1, 2, 4, 8] {
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(
+            BenchmarkId::new("manual_clock", threads),
+            &threads,
+            |b, &threads| {
+                let config = FluxLimiterConfig::new(1.0, 0.0);
+                let limiter = FluxLimiter::with_config(config, ManualClock::default()).unwrap();
+                limiter.check_request(0).unwrap();
+                b.iter_custom(|iters| contended(&limiter, threads, iters));
+            },
+        );
+    }
+    group.finish();
+}
+
+fn unique_keys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unique_keys");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("system_clock", |b| {
+        let limiter = FluxLimiter::with_config(generous_config(), SystemClock).unwrap();
+        let mut key = 0u64;
+        b.iter(|| {
+            key = (key + 1) % UNIQUE_KEYS;
+            black_box(limiter.check_request(key).ok())
+        });
+    });
+    group.bench_function("manual_clock", |b| {
+        let limiter = FluxLimiter::with_config(generous_config(), ManualClock::default()).unwrap();
+        let mut key = 0u64;
+        b.iter(|| {
+            key = (key + 1) % UNIQUE_KEYS;
+            black_box(limiter.check_request(key).ok())
+        });
+    });
+    group.bench_function("borrowed_string_keys", |b| {
+        let limiter =
+            FluxLimiter::<String, _>::with_config(generous_config(), ManualClock::default())
+                .unwrap();
+        let keys: Vec<String> = (0..UNIQUE_KEYS).map(|key| format!("user:{key}")).collect();
+        let mut index = 0;
+        b.iter(|| {
+            index = (index + 1) % keys.len();
+            black_box(limiter.check_request_ref(keys[index].as_str()).ok())
+        });
+    });
+    group.bench_function("local_manual_clock", |b| {
+        let limiter =
+            LocalFluxLimiter::with_config(generous_config(), ManualClock::default()).unwrap();
+        let mut key = 0u64;
+        b.iter(|| {
+            key = (key + 1) % UNIQUE_KEYS;
+            black_box(limiter.check_request(key).ok())
+        });
+    });
+    group.finish();
+}
+
+fn mixed_allow_deny(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mixed_allow_deny");
+    group.throughput(Throughput::Elements(1));
+
+    // 1 req/s without burst against the same few keys: nearly every check is a denial
+    group.bench_function("mostly_denied", |b| {
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, ManualClock::default()).unwrap();
+        let mut key = 0u64;
+        b.iter(|| {
+            key = (key + 1) % 16;
+            black_box(limiter.check_request(key).ok())
+        });
+    });
+    // time moves half an interval per check, so allowed and denied checks alternate
+    group.bench_function("half_denied", |b| {
+        let clock = ManualClock::default();
+        let config = FluxLimiterConfig::new(1_000.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        b.iter(|| {
+            clock.advance(500_000);
+            black_box(limiter.check_request(0).ok())
+        });
+    });
+    group.bench_function("snapshot_reads", |b| {
+        let limiter = FluxLimiter::with_config(generous_config(), ManualClock::default()).unwrap();
+        for key in 0..1_000u64 {
+            limiter.check_request(key).unwrap();
+        }
+        let mut key = 0u64;
+        b.iter(|| {
+            key = (key + 1) % 1_000;
+            black_box(limiter.client_snapshot(&key).ok())
+        });
+    });
+    group.finish();
+}
+
+fn cleanup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cleanup");
+    for clients in [1_000u64, 10_000, 100_000] {
+        group.throughput(Throughput::Elements(clients));
+        group.bench_with_input(
+            BenchmarkId::new("all_stale", clients),
+            &clients,
+            |b, &clients| {
+                b.iter_batched(
+                    || {
+                        let clock = ManualClock::default();
+                        let limiter =
+                            FluxLimiter::with_config(generous_config(), clock.clone()).unwrap();
+                        (0..clients).for_each(|key| {
+                            limiter.check_request(key).unwrap();
+                        });
+                        clock.advance(3_600_000_000_000);
+                        limiter
+                    },
+                    |limiter| limiter.cleanup_stale_clients(1_000_000_000).unwrap(),
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("none_stale", clients),
+            &clients,
+            |b, &clients| {
+                let limiter =
+                    FluxLimiter::with_config(generous_config(), ManualClock::default()).unwrap();
+                (0..clients).for_each(|key| {
+                    limiter.check_request(key).unwrap();
+                });
+                b.iter(|| limiter.cleanup_stale_clients(3_600_000_000_000).unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    single_key_contention,
+    denial_storm,
+    unique_keys,
+    mixed_allow_deny,
+    cleanup
+);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+/* include/flux_limiter.h
+ *
+ * C bindings for flux-limiter, built with the `ffi` feature:
+ *
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * A limiter may be shared between threads. Client keys are the bytes of
+ * NUL-terminated strings. Panics inside the library never unwind into the
+ * caller: they are reported like any other failure, as NULL,
+ * FLUX_LIMITER_ERR_LIMITER or -1.
+ */
+
+#ifndef FLUX_LIMITER_H
+#define FLUX_LIMITER_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* the call succeeded */
+#define FLUX_LIMITER_OK 0
+/* a pointer argument was null */
+#define FLUX_LIMITER_ERR_NULL 1
+/* the limiter failed, e.g. because the system clock could not be read or
+ * the check panicked */
+#define FLUX_LIMITER_ERR_LIMITER 2
+
+typedef struct flux_limiter flux_limiter;
+
+typedef struct flux_limiter_decision {
+    /* whether the request is allowed */
+    bool allowed;
+    /* seconds to wait before retrying; 0 for allowed requests */
+    double retry_after_seconds;
+    /* requests still available in the burst */
+    double remaining_capacity;
+    /* when the client's capacity is fully restored (nanoseconds since epoch) */
+    uint64_t reset_time_nanos;
+} flux_limiter_decision;
+
+/* Create a limiter allowing `rate_per_second` requests with `burst_capacity`
+ * extra; returns NULL if either is invalid or creation panicked. */
+flux_limiter *flux_limiter_new(double rate_per_second, double burst_capacity);
+
+/* Check a request for `client_id`, writing the decision on FLUX_LIMITER_OK;
+ * returns FLUX_LIMITER_ERR_LIMITER if the check failed or panicked. */
+int flux_limiter_check(const flux_limiter *limiter,
+                       const char *client_id,
+                       flux_limiter_decision *decision);
+
+/* Drop clients fully recovered for at least `max_stale_nanos`; returns the
+ * number removed, or -1 on error or panic. */
+int64_t flux_limiter_cleanup(const flux_limiter *limiter, uint64_t max_stale_nanos);
+
+/* Free a limiter; NULL is ignored. */
+void flux_limiter_free(flux_limiter *limiter);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FLUX_LIMITER_H */
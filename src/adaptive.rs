@@ -0,0 +1,189 @@
+// src/adaptive.rs
+
+//! Adaptive rate adjustment driven by downstream feedback (AIMD)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+/// Feedback reported to an [`AdaptiveLimiter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The downstream handled the request normally
+    Success,
+    /// The downstream signalled overload (e.g. a 503, timeout or queue full)
+    Overload,
+}
+
+/// Bounds and step sizes for additive-increase/multiplicative-decrease.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::AimdConfig;
+///
+/// // between 5 and 500 req/s, +2 req/s per success, halve on overload
+/// let aimd = AimdConfig::new(5.0, 500.0)
+///     .additive_increase(2.0)
+///     .multiplicative_decrease(0.5);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AimdConfig {
+    pub(crate) min_rate: f64,
+    pub(crate) max_rate: f64,
+    pub(crate) increase: f64,
+    pub(crate) decrease_factor: f64,
+}
+
+impl AimdConfig {
+    /// Create an AIMD config that keeps the rate between `min_rate` and
+    /// `max_rate` requests per second.
+    ///
+    /// Defaults to +1 req/s per success and halving on overload.
+    pub fn new(min_rate: f64, max_rate: f64) -> Self {
+        Self {
+            min_rate,
+            max_rate,
+            increase: 1.0,
+            decrease_factor: 0.5,
+        }
+    }
+
+    /// Requests per second added for every reported success
+    pub fn additive_increase(mut self, increase: f64) -> Self {
+        self.increase = increase;
+        self
+    }
+
+    /// Factor (between 0 and 1, exclusive) the rate is multiplied by on overload
+    pub fn multiplicative_decrease(mut self, factor: f64) -> Self {
+        self.decrease_factor = factor;
+        self
+    }
+
+    /// Validate the configuration
+    ///
+    /// Both bounds must be valid rates with `min_rate <= max_rate`, the
+    /// increase must be finite and non-negative, and the decrease factor must
+    /// lie strictly between 0 and 1.
+    pub fn validate(&self) -> Result<(), FluxLimiterError> {
+        FluxLimiterConfig::new(self.min_rate, 0.0).validate()?;
+        FluxLimiterConfig::new(self.max_rate, 0.0).validate()?;
+        let step_valid = self.increase.is_finite() && self.increase >= 0.0;
+        let factor_valid = self.decrease_factor > 0.0 && self.decrease_factor < 1.0;
+        if self.min_rate > self.max_rate || !step_valid || !factor_valid {
+            return Err(FluxLimiterError::InvalidAdaptiveConfig);
+        }
+        Ok(())
+    }
+
+    // apply one feedback signal to a rate
+    fn next_rate(&self, rate: f64, outcome: Outcome) -> f64 {
+        let next = match outcome {
+            Outcome::Success => rate + self.increase,
+            Outcome::Overload => rate * self.decrease_factor,
+        };
+        next.clamp(self.min_rate, self.max_rate)
+    }
+}
+
+/// A rate limiter whose sustained rate adapts to reported outcomes.
+///
+/// Every [`Outcome::Success`] raises the rate additively and every
+/// [`Outcome::Overload`] cuts it multiplicatively, within the bounds of the
+/// [`AimdConfig`]. The rate applies to all clients at once and their state
+/// is kept across adjustments; the configured burst stays the same number
+/// of requests.
+///
+/// Dereferences to [`FluxLimiter`], so checks work exactly as usual.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{AdaptiveLimiter, AimdConfig, FluxLimiterConfig, Outcome, SystemClock};
+///
+/// let limiter = AdaptiveLimiter::new(
+///     FluxLimiterConfig::new(100.0, 10.0),
+///     AimdConfig::new(10.0, 1_000.0),
+///     SystemClock,
+/// )
+/// .unwrap();
+///
+/// if limiter.check_request("downstream").unwrap().allowed {
+///     // call the downstream, then report how it went
+///     limiter.report_outcome(Outcome::Overload);
+/// }
+/// assert_eq!(limiter.current_rate(), 50.0);
+/// ```
+pub struct AdaptiveLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    limiter: FluxLimiter<T, C>,
+    aimd: AimdConfig,
+    rate: Mutex<f64>,
+}
+
+impl<T, C> AdaptiveLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    /// Create an adaptive limiter starting at the config's rate, clamped to
+    /// the AIMD bounds.
+    ///
+    /// # Errors
+    ///
+    /// Returns the validation error of either config.
+    pub fn new(
+        config: FluxLimiterConfig,
+        aimd: AimdConfig,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError> {
+        config.validate()?;
+        aimd.validate()?;
+        let rate = config.rate_per_second.clamp(aimd.min_rate, aimd.max_rate);
+        let limiter = FluxLimiter::with_config(config.rate(rate), clock)?;
+
+        Ok(Self {
+            limiter,
+            aimd,
+            rate: Mutex::new(rate),
+        })
+    }
+
+    /// Report the outcome of a downstream call and return the new rate
+    pub fn report_outcome(&self, outcome: Outcome) -> f64 {
+        // an f64 cannot be left half-updated, so a poisoned lock is still usable
+        let mut rate = self.rate.lock().unwrap_or_else(|err| err.into_inner());
+        let next = self.aimd.next_rate(*rate, outcome);
+        if next != *rate && self.limiter.set_rate(next).is_ok() {
+            *rate = next;
+        }
+        *rate
+    }
+
+    /// The current sustained rate in requests per second
+    pub fn current_rate(&self) -> f64 {
+        *self.rate.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<T, C> Deref for AdaptiveLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    type Target = FluxLimiter<T, C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.limiter
+    }
+}
@@ -0,0 +1,248 @@
+// src/admin.rs
+
+//! HTTP admin endpoints for inspecting and steering a limiter (`admin` feature)
+
+// dependencies
+use crate::clock::Clock;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::snapshot::ClientSnapshot;
+use crate::stats::{CleanupReport, FluxLimiterStats};
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const DEFAULT_OFFENDERS: usize = 10;
+
+/// Header naming who made an admin call, recorded as the actor of its audit event
+pub const ACTOR_HEADER: &str = "x-admin-actor";
+
+type Shared<C> = State<Arc<FluxLimiter<String, C>>>;
+
+// body of `POST /clients/{id}/penalize`
+#[derive(Deserialize)]
+struct Penalty {
+    cost: f64,
+}
+
+// query of `GET /offenders`
+#[derive(Deserialize)]
+struct OffendersQuery {
+    limit: Option<usize>,
+}
+
+// one entry of `GET /offenders`
+#[derive(Serialize)]
+struct Offender {
+    client_id: String,
+    #[serde(flatten)]
+    snapshot: ClientSnapshot,
+}
+
+// body of `GET /config` and `PUT /config`; only the rate can be changed live
+#[derive(Serialize, Deserialize)]
+struct LiveConfig {
+    rate: f64,
+    #[serde(default)]
+    burst: Option<f64>,
+}
+
+// query of `POST /cleanup`
+#[derive(Deserialize)]
+struct CleanupQuery {
+    max_stale_seconds: f64,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+// body of error responses
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// An [axum](https://docs.rs/axum) router with operational endpoints for a
+/// limiter, to be nested under a path of an existing admin server.
+///
+/// | Endpoint | Action |
+/// |----------|--------|
+/// | `GET /clients/{id}` | [`client_snapshot`](FluxLimiter::client_snapshot) as JSON |
+/// | `DELETE /clients/{id}` | [`reset_client`](FluxLimiter::reset_client) |
+/// | `POST /clients/{id}/penalize` | [`penalize`](FluxLimiter::penalize) by `{"cost": 10.0}` |
+/// | `GET /offenders?limit=10` | the clients furthest behind schedule, worst first |
+/// | `GET /config` | the live `{"rate", "burst"}` |
+/// | `PUT /config` | change the rate with `{"rate": 50.0}`; the burst is fixed |
+/// | `POST /cleanup?max_stale_seconds=60` | [`cleanup_stale_clients`](FluxLimiter::cleanup_stale_clients), or its dry run with `&dry_run=true` |
+/// | `GET /stats` | [`stats`](FluxLimiter::stats) |
+///
+/// Resets, penalties and rate changes are recorded in the limiter's
+/// [`AuditSink`](crate::AuditSink), if it has one, with the
+/// [`ACTOR_HEADER`] value of the call as the actor. The header is taken as
+/// sent, so have the auth layer in front of the router set it.
+///
+/// Untracked clients answer `404 Not Found`, invalid costs and rates
+/// `400 Bad Request`, and clock failures `503 Service Unavailable`, each
+/// with an `{"error": "..."}` body. The router does no authentication: serve
+/// it on an internal address or put it behind the service's own auth layer.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock, admin_router};
+/// use std::sync::Arc;
+///
+/// let config = FluxLimiterConfig::new(100.0, 50.0).stats(true);
+/// let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+///
+/// let app: axum::Router = axum::Router::new().nest("/admin/limiter", admin_router(limiter));
+/// ```
+pub fn admin_router<C>(limiter: Arc<FluxLimiter<String, C>>) -> Router
+where
+    C: Clock + 'static,
+{
+    Router::new()
+        .route(
+            "/clients/:client_id",
+            get(client_snapshot::<C>).delete(reset_client::<C>),
+        )
+        .route("/clients/:client_id/penalize", post(penalize::<C>))
+        .route("/offenders", get(offenders::<C>))
+        .route("/config", get(config::<C>).put(set_config::<C>))
+        .route("/cleanup", post(cleanup::<C>))
+        .route("/stats", get(stats::<C>))
+        .with_state(limiter)
+}
+
+// `GET /clients/{id}`
+async fn client_snapshot<C: Clock>(
+    State(limiter): Shared<C>,
+    Path(client_id): Path<String>,
+) -> Result<Json<ClientSnapshot>, Response> {
+    match limiter.client_snapshot(client_id.as_str()) {
+        Ok(Some(snapshot)) => Ok(Json(snapshot)),
+        Ok(None) => Err(not_tracked()),
+        Err(err) => Err(limiter_error(err)),
+    }
+}
+
+// `DELETE /clients/{id}`
+async fn reset_client<C: Clock>(
+    State(limiter): Shared<C>,
+    Path(client_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, Response> {
+    match limiter.reset_client_by(actor(&headers), client_id.as_str()) {
+        true => Ok(StatusCode::NO_CONTENT),
+        false => Err(not_tracked()),
+    }
+}
+
+// `POST /clients/{id}/penalize`, answering with the penalized client's state
+async fn penalize<C: Clock>(
+    State(limiter): Shared<C>,
+    Path(client_id): Path<String>,
+    headers: HeaderMap,
+    Json(penalty): Json<Penalty>,
+) -> Result<Json<ClientSnapshot>, Response> {
+    limiter
+        .penalize_by(actor(&headers), client_id.clone(), penalty.cost)
+        .map_err(limiter_error)?;
+    client_snapshot(State(limiter), Path(client_id)).await
+}
+
+// `GET /offenders`
+async fn offenders<C: Clock>(
+    State(limiter): Shared<C>,
+    Query(query): Query<OffendersQuery>,
+) -> Result<Json<Vec<Offender>>, Response> {
+    let offenders = limiter
+        .top_offenders(query.limit.unwrap_or(DEFAULT_OFFENDERS))
+        .map_err(limiter_error)?;
+    Ok(Json(
+        offenders
+            .into_iter()
+            .map(|(client_id, snapshot)| Offender {
+                client_id,
+                snapshot,
+            })
+            .collect(),
+    ))
+}
+
+// `GET /config`
+async fn config<C: Clock>(State(limiter): Shared<C>) -> Json<LiveConfig> {
+    Json(LiveConfig {
+        rate: limiter.rate(),
+        burst: Some(limiter.burst()),
+    })
+}
+
+// `PUT /config`
+async fn set_config<C: Clock>(
+    State(limiter): Shared<C>,
+    headers: HeaderMap,
+    Json(update): Json<LiveConfig>,
+) -> Result<Json<LiveConfig>, Response> {
+    if update.burst.is_some_and(|burst| burst != limiter.burst()) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "the burst cannot be changed live".to_string(),
+        ));
+    }
+    limiter
+        .set_rate_by(actor(&headers), update.rate)
+        .map_err(limiter_error)?;
+    Ok(config(State(limiter)).await)
+}
+
+// `POST /cleanup`
+async fn cleanup<C: Clock>(
+    State(limiter): Shared<C>,
+    Query(query): Query<CleanupQuery>,
+) -> Result<Json<CleanupReport>, Response> {
+    if !query.max_stale_seconds.is_finite() || query.max_stale_seconds < 0.0 {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "max_stale_seconds must be a non-negative number".to_string(),
+        ));
+    }
+    let max_stale_nanos = (query.max_stale_seconds * 1_000_000_000.0) as u64;
+    let report = match query.dry_run {
+        true => limiter.cleanup_stale_clients_dry_run(max_stale_nanos),
+        false => limiter.cleanup_stale_clients(max_stale_nanos),
+    };
+    report.map(Json).map_err(limiter_error)
+}
+
+// `GET /stats`
+async fn stats<C: Clock>(State(limiter): Shared<C>) -> Json<FluxLimiterStats> {
+    Json(limiter.stats())
+}
+
+// the caller's actor header, if it is set and readable
+fn actor(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(ACTOR_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn not_tracked() -> Response {
+    error(StatusCode::NOT_FOUND, "client is not tracked".to_string())
+}
+
+// clock failures may pass on retry; everything else is a bad request
+fn limiter_error(err: FluxLimiterError) -> Response {
+    let status = match err {
+        FluxLimiterError::ClockError(_) => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    error(status, err.to_string())
+}
+
+fn error(status: StatusCode, error: String) -> Response {
+    (status, Json(ErrorBody { error })).into_response()
+}
@@ -0,0 +1,81 @@
+// src/anonymize.rs
+
+//! Client keys hashed with a per-process secret
+
+// dependencies
+use std::fmt;
+use std::hash::{BuildHasher, Hash, RandomState};
+
+/// A client identifier after hashing by a [`KeyAnonymizer`].
+///
+/// Holds 128 bits of keyed SipHash output and nothing of the original
+/// identifier, so limiters keyed by it never store emails, tokens or
+/// addresses, in memory or in snapshots.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnonymizedKey(u128);
+
+impl fmt::Debug for AnonymizedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AnonymizedKey({self})")
+    }
+}
+
+impl fmt::Display for AnonymizedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Hashes raw client identifiers into [`AnonymizedKey`]s.
+///
+/// Each anonymizer draws fresh random SipHash keys, so its output cannot be
+/// matched against precomputed hashes of known emails or addresses, and is
+/// meaningless to any other anonymizer. Clones share the keys. The same
+/// identifier always maps to the same key within one anonymizer, and two
+/// independent 64-bit hashes make collisions between clients negligible, so
+/// limiting stays exact.
+///
+/// Because the keys are not persisted, state snapshots taken with one
+/// process's anonymizer do not match clients in the next; clients restart
+/// with a fresh budget.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{AnonymizedKey, FluxLimiter, FluxLimiterConfig, KeyAnonymizer, SystemClock};
+///
+/// let anonymizer = KeyAnonymizer::new();
+/// let limiter = FluxLimiter::<AnonymizedKey, _>::with_config(
+///     FluxLimiterConfig::new(10.0, 5.0),
+///     SystemClock,
+/// )
+/// .unwrap();
+///
+/// let decision = limiter
+///     .check_request(anonymizer.key("alice@example.com"))
+///     .unwrap();
+/// assert!(decision.allowed);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeyAnonymizer {
+    high: RandomState,
+    low: RandomState,
+}
+
+impl KeyAnonymizer {
+    /// Create an anonymizer with new random keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The anonymized key for `client_id`
+    pub fn key<Q>(&self, client_id: &Q) -> AnonymizedKey
+    where
+        Q: Hash + ?Sized,
+    {
+        let high = u128::from(self.high.hash_one(client_id));
+        let low = u128::from(self.low.hash_one(client_id));
+        AnonymizedKey((high << 64) | low)
+    }
+}
@@ -0,0 +1,194 @@
+// src/audit.rs
+
+//! Structured audit events for administrative actions
+
+// dependencies
+use crate::clock::Clock;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+/// An administrative action taken on a limiter.
+///
+/// New actions may be added, so match with a wildcard arm.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "action", rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum AuditAction<T> {
+    /// A tracked client's state was forgotten with
+    /// [`reset_client`](FluxLimiter::reset_client)
+    ResetClient {
+        /// The client reset
+        client_id: T,
+    },
+    /// `cost` requests of a client's capacity were consumed with
+    /// [`penalize`](FluxLimiter::penalize)
+    Penalize {
+        /// The client penalized
+        client_id: T,
+        /// Requests consumed
+        cost: f64,
+    },
+    /// The sustained rate was changed with [`Operator::set_rate`] or the
+    /// admin router
+    SetRate {
+        /// Rate before the change, in requests per second
+        previous: f64,
+        /// Rate after the change, in requests per second
+        rate: f64,
+    },
+}
+
+/// One administrative action, with who took it and when.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuditEvent<T> {
+    /// Who took the action, as named with [`FluxLimiter::operator`]; `None`
+    /// for calls made on the limiter directly
+    pub actor: Option<String>,
+    /// What was done
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub action: AuditAction<T>,
+    /// Limiter time of the action (nanoseconds since epoch); 0 if the clock
+    /// could not be read
+    pub timestamp_nanos: u64,
+}
+
+/// Receives an [`AuditEvent`] for every administrative action taken on a
+/// limiter, so regulated environments can show who changed limits and when.
+///
+/// Attach a sink with
+/// [`FluxLimiter::with_audit_sink`](crate::FluxLimiter::with_audit_sink).
+/// Events are recorded synchronously, after the action succeeded and before
+/// the call returns, so a sink that writes to durable storage gives a
+/// complete trail; a slow sink slows administrative calls down, not checks.
+/// Closures taking an `AuditEvent` implement the trait. With the `serde`
+/// feature events serialize to flat records such as
+/// `{"actor":"alice","action":"penalize","client_id":"bot","cost":10.0,"timestamp_nanos":...}`.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{AuditAction, AuditEvent, FluxLimiter, FluxLimiterConfig, SystemClock};
+/// use std::sync::{Arc, Mutex};
+///
+/// let trail = Arc::new(Mutex::new(Vec::new()));
+/// let sink = trail.clone();
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock)
+///     .unwrap()
+///     .with_audit_sink(move |event: AuditEvent<String>| sink.lock().unwrap().push(event));
+///
+/// limiter.operator("alice").penalize("bot".to_string(), 10.0).unwrap();
+///
+/// let trail = trail.lock().unwrap();
+/// assert_eq!(trail[0].actor.as_deref(), Some("alice"));
+/// assert!(matches!(trail[0].action, AuditAction::Penalize { .. }));
+/// ```
+pub trait AuditSink<T>: Send + Sync {
+    /// Record one event
+    fn record(&self, event: AuditEvent<T>);
+}
+
+impl<T, F> AuditSink<T> for F
+where
+    F: Fn(AuditEvent<T>) + Send + Sync,
+{
+    fn record(&self, event: AuditEvent<T>) {
+        self(event)
+    }
+}
+
+// shared handle to a sink, so the limiter can keep deriving Debug
+pub(crate) struct AuditHook<T>(Arc<dyn AuditSink<T>>);
+
+impl<T> AuditHook<T> {
+    pub(crate) fn new(sink: impl AuditSink<T> + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    pub(crate) fn record(&self, actor: Option<&str>, action: AuditAction<T>, timestamp_nanos: u64) {
+        self.0.record(AuditEvent {
+            actor: actor.map(str::to_string),
+            action,
+            timestamp_nanos,
+        });
+    }
+}
+
+// derived Clone would require T: Clone
+impl<T> Clone for AuditHook<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for AuditHook<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditHook")
+    }
+}
+
+/// A limiter handle that records the administrative actions taken through
+/// it under an actor's name.
+///
+/// Returned by [`FluxLimiter::operator`]. Use one per authenticated operator
+/// or API credential, so the [`AuditSink`] can tell who did what; the actor
+/// is taken as given.
+#[derive(Debug)]
+pub struct Operator<'a, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    pub(crate) limiter: &'a FluxLimiter<T, C, H>,
+    pub(crate) actor: &'a str,
+}
+
+impl<T, C, H> Operator<'_, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    /// The actor actions are recorded under
+    pub fn actor(&self) -> &str {
+        self.actor
+    }
+
+    /// [`FluxLimiter::reset_client`], recorded under the actor
+    pub fn reset_client<Q>(&self, client_id: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.limiter.reset_client_by(Some(self.actor), client_id)
+    }
+
+    /// [`FluxLimiter::penalize`], recorded under the actor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::penalize`].
+    pub fn penalize(&self, client_id: T, cost: f64) -> Result<(), FluxLimiterError> {
+        self.limiter.penalize_by(Some(self.actor), client_id, cost)
+    }
+
+    /// Change the sustained rate in place, keeping the burst and all client
+    /// state, and record the change under the actor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config validation error for an invalid rate, leaving the
+    /// limiter unchanged.
+    pub fn set_rate(&self, rate_per_second: f64) -> Result<(), FluxLimiterError> {
+        self.limiter.set_rate_by(Some(self.actor), rate_per_second)
+    }
+}
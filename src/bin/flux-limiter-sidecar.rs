@@ -0,0 +1,82 @@
+// src/bin/flux-limiter-sidecar.rs
+
+//! Standalone rate-limit sidecar serving `flux_limiter.v1.RateLimiter` over gRPC
+//!
+//! Configured from the environment:
+//!
+//! - `FLUX_LIMITER_RATE` (required) and `FLUX_LIMITER_BURST`: the limit, as for
+//!   `FluxLimiterConfig::from_env`
+//! - `FLUX_LIMITER_LISTEN`: address to listen on, `127.0.0.1:50051` by default
+//! - `FLUX_LIMITER_CLEANUP_SECONDS`: how often to drop clients idle for as
+//!   long, 60 by default
+//!
+//! Built with the `envoy` feature, it also serves Envoy's rate limit service
+//! on the same address, applying the limit to every descriptor.
+
+// dependencies
+#[cfg(feature = "envoy")]
+use flux_limiter::{EnvoyRateLimitService, PolicyRouter};
+use flux_limiter::{FluxLimiter, FluxLimiterConfig, RateLimiterService, SystemClock};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::transport::Server;
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:50051";
+const DEFAULT_CLEANUP_SECONDS: u64 = 60;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("flux-limiter-sidecar: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = FluxLimiterConfig::from_env("FLUX_LIMITER")?.stats(true);
+    let listen: SocketAddr = std::env::var("FLUX_LIMITER_LISTEN")
+        .unwrap_or_else(|_| DEFAULT_LISTEN.to_string())
+        .parse()?;
+    let cleanup_seconds = match std::env::var("FLUX_LIMITER_CLEANUP_SECONDS") {
+        Ok(value) => value.parse::<u64>()?.max(1),
+        Err(_) => DEFAULT_CLEANUP_SECONDS,
+    };
+
+    let limiter = Arc::new(FluxLimiter::with_config(config.clone(), SystemClock)?);
+    // Envoy descriptors get the same limit, each descriptor key its own state
+    #[cfg(feature = "envoy")]
+    let router = {
+        let mut router = PolicyRouter::new(SystemClock);
+        router.fallback(config)?;
+        Arc::new(router)
+    };
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        let cleanup = Arc::clone(&limiter);
+        #[cfg(feature = "envoy")]
+        let cleanup_router = Arc::clone(&router);
+        tokio::spawn(async move {
+            let period = Duration::from_secs(cleanup_seconds);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                // a failing clock only delays cleanup until the next tick
+                let _ = cleanup.cleanup_stale_clients(period.as_nanos() as u64);
+                #[cfg(feature = "envoy")]
+                let _ = cleanup_router.cleanup_stale_clients(period.as_nanos() as u64);
+            }
+        });
+
+        let server = Server::builder().add_service(RateLimiterService::new(limiter));
+        #[cfg(feature = "envoy")]
+        let server = server.add_service(EnvoyRateLimitService::new(router));
+        eprintln!("flux-limiter-sidecar: listening on {listen}");
+        server.serve(listen).await
+    })?;
+    Ok(())
+}
@@ -0,0 +1,284 @@
+// src/cached_store.rs
+
+//! Two-tier [`StateStore`]: a local cache leasing keys from a remote store
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::store::{StateStore, StoreError};
+use dashmap::DashMap;
+use std::hash::Hash;
+use std::time::Duration;
+
+// a key's TAT as cached under a lease
+#[derive(Debug, Clone, Copy)]
+struct Lease {
+    tat_nanos: Option<u64>,
+    // schedule consumed locally and not yet added to the remote TAT
+    pending_nanos: u64,
+    expires_nanos: u64,
+}
+
+/// A [`StateStore`] serving checks from memory and reconciling with a
+/// remote store, such as [`PostgresStore`](crate::PostgresStore) or a Redis
+/// store of your own, once per lease.
+///
+/// The first check of a key reads its TAT from the remote store and leases
+/// it for `lease`. Until the lease expires, checks of that key run against
+/// the cached TAT without any round trip, and the schedule they consume is
+/// tallied. The next check after expiry adds the tally to the remote TAT
+/// with a compare-and-swap, so consumption from every instance accumulates
+/// there, and takes a fresh lease on the result. A key thus costs at most
+/// three remote calls per lease, however hot it is, unless other instances
+/// race it for the compare-and-swap.
+///
+/// Instances see each other's traffic only when their leases renew: with
+/// `n` instances, a key may be over-admitted by up to `n - 1` leases' worth
+/// of its traffic. Keep leases short (tens of milliseconds) where that
+/// matters. Call [`flush`](Self::flush) periodically to push tallies for
+/// keys that went quiet and drop their expired leases; it also runs on
+/// drop.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{
+///     CachedStore, FluxLimiterConfig, StateStore, StoreBackedLimiter, StoreError, SystemClock,
+/// };
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// // stands in for a network store
+/// #[derive(Default)]
+/// struct Remote(Mutex<HashMap<String, u64>>);
+///
+/// impl StateStore<String> for Remote {
+///     fn get(&self, key: &String) -> Result<Option<u64>, StoreError> {
+///         Ok(self.0.lock().unwrap().get(key).copied())
+///     }
+///
+///     fn compare_and_swap(
+///         &self,
+///         key: &String,
+///         expected: Option<u64>,
+///         new: u64,
+///     ) -> Result<bool, StoreError> {
+///         let mut map = self.0.lock().unwrap();
+///         if map.get(key).copied() != expected {
+///             return Ok(false);
+///         }
+///         map.insert(key.clone(), new);
+///         Ok(true)
+///     }
+/// }
+///
+/// let store = CachedStore::new(Remote::default(), SystemClock, Duration::from_millis(50));
+/// let config = FluxLimiterConfig::new(1000.0, 100.0);
+/// let limiter = StoreBackedLimiter::with_config(config, SystemClock, store).unwrap();
+///
+/// assert!(limiter.check_request(&"user_123".to_string()).unwrap().allowed);
+/// limiter.store().flush().unwrap();
+/// ```
+pub struct CachedStore<K, S, C = SystemClock>
+where
+    K: Hash + Eq + Clone,
+    S: StateStore<K>,
+    C: Clock,
+{
+    remote: S,
+    clock: C,
+    lease_nanos: u64,
+    leases: DashMap<K, Lease>,
+}
+
+impl<K, S, C> CachedStore<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: StateStore<K>,
+    C: Clock,
+{
+    /// Cache `remote`'s TATs, leasing each key for `lease`.
+    ///
+    /// `clock` should be the limiter's clock. A zero lease reconciles on
+    /// every call, which only adds round trips over using `remote` directly.
+    pub fn new(remote: S, clock: C, lease: Duration) -> Self {
+        Self {
+            remote,
+            clock,
+            lease_nanos: u64::try_from(lease.as_nanos()).unwrap_or(u64::MAX),
+            leases: DashMap::new(),
+        }
+    }
+
+    /// The remote store
+    pub fn remote(&self) -> &S {
+        &self.remote
+    }
+
+    /// Number of keys currently cached
+    pub fn len(&self) -> usize {
+        self.leases.len()
+    }
+
+    /// Whether no keys are cached
+    pub fn is_empty(&self) -> bool {
+        self.leases.is_empty()
+    }
+
+    /// Add every key's locally consumed schedule to the remote store, and
+    /// drop leases that have expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`StoreError`] from the clock or the remote store;
+    /// keys that failed keep their tally for the next attempt.
+    pub fn flush(&self) -> Result<(), StoreError> {
+        let now_nanos = self.now_nanos()?;
+        let keys: Vec<K> = self
+            .leases
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        let mut first_error = None;
+        for key in keys {
+            if let Err(err) = self.reconcile(&key, now_nanos, false) {
+                first_error.get_or_insert(err);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    // read the store's clock
+    fn now_nanos(&self) -> Result<u64, StoreError> {
+        self.clock
+            .now()
+            .map(|now| now.as_nanos())
+            .map_err(StoreError::new)
+    }
+
+    // push `key`'s tally to the remote store; with `renew`, lease the key
+    // again, otherwise drop its lease once expired
+    fn reconcile(&self, key: &K, now_nanos: u64, renew: bool) -> Result<Lease, StoreError> {
+        // take the tally so a concurrent reconcile cannot push it twice
+        let (pending_nanos, local_nanos) =
+            self.leases.get_mut(key).map_or((0, None), |mut lease| {
+                (std::mem::take(&mut lease.pending_nanos), lease.tat_nanos)
+            });
+
+        let tat_nanos = match self.push(key, pending_nanos, local_nanos) {
+            Ok(tat_nanos) => tat_nanos,
+            Err(err) => {
+                // keep the tally for the next attempt
+                if let Some(mut lease) = self.leases.get_mut(key) {
+                    lease.pending_nanos = lease.pending_nanos.saturating_add(pending_nanos);
+                }
+                return Err(err);
+            }
+        };
+
+        let mut lease = self.leases.entry(key.clone()).or_insert(Lease {
+            tat_nanos,
+            pending_nanos: 0,
+            expires_nanos: 0,
+        });
+        if renew || lease.expires_nanos > now_nanos {
+            // checks made meanwhile may have moved the cached TAT further
+            lease.tat_nanos = tat_nanos.max(lease.tat_nanos);
+        }
+        if renew {
+            lease.expires_nanos = now_nanos.saturating_add(self.lease_nanos);
+        }
+        let current = *lease;
+        drop(lease);
+
+        if current.expires_nanos <= now_nanos {
+            self.leases.remove_if(key, |_, lease| {
+                lease.pending_nanos == 0 && lease.expires_nanos <= now_nanos
+            });
+        }
+        Ok(current)
+    }
+
+    // add `pending_nanos` of consumption to the remote TAT and return the
+    // result, which is never behind the locally cached `local_nanos`
+    fn push(
+        &self,
+        key: &K,
+        pending_nanos: u64,
+        local_nanos: Option<u64>,
+    ) -> Result<Option<u64>, StoreError> {
+        if pending_nanos == 0 {
+            return self.remote.get(key);
+        }
+        loop {
+            let remote_nanos = self.remote.get(key)?;
+            // stacked on the other instances' consumption, or as consumed here
+            // if theirs has already been recovered
+            let new_nanos = remote_nanos
+                .unwrap_or(0)
+                .saturating_add(pending_nanos)
+                .max(local_nanos.unwrap_or(0));
+            if self.remote.compare_and_swap(key, remote_nanos, new_nanos)? {
+                return Ok(Some(new_nanos));
+            }
+        }
+    }
+
+    // the lease for `key`, renewed if missing or expired
+    fn lease(&self, key: &K, now_nanos: u64) -> Result<Lease, StoreError> {
+        // copy the lease out so no shard lock is held while reconciling
+        let cached = self.leases.get(key).map(|lease| *lease);
+        match cached {
+            Some(lease) if lease.expires_nanos > now_nanos => Ok(lease),
+            _ => self.reconcile(key, now_nanos, true),
+        }
+    }
+}
+
+impl<K, S, C> StateStore<K> for CachedStore<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: StateStore<K>,
+    C: Clock,
+{
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError> {
+        let now_nanos = self.now_nanos()?;
+        Ok(self.lease(key, now_nanos)?.tat_nanos)
+    }
+
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError> {
+        let now_nanos = self.now_nanos()?;
+        self.lease(key, now_nanos)?;
+
+        let Some(mut lease) = self.leases.get_mut(key) else {
+            // flushed away in between; the limiter reads the key again
+            return Ok(false);
+        };
+        if lease.tat_nanos != expected {
+            return Ok(false);
+        }
+        // only the advance beyond an idle schedule is consumption
+        let consumed_nanos = new.saturating_sub(expected.unwrap_or(now_nanos).max(now_nanos));
+        lease.tat_nanos = Some(new);
+        lease.pending_nanos = lease.pending_nanos.saturating_add(consumed_nanos);
+        Ok(true)
+    }
+}
+
+impl<K, S, C> Drop for CachedStore<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: StateStore<K>,
+    C: Clock,
+{
+    fn drop(&mut self) {
+        // best effort; call flush() explicitly to observe errors
+        let _ = self.flush();
+    }
+}
@@ -3,37 +3,258 @@
 // clock module definition and implementations
 
 // dependencies
-use std::time::{SystemTime, UNIX_EPOCH};
+use alloc::boxed::Box;
+use core::error::Error;
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTimeError;
 
 /// Clock trait to abstract time retrieval.
 /// Implementors must be thread-safe (Send + Sync).
-/// The `now` method returns the current time in nanoseconds as a u64.
+/// The `now` method returns the current time as a [`Timestamp`].
 /// This trait allows for different clock implementations, such as system time or a test clock.
 /// The Clock trait is used by the RateLimiter to get the current time.
 pub trait Clock: Send + Sync {
-    fn now(&self) -> Result<u64, ClockError>;
+    fn now(&self) -> Result<Timestamp, ClockError>;
 }
 
-/// Clock error type
+/// Clock error type.
+///
+/// The underlying error, if any, is available through [`Error::source`].
+#[non_exhaustive]
 #[derive(Debug)]
 pub enum ClockError {
-    SystemTimeError,
+    // the system clock reads before the Unix epoch
+    #[cfg(feature = "std")]
+    SystemTimeError(SystemTimeError),
+    // the time does not fit in a Timestamp (after 2554)
+    OutOfRange,
+    // failure reported by a custom clock
+    Other(Box<dyn Error + Send + Sync>),
+}
+
+impl ClockError {
+    /// Wrap an error from a custom clock
+    pub fn other(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        ClockError::Other(err.into())
+    }
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ClockError::SystemTimeError(_) => write!(f, "System time is before the Unix epoch"),
+            ClockError::OutOfRange => write!(f, "Time is past the end of the timestamp range"),
+            ClockError::Other(_) => write!(f, "Clock failed"),
+        }
+    }
+}
+
+impl Error for ClockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ClockError::SystemTimeError(err) => Some(err),
+            ClockError::OutOfRange => None,
+            ClockError::Other(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// A point in time, in nanoseconds since the clock's epoch.
+///
+/// For [`SystemClock`] the epoch is the Unix epoch. Arithmetic is checked:
+/// operations that would leave the representable range return `None`
+/// instead of wrapping or silently pinning to the maximum.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::Timestamp;
+/// use std::time::Duration;
+///
+/// let start = Timestamp::from_nanos(1_000);
+/// let later = start.checked_add(Duration::from_nanos(500)).unwrap();
+///
+/// assert_eq!(later.as_nanos(), 1_500);
+/// assert_eq!(later.checked_duration_since(start), Some(Duration::from_nanos(500)));
+/// assert_eq!(Timestamp::MAX.checked_add(Duration::from_nanos(1)), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    /// The latest representable time
+    pub const MAX: Timestamp = Timestamp(u64::MAX);
+
+    /// Create a timestamp from nanoseconds since the epoch
+    pub const fn from_nanos(nanos: u64) -> Self {
+        Self(nanos)
+    }
+
+    /// Create a timestamp from the time elapsed since the epoch.
+    ///
+    /// Returns `None` if the duration does not fit in 64 bits of nanoseconds.
+    pub fn from_duration(since_epoch: Duration) -> Option<Self> {
+        u64::try_from(since_epoch.as_nanos()).ok().map(Self)
+    }
+
+    /// Nanoseconds since the epoch
+    pub const fn as_nanos(self) -> u64 {
+        self.0
+    }
+
+    /// The time `duration` later, or `None` on overflow
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_add(nanos).map(Self)
+    }
+
+    /// The time `duration` earlier, or `None` if that is before the epoch
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        let nanos = u64::try_from(duration.as_nanos()).ok()?;
+        self.0.checked_sub(nanos).map(Self)
+    }
+
+    /// Time elapsed from `earlier` to `self`, or `None` if `earlier` is later
+    pub fn checked_duration_since(self, earlier: Timestamp) -> Option<Duration> {
+        self.0.checked_sub(earlier.0).map(Duration::from_nanos)
+    }
+}
+
+impl From<Timestamp> for u64 {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
 }
 
 /// SystemClock implementation using the system time.
 /// Returns the current time in nanoseconds since the Unix epoch.
-/// Panics if the system clock is before the Unix epoch.
+/// Returns an error if the system clock is before the Unix epoch.
 /// This is the default clock used in the RateLimiter.
 /// Implements the Clock trait.
 /// Thread-safe and can be shared across threads.
+/// On `wasm32-unknown-unknown`, where `SystemTime` is unavailable, the `wasm`
+/// feature makes it read the host's `Date.now()` like [`WasmClock`].
+/// Requires the `std` feature.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct SystemClock;
 
+#[cfg(feature = "std")]
 impl Clock for SystemClock {
-    fn now(&self) -> Result<u64, ClockError> {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_nanos() as u64)
-            .map_err(|_| ClockError::SystemTimeError)
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown")))]
+    fn now(&self) -> Result<Timestamp, ClockError> {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(ClockError::SystemTimeError)?;
+        Timestamp::from_duration(since_epoch).ok_or(ClockError::OutOfRange)
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+    fn now(&self) -> Result<Timestamp, ClockError> {
+        WasmClock.now()
+    }
+}
+
+/// Clock reading JavaScript's `Date.now()`, for browsers and edge runtimes.
+///
+/// `std::time::SystemTime` panics on `wasm32-unknown-unknown`, so limiters
+/// compiled for Cloudflare Workers or browser WASM need the host's clock.
+/// `Date.now()` counts milliseconds since the Unix epoch, the same time base
+/// as [`SystemClock`], so decisions and saved state stay interchangeable.
+///
+/// Only call it where a JavaScript host is present; on other targets the
+/// underlying binding panics. Resolution is a millisecond, coarsened further
+/// by browsers that reduce timer precision.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, WasmClock};
+///
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(5.0, 2.0), WasmClock).unwrap();
+/// let decision = limiter.check_request("button_click").unwrap();
+/// ```
+#[cfg(feature = "wasm")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WasmClock;
+
+#[cfg(feature = "wasm")]
+impl Clock for WasmClock {
+    fn now(&self) -> Result<Timestamp, ClockError> {
+        // negative or non-finite readings have no place on the timeline
+        Duration::try_from_secs_f64(js_sys::Date::now() / 1_000.0)
+            .ok()
+            .and_then(Timestamp::from_duration)
+            .ok_or(ClockError::OutOfRange)
+    }
+}
+
+/// Clock driven by tokio's time source, so paused test time applies.
+///
+/// Reads [`tokio::time::Instant`], which stands still under
+/// `tokio::time::pause()` and moves with `tokio::time::advance()`, so async
+/// tests control the limiter's time the same way they control timers and
+/// sleeps. Timestamps count from the system time at which the clock was
+/// created, keeping them on the Unix epoch time base like [`SystemClock`].
+/// Clones share the same starting point.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, TokioClock};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), TokioClock::new()).unwrap();
+///
+/// assert!(limiter.check_request("user_123").unwrap().allowed);
+/// assert!(!limiter.check_request("user_123").unwrap().allowed);
+///
+/// tokio::time::advance(Duration::from_secs(1)).await;
+/// assert!(limiter.check_request("user_123").unwrap().allowed);
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy)]
+pub struct TokioClock {
+    start: tokio::time::Instant,
+    start_time: Timestamp,
+}
+
+#[cfg(feature = "tokio")]
+impl TokioClock {
+    /// Create a clock starting at the current system time
+    pub fn new() -> Self {
+        let start_time = SystemClock.now().unwrap_or_default();
+        Self::starting_at(start_time)
+    }
+
+    /// Create a clock whose current time is `start_time`
+    pub fn starting_at(start_time: Timestamp) -> Self {
+        Self {
+            start: tokio::time::Instant::now(),
+            start_time,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Default for TokioClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Clock for TokioClock {
+    fn now(&self) -> Result<Timestamp, ClockError> {
+        self.start_time
+            .checked_add(self.start.elapsed())
+            .ok_or(ClockError::OutOfRange)
     }
 }
@@ -4,6 +4,11 @@
 
 // dependencies
 use crate::errors::FluxLimiterError;
+use crate::gcra::{self, Precision};
+use std::collections::BTreeMap;
+
+/// Default time constant of the per-client observed rate, in seconds.
+pub(crate) const DEFAULT_OBSERVED_RATE_WINDOW_SECONDS: f64 = 60.0;
 
 /// Configuration for rate limiter behavior.
 ///
@@ -17,10 +22,144 @@ use crate::errors::FluxLimiterError;
 ///     .burst(10.0);
 /// ```
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FluxLimiterConfig {
     pub(crate) rate_per_second: f64,
     pub(crate) burst_capacity: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) stats_enabled: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) failure_policy: FailurePolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) debt_ceiling: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) priority_reserve: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) pacing_hints: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) retry_jitter_seconds: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) initial_capacity: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) shard_amount: Option<usize>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_observed_rate_window_seconds")
+    )]
+    pub(crate) observed_rate_window_seconds: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) expiry_resolution_seconds: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) entry_ttl_seconds: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) max_clients: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) precision: Precision,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) drain_mode: DrainMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) operation_costs: BTreeMap<String, f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) replenishment: Replenishment,
+}
+
+/// What a check returns when the clock or a state store fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FailurePolicy, FluxLimiterConfig};
+///
+/// // keep serving traffic if the backing store is unreachable
+/// let config = FluxLimiterConfig::new(100.0, 20.0).failure_policy(FailurePolicy::FailOpen);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum FailurePolicy {
+    /// Return the error to the caller
+    #[default]
+    Propagate,
+    /// Allow the request
+    FailOpen,
+    /// Deny the request
+    FailClosed,
+}
+
+/// Which clients a draining limiter turns away.
+///
+/// Set with [`FluxLimiterConfig::drain_mode`]; draining is switched on and
+/// off at runtime with [`FluxLimiter::set_drain`](crate::FluxLimiter::set_drain).
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{DrainMode, FluxLimiterConfig};
+///
+/// // during a drain, let new clients in and stop serving the known ones
+/// let config = FluxLimiterConfig::new(100.0, 20.0).drain_mode(DrainMode::ExistingClients);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DrainMode {
+    /// Deny clients the limiter does not track yet; tracked clients keep
+    /// their schedules
+    #[default]
+    NewClients,
+    /// Deny tracked clients; clients seen for the first time are checked
+    /// as usual
+    ExistingClients,
+}
+
+/// How a client regains burst capacity after using it.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, Replenishment};
+///
+/// // a client that used its burst must go quiet before it can burst again
+/// let config = FluxLimiterConfig::new(10.0, 20.0).replenishment(Replenishment::WhenIdle);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Replenishment {
+    /// Regain capacity continuously, one request per emission interval
+    /// (plain GCRA)
+    #[default]
+    Linear,
+    /// Regain capacity only at boundaries of steps of this many seconds:
+    /// time within a step is not credited, so every request in a step is
+    /// checked as if it arrived at the step's start
+    Stepwise(f64),
+    /// Once a client has been denied for exhausting its burst, deny it until
+    /// its schedule has fully caught up, then grant the whole burst again
+    WhenIdle,
+}
+
+/// Priority class of a request.
+///
+/// Used with [`FluxLimiter::check_request_with_priority`](crate::FluxLimiter::check_request_with_priority)
+/// and [`FluxLimiterConfig::priority_reserve`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Priority {
+    /// May use the full capacity, including the reserved share
+    #[default]
+    High,
+    /// Denied once only the reserved share is left
+    Low,
 }
 
 impl FluxLimiterConfig {
@@ -34,9 +173,52 @@ impl FluxLimiterConfig {
         Self {
             rate_per_second,
             burst_capacity,
+            stats_enabled: false,
+            failure_policy: FailurePolicy::default(),
+            debt_ceiling: 0.0,
+            priority_reserve: 0.0,
+            pacing_hints: false,
+            retry_jitter_seconds: 0.0,
+            initial_capacity: 0,
+            shard_amount: None,
+            observed_rate_window_seconds: DEFAULT_OBSERVED_RATE_WINDOW_SECONDS,
+            expiry_resolution_seconds: None,
+            entry_ttl_seconds: None,
+            max_clients: None,
+            precision: Precision::default(),
+            drain_mode: DrainMode::default(),
+            operation_costs: BTreeMap::new(),
+            replenishment: Replenishment::default(),
         }
     }
 
+    /// Create a configuration allowing `requests` per second with no burst.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests as f64, 0.0)
+    }
+
+    /// Create a configuration allowing `requests` per minute with no burst.
+    ///
+    /// ```rust
+    /// use flux_limiter::FluxLimiterConfig;
+    ///
+    /// // 60 requests per minute, bursts of up to 10
+    /// let config = FluxLimiterConfig::per_minute(60).burst(10.0);
+    /// ```
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests as f64 / 60.0, 0.0)
+    }
+
+    /// Create a configuration allowing `requests` per hour with no burst.
+    pub fn per_hour(requests: u32) -> Self {
+        Self::new(requests as f64 / 3_600.0, 0.0)
+    }
+
+    /// Create a configuration allowing `requests` per day with no burst.
+    pub fn per_day(requests: u32) -> Self {
+        Self::new(requests as f64 / 86_400.0, 0.0)
+    }
+
     /// Builder-style: set rate per second
     pub fn rate(mut self, rate_per_second: f64) -> Self {
         self.rate_per_second = rate_per_second;
@@ -49,14 +231,306 @@ impl FluxLimiterConfig {
         self
     }
 
+    /// Builder-style: enable or disable statistics counters (disabled by default)
+    ///
+    /// When enabled, the limiter counts allowed and denied checks and cleanup
+    /// evictions using relaxed atomics; see [`FluxLimiter::stats`](crate::FluxLimiter::stats).
+    pub fn stats(mut self, enabled: bool) -> Self {
+        self.stats_enabled = enabled;
+        self
+    }
+
+    /// Builder-style: include pacing hints on allowed decisions (disabled by default)
+    ///
+    /// When enabled, allowed decisions carry `suggested_delay_seconds`: how
+    /// long to wait before the next request to stay on the sustained schedule
+    /// instead of spending the burst up front.
+    pub fn pacing_hints(mut self, enabled: bool) -> Self {
+        self.pacing_hints = enabled;
+        self
+    }
+
+    /// Builder-style: add up to `max_seconds` of random delay to denials (0 by default)
+    ///
+    /// Jitter only ever lengthens the advised delay, so clients denied at the
+    /// same instant spread their retries out instead of returning together.
+    /// The delay is drawn from [`UniformJitter`](crate::UniformJitter) unless
+    /// another [`JitterStrategy`](crate::JitterStrategy) is plugged in.
+    pub fn retry_jitter(mut self, max_seconds: f64) -> Self {
+        self.retry_jitter_seconds = max_seconds;
+        self
+    }
+
+    /// Builder-style: set the failure policy (defaults to [`FailurePolicy::Propagate`])
+    ///
+    /// With `FailOpen` or `FailClosed`, checks that hit a clock or state store
+    /// error, or the [`max_clients`](Self::max_clients) cap, return an allowed
+    /// or denied decision instead of `Err`. Such decisions carry no
+    /// `remaining_capacity` and a `reset_time_nanos` of 0.
+    pub fn failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Builder-style: let clients go up to `requests` over the limit (0 by default)
+    ///
+    /// Requests beyond the burst are still allowed while the client's debt
+    /// stays within the ceiling. Debt is repaid at the sustained rate before
+    /// any new capacity accrues, so a client that borrowed must wait out its
+    /// overage. Allowed decisions report the outstanding debt in `debt`.
+    pub fn debt_ceiling(mut self, requests: f64) -> Self {
+        self.debt_ceiling = requests;
+        self
+    }
+
+    /// Builder-style: reserve a fraction of the capacity for high-priority requests (0 by default)
+    ///
+    /// `fraction` (between 0 and 1) is the share of the burst window that
+    /// [`Priority::Low`] requests may not use. Low-priority traffic keeps the
+    /// same sustained rate but a smaller burst.
+    pub fn priority_reserve(mut self, fraction: f64) -> Self {
+        self.priority_reserve = fraction;
+        self
+    }
+
+    /// Builder-style: pre-allocate room for `clients` in the client map (0 by default)
+    ///
+    /// Sizing the map for the expected number of keys up front avoids
+    /// repeated rehashing while a large deployment warms up.
+    pub fn capacity(mut self, clients: usize) -> Self {
+        self.initial_capacity = clients;
+        self
+    }
+
+    /// Builder-style: split the client map into `shards` independently locked shards
+    ///
+    /// Must be a power of two greater than 1. More shards mean less lock
+    /// contention between cores at the cost of some memory. Defaults to
+    /// DashMap's choice of four times the number of CPUs, rounded up to a
+    /// power of two.
+    pub fn shards(mut self, shards: usize) -> Self {
+        self.shard_amount = Some(shards);
+        self
+    }
+
+    /// Builder-style: set the averaging window of per-client observed rates (60s by default)
+    ///
+    /// Only used with the `per-key-stats` feature. Each client's request
+    /// rate is averaged with an exponential decay whose time constant is
+    /// `seconds`: shorter windows react faster, longer ones smooth out bursts.
+    pub fn observed_rate_window(mut self, seconds: f64) -> Self {
+        self.observed_rate_window_seconds = seconds;
+        self
+    }
+
+    /// Builder-style: expire recovered clients with a timer wheel ticking every `resolution_seconds`
+    ///
+    /// Every client is scheduled for removal when its TAT plus the burst
+    /// tolerance passes, i.e. once it has fully recovered and forgetting it
+    /// changes no decision. Checks advance the wheel as they go, so expired
+    /// clients are removed in amortized constant time without calling
+    /// [`cleanup_stale_clients`](crate::FluxLimiter::cleanup_stale_clients)
+    /// on a schedule. Clients are removed up to one resolution late. Off by
+    /// default.
+    pub fn expiry_wheel(mut self, resolution_seconds: f64) -> Self {
+        self.expiry_resolution_seconds = Some(resolution_seconds);
+        self
+    }
+
+    /// Builder-style: forget clients `seconds` after their last check, whatever their TAT
+    ///
+    /// Matches retention policies such as "forget idle users after an hour":
+    /// cleanup and the expiry wheel remove a client once it has gone
+    /// unchecked for `seconds`, even if it is still paying off a burst or
+    /// debt, and it starts over as a new client when it returns. Clients are
+    /// otherwise kept until they have recovered, as without a TTL.
+    pub fn entry_ttl(mut self, seconds: f64) -> Self {
+        self.entry_ttl_seconds = Some(seconds);
+        self
+    }
+
+    /// Builder-style: track at most `clients` clients at once (unlimited by default)
+    ///
+    /// Bounds the memory an attacker can make the limiter use by cycling
+    /// through keys. Once the map is full, checks for clients that are not
+    /// yet tracked fail with [`FluxLimiterError::CapacityExceeded`], or
+    /// return the [failure policy](Self::failure_policy)'s decision, while
+    /// known clients are checked as usual. Cleanup and expiry make room
+    /// again. Concurrent checks for new clients may overshoot the cap by a
+    /// few entries.
+    pub fn max_clients(mut self, clients: usize) -> Self {
+        self.max_clients = Some(clients);
+        self
+    }
+
+    /// Builder-style: choose which clients are denied while draining
+    /// (defaults to [`DrainMode::NewClients`])
+    ///
+    /// Draining is off until [`FluxLimiter::set_drain`](crate::FluxLimiter::set_drain)
+    /// turns it on, e.g. when an instance is about to be taken out of a
+    /// rolling deploy.
+    pub fn drain_mode(mut self, mode: DrainMode) -> Self {
+        self.drain_mode = mode;
+        self
+    }
+
+    /// Builder-style: choose how burst capacity is regained (defaults to
+    /// [`Replenishment::Linear`])
+    ///
+    /// [`Replenishment::WhenIdle`] keeps its hold in the client's in-memory
+    /// entry; checks against an external state store replenish linearly.
+    pub fn replenishment(mut self, replenishment: Replenishment) -> Self {
+        self.replenishment = replenishment;
+        self
+    }
+
+    /// Builder-style: charge `cost` requests for operations of class `operation`
+    ///
+    /// Lets one client entry govern a blended workload, e.g. a cost of 1 for
+    /// `"read"` and 5 for `"write"`: operations checked with
+    /// [`check_request_op`](crate::FluxLimiter::check_request_op) take
+    /// `cost` emission intervals of the client's schedule instead of one.
+    /// Setting a class again replaces its cost.
+    pub fn operation_cost(mut self, operation: impl Into<String>, cost: f64) -> Self {
+        self.operation_costs.insert(operation.into(), cost);
+        self
+    }
+
+    /// Builder-style: set how the rate and burst are rounded to nanoseconds
+    ///
+    /// Defaults to truncating with a minimum emission interval of 1ns; see
+    /// [`Precision`]. The resulting values are reported by
+    /// [`FluxLimiter::emission_interval_nanos`](crate::FluxLimiter::emission_interval_nanos)
+    /// and [`FluxLimiter::tolerance_nanos`](crate::FluxLimiter::tolerance_nanos).
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Load a configuration from environment variables.
+    ///
+    /// Reads `{prefix}_RATE` (required), `{prefix}_BURST` (optional,
+    /// defaults to `0.0`) and `{prefix}_MAX_CLIENTS` (optional, no cap by
+    /// default), then validates the result.
+    ///
+    /// # Errors
+    ///
+    /// * `FluxLimiterError::MissingEnvVar` - a required variable is not set
+    /// * `FluxLimiterError::InvalidEnvVar` - a variable is not a valid number,
+    ///   or `{prefix}_MAX_CLIENTS` is not a non-negative integer
+    /// * Any error returned by [`validate`](Self::validate)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use flux_limiter::FluxLimiterConfig;
+    ///
+    /// // FLUX_RATE=100 FLUX_BURST=20 FLUX_MAX_CLIENTS=100000
+    /// let config = FluxLimiterConfig::from_env("FLUX").unwrap();
+    /// ```
+    pub fn from_env(prefix: &str) -> Result<Self, FluxLimiterError> {
+        let rate_per_second = read_env::<f64>(prefix, "RATE")?
+            .ok_or_else(|| FluxLimiterError::MissingEnvVar(env_key(prefix, "RATE")))?;
+        let burst_capacity = read_env::<f64>(prefix, "BURST")?.unwrap_or(0.0);
+
+        let mut config = Self::new(rate_per_second, burst_capacity);
+        config.max_clients = read_env::<usize>(prefix, "MAX_CLIENTS")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The sustained rate, in requests per second
+    pub fn rate_per_second(&self) -> f64 {
+        self.rate_per_second
+    }
+
+    /// The burst capacity, in requests beyond the sustained rate
+    pub fn burst_capacity(&self) -> f64 {
+        self.burst_capacity
+    }
+
     /// Validate the configuration
+    ///
+    /// Rejects non-finite values, non-positive rates, negative bursts or debt
+    /// ceilings or retry jitter, priority reserves outside 0–1, shard counts
+    /// that are not a power of two above 1, non-positive observed rate
+    /// windows, operation costs that are not positive or that would advance
+    /// the schedule by less than the precision's minimum interval,
+    /// replenishment steps below 1ns, and rates above one request per
+    /// nanosecond (which cannot be represented).
     pub fn validate(&self) -> Result<(), FluxLimiterError> {
-        if self.rate_per_second <= 0.0 {
-            return Err(FluxLimiterError::InvalidRate);
+        gcra::validate(self.rate_per_second, self.burst_capacity)?;
+        if !self.debt_ceiling.is_finite() || self.debt_ceiling < 0.0 {
+            return Err(FluxLimiterError::InvalidDebtCeiling);
+        }
+        if !(0.0..=1.0).contains(&self.priority_reserve) {
+            return Err(FluxLimiterError::InvalidPriorityReserve);
+        }
+        if !self.retry_jitter_seconds.is_finite() || self.retry_jitter_seconds < 0.0 {
+            return Err(FluxLimiterError::InvalidRetryJitter);
+        }
+        if let Some(shards) = self.shard_amount
+            && (shards < 2 || !shards.is_power_of_two())
+        {
+            return Err(FluxLimiterError::InvalidShardAmount(shards));
+        }
+        if !self.observed_rate_window_seconds.is_finite()
+            || self.observed_rate_window_seconds <= 0.0
+        {
+            return Err(FluxLimiterError::InvalidObservedRateWindow);
+        }
+        if let Some(resolution) = self.expiry_resolution_seconds
+            && !(resolution.is_finite() && resolution * 1_000_000_000.0 >= 1.0)
+        {
+            return Err(FluxLimiterError::InvalidExpiryResolution);
+        }
+        if let Some(ttl) = self.entry_ttl_seconds
+            && !(ttl.is_finite() && ttl > 0.0)
+        {
+            return Err(FluxLimiterError::InvalidEntryTtl);
         }
-        if self.burst_capacity < 0.0 {
-            return Err(FluxLimiterError::InvalidBurst);
+        // a cost must still advance the schedule by at least the minimum interval
+        let increment_nanos = gcra::emission_interval_nanos(self.rate_per_second, self.precision)?;
+        let min_interval_nanos = self.precision.min_interval_nanos();
+        if let Some((operation, _)) = self.operation_costs.iter().find(|(_, cost)| {
+            !(cost.is_finite() && **cost > 0.0)
+                || ((increment_nanos as f64 * **cost) as u64) < min_interval_nanos
+        }) {
+            return Err(FluxLimiterError::InvalidOperationCost(operation.clone()));
+        }
+        if let Replenishment::Stepwise(step) = self.replenishment
+            && !(step.is_finite() && step * 1_000_000_000.0 >= 1.0)
+        {
+            return Err(FluxLimiterError::InvalidReplenishmentStep);
         }
         Ok(())
     }
 }
+
+// serde default for configs written before observed rates existed
+#[cfg(feature = "serde")]
+fn default_observed_rate_window_seconds() -> f64 {
+    DEFAULT_OBSERVED_RATE_WINDOW_SECONDS
+}
+
+// build the environment variable name for a given prefix and setting
+fn env_key(prefix: &str, name: &str) -> String {
+    format!("{}_{}", prefix.trim_end_matches('_'), name)
+}
+
+// read an optional setting from the environment, parsed as `V`
+fn read_env<V: std::str::FromStr>(prefix: &str, name: &str) -> Result<Option<V>, FluxLimiterError> {
+    let key = env_key(prefix, name);
+    match std::env::var(&key) {
+        Ok(value) => value
+            .trim()
+            .parse::<V>()
+            .map(Some)
+            .map_err(|_| FluxLimiterError::InvalidEnvVar { name: key, value }),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(value)) => Err(FluxLimiterError::InvalidEnvVar {
+            name: key,
+            value: value.to_string_lossy().into_owned(),
+        }),
+    }
+}
@@ -0,0 +1,556 @@
+// src/decision.rs
+
+//! Decision types returned by the flux limiter
+
+// dependencies
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Result of a rate limiting decision with metadata for HTTP responses.
+///
+/// Displays compactly for log lines, e.g. `allowed, 4 remaining` or
+/// `denied, retry in 0.48s, 0 remaining`, naming the reason when it is not
+/// the usual one for the outcome (`denied (shed), ...`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FluxLimiterDecision {
+    /// Whether the request should be allowed
+    pub allowed: bool,
+    /// Seconds until the client can make another request (when denied)
+    pub retry_after_seconds: Option<f64>,
+    /// Approximate remaining burst capacity
+    pub remaining_capacity: Option<f64>,
+    /// When the rate limit window resets (nanoseconds since epoch)
+    pub reset_time_nanos: u64,
+    /// Sustained rate (requests per second) of the policy that was applied;
+    /// infinite for exempt requests, which serializes as `null`
+    #[cfg_attr(feature = "serde", serde(with = "unbounded"))]
+    pub limit_per_second: f64,
+    /// Burst capacity of the policy that was applied; infinite for exempt requests
+    #[cfg_attr(feature = "serde", serde(with = "unbounded"))]
+    pub burst_capacity: f64,
+    /// Whether load shedding reduced the rate for this decision
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shedding: bool,
+    /// Requests the client owes beyond its limit (always 0 without a debt ceiling)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub debt: f64,
+    /// Seconds to wait before the next request to stay on the sustained
+    /// schedule (allowed decisions with pacing hints enabled only)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub suggested_delay_seconds: Option<f64>,
+    /// Why the request was allowed or denied
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reason: DecisionReason,
+}
+
+impl FluxLimiterDecision {
+    /// How long the client should wait before retrying, if denied.
+    ///
+    /// The same delay as `retry_after_seconds`, converted once here so call
+    /// sites never round seconds themselves. Delays too long for a
+    /// `Duration` saturate at `Duration::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use std::time::Duration;
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+    /// assert_eq!(limiter.check_request("user_123").unwrap().retry_after(), None);
+    ///
+    /// let retry_after = limiter.check_request("user_123").unwrap().retry_after().unwrap();
+    /// assert!(retry_after <= Duration::from_secs(1));
+    /// ```
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after_seconds.map(seconds_to_duration)
+    }
+
+    /// When the rate limit window resets, as a wall-clock time.
+    ///
+    /// Assumes the limiter's clock counts from the Unix epoch, as
+    /// [`SystemClock`](crate::SystemClock) does. Decisions made without a
+    /// clock reading report `UNIX_EPOCH`.
+    #[cfg(feature = "std")]
+    pub fn reset_time(&self) -> SystemTime {
+        nanos_to_system_time(self.reset_time_nanos)
+    }
+
+    /// `Retry-After` as delta-seconds, if denied.
+    ///
+    /// `retry_after_seconds` rounded up to whole seconds, so clients never
+    /// retry early; with the `http` feature this is the value
+    /// `apply_headers` writes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(0.5, 0.0), SystemClock).unwrap();
+    /// assert_eq!(limiter.check_request("user_123").unwrap().retry_after_delta_seconds(), None);
+    ///
+    /// let delta = limiter.check_request("user_123").unwrap().retry_after_delta_seconds();
+    /// assert!(matches!(delta, Some(1 | 2)));
+    /// ```
+    pub fn retry_after_delta_seconds(&self) -> Option<u64> {
+        self.retry_after_seconds.map(ceil_seconds)
+    }
+
+    /// `Retry-After` as an HTTP-date, if denied.
+    ///
+    /// Renders `reset_time_nanos`, rounded up to the whole second, in the
+    /// IMF-fixdate form of RFC 9110, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`,
+    /// for clients that only honor dates. The reset time is when the
+    /// client's burst is fully restored, so the date is never earlier than
+    /// the delta form (before jitter) and matches it for limits without
+    /// burst. Like [`reset_time`](Self::reset_time), it assumes a clock on
+    /// the Unix epoch; decisions made without a clock reading give `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let date = limiter.check_request("user_123").unwrap().retry_after_http_date().unwrap();
+    /// assert!(date.ends_with(" GMT"));
+    /// ```
+    pub fn retry_after_http_date(&self) -> Option<String> {
+        if self.retry_after_seconds.is_none() || self.reset_time_nanos == 0 {
+            return None;
+        }
+        Some(http_date(self.reset_time_nanos.div_ceil(1_000_000_000)))
+    }
+}
+
+/// Why a decision came out the way it did.
+///
+/// `allowed` says what to do with a request; the reason tells logs and
+/// middleware why, e.g. to separate clients that ran out of burst from
+/// requests shed under load. New reasons may be added as features land.
+///
+/// `Blocked` and `ShadowDenied` are not produced by the limiters in this
+/// crate; they let layers built on top (deny lists, shadow rollouts) report
+/// their outcomes in the same decision type.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{DecisionReason, FluxLimiter, FluxLimiterConfig, SystemClock};
+///
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+///
+/// assert_eq!(limiter.check_request("user_123").unwrap().reason, DecisionReason::Conforming);
+/// assert_eq!(limiter.check_request("user_123").unwrap().reason, DecisionReason::BurstExhausted);
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DecisionReason {
+    /// Allowed: the request fits the client's rate and burst
+    #[default]
+    Conforming,
+    /// Denied: the client used up its burst and must wait for capacity
+    BurstExhausted,
+    /// Denied: the client is blocked regardless of its remaining capacity
+    Blocked,
+    /// Allowed: the key is not rate limited at all
+    Exempt,
+    /// Denied while load shedding reduced the rate
+    Shed,
+    /// Allowed, but would have been denied outside shadow mode
+    ShadowDenied,
+    /// Decided by the [`FailurePolicy`](crate::FailurePolicy) after a clock
+    /// or store failure, or for a new client beyond
+    /// [`max_clients`](crate::FluxLimiterConfig::max_clients)
+    Fallback,
+    /// Denied: the request is within the sustained rate but too fast for a
+    /// [`TwoRateLimiter`](crate::TwoRateLimiter)'s peak rate
+    PeakExceeded,
+    /// Denied: the limiter is draining and turns away this client; see
+    /// [`FluxLimiter::set_drain`](crate::FluxLimiter::set_drain)
+    Draining,
+}
+
+impl DecisionReason {
+    /// Short snake_case name for logs and metric attributes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecisionReason::Conforming => "conforming",
+            DecisionReason::BurstExhausted => "burst_exhausted",
+            DecisionReason::Blocked => "blocked",
+            DecisionReason::Exempt => "exempt",
+            DecisionReason::Shed => "shed",
+            DecisionReason::ShadowDenied => "shadow_denied",
+            DecisionReason::Fallback => "fallback",
+            DecisionReason::PeakExceeded => "peak_exceeded",
+            DecisionReason::Draining => "draining",
+        }
+    }
+}
+
+/// Outcome of an all-or-nothing check across several keys.
+///
+/// Returned by [`FluxLimiter::check_all`](crate::FluxLimiter::check_all).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiKeyDecision<T> {
+    /// Whether every key conformed (and capacity was consumed for all of them)
+    pub allowed: bool,
+    /// The first key that did not conform, if any
+    pub denied_by: Option<T>,
+    /// Per-key decisions in input order
+    pub decisions: Vec<(T, FluxLimiterDecision)>,
+}
+
+/// The GCRA values behind a decision, for debugging.
+///
+/// Returned by [`FluxLimiter::check_request_explained`](crate::FluxLimiter::check_request_explained).
+/// A request is allowed when `now_nanos + tolerance_nanos` reaches
+/// `previous_tat_nanos + reserved_nanos`; the new TAT is then
+/// `max(previous_tat_nanos, now_nanos) + emission_interval_nanos`. All times
+/// are nanoseconds in the limiter clock's time base. Displays on one line,
+/// e.g. `now 1000, tat 1500 -> 1600, interval 100, tolerance 500`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecisionTrace {
+    /// The time the request was checked at, rounded down to the start of
+    /// its step under [stepwise replenishment](crate::Replenishment::Stepwise)
+    pub now_nanos: u64,
+    /// The client's stored TAT before the check; `now_nanos` for new clients
+    pub previous_tat_nanos: u64,
+    /// The TAT stored by the check, or `None` if it was denied
+    pub new_tat_nanos: Option<u64>,
+    /// How far the TAT advances per request, lengthened under load shedding
+    pub emission_interval_nanos: u64,
+    /// How far ahead of the schedule the client may run: the burst
+    /// tolerance plus any debt ceiling
+    pub tolerance_nanos: u64,
+    /// Share of the tolerance held back from low-priority requests
+    pub reserved_nanos: u64,
+    /// Whether the check started tracking the client
+    pub new_client: bool,
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "now {}, tat {} -> ",
+            self.now_nanos, self.previous_tat_nanos
+        )?;
+        match self.new_tat_nanos {
+            Some(new_tat_nanos) => write!(f, "{}", new_tat_nanos)?,
+            None => f.write_str("unchanged")?,
+        }
+        write!(
+            f,
+            ", interval {}, tolerance {}",
+            self.emission_interval_nanos, self.tolerance_nanos
+        )?;
+        if self.reserved_nanos > 0 {
+            write!(f, ", reserved {}", self.reserved_nanos)?;
+        }
+        if self.new_client {
+            f.write_str(", new client")?;
+        }
+        Ok(())
+    }
+}
+
+/// Typed rate limiting decision.
+///
+/// Returned by [`FluxLimiter::check_request_typed`](crate::FluxLimiter::check_request_typed).
+/// Metadata that only makes sense for one outcome lives on that variant.
+/// Displays like the [`FluxLimiterDecision`] it was converted from.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{Decision, FluxLimiter, FluxLimiterConfig, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+///
+/// match limiter.check_request_typed("user_123").unwrap() {
+///     Decision::Allowed(meta) => println!("{} requests left", meta.remaining_capacity),
+///     Decision::Denied(meta) => println!("retry after {:.2}s", meta.retry_after_seconds),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Decision {
+    /// The request conforms and should be processed
+    Allowed(AllowedMeta),
+    /// The request exceeds the limit and should be rejected
+    Denied(DeniedMeta),
+}
+
+/// Metadata for an allowed request
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AllowedMeta {
+    /// Approximate remaining burst capacity
+    pub remaining_capacity: f64,
+    /// When the rate limit window resets (nanoseconds since epoch)
+    pub reset_time_nanos: u64,
+    /// Sustained rate (requests per second) of the policy that was applied;
+    /// infinite for exempt requests, which serializes as `null`
+    #[cfg_attr(feature = "serde", serde(with = "unbounded"))]
+    pub limit_per_second: f64,
+    /// Burst capacity of the policy that was applied; infinite for exempt requests
+    #[cfg_attr(feature = "serde", serde(with = "unbounded"))]
+    pub burst_capacity: f64,
+    /// Whether load shedding reduced the rate for this decision
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shedding: bool,
+    /// Requests the client owes beyond its limit (always 0 without a debt ceiling)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub debt: f64,
+    /// Seconds to wait before the next request to stay on the sustained
+    /// schedule (allowed decisions with pacing hints enabled only)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub suggested_delay_seconds: Option<f64>,
+    /// Why the request was allowed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reason: DecisionReason,
+}
+
+/// Metadata for a denied request
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeniedMeta {
+    /// Seconds until the client can make another request
+    pub retry_after_seconds: f64,
+    /// When the rate limit window resets (nanoseconds since epoch)
+    pub reset_time_nanos: u64,
+    /// Sustained rate (requests per second) of the policy that was applied
+    pub limit_per_second: f64,
+    /// Burst capacity of the policy that was applied
+    pub burst_capacity: f64,
+    /// Whether load shedding reduced the rate for this decision
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shedding: bool,
+    /// Requests the client owes beyond its limit (always 0 without a debt ceiling)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub debt: f64,
+    /// Why the request was denied
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reason: DecisionReason,
+}
+
+impl Decision {
+    /// Whether the request should be allowed
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Decision::Allowed(_))
+    }
+
+    /// When the rate limit window resets (nanoseconds since epoch)
+    pub fn reset_time_nanos(&self) -> u64 {
+        match self {
+            Decision::Allowed(meta) => meta.reset_time_nanos,
+            Decision::Denied(meta) => meta.reset_time_nanos,
+        }
+    }
+
+    /// When the rate limit window resets; see [`FluxLimiterDecision::reset_time`]
+    #[cfg(feature = "std")]
+    pub fn reset_time(&self) -> SystemTime {
+        nanos_to_system_time(self.reset_time_nanos())
+    }
+}
+
+impl AllowedMeta {
+    /// When the rate limit window resets; see [`FluxLimiterDecision::reset_time`]
+    #[cfg(feature = "std")]
+    pub fn reset_time(&self) -> SystemTime {
+        nanos_to_system_time(self.reset_time_nanos)
+    }
+}
+
+impl DeniedMeta {
+    /// How long the client should wait before retrying; see
+    /// [`FluxLimiterDecision::retry_after`]
+    pub fn retry_after(&self) -> Duration {
+        seconds_to_duration(self.retry_after_seconds)
+    }
+
+    /// When the rate limit window resets; see [`FluxLimiterDecision::reset_time`]
+    #[cfg(feature = "std")]
+    pub fn reset_time(&self) -> SystemTime {
+        nanos_to_system_time(self.reset_time_nanos)
+    }
+}
+
+impl fmt::Display for FluxLimiterDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_decision(
+            f,
+            self.allowed,
+            self.reason,
+            self.retry_after_seconds,
+            self.remaining_capacity,
+        )
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Allowed(meta) => {
+                write_decision(f, true, meta.reason, None, Some(meta.remaining_capacity))
+            }
+            // denied requests have nothing left in the burst
+            Decision::Denied(meta) => write_decision(
+                f,
+                false,
+                meta.reason,
+                Some(meta.retry_after_seconds),
+                Some(0.0),
+            ),
+        }
+    }
+}
+
+// "allowed, 4 remaining" / "denied (shed), retry in 0.48s, 0 remaining"
+fn write_decision(
+    f: &mut fmt::Formatter<'_>,
+    allowed: bool,
+    reason: DecisionReason,
+    retry_after_seconds: Option<f64>,
+    remaining_capacity: Option<f64>,
+) -> fmt::Result {
+    let (outcome, usual_reason) = if allowed {
+        ("allowed", DecisionReason::Conforming)
+    } else {
+        ("denied", DecisionReason::BurstExhausted)
+    };
+    f.write_str(outcome)?;
+    if reason != usual_reason {
+        write!(f, " ({})", reason.as_str())?;
+    }
+    if let Some(retry_after_seconds) = retry_after_seconds {
+        write!(f, ", retry in {:.2}s", retry_after_seconds)?;
+    }
+    if let Some(remaining_capacity) = remaining_capacity {
+        // only whole requests can be made; capacity is never negative
+        write!(f, ", {} remaining", remaining_capacity as u64)?;
+    }
+    Ok(())
+}
+
+// retry delays are never negative; NaN counts as no delay and overlong delays saturate
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+// whole seconds, rounded up; negative and NaN delays count as none (no `f64::ceil` without std)
+fn ceil_seconds(seconds: f64) -> u64 {
+    let whole = seconds as u64;
+    if (whole as f64) < seconds {
+        whole.saturating_add(1)
+    } else {
+        whole
+    }
+}
+
+// seconds since the Unix epoch as an IMF-fixdate, with days converted to
+// civil dates by Howard Hinnant's `civil_from_days`
+fn http_date(unix_seconds: u64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let days = unix_seconds / 86_400;
+    let seconds_of_day = unix_seconds % 86_400;
+
+    // count from 0000-03-01 so leap days end each 400-year era
+    let days_from_era_start = days + 719_468;
+    let era = days_from_era_start / 146_097;
+    let day_of_era = days_from_era_start % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60,
+    )
+}
+
+// nanoseconds since the Unix epoch as a wall-clock time
+#[cfg(feature = "std")]
+fn nanos_to_system_time(nanos: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_nanos(nanos)
+}
+
+// convert the flat decision struct into the typed representation
+impl From<FluxLimiterDecision> for Decision {
+    fn from(decision: FluxLimiterDecision) -> Self {
+        if decision.allowed {
+            Decision::Allowed(AllowedMeta {
+                remaining_capacity: decision.remaining_capacity.unwrap_or(0.0),
+                reset_time_nanos: decision.reset_time_nanos,
+                limit_per_second: decision.limit_per_second,
+                burst_capacity: decision.burst_capacity,
+                shedding: decision.shedding,
+                debt: decision.debt,
+                suggested_delay_seconds: decision.suggested_delay_seconds,
+                reason: decision.reason,
+            })
+        } else {
+            Decision::Denied(DeniedMeta {
+                retry_after_seconds: decision.retry_after_seconds.unwrap_or(0.0),
+                reset_time_nanos: decision.reset_time_nanos,
+                limit_per_second: decision.limit_per_second,
+                burst_capacity: decision.burst_capacity,
+                shedding: decision.shedding,
+                debt: decision.debt,
+                reason: decision.reason,
+            })
+        }
+    }
+}
+
+// limits that are infinite on exempt decisions; JSON has no infinity, so they
+// are written as null and read back as infinite
+#[cfg(feature = "serde")]
+mod unbounded {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        let finite = (*value != f64::INFINITY).then_some(*value);
+        finite.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+    }
+}
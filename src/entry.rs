@@ -0,0 +1,165 @@
+// src/entry.rs
+
+//! Per-client state stored in the limiter's map
+
+// dependencies
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// State tracked for each client.
+///
+/// The theoretical arrival time and the time of the client's last check are
+/// always present; with the `per-key-stats`
+/// feature each entry also counts the client's allowed and denied requests
+/// and keeps an exponentially decayed request count for its observed rate.
+///
+/// Every field is an atomic, so checks update an entry through a shared
+/// reference with compare-and-swap instead of holding the map's shard write
+/// lock. The map itself is only written when a client is first seen or
+/// removed. A check first reads the TAT and only swaps in a new one when
+/// the request conforms, so denials never write it; a denial at the same
+/// clock reading as the client's last check writes nothing at all.
+#[derive(Debug)]
+pub struct ClientEntry {
+    tat_nanos: AtomicU64,
+    last_access_nanos: AtomicU64,
+    held_until_nanos: AtomicU64,
+    #[cfg(feature = "per-key-stats")]
+    allowed: AtomicU64,
+    #[cfg(feature = "per-key-stats")]
+    denied: AtomicU64,
+    #[cfg(feature = "per-key-stats")]
+    demand: AtomicU64, // decayed request count, as f64 bits
+    #[cfg(feature = "per-key-stats")]
+    last_seen_nanos: AtomicU64,
+}
+
+impl ClientEntry {
+    // create an entry for a client last checked at `last_access_nanos`
+    pub(crate) fn new(tat_nanos: u64, last_access_nanos: u64) -> Self {
+        Self {
+            tat_nanos: AtomicU64::new(tat_nanos),
+            last_access_nanos: AtomicU64::new(last_access_nanos),
+            held_until_nanos: AtomicU64::new(0),
+            #[cfg(feature = "per-key-stats")]
+            allowed: AtomicU64::new(0),
+            #[cfg(feature = "per-key-stats")]
+            denied: AtomicU64::new(0),
+            #[cfg(feature = "per-key-stats")]
+            demand: AtomicU64::new(0.0f64.to_bits()),
+            #[cfg(feature = "per-key-stats")]
+            last_seen_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Theoretical arrival time (nanoseconds since epoch)
+    pub fn tat_nanos(&self) -> u64 {
+        self.tat_nanos.load(Ordering::Acquire)
+    }
+
+    /// Time of the client's latest check (nanoseconds since epoch)
+    pub fn last_access_nanos(&self) -> u64 {
+        self.last_access_nanos.load(Ordering::Relaxed)
+    }
+
+    /// Requests allowed for this client
+    #[cfg(feature = "per-key-stats")]
+    pub fn allowed(&self) -> u64 {
+        self.allowed.load(Ordering::Relaxed)
+    }
+
+    /// Requests denied for this client
+    #[cfg(feature = "per-key-stats")]
+    pub fn denied(&self) -> u64 {
+        self.denied.load(Ordering::Relaxed)
+    }
+
+    // replace the TAT if it still holds `current`; on failure return the value found instead
+    pub(crate) fn compare_exchange_tat(&self, current: u64, new: u64) -> Result<(), u64> {
+        self.tat_nanos
+            .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+    }
+
+    // apply `update` to the TAT, retrying until no concurrent write interferes
+    pub(crate) fn update_tat(&self, mut update: impl FnMut(u64) -> u64) {
+        // the closure always returns Some, so fetch_update cannot fail
+        let _ = self
+            .tat_nanos
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |tat| Some(update(tat)));
+    }
+
+    // note a check at `now_nanos`; out-of-order timestamps never move it back.
+    // Reading first keeps repeated checks at the same time, such as a denial
+    // storm between ticks of a cached clock, from writing the entry at all
+    pub(crate) fn touch(&self, now_nanos: u64) {
+        if self.last_access_nanos.load(Ordering::Relaxed) < now_nanos {
+            self.last_access_nanos
+                .fetch_max(now_nanos, Ordering::Relaxed);
+        }
+    }
+
+    // until when the client is denied while it regains its burst
+    pub(crate) fn held_until_nanos(&self) -> u64 {
+        self.held_until_nanos.load(Ordering::Relaxed)
+    }
+
+    // deny the client until `nanos`; like `touch`, repeated holds to the same
+    // time only read the entry
+    pub(crate) fn hold_until(&self, nanos: u64) {
+        if self.held_until_nanos.load(Ordering::Relaxed) < nanos {
+            self.held_until_nanos.fetch_max(nanos, Ordering::Relaxed);
+        }
+    }
+
+    // count `count` decisions for this client (no-op without per-key-stats)
+    #[cfg_attr(not(feature = "per-key-stats"), allow(unused_variables))]
+    pub(crate) fn record(&self, allowed: bool, count: u64) {
+        #[cfg(feature = "per-key-stats")]
+        if allowed {
+            self.allowed.fetch_add(count, Ordering::Relaxed);
+        } else {
+            self.denied.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    // take back counts recorded by a commit that was rolled back
+    #[cfg_attr(not(feature = "per-key-stats"), allow(unused_variables))]
+    pub(crate) fn unrecord_allowed(&self, count: u64) {
+        #[cfg(feature = "per-key-stats")]
+        {
+            let _ = self
+                .allowed
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |allowed| {
+                    Some(allowed.saturating_sub(count))
+                });
+        }
+    }
+
+    // fold `count` requests seen at `now_nanos` into the decayed request count;
+    // concurrent updates may apply their decay in either order, which only
+    // blurs the estimate slightly
+    #[cfg(feature = "per-key-stats")]
+    pub(crate) fn observe(&self, count: u64, now_nanos: u64, window_nanos: f64) {
+        let last_seen = self.last_seen_nanos.fetch_max(now_nanos, Ordering::Relaxed);
+        let decay = decay(now_nanos.saturating_sub(last_seen), window_nanos);
+        let _ = self
+            .demand
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                Some((f64::from_bits(bits) * decay + count as f64).to_bits())
+            });
+    }
+
+    // requests per second over the decay window, as of `now_nanos`
+    #[cfg(feature = "per-key-stats")]
+    pub(crate) fn observed_rate(&self, now_nanos: u64, window_nanos: f64) -> f64 {
+        let idle_nanos = now_nanos.saturating_sub(self.last_seen_nanos.load(Ordering::Relaxed));
+        let demand = f64::from_bits(self.demand.load(Ordering::Relaxed));
+        demand * decay(idle_nanos, window_nanos) / (window_nanos / 1_000_000_000.0)
+    }
+}
+
+// weight left after `elapsed_nanos` for an exponential decay with time constant `window_nanos`
+#[cfg(feature = "per-key-stats")]
+fn decay(elapsed_nanos: u64, window_nanos: f64) -> f64 {
+    (-(elapsed_nanos as f64) / window_nanos).exp()
+}
@@ -0,0 +1,354 @@
+// src/envoy.rs
+
+//! Envoy rate limit service (RLS) protocol over policy-routed limiters (`envoy` feature)
+
+// handlers return tonic's `Status`, large as it is, like generated services
+#![allow(clippy::result_large_err)]
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::router::PolicyRouter;
+use crate::sidecar::{unary, unavailable};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::Status;
+use tonic::body::BoxBody;
+use tonic::codegen::{Body, BoxFuture, Service, StdError, http};
+use tonic::server::NamedService;
+
+/// `envoy.service.ratelimit.v3.RateLimitRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RateLimitRequest {
+    /// Namespace of the descriptors, from the Envoy filter config
+    #[prost(string, tag = "1")]
+    pub domain: String,
+    /// Descriptors to check; the request is limited if any is over its limit
+    #[prost(message, repeated, tag = "2")]
+    pub descriptors: Vec<RateLimitDescriptor>,
+    /// Requests to count against every descriptor; 0 means 1
+    #[prost(uint32, tag = "3")]
+    pub hits_addend: u32,
+}
+
+/// `envoy.extensions.common.ratelimit.v3.RateLimitDescriptor`
+///
+/// Limit overrides (field 2) are not supported and are skipped when decoding.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RateLimitDescriptor {
+    /// Ordered key/value pairs, e.g. `remote_address=10.0.0.1`
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<DescriptorEntry>,
+    /// Requests to count against this descriptor, overriding the request's
+    #[prost(message, optional, tag = "3")]
+    pub hits_addend: Option<u64>,
+}
+
+/// `envoy.extensions.common.ratelimit.v3.RateLimitDescriptor.Entry`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DescriptorEntry {
+    /// Descriptor key, e.g. `remote_address`
+    #[prost(string, tag = "1")]
+    pub key: String,
+    /// Descriptor value, e.g. `10.0.0.1`
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+/// `envoy.service.ratelimit.v3.RateLimitResponse`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RateLimitResponse {
+    /// [`RateLimitCode::OverLimit`] if any descriptor is over its limit
+    #[prost(enumeration = "RateLimitCode", tag = "1")]
+    pub overall_code: i32,
+    /// One status per descriptor, in request order
+    #[prost(message, repeated, tag = "2")]
+    pub statuses: Vec<DescriptorStatus>,
+    /// Headers for Envoy to add to the response; `retry-after` when limited
+    #[prost(message, repeated, tag = "3")]
+    pub response_headers_to_add: Vec<ResponseHeader>,
+}
+
+/// `envoy.service.ratelimit.v3.RateLimitResponse.Code`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum RateLimitCode {
+    /// Not set
+    Unknown = 0,
+    /// Within the limit
+    Ok = 1,
+    /// Over the limit
+    OverLimit = 2,
+}
+
+/// `envoy.service.ratelimit.v3.RateLimitResponse.DescriptorStatus`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DescriptorStatus {
+    /// Whether this descriptor is within its limit
+    #[prost(enumeration = "RateLimitCode", tag = "1")]
+    pub code: i32,
+    /// The limit applied; unset for unlimited descriptors
+    #[prost(message, optional, tag = "2")]
+    pub current_limit: Option<CurrentLimit>,
+    /// Whole requests left in the burst
+    #[prost(uint32, tag = "3")]
+    pub limit_remaining: u32,
+    /// Time until a request would be allowed; set when over the limit
+    #[prost(message, optional, tag = "4")]
+    pub duration_until_reset: Option<prost_types::Duration>,
+}
+
+/// `envoy.service.ratelimit.v3.RateLimitResponse.RateLimit`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CurrentLimit {
+    /// Requests allowed per `unit`, rounded to the nearest whole request
+    #[prost(uint32, tag = "1")]
+    pub requests_per_unit: u32,
+    /// The unit `requests_per_unit` is counted in
+    #[prost(enumeration = "RateLimitUnit", tag = "2")]
+    pub unit: i32,
+}
+
+/// `envoy.service.ratelimit.v3.RateLimitResponse.RateLimit.Unit`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum RateLimitUnit {
+    /// Not set
+    Unknown = 0,
+    /// Per second
+    Second = 1,
+    /// Per minute
+    Minute = 2,
+    /// Per hour
+    Hour = 3,
+    /// Per day
+    Day = 4,
+}
+
+/// `envoy.config.core.v3.HeaderValue`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResponseHeader {
+    /// Header name
+    #[prost(string, tag = "1")]
+    pub key: String,
+    /// Header value
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+/// The Envoy rate limit service (`envoy.service.ratelimit.v3.RateLimitService`)
+/// over a [`PolicyRouter`], so Envoy and Istio can use this crate as their
+/// global rate limit service.
+///
+/// Every descriptor is turned into a composite key with
+/// [`descriptor_key`](Self::descriptor_key) — the domain, then each entry as
+/// `key=value`, joined by `|` — and checked against the router's policy for
+/// that key, so routes select limits by descriptor:
+/// `"edge|remote_address=*"` limits each client address, and
+/// `"edge|path=/login"` that path across all callers. Descriptors are checked
+/// independently, as in Envoy's reference service, and the request is over
+/// the limit if any of them is; `hits_addend` counts as that many requests,
+/// all allowed or none.
+///
+/// Limited responses carry a `retry-after` header for Envoy to pass on.
+/// Limit overrides sent in descriptors are ignored. Calls without a domain
+/// or descriptors fail with `INVALID_ARGUMENT`, and limiter errors with
+/// `UNAVAILABLE`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use flux_limiter::{EnvoyRateLimitService, FluxLimiterConfig, Policy, PolicyRouter, SystemClock};
+/// use std::sync::Arc;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let router = PolicyRouter::with_routes(
+///     [("edge|remote_address=*", Policy::Limit(FluxLimiterConfig::new(10.0, 20.0)))],
+///     SystemClock,
+/// )?;
+///
+/// tonic::transport::Server::builder()
+///     .add_service(EnvoyRateLimitService::new(Arc::new(router)))
+///     .serve("0.0.0.0:8081".parse()?)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct EnvoyRateLimitService<C = SystemClock>
+where
+    C: Clock + Clone,
+{
+    router: Arc<PolicyRouter<C>>,
+}
+
+impl<C> EnvoyRateLimitService<C>
+where
+    C: Clock + Clone + 'static,
+{
+    /// Serve the policies of `router`
+    pub fn new(router: Arc<PolicyRouter<C>>) -> Self {
+        Self { router }
+    }
+
+    /// The router descriptors are checked against
+    pub fn router(&self) -> &Arc<PolicyRouter<C>> {
+        &self.router
+    }
+
+    /// The key a descriptor in `domain` is checked under, e.g.
+    /// `edge|remote_address=10.0.0.1|path=/login`.
+    ///
+    /// Keys and values are not escaped, so route patterns can be written by
+    /// hand; avoid `|` and `=` in descriptor keys.
+    pub fn descriptor_key(domain: &str, descriptor: &RateLimitDescriptor) -> String {
+        let mut key = domain.to_string();
+        for entry in &descriptor.entries {
+            key.push('|');
+            key.push_str(&entry.key);
+            key.push('=');
+            key.push_str(&entry.value);
+        }
+        key
+    }
+
+    // run `ShouldRateLimit`
+    fn should_rate_limit(&self, request: RateLimitRequest) -> Result<RateLimitResponse, Status> {
+        if request.domain.is_empty() || request.descriptors.is_empty() {
+            return Err(Status::invalid_argument(
+                "domain and descriptors must not be empty",
+            ));
+        }
+
+        let mut response = RateLimitResponse {
+            overall_code: RateLimitCode::Ok as i32,
+            ..RateLimitResponse::default()
+        };
+        let mut retry_after_seconds: Option<f64> = None;
+        for descriptor in &request.descriptors {
+            let hits = descriptor
+                .hits_addend
+                .unwrap_or(u64::from(request.hits_addend))
+                .max(1);
+            let key = Self::descriptor_key(&request.domain, descriptor);
+            let decision = self.check(&key, hits).map_err(unavailable)?;
+
+            if !decision.allowed {
+                response.overall_code = RateLimitCode::OverLimit as i32;
+                // the caller has to wait for the slowest descriptor
+                retry_after_seconds = [retry_after_seconds, decision.retry_after_seconds]
+                    .into_iter()
+                    .flatten()
+                    .reduce(f64::max);
+            }
+            response.statuses.push(status(&decision));
+        }
+
+        if let Some(retry_after_seconds) = retry_after_seconds {
+            response.response_headers_to_add.push(ResponseHeader {
+                key: "retry-after".to_string(),
+                value: (retry_after_seconds.ceil() as u64).to_string(),
+            });
+        }
+        Ok(response)
+    }
+
+    // check `hits` requests for `key` at once
+    fn check(&self, key: &str, hits: u64) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let limiter = match self.router.limiter_for(key) {
+            Some(limiter) if hits > 1 => limiter,
+            // single hits and unlimited keys
+            _ => return self.router.check(key),
+        };
+        // more hits than the burst plus one can never conform, so stop there
+        let hits = hits.min(limiter.burst() as u64 + 2) as usize;
+        let keys = vec![key.to_string(); hits];
+        let decision = limiter.check_all(&keys)?;
+        let index = match decision.allowed {
+            true => decision.decisions.len() - 1,
+            false => decision
+                .decisions
+                .iter()
+                .position(|(_, decision)| !decision.allowed)
+                .unwrap_or(0),
+        };
+        Ok(decision.decisions[index].1)
+    }
+}
+
+impl<C> NamedService for EnvoyRateLimitService<C>
+where
+    C: Clock + Clone,
+{
+    const NAME: &'static str = "envoy.service.ratelimit.v3.RateLimitService";
+}
+
+impl<C, B> Service<http::Request<B>> for EnvoyRateLimitService<C>
+where
+    C: Clock + Clone + 'static,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let service = self.clone();
+        match request.uri().path() {
+            "/envoy.service.ratelimit.v3.RateLimitService/ShouldRateLimit" => {
+                unary(request, move |message| service.should_rate_limit(message))
+            }
+            _ => Box::pin(async { Ok(Status::unimplemented("unknown method").into_http()) }),
+        }
+    }
+}
+
+// a descriptor's status from its decision
+fn status(decision: &FluxLimiterDecision) -> DescriptorStatus {
+    let code = match decision.allowed {
+        true => RateLimitCode::Ok,
+        false => RateLimitCode::OverLimit,
+    };
+    DescriptorStatus {
+        code: code as i32,
+        current_limit: current_limit(decision.limit_per_second),
+        limit_remaining: decision
+            .remaining_capacity
+            .map_or(0, |remaining| remaining.floor() as u32),
+        duration_until_reset: decision
+            .retry_after_seconds
+            .filter(|_| !decision.allowed)
+            .map(|seconds| prost_types::Duration {
+                seconds: seconds.trunc() as i64,
+                nanos: (seconds.fract() * 1e9) as i32,
+            }),
+    }
+}
+
+// express a per-second rate in the smallest unit that holds a whole request
+fn current_limit(rate_per_second: f64) -> Option<CurrentLimit> {
+    if !rate_per_second.is_finite() {
+        return None;
+    }
+    let (unit, seconds) = [
+        (RateLimitUnit::Second, 1.0),
+        (RateLimitUnit::Minute, 60.0),
+        (RateLimitUnit::Hour, 3_600.0),
+        (RateLimitUnit::Day, 86_400.0),
+    ]
+    .into_iter()
+    .find(|(_, seconds)| rate_per_second * seconds >= 1.0)
+    .unwrap_or((RateLimitUnit::Day, 86_400.0));
+
+    Some(CurrentLimit {
+        requests_per_unit: (rate_per_second * seconds).round().max(1.0) as u32,
+        unit: unit as i32,
+    })
+}
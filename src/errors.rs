@@ -3,18 +3,68 @@
 // error handling for the flux limiter type
 
 // dependencies
-use std::error::Error;
-use std::fmt;
+use alloc::string::String;
+use core::error::Error;
+use core::fmt;
+use core::time::Duration;
 
 use crate::clock::ClockError;
+use crate::decision::DeniedMeta;
+#[cfg(feature = "std")]
+use crate::store::StoreError;
 
 /// Error type for FluxLimiter configuration issues.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum FluxLimiterError {
-    InvalidRate,            // for rate <= 0
-    InvalidBurst,           // for burst < 0
-    ClockError(ClockError), // error variant for issues with the system clock
+    InvalidRate,                  // for rate <= 0
+    InvalidBurst,                 // for burst < 0
+    NonFiniteRate,                // for rate that is NaN or infinite
+    NonFiniteBurst,               // for burst that is NaN or infinite
+    RateTooHigh,                  // for rate whose emission interval rounds to 0ns
+    InvalidCost,                  // for request cost that is negative or not finite
+    ClockError(ClockError),       // error variant for issues with the system clock
+    MissingEnvVar(String),        // required environment variable is not set
+    UnsupportedStateVersion(u32), // for state snapshots in an unknown format
+    // for failures reading or writing a state file
+    #[cfg(feature = "std")]
+    PersistenceError(std::io::Error),
+    // for failures in an external state store
+    #[cfg(feature = "std")]
+    StoreError(StoreError),
+    // for policy files that cannot be read, parsed or watched
+    #[cfg(feature = "hot-reload")]
+    PolicyFileError(String),
+    // for peer-sync transports that fail to send or receive
+    #[cfg(feature = "gossip")]
+    GossipError(std::io::Error),
+    UnknownLimiter(String),       // for registry lookups of unregistered names
+    InvalidTimeWindow,            // for schedule times outside 00:00-24:00 or empty windows
+    InvalidAdaptiveConfig,        // for AIMD bounds, step or factor out of range
+    InvalidDebtCeiling,           // for debt ceiling that is negative or not finite
+    InvalidPriorityReserve,       // for priority reserve outside 0.0..=1.0
+    InvalidRetryJitter,           // for retry jitter that is negative or not finite
+    InvalidShardAmount(usize),    // for shard counts that are not a power of two above 1
+    InvalidSimulation,            // for simulation durations or arrival patterns out of range
+    InvalidObservedRateWindow,    // for observed rate windows that are not positive and finite
+    InvalidExpiryResolution,      // for expiry wheel resolutions below 1ns or not finite
+    InvalidEntryTtl,              // for entry TTLs that are not positive and finite
+    InvalidReplenishmentStep,     // for stepwise replenishment steps below 1ns or not finite
+    InvalidIpPrefix(String),      // for unparseable IP prefixes or ones longer than the address
+    InvalidPeakRate,              // for two-rate peak rates below the sustained rate
+    UnknownEndpoint(String),      // for matrix endpoints with no group and no fallback
+    UnknownOperation(String),     // for operation classes missing from the cost table
+    InvalidOperationCost(String), // for operation costs that are not finite and positive or too small to charge
+    // new client refused because `max_clients` clients are already tracked
+    CapacityExceeded {
+        current: usize,
+        max: usize,
+    },
+    // environment variable could not be parsed
+    InvalidEnvVar {
+        name: String,
+        value: String,
+    },
 }
 
 // implement the Display trait for the FluxLimiterError type
@@ -23,12 +73,273 @@ impl fmt::Display for FluxLimiterError {
         match self {
             FluxLimiterError::InvalidRate => write!(f, "Rate must be positive"),
             FluxLimiterError::InvalidBurst => write!(f, "Burst must be non-negative"),
+            FluxLimiterError::NonFiniteRate => write!(f, "Rate must be a finite number"),
+            FluxLimiterError::NonFiniteBurst => write!(f, "Burst must be a finite number"),
+            FluxLimiterError::RateTooHigh => {
+                write!(f, "Rate must not exceed one request per nanosecond")
+            }
+            FluxLimiterError::InvalidCost => {
+                write!(f, "Cost must be a finite, non-negative number")
+            }
             FluxLimiterError::ClockError(_) => {
                 write!(f, "Clock error occurred")
             }
+            FluxLimiterError::MissingEnvVar(name) => {
+                write!(f, "Environment variable {} is not set", name)
+            }
+            FluxLimiterError::UnsupportedStateVersion(version) => {
+                write!(f, "Unsupported state snapshot version {}", version)
+            }
+            #[cfg(feature = "std")]
+            FluxLimiterError::PersistenceError(err) => {
+                write!(f, "Failed to persist limiter state: {}", err)
+            }
+            #[cfg(feature = "std")]
+            FluxLimiterError::StoreError(err) => write!(f, "{}", err),
+            #[cfg(feature = "hot-reload")]
+            FluxLimiterError::PolicyFileError(message) => {
+                write!(f, "Failed to load policy file: {}", message)
+            }
+            #[cfg(feature = "gossip")]
+            FluxLimiterError::GossipError(err) => {
+                write!(f, "Failed to exchange consumption with peers: {}", err)
+            }
+            FluxLimiterError::UnknownLimiter(name) => {
+                write!(f, "No limiter registered under {:?}", name)
+            }
+            FluxLimiterError::InvalidTimeWindow => {
+                write!(
+                    f,
+                    "Time window must be a non-empty range within 00:00-24:00"
+                )
+            }
+            FluxLimiterError::InvalidDebtCeiling => {
+                write!(f, "Debt ceiling must be a finite, non-negative number")
+            }
+            FluxLimiterError::InvalidPriorityReserve => {
+                write!(f, "Priority reserve must be between 0 and 1")
+            }
+            FluxLimiterError::InvalidRetryJitter => {
+                write!(f, "Retry jitter must be a finite, non-negative number")
+            }
+            FluxLimiterError::InvalidObservedRateWindow => {
+                write!(f, "Observed rate window must be a positive, finite number")
+            }
+            FluxLimiterError::InvalidExpiryResolution => {
+                write!(
+                    f,
+                    "Expiry wheel resolution must be a positive, finite number of at least 1ns"
+                )
+            }
+            FluxLimiterError::InvalidEntryTtl => {
+                write!(f, "Entry TTL must be a positive, finite number")
+            }
+            FluxLimiterError::InvalidReplenishmentStep => {
+                write!(
+                    f,
+                    "Replenishment step must be a positive, finite number of at least 1ns"
+                )
+            }
+            FluxLimiterError::InvalidIpPrefix(prefix) => {
+                write!(f, "Invalid IP prefix {}", prefix)
+            }
+            FluxLimiterError::InvalidPeakRate => {
+                write!(f, "Peak rate must be at least the sustained rate")
+            }
+            FluxLimiterError::UnknownEndpoint(endpoint) => {
+                write!(f, "No endpoint group or fallback for {:?}", endpoint)
+            }
+            FluxLimiterError::UnknownOperation(operation) => {
+                write!(f, "No cost configured for operation {:?}", operation)
+            }
+            FluxLimiterError::InvalidOperationCost(operation) => {
+                write!(
+                    f,
+                    "Cost of operation {:?} must be finite, positive and at least the minimum interval",
+                    operation
+                )
+            }
+            FluxLimiterError::InvalidSimulation => {
+                write!(
+                    f,
+                    "Simulation duration and arrival pattern must be positive and finite"
+                )
+            }
+            FluxLimiterError::InvalidShardAmount(shards) => {
+                write!(
+                    f,
+                    "Shard count must be a power of two above 1, got {}",
+                    shards
+                )
+            }
+            FluxLimiterError::InvalidAdaptiveConfig => {
+                write!(
+                    f,
+                    "Adaptive config needs min <= max, a non-negative increase and a decrease factor in (0, 1)"
+                )
+            }
+            FluxLimiterError::CapacityExceeded { current, max } => {
+                write!(
+                    f,
+                    "Client capacity exceeded: {} clients tracked, at most {} allowed",
+                    current, max
+                )
+            }
+            FluxLimiterError::InvalidEnvVar { name, value } => {
+                write!(
+                    f,
+                    "Environment variable {} has invalid value {:?}",
+                    name, value
+                )
+            }
         }
     }
 }
 
 // implement the Error trait for the RateLimiter type
-impl Error for FluxLimiterError {}
+impl Error for FluxLimiterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FluxLimiterError::ClockError(err) => Some(err),
+            #[cfg(feature = "std")]
+            FluxLimiterError::PersistenceError(err) => Some(err),
+            #[cfg(feature = "std")]
+            FluxLimiterError::StoreError(err) => Some(err),
+            #[cfg(feature = "gossip")]
+            FluxLimiterError::GossipError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by [`FluxLimiter::try_check`](crate::FluxLimiter::try_check).
+///
+/// Denials are errors here so handlers can return early with `?`; the
+/// denial metadata carries what a `429` response needs.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, RateLimitedError, SystemClock};
+///
+/// fn handle(limiter: &FluxLimiter<String>, user: String) -> Result<String, RateLimitedError> {
+///     let meta = limiter.try_check(user)?;
+///     Ok(format!("{} requests left", meta.remaining_capacity))
+/// }
+///
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+/// assert!(handle(&limiter, "user_123".to_string()).is_ok());
+///
+/// let err = handle(&limiter, "user_123".to_string()).unwrap_err();
+/// assert!(err.retry_after_seconds().unwrap() > 0.0);
+/// ```
+#[derive(Debug)]
+pub enum RateLimitedError {
+    /// The request exceeded the limit
+    Denied(DeniedMeta),
+    /// The check itself failed
+    Failed(FluxLimiterError),
+}
+
+impl RateLimitedError {
+    /// Seconds until the client may retry, if the request was denied
+    pub fn retry_after_seconds(&self) -> Option<f64> {
+        match self {
+            RateLimitedError::Denied(meta) => Some(meta.retry_after_seconds),
+            RateLimitedError::Failed(_) => None,
+        }
+    }
+
+    /// How long the client should wait before retrying, if the request was denied
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RateLimitedError::Denied(meta) => Some(meta.retry_after()),
+            RateLimitedError::Failed(_) => None,
+        }
+    }
+
+    /// When the rate limit window resets (nanoseconds since epoch), if the request was denied
+    pub fn reset_time_nanos(&self) -> Option<u64> {
+        match self {
+            RateLimitedError::Denied(meta) => Some(meta.reset_time_nanos),
+            RateLimitedError::Failed(_) => None,
+        }
+    }
+}
+
+impl From<FluxLimiterError> for RateLimitedError {
+    fn from(err: FluxLimiterError) -> Self {
+        RateLimitedError::Failed(err)
+    }
+}
+
+impl fmt::Display for RateLimitedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateLimitedError::Denied(meta) => {
+                write!(
+                    f,
+                    "Rate limited; retry after {:.3}s",
+                    meta.retry_after_seconds
+                )
+            }
+            RateLimitedError::Failed(err) => write!(f, "Rate limit check failed: {}", err),
+        }
+    }
+}
+
+impl Error for RateLimitedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RateLimitedError::Denied(_) => None,
+            RateLimitedError::Failed(err) => Some(err),
+        }
+    }
+}
+
+/// Error returned by the waiting acquires, such as
+/// [`FluxLimiter::acquire_timeout`](crate::FluxLimiter::acquire_timeout).
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub enum AcquireError {
+    /// The request would not conform before the deadline; holds the latest denial
+    Elapsed(DeniedMeta),
+    /// The wait was cancelled before the request was admitted
+    Cancelled,
+    /// The check itself failed
+    Failed(FluxLimiterError),
+}
+
+#[cfg(feature = "tokio")]
+impl From<FluxLimiterError> for AcquireError {
+    fn from(err: FluxLimiterError) -> Self {
+        AcquireError::Failed(err)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AcquireError::Elapsed(meta) => {
+                write!(
+                    f,
+                    "Timed out waiting for capacity; retry after {:.3}s",
+                    meta.retry_after_seconds
+                )
+            }
+            AcquireError::Cancelled => write!(f, "Cancelled while waiting for capacity"),
+            AcquireError::Failed(err) => write!(f, "Rate limit check failed: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Error for AcquireError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AcquireError::Elapsed(_) | AcquireError::Cancelled => None,
+            AcquireError::Failed(err) => Some(err),
+        }
+    }
+}
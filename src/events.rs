@@ -0,0 +1,121 @@
+// src/events.rs
+
+//! Decision events published to subscribers
+
+// dependencies
+use crate::decision::FluxLimiterDecision;
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+
+/// Events each subscriber can fall behind by before further events are dropped
+pub(crate) const SUBSCRIBER_BUFFER: usize = 1024;
+
+/// One decision, as seen by subscribers of
+/// [`FluxLimiter::subscribe`](crate::FluxLimiter::subscribe).
+#[derive(Debug, Clone)]
+pub struct DecisionEvent<T> {
+    /// The client the decision was made for
+    pub client_id: T,
+    /// The decision returned to the caller
+    pub decision: FluxLimiterDecision,
+    /// Limiter time of the check (nanoseconds since epoch); 0 for decisions
+    /// made by the failure policy without a clock reading
+    pub timestamp_nanos: u64,
+}
+
+// where events are delivered
+enum Subscriber<T> {
+    Std(SyncSender<DecisionEvent<T>>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::sync::mpsc::Sender<DecisionEvent<T>>),
+}
+
+impl<T> Subscriber<T> {
+    // hand over an event without blocking; false once the receiver is gone
+    fn try_send(&self, event: DecisionEvent<T>) -> bool {
+        match self {
+            Subscriber::Std(sender) => {
+                !matches!(sender.try_send(event), Err(TrySendError::Disconnected(_)))
+            }
+            #[cfg(feature = "tokio")]
+            Subscriber::Tokio(sender) => !matches!(
+                sender.try_send(event),
+                Err(tokio::sync::mpsc::error::TrySendError::Closed(_))
+            ),
+        }
+    }
+}
+
+// subscribers shared by every clone of a limiter
+pub(crate) struct Subscribers<T> {
+    // lets checks skip the lock while nobody listens
+    active: AtomicBool,
+    senders: Mutex<Vec<Subscriber<T>>>,
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            active: AtomicBool::new(false),
+            senders: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Clone> Subscribers<T> {
+    pub(crate) fn subscribe(&self) -> Receiver<DecisionEvent<T>> {
+        let (sender, receiver) = mpsc::sync_channel(SUBSCRIBER_BUFFER);
+        self.add(Subscriber::Std(sender));
+        receiver
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn subscribe_async(&self) -> tokio::sync::mpsc::Receiver<DecisionEvent<T>> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(SUBSCRIBER_BUFFER);
+        self.add(Subscriber::Tokio(sender));
+        receiver
+    }
+
+    fn add(&self, subscriber: Subscriber<T>) {
+        let mut senders = self.senders.lock().unwrap_or_else(|err| err.into_inner());
+        senders.push(subscriber);
+        self.active.store(true, Ordering::Release);
+    }
+
+    // whether any subscriber may be listening
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    // deliver an event to every subscriber, dropping it for those whose buffer
+    // is full and forgetting those whose receiver was dropped
+    pub(crate) fn publish(
+        &self,
+        client_id: T,
+        decision: &FluxLimiterDecision,
+        timestamp_nanos: u64,
+    ) {
+        let mut senders = self.senders.lock().unwrap_or_else(|err| err.into_inner());
+        senders.retain(|subscriber| {
+            subscriber.try_send(DecisionEvent {
+                client_id: client_id.clone(),
+                decision: *decision,
+                timestamp_nanos,
+            })
+        });
+        if senders.is_empty() {
+            self.active.store(false, Ordering::Release);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Subscribers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subscribers = self.senders.lock().map_or(0, |senders| senders.len());
+        f.debug_struct("Subscribers")
+            .field("subscribers", &subscribers)
+            .finish()
+    }
+}
@@ -0,0 +1,143 @@
+// src/ffi.rs
+
+//! C ABI over a limiter, for embedding in non-Rust processes (`ffi` feature)
+
+// dependencies
+use crate::clock::SystemClock;
+use crate::config::FluxLimiterConfig;
+use crate::flux_limiter::FluxLimiter;
+use std::ffi::{CStr, c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+
+/// The call succeeded
+pub const FLUX_LIMITER_OK: c_int = 0;
+/// A pointer argument was null
+pub const FLUX_LIMITER_ERR_NULL: c_int = 1;
+/// The limiter failed, e.g. because the system clock could not be read or
+/// the check panicked
+pub const FLUX_LIMITER_ERR_LIMITER: c_int = 2;
+
+/// The limiter behind a `flux_limiter *`: client keys are the bytes of the
+/// C strings passed to [`flux_limiter_check`]
+pub type FfiLimiter = FluxLimiter<Vec<u8>, SystemClock>;
+
+/// A decision as written by [`flux_limiter_check`], `flux_limiter_decision`
+/// in C
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FfiDecision {
+    /// Whether the request is allowed
+    pub allowed: bool,
+    /// Seconds to wait before retrying; 0 for allowed requests
+    pub retry_after_seconds: f64,
+    /// Requests still available in the burst
+    pub remaining_capacity: f64,
+    /// When the client's capacity is fully restored (nanoseconds since epoch)
+    pub reset_time_nanos: u64,
+}
+
+/// Create a limiter allowing `rate_per_second` requests with
+/// `burst_capacity` extra, using the system clock.
+///
+/// Returns null if the rate or burst is invalid, or if creating the limiter
+/// panicked. Free the limiter with [`flux_limiter_free`]. It may be shared
+/// between threads.
+#[unsafe(no_mangle)]
+pub extern "C" fn flux_limiter_new(rate_per_second: f64, burst_capacity: f64) -> *mut FfiLimiter {
+    guard(std::ptr::null_mut(), || {
+        match FluxLimiter::with_config(
+            FluxLimiterConfig::new(rate_per_second, burst_capacity),
+            SystemClock,
+        ) {
+            Ok(limiter) => Box::into_raw(Box::new(limiter)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Check a request for `client_id` and write the decision to `decision`.
+///
+/// Returns [`FLUX_LIMITER_OK`] on success, [`FLUX_LIMITER_ERR_NULL`] if any
+/// argument is null, and [`FLUX_LIMITER_ERR_LIMITER`] if the limiter fails
+/// or panics; `decision` is only written on success.
+///
+/// # Safety
+///
+/// `limiter` must be null or returned by [`flux_limiter_new`] and not yet
+/// freed, `client_id` null or a NUL-terminated string, and `decision` null
+/// or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn flux_limiter_check(
+    limiter: *const FfiLimiter,
+    client_id: *const c_char,
+    decision: *mut FfiDecision,
+) -> c_int {
+    if limiter.is_null() || client_id.is_null() || decision.is_null() {
+        return FLUX_LIMITER_ERR_NULL;
+    }
+    // SAFETY: the pointers are non-null and valid per the contract above
+    let (limiter, client_id) = unsafe { (&*limiter, CStr::from_ptr(client_id)) };
+    let checked = guard(None, || {
+        limiter.check_request_ref(client_id.to_bytes()).ok()
+    });
+    match checked {
+        Some(checked) => {
+            let out = FfiDecision {
+                allowed: checked.allowed,
+                retry_after_seconds: checked.retry_after_seconds.unwrap_or(0.0),
+                remaining_capacity: checked.remaining_capacity.unwrap_or(0.0),
+                reset_time_nanos: checked.reset_time_nanos,
+            };
+            // SAFETY: `decision` is non-null and valid for writes
+            unsafe { decision.write(out) };
+            FLUX_LIMITER_OK
+        }
+        None => FLUX_LIMITER_ERR_LIMITER,
+    }
+}
+
+/// Drop clients that have been fully recovered for at least
+/// `max_stale_nanos`, returning how many were removed, or -1 if `limiter`
+/// is null or the limiter fails or panics.
+///
+/// # Safety
+///
+/// `limiter` must be null or returned by [`flux_limiter_new`] and not yet
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn flux_limiter_cleanup(
+    limiter: *const FfiLimiter,
+    max_stale_nanos: u64,
+) -> i64 {
+    if limiter.is_null() {
+        return -1;
+    }
+    // SAFETY: `limiter` is non-null and valid per the contract above
+    let limiter = unsafe { &*limiter };
+    guard(-1, || {
+        limiter
+            .cleanup_stale_clients(max_stale_nanos)
+            .map_or(-1, |report| report.removed as i64)
+    })
+}
+
+/// Free a limiter returned by [`flux_limiter_new`]; null is ignored.
+///
+/// # Safety
+///
+/// `limiter` must be null or returned by [`flux_limiter_new`], not already
+/// freed, and not in use by another thread.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn flux_limiter_free(limiter: *mut FfiLimiter) {
+    if !limiter.is_null() {
+        // SAFETY: the pointer came from `Box::into_raw` in `flux_limiter_new`
+        drop(unsafe { Box::from_raw(limiter) });
+    }
+}
+
+// run `body`, returning `on_panic` instead of unwinding into the C caller,
+// which would abort the host process. The limiter's state is atomics, so a
+// check that panicked part-way leaves it usable
+fn guard<R>(on_panic: R, body: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(on_panic)
+}
@@ -3,156 +3,2548 @@
 // flux-limiter: A rate limiter based on the Generic Cell Rate Algorithm (GCRA).
 
 // dependencies
-use crate::clock::{Clock, SystemClock};
-use crate::config::FluxLimiterConfig;
-use crate::errors::FluxLimiterError;
+use crate::audit::{AuditAction, AuditHook, AuditSink, Operator};
+use crate::classify::{Classifier, ClassifierHook};
+use crate::clock::{Clock, ClockError, SystemClock, Timestamp};
+use crate::config::{DrainMode, FailurePolicy, FluxLimiterConfig, Priority, Replenishment};
+use crate::decision::{
+    AllowedMeta, Decision, DecisionReason, DecisionTrace, FluxLimiterDecision, MultiKeyDecision,
+};
+use crate::entry::ClientEntry;
+use crate::errors::{FluxLimiterError, RateLimitedError};
+use crate::events::{DecisionEvent, Subscribers};
+use crate::gcra::{self, Precision};
+use crate::jitter::{JitterHook, JitterStrategy};
+#[cfg(feature = "otel")]
+use crate::otel::OtelHook;
+use crate::pressure::{PressureHook, PressureProvider};
+use crate::same_instant::SameInstant;
+use crate::snapshot::{ClientSnapshot, StateSnapshot};
+#[cfg(feature = "per-key-stats")]
+use crate::stats::ClientStats;
+use crate::stats::{CleanupReport, FluxLimiterStats, StatsCounters};
+use crate::store::{AsyncStateStore, StateStore};
+#[cfg(feature = "tokio")]
+use crate::wait_queue::WaitQueues;
+use crate::wheel::ExpiryWheel;
 use dashmap::DashMap;
-use std::hash::Hash;
-use std::sync::Arc;
+use dashmap::mapref::entry::Entry;
+use dashmap::mapref::one::Ref;
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
 
 /// The main FluxLimiter model.
 /// T is the type used to identify clients (e.g., String, u64, etc.).
 /// C is the clock type, defaulting to SystemClock.
+/// H is the hasher for the client map, defaulting to the standard library's RandomState.
 /// We use `Arc<DashMap>` for thread-safe concurrent access to client state.
-#[derive(Debug)]
-pub struct FluxLimiter<T, C = SystemClock>
+///
+/// Cloning is cheap and yields another handle to the same limiter: clones
+/// share client state, statistics, decision subscribers, the current rate
+/// (including AIMD adjustments) and drain mode, so hand clones to tasks
+/// instead of wrapping the limiter in another `Arc`. The clock is cloned, so
+/// clones see the same time as long as the clock's clones do (as with
+/// [`SystemClock`]). Hooks attached afterwards with `with_pressure_provider`,
+/// `with_jitter_strategy`, `with_classifier`, `with_otel_meter`,
+/// `with_audit_sink` or `with_fifo_waiters` only apply to the handle they
+/// were attached to.
+#[derive(Debug, Clone)]
+pub struct FluxLimiter<T, C = SystemClock, H = RandomState>
 where
     T: Hash + Eq + Clone,
     C: Clock,
+    H: BuildHasher + Clone,
 {
-    rate_nanos: u64,
-    tolerance_nanos: u64,
-    pub client_state: Arc<DashMap<T, u64>>,
+    intervals: Arc<Intervals>,
+    config: Arc<FluxLimiterConfig>,
+    burst_capacity: f64,
+    debt_ceiling: f64,
+    priority_reserve: f64,
+    pacing_hints: bool,
+    retry_jitter_seconds: f64,
+    jitter: JitterHook,
+    pub client_state: Arc<DashMap<T, ClientEntry, H>>,
     clock: C,
+    stats: Option<Arc<StatsCounters>>,
+    subscribers: Arc<Subscribers<T>>,
+    failure_policy: FailurePolicy,
+    draining: Arc<AtomicBool>,
+    drain_mode: DrainMode,
+    replenishment: Replenishment,
+    pressure: Option<PressureHook>,
+    classifier: Option<ClassifierHook<T>>,
+    audit: Option<AuditHook<T>>,
+    #[cfg(feature = "otel")]
+    otel: Option<OtelHook>,
+    cleanup_cursor: Arc<Mutex<CleanupCursor>>,
+    expiry: Option<Arc<ExpiryWheel<T>>>,
+    entry_ttl_nanos: Option<u64>,
+    max_clients: Option<usize>,
+    precision: Precision,
+    #[cfg(feature = "per-key-stats")]
+    observed_rate_window_nanos: f64,
+    #[cfg(feature = "tokio")]
+    wait_queues: Option<Arc<WaitQueues<T>>>,
 }
 
-// methods for the RateLimiter type
+// where the next bounded cleanup pass resumes: the number of entries, in map
+// iteration order, already examined and kept in the current cycle
+#[derive(Debug, Default)]
+struct CleanupCursor {
+    position: usize,
+}
+
+// emission interval and allowances, shared by every clone and updated in place on rate changes
+#[derive(Debug)]
+struct Intervals {
+    // the requested rate as `f64` bits, before rounding to nanoseconds
+    rate_bits: AtomicU64,
+    rate_nanos: AtomicU64,
+    tolerance_nanos: AtomicU64,
+    debt_nanos: AtomicU64,
+}
+
+// constructors for limiters using the default hasher
 impl<T, C> FluxLimiter<T, C>
 where
     T: Hash + Eq + Clone,
     C: Clock,
 {
-    // method to create a new flux limiter given a desired rate and burst value
-    fn new(rate_per_second: f64, burst_capacity: f64, clock: C) -> Result<Self, FluxLimiterError> {
+    // method to create a new flux limiter from a config object
+    pub fn with_config(config: FluxLimiterConfig, clock: C) -> Result<Self, FluxLimiterError> {
+        config.validate()?;
+        Self::new(config, clock, RandomState::new())
+    }
+
+    /// Create a limiter that resumes from a previously exported state.
+    ///
+    /// Restoring state after a restart keeps clients from all bursting at
+    /// once because their TATs were forgotten.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config.clone(), SystemClock).unwrap();
+    /// limiter.check_request("client1".to_string()).unwrap();
+    ///
+    /// let snapshot = limiter.export_state();
+    /// let restored = FluxLimiter::with_state(config, SystemClock, snapshot).unwrap();
+    /// assert!(restored.client_state.contains_key("client1"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the usual configuration errors, or
+    /// [`FluxLimiterError::UnsupportedStateVersion`] if the snapshot was written
+    /// in a format this release does not understand.
+    pub fn with_state(
+        config: FluxLimiterConfig,
+        clock: C,
+        snapshot: StateSnapshot<T>,
+    ) -> Result<Self, FluxLimiterError> {
+        if snapshot.version != StateSnapshot::<T>::VERSION {
+            return Err(FluxLimiterError::UnsupportedStateVersion(snapshot.version));
+        }
+
+        let limiter = Self::with_config(config, clock)?;
+        // snapshots do not record access times, so restored clients count as checked at their TAT
+        for (client_id, tat_nanos) in snapshot.clients {
+            limiter
+                .client_state
+                .insert(client_id, ClientEntry::new(tat_nanos, tat_nanos));
+        }
+        if let Some(expiry) = &limiter.expiry {
+            // collected first, so the map is never read while the wheel is locked
+            let scheduled: Vec<_> = limiter
+                .client_state
+                .iter()
+                .map(|entry| (entry.key().clone(), limiter.expiry_deadline(&entry)))
+                .collect();
+            expiry.schedule_all(scheduled);
+        }
+
+        Ok(limiter)
+    }
+}
+
+// methods for the RateLimiter type
+impl<T, C, H> FluxLimiter<T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    // method to create a new flux limiter from a validated config
+    fn new(config: FluxLimiterConfig, clock: C, hasher: H) -> Result<Self, FluxLimiterError> {
+        let config = Arc::new(config);
         // Convert to nanoseconds
-        let rate_nanos = (1_000_000_000.0 / rate_per_second) as u64;
-        let tolerance_nanos = (burst_capacity * rate_nanos as f64) as u64;
+        let precision = config.precision;
+        let rate_nanos = gcra::emission_interval_nanos(config.rate_per_second, precision)?;
+        let tolerance_nanos = gcra::allowance_nanos(config.burst_capacity, rate_nanos, precision);
+        let debt_nanos = gcra::allowance_nanos(config.debt_ceiling, rate_nanos, precision);
 
         Ok(Self {
-            rate_nanos,
-            tolerance_nanos,
-            client_state: Arc::new(DashMap::new()),
+            intervals: Arc::new(Intervals {
+                rate_bits: AtomicU64::new(config.rate_per_second.to_bits()),
+                rate_nanos: AtomicU64::new(rate_nanos),
+                tolerance_nanos: AtomicU64::new(tolerance_nanos),
+                debt_nanos: AtomicU64::new(debt_nanos),
+            }),
+            config: Arc::clone(&config),
+            burst_capacity: config.burst_capacity,
+            debt_ceiling: config.debt_ceiling,
+            priority_reserve: config.priority_reserve,
+            pacing_hints: config.pacing_hints,
+            retry_jitter_seconds: config.retry_jitter_seconds,
+            jitter: JitterHook::default(),
+            client_state: Arc::new(match config.shard_amount {
+                Some(shards) => DashMap::with_capacity_and_hasher_and_shard_amount(
+                    config.initial_capacity,
+                    hasher,
+                    shards,
+                ),
+                None => DashMap::with_capacity_and_hasher(config.initial_capacity, hasher),
+            }),
             clock,
+            stats: config
+                .stats_enabled
+                .then(|| Arc::new(StatsCounters::default())),
+            subscribers: Arc::default(),
+            failure_policy: config.failure_policy,
+            draining: Arc::default(),
+            drain_mode: config.drain_mode,
+            replenishment: config.replenishment,
+            pressure: None,
+            classifier: None,
+            audit: None,
+            #[cfg(feature = "otel")]
+            otel: None,
+            cleanup_cursor: Arc::default(),
+            expiry: config.expiry_resolution_seconds.map(|resolution| {
+                Arc::new(ExpiryWheel::new((resolution * 1_000_000_000.0) as u64))
+            }),
+            entry_ttl_nanos: config
+                .entry_ttl_seconds
+                .map(|ttl| (ttl * 1_000_000_000.0) as u64),
+            max_clients: config.max_clients,
+            precision,
+            #[cfg(feature = "per-key-stats")]
+            observed_rate_window_nanos: config.observed_rate_window_seconds * 1_000_000_000.0,
+            #[cfg(feature = "tokio")]
+            wait_queues: None,
         })
     }
 
-    // method to create a new flux limiter from a config object
-    pub fn with_config(config: FluxLimiterConfig, clock: C) -> Result<Self, FluxLimiterError> {
+    /// Create a limiter whose client map uses `hasher` instead of the
+    /// standard library's DoS-resistant default.
+    ///
+    /// A faster hasher such as `ahash` or `fxhash` can pay off under high key
+    /// cardinality. Only pick a non-randomized hasher when client IDs cannot
+    /// be chosen by an attacker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// let limiter = FluxLimiter::<u64, _, _>::with_config_and_hasher(
+    ///     FluxLimiterConfig::new(10.0, 5.0),
+    ///     SystemClock,
+    ///     BuildHasherDefault::<DefaultHasher>::default(),
+    /// )
+    /// .unwrap();
+    /// assert!(limiter.check_request(42).unwrap().allowed);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same configuration errors as [`FluxLimiter::with_config`].
+    pub fn with_config_and_hasher(
+        config: FluxLimiterConfig,
+        clock: C,
+        hasher: H,
+    ) -> Result<Self, FluxLimiterError> {
         config.validate()?;
-        Self::new(config.rate_per_second, config.burst_capacity, clock)
+        Self::new(config, clock, hasher)
+    }
+
+    /// Scale the effective rate down while `provider` reports pressure.
+    ///
+    /// Decisions made with less than the full rate have `shedding` set and
+    /// report the reduced rate in `limit_per_second`. See [`PressureProvider`].
+    pub fn with_pressure_provider(mut self, provider: impl PressureProvider + 'static) -> Self {
+        self.pressure = Some(PressureHook::new(provider));
+        self
+    }
+
+    /// Use `strategy` to pick the retry jitter configured with
+    /// [`FluxLimiterConfig::retry_jitter`].
+    ///
+    /// Has no effect while the configured jitter is zero. See [`JitterStrategy`].
+    pub fn with_jitter_strategy(mut self, strategy: impl JitterStrategy + 'static) -> Self {
+        self.jitter = JitterHook::new(strategy);
+        self
+    }
+
+    /// Classify each request with `classifier` before it is limited.
+    ///
+    /// [`Classification::Bypass`](crate::Classification::Bypass) requests
+    /// are allowed without touching client state, and `Strict(multiplier)`
+    /// ones are limited at a share of the rate, on top of any load
+    /// shedding. Applies to checks against the limiter's own client map;
+    /// checks against external state stores are not classified. See
+    /// [`Classifier`].
+    pub fn with_classifier(mut self, classifier: impl Classifier<T> + 'static) -> Self {
+        self.classifier = Some(ClassifierHook::new(classifier));
+        self
+    }
+
+    /// Record administrative actions — resets, penalties and rate changes —
+    /// in `sink`.
+    ///
+    /// Actions taken through an [`operator`](Self::operator) handle carry its
+    /// actor. See [`AuditSink`].
+    pub fn with_audit_sink(mut self, sink: impl AuditSink<T> + 'static) -> Self {
+        self.audit = Some(AuditHook::new(sink));
+        self
+    }
+
+    /// A handle whose administrative actions are audited as taken by `actor`,
+    /// e.g. an operator's user name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{AuditEvent, FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock)
+    ///     .unwrap()
+    ///     .with_audit_sink(|event: AuditEvent<String>| println!("{event:?}"));
+    ///
+    /// limiter.operator("alice").set_rate(20.0).unwrap();
+    /// assert_eq!(limiter.rate(), 20.0);
+    /// ```
+    pub fn operator<'a>(&'a self, actor: &'a str) -> Operator<'a, T, C, H> {
+        Operator {
+            limiter: self,
+            actor,
+        }
+    }
+
+    /// Export decisions through OpenTelemetry instruments created from `meter`.
+    ///
+    /// Every decision adds to the `flux_limiter.decisions` counter, with a
+    /// `rate_limit.allowed` attribute, and each denial's retry delay is
+    /// recorded in the `flux_limiter.retry_after` histogram, in seconds. The
+    /// span active when a check runs gets `rate_limit.allowed` and, for
+    /// denials, `rate_limit.retry_after` attributes. Decisions are exported
+    /// whether or not statistics are enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let meter = opentelemetry::global::meter("api");
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock)
+    ///     .unwrap()
+    ///     .with_otel_meter(&meter);
+    ///
+    /// assert!(limiter.check_request("user_123").unwrap().allowed);
+    /// ```
+    #[cfg(feature = "otel")]
+    pub fn with_otel_meter(mut self, meter: &opentelemetry::metrics::Meter) -> Self {
+        self.otel = Some(OtelHook::new(meter));
+        self
+    }
+
+    /// Grant capacity to waiting acquires in arrival order.
+    ///
+    /// Without a queue, tasks waiting in
+    /// [`acquire_timeout`](Self::acquire_timeout) for the same key each sleep
+    /// through their own retry delay and race to check when they wake, so a
+    /// task can lose every race and starve. With one, waiters line up per key
+    /// and only the one at the head checks; the others wait their turn, and
+    /// the next slot always goes to the longest-waiting task. Waiters that
+    /// time out or are cancelled leave the line without holding it up.
+    ///
+    /// Only waiting acquires queue: `check_request` and `acquire` still take
+    /// free capacity immediately, ahead of any waiters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::<String, _>::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock)
+    ///     .unwrap()
+    ///     .with_fifo_waiters();
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_fifo_waiters(mut self) -> Self {
+        self.wait_queues = Some(Arc::default());
+        self
+    }
+
+    // the per-key wait queues, if waiters are served in arrival order
+    #[cfg(feature = "tokio")]
+    pub(crate) fn wait_queues(&self) -> Option<&WaitQueues<T>> {
+        self.wait_queues.as_deref()
+    }
+
+    /// Receive a [`DecisionEvent`] for every check made from now on.
+    ///
+    /// Events are published by every clone of the limiter for checks against
+    /// its own client map, including decisions made by the failure policy;
+    /// checks against an external store are not published. Publishing never
+    /// blocks a check: a subscriber that falls more than 1024 events behind
+    /// misses events until it catches up, and dropping the receiver
+    /// unsubscribes it. While nobody is subscribed, checks only pay for an
+    /// atomic load.
+    ///
+    /// Events arrive in the order decisions were made, so a
+    /// [`check_requests`](Self::check_requests) batch is published in shard
+    /// order rather than input order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+    /// let events = limiter.subscribe();
+    ///
+    /// limiter.check_request("user_123").unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let denials: Vec<_> = events.try_iter().filter(|event| !event.decision.allowed).collect();
+    /// assert_eq!(denials.len(), 1);
+    /// assert_eq!(denials[0].client_id, "user_123");
+    /// ```
+    pub fn subscribe(&self) -> Receiver<DecisionEvent<T>> {
+        self.subscribers.subscribe()
+    }
+
+    /// Receive decision events on a tokio channel; see [`subscribe`](Self::subscribe).
+    #[cfg(feature = "tokio")]
+    pub fn subscribe_async(&self) -> tokio::sync::mpsc::Receiver<DecisionEvent<T>> {
+        self.subscribers.subscribe_async()
+    }
+
+    /// Export every client's TAT so it can be restored with [`FluxLimiter::with_state`].
+    ///
+    /// The export is not a consistent cut: checks running concurrently may or
+    /// may not be reflected.
+    pub fn export_state(&self) -> StateSnapshot<T> {
+        StateSnapshot::new(
+            self.client_state
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.tat_nanos()))
+                .collect(),
+        )
     }
 
     // accessor method to return the rate field (convert back to requests per second)
     pub fn rate(&self) -> f64 {
-        1_000_000_000.0 / self.rate_nanos as f64
+        1_000_000_000.0 / self.increment_nanos() as f64
     }
 
     // accessor method to return the burst field (convert back to burst capacity)
     pub fn burst(&self) -> f64 {
-        self.tolerance_nanos as f64 / self.rate_nanos as f64
+        self.tolerance_nanos() as f64 / self.increment_nanos() as f64
+    }
+
+    // change the sustained rate in place, keeping the configured burst and all client state
+    pub(crate) fn set_rate(&self, rate_per_second: f64) -> Result<(), FluxLimiterError> {
+        FluxLimiterConfig::new(rate_per_second, self.burst_capacity).validate()?;
+        let rate_nanos = gcra::emission_interval_nanos(rate_per_second, self.precision)?;
+        let tolerance_nanos =
+            gcra::allowance_nanos(self.burst_capacity, rate_nanos, self.precision);
+        let debt_nanos = gcra::allowance_nanos(self.debt_ceiling, rate_nanos, self.precision);
+
+        // checks racing with the update may briefly mix old and new values
+        self.intervals
+            .rate_bits
+            .store(rate_per_second.to_bits(), Ordering::Relaxed);
+        self.intervals
+            .rate_nanos
+            .store(rate_nanos, Ordering::Relaxed);
+        self.intervals
+            .tolerance_nanos
+            .store(tolerance_nanos, Ordering::Relaxed);
+        self.intervals
+            .debt_nanos
+            .store(debt_nanos, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Start or stop draining.
+    ///
+    /// While draining, the clients picked by the config's
+    /// [`DrainMode`] are denied with [`DecisionReason::Draining`] and
+    /// their state is left alone; by default that is clients the limiter
+    /// does not track yet, so an instance about to leave a rolling deploy
+    /// sheds new load while existing clients keep their schedules. Denials
+    /// suggest retrying after one emission interval. Clones share the
+    /// switch, so one call drains every handle.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{DecisionReason, FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock).unwrap();
+    /// limiter.check_request("existing").unwrap();
+    ///
+    /// limiter.set_drain(true);
+    /// assert!(limiter.check_request("existing").unwrap().allowed);
+    /// assert_eq!(limiter.check_request("new").unwrap().reason, DecisionReason::Draining);
+    ///
+    /// limiter.set_drain(false);
+    /// assert!(limiter.check_request("new").unwrap().allowed);
+    /// ```
+    pub fn set_drain(&self, draining: bool) {
+        self.draining.store(draining, Ordering::Relaxed);
+    }
+
+    /// Whether the limiter is draining; see [`set_drain`](Self::set_drain)
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    /// The config this limiter currently applies to every client.
+    ///
+    /// The config it was built with, with the sustained rate as it stands
+    /// after [`AdaptiveLimiter`](crate::AdaptiveLimiter) adjustments and
+    /// [`Operator::set_rate`] changes. These are the limits checks are made
+    /// against, so it suits debugging and describing the limit in a 429
+    /// body (it serializes with the `serde` feature). Load shedding is not
+    /// included: it scales the rate per decision, and each decision reports
+    /// the rate it applied as `limit_per_second`. For limits that depend on
+    /// the key or the time, see [`PolicyRouter::effective_config`] and
+    /// [`ScheduledLimiter::effective_config`].
+    ///
+    /// [`PolicyRouter::effective_config`]: crate::PolicyRouter::effective_config
+    /// [`ScheduledLimiter::effective_config`]: crate::ScheduledLimiter::effective_config
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::<String>::with_config(config.clone(), SystemClock).unwrap();
+    /// assert_eq!(limiter.effective_config(), config);
+    ///
+    /// limiter.operator("alice").set_rate(20.0).unwrap();
+    /// assert_eq!(limiter.effective_config().rate_per_second(), 20.0);
+    /// ```
+    pub fn effective_config(&self) -> FluxLimiterConfig {
+        let rate = f64::from_bits(self.intervals.rate_bits.load(Ordering::Relaxed));
+        FluxLimiterConfig::clone(&self.config).rate(rate)
+    }
+
+    /// The effective emission interval: nanoseconds between conforming
+    /// requests, after rounding the rate with the config's [`Precision`]
+    /// and applying any AIMD adjustment.
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(3.0, 2.0);
+    /// let limiter = FluxLimiter::<String>::with_config(config, SystemClock).unwrap();
+    /// assert_eq!(limiter.emission_interval_nanos(), 333_333_333);
+    /// assert_eq!(limiter.tolerance_nanos(), 666_666_666);
+    /// ```
+    pub fn emission_interval_nanos(&self) -> u64 {
+        self.increment_nanos()
+    }
+
+    /// The effective tolerance: how many nanoseconds ahead of its schedule a
+    /// client may run, i.e. the burst after rounding.
+    pub fn tolerance_nanos(&self) -> u64 {
+        self.intervals.tolerance_nanos.load(Ordering::Relaxed)
     }
 
     // internal method to get the increment in nanoseconds
-    #[allow(dead_code)]
     fn increment_nanos(&self) -> u64 {
-        self.rate_nanos
+        self.intervals.rate_nanos.load(Ordering::Relaxed)
     }
 
-    // Optional: internal method to get the tolerance in nanoseconds
-    #[allow(dead_code)]
-    fn tolerance_nanos(&self) -> u64 {
-        self.tolerance_nanos
+    // read the clock as nanoseconds since its epoch
+    fn now_nanos(&self) -> Result<u64, ClockError> {
+        self.clock.now().map(Timestamp::as_nanos)
     }
 
     // Optional: keep the old method names for backwards compatibility
-    #[allow(dead_code)]
     fn increment(&self) -> f64 {
-        self.rate_nanos as f64 / 1_000_000_000.0
+        self.increment_nanos() as f64 / 1_000_000_000.0
     }
 
     // Optional: internal method to get the tolerance in seconds
     #[allow(dead_code)]
     fn tolerance(&self) -> f64 {
-        self.tolerance_nanos as f64 / 1_000_000_000.0
+        self.tolerance_nanos() as f64 / 1_000_000_000.0
     }
 
     pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
-        let current_time_nanos = self.clock.now().map_err(FluxLimiterError::ClockError)?;
-        let previous_tat_nanos = self
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => return self.fail_for(|| client_id, FluxLimiterError::ClockError(err)),
+        };
+        self.check_request_at(client_id, current_time_nanos)
+    }
+
+    /// Check a request at a caller-supplied time instead of reading the clock.
+    ///
+    /// `timestamp_nanos` uses the same time base as the limiter's clock
+    /// (nanoseconds since the Unix epoch for [`SystemClock`]). This lets
+    /// log-replay tools and event-sourced pipelines evaluate limits against
+    /// historical event times. Events for a client should be supplied in
+    /// time order; an event older than the client's schedule is denied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let t0 = 1_700_000_000_000_000_000;
+    /// assert!(limiter.check_request_at("user_123", t0).unwrap().allowed);
+    /// assert!(!limiter.check_request_at("user_123", t0 + 500_000_000).unwrap().allowed);
+    /// assert!(limiter.check_request_at("user_123", t0 + 1_000_000_000).unwrap().allowed);
+    /// ```
+    pub fn check_request_at(
+        &self,
+        client_id: T,
+        timestamp_nanos: u64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.check_at(client_id, timestamp_nanos, Priority::High)
+    }
+
+    /// Check an operation of a named class, charging its configured cost.
+    ///
+    /// Costs come from
+    /// [`FluxLimiterConfig::operation_cost`](crate::FluxLimiterConfig::operation_cost)
+    /// and share the client's single entry, so a client issuing a mix of
+    /// cheap reads and expensive writes is held to one blended budget. An
+    /// operation is admitted while the client is within its burst and then
+    /// advances the schedule by `cost` emission intervals; the decision's
+    /// `limit_per_second` is the sustained rate for that class alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::UnknownOperation`] if `operation` has no
+    /// configured cost, before any state is touched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0)
+    ///     .operation_cost("read", 1.0)
+    ///     .operation_cost("write", 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let decision = limiter.check_request_op("user_123", "write").unwrap();
+    /// assert!(decision.allowed);
+    /// assert_eq!(decision.limit_per_second, 2.0);
+    /// assert!(limiter.check_request_op("user_123", "delete").is_err());
+    /// ```
+    pub fn check_request_op(
+        &self,
+        client_id: T,
+        operation: &str,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let Some(cost) = self.operation_cost(operation) else {
+            return Err(FluxLimiterError::UnknownOperation(operation.to_owned()));
+        };
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => return self.fail_for(|| client_id, FluxLimiterError::ClockError(err)),
+        };
+        self.check_costed_at(client_id, current_time_nanos, Priority::High, cost)
+    }
+
+    /// The configured cost of operations of class `operation`, if any
+    pub fn operation_cost(&self, operation: &str) -> Option<f64> {
+        self.config.operation_costs.get(operation).copied()
+    }
+
+    /// Check a batch of requests in one call.
+    ///
+    /// The clock is read once for the whole batch and keys are evaluated
+    /// grouped by their DashMap shard, so checking several limit dimensions
+    /// for one incoming request touches each shard lock in a single run.
+    /// Results are returned in input order; a key that appears more than once
+    /// consumes capacity once per occurrence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let decisions = limiter.check_requests(["user:42", "ip:10.0.0.1"]).unwrap();
+    /// assert!(decisions.iter().all(|(_, decision)| decision.allowed));
+    /// ```
+    pub fn check_requests(
+        &self,
+        client_ids: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<(T, FluxLimiterDecision)>, FluxLimiterError> {
+        // an empty batch has no decisions to make, even if the clock would fail
+        let client_ids: Vec<T> = client_ids.into_iter().collect();
+        if client_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => {
+                let decision = self.fail(FluxLimiterError::ClockError(err))?;
+                return Ok(client_ids
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, client_id)| {
+                        // fail() counted the first key's decision
+                        if index > 0 {
+                            self.record_decision(decision.allowed, decision.retry_after_seconds);
+                        }
+                        self.publish(|| client_id.clone(), &decision, 0);
+                        (client_id, decision)
+                    })
+                    .collect());
+            }
+        };
+
+        // order by shard, keeping input order within a shard
+        let mut batch: Vec<(usize, usize, T)> = client_ids
+            .into_iter()
+            .enumerate()
+            .map(|(index, client_id)| {
+                (
+                    self.client_state.determine_map(&client_id),
+                    index,
+                    client_id,
+                )
+            })
+            .collect();
+        batch.sort_unstable_by_key(|&(shard, index, _)| (shard, index));
+
+        let mut results: Vec<Option<(T, FluxLimiterDecision)>> =
+            std::iter::repeat_with(|| None).take(batch.len()).collect();
+        for (_, index, client_id) in batch {
+            let decision = self.check_at(client_id.clone(), current_time_nanos, Priority::High)?;
+            results[index] = Some((client_id, decision));
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Check several requests at a single clock reading, in input order.
+    ///
+    /// Meant for the handful of limit dimensions one incoming request is
+    /// checked against, such as the user, the IP and the route: the clock is
+    /// read once instead of once per key, and keys are checked in turn
+    /// without the shard grouping of [`check_requests`](Self::check_requests).
+    /// Every key consumes capacity independently; use
+    /// [`check_all`](Self::check_all) to consume it only if all conform. To
+    /// share a reading between checks made in different places, or across
+    /// borrowed keys, use [`same_instant`](Self::same_instant).
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error a check returns, as
+    /// [`check_request`](Self::check_request) would for that key; earlier
+    /// keys keep the capacity they consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let decisions = limiter
+    ///     .check_many_same_instant(["user:42", "ip:10.0.0.1", "route:/search"])
+    ///     .unwrap();
+    /// assert!(decisions.iter().all(|decision| decision.allowed));
+    /// assert!(decisions.windows(2).all(|pair| pair[0].reset_time_nanos == pair[1].reset_time_nanos));
+    /// ```
+    pub fn check_many_same_instant(
+        &self,
+        client_ids: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<FluxLimiterDecision>, FluxLimiterError> {
+        let instant = self.same_instant();
+        client_ids
+            .into_iter()
+            .map(|client_id| instant.check_request(client_id))
+            .collect()
+    }
+
+    /// Read the clock once for several checks.
+    ///
+    /// The returned [`SameInstant`] checks requests at that reading, so a
+    /// handler checking several keys, owned or borrowed, in different places
+    /// pays for one clock read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::<String>::with_config(config, SystemClock).unwrap();
+    ///
+    /// let instant = limiter.same_instant();
+    /// let user = instant.check_request_ref("user:42").unwrap();
+    /// let ip = instant.check_request_ref("ip:10.0.0.1").unwrap();
+    /// assert_eq!(user.reset_time_nanos, ip.reset_time_nanos);
+    /// ```
+    pub fn same_instant(&self) -> SameInstant<'_, T, C, H> {
+        SameInstant {
+            limiter: self,
+            timestamp_nanos: self.now_nanos().ok(),
+        }
+    }
+
+    /// Check a request using a borrowed form of the client ID.
+    ///
+    /// With `T = String` this accepts a `&str`, so clients that are already
+    /// tracked are checked without allocating. The key is only converted into
+    /// an owned `T` the first time a client is seen.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// let decision = limiter.check_request_ref("user_123").unwrap();
+    /// assert!(decision.allowed);
+    /// ```
+    pub fn check_request_ref<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    {
+        self.check_borrowed(client_id, Q::to_owned)
+    }
+
+    // check a borrowed key, converting it with `to_owned` only for new clients
+    fn check_borrowed<Q>(
+        &self,
+        client_id: &Q,
+        to_owned: impl Fn(&Q) -> T,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => {
+                return self.fail_for(|| to_owned(client_id), FluxLimiterError::ClockError(err));
+            }
+        };
+        self.check_borrowed_at(client_id, to_owned, current_time_nanos)
+    }
+
+    // check a borrowed key against an already-read timestamp
+    pub(crate) fn check_borrowed_at<Q>(
+        &self,
+        client_id: &Q,
+        to_owned: impl Fn(&Q) -> T,
+        current_time_nanos: u64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.expire_recovered(current_time_nanos);
+
+        // fast path: known client, no key allocation
+        if let Some(entry) = self.client_state.get(client_id) {
+            let Some(multiplier) = self.rate_multiplier(entry.key()) else {
+                return Ok(self.bypass(|| entry.key().clone(), current_time_nanos));
+            };
+            if self.drains(true) {
+                let tat_nanos = entry.tat_nanos();
+                return Ok(self.turn_away(|| entry.key().clone(), tat_nanos, current_time_nanos));
+            }
+            return Ok(self.apply(&entry, current_time_nanos, Priority::High, multiplier));
+        }
+
+        // first request from this client: allocate the owned key
+        let client_id = to_owned(client_id);
+        let Some(multiplier) = self.rate_multiplier(&client_id) else {
+            return Ok(self.bypass(|| client_id, current_time_nanos));
+        };
+        if self.drains(false) {
+            return Ok(self.turn_away(|| client_id, current_time_nanos, current_time_nanos));
+        }
+        if let Err(err) = self.ensure_capacity(1) {
+            return self.fail_for(|| client_id, err);
+        }
+        let entry = self.insert_client(client_id, current_time_nanos);
+        Ok(self.apply(&entry, current_time_nanos, Priority::High, multiplier))
+    }
+
+    /// Check a request in a priority class.
+    ///
+    /// With [`FluxLimiterConfig::priority_reserve`] set, low-priority requests
+    /// are denied once they would dip into the reserved share of a client's
+    /// burst, which stays available to high-priority requests such as health
+    /// checks and admin calls. [`check_request`](Self::check_request) behaves
+    /// like `Priority::High`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, Priority, SystemClock};
+    ///
+    /// // 1 of the 2 immediate requests is held back for high priority
+    /// let config = FluxLimiterConfig::new(1.0, 1.0).priority_reserve(0.5);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// assert!(limiter.check_request_with_priority("svc", Priority::Low).unwrap().allowed);
+    /// assert!(!limiter.check_request_with_priority("svc", Priority::Low).unwrap().allowed);
+    /// assert!(limiter.check_request_with_priority("svc", Priority::High).unwrap().allowed);
+    /// ```
+    pub fn check_request_with_priority(
+        &self,
+        client_id: T,
+        priority: Priority,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => return self.fail_for(|| client_id, FluxLimiterError::ClockError(err)),
+        };
+        self.check_at(client_id, current_time_nanos, priority)
+    }
+
+    /// Check several keys as one all-or-nothing operation.
+    ///
+    /// Capacity is only consumed if every key conforms. If any key is over its
+    /// limit nothing is consumed and `denied_by` names the first offending key.
+    /// This is meant for operations spanning a handful of quotas (e.g.
+    /// per-user + per-endpoint) where partial consumption is unacceptable.
+    ///
+    /// The decisions are evaluated against a snapshot of the involved entries
+    /// and committed only if none of them changed in the meantime; on a
+    /// concurrent modification the whole check is retried.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let outcome = limiter.check_all(&["user:42", "endpoint:/upload"]).unwrap();
+    /// assert!(outcome.allowed);
+    ///
+    /// // the user quota is exhausted, so the endpoint quota is not touched either
+    /// let outcome = limiter.check_all(&["user:42", "endpoint:/search"]).unwrap();
+    /// assert_eq!(outcome.denied_by, Some("user:42"));
+    /// assert!(!limiter.client_state.contains_key("endpoint:/search"));
+    /// ```
+    pub fn check_all(&self, client_ids: &[T]) -> Result<MultiKeyDecision<T>, FluxLimiterError> {
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => return self.fail_all(client_ids, FluxLimiterError::ClockError(err)),
+        };
+        self.expire_recovered(current_time_nanos);
+
+        loop {
+            let mut plan: Vec<PlannedEntry<'_, T>> = Vec::with_capacity(client_ids.len());
+            let mut decisions = Vec::with_capacity(client_ids.len());
+            let mut denied_by = None;
+            let mut new_clients = 0;
+
+            for client_id in client_ids {
+                // bypassed keys are allowed whatever the others decide, and never planned
+                let Some(multiplier) = self.rate_multiplier(client_id) else {
+                    decisions.push((client_id.clone(), self.exempt_decision()));
+                    continue;
+                };
+                let slot = match plan
+                    .iter()
+                    .position(|planned| planned.client_id == client_id)
+                {
+                    Some(slot) => slot,
+                    None => {
+                        let (observed, held_until_nanos) =
+                            self.client_state.get(client_id).map_or((None, 0), |entry| {
+                                (Some(entry.tat_nanos()), entry.held_until_nanos())
+                            });
+                        new_clients += usize::from(observed.is_none());
+                        plan.push(PlannedEntry {
+                            client_id,
+                            observed,
+                            held_until_nanos,
+                            tat_nanos: observed.unwrap_or(self.credited_nanos(current_time_nanos)),
+                            count: 0,
+                            multiplier,
+                        });
+                        plan.len() - 1
+                    }
+                };
+
+                let (decision, new_tat_nanos) = if self.drains(plan[slot].observed.is_some()) {
+                    (self.drain_decision(plan[slot].tat_nanos), None)
+                } else {
+                    self.decide(
+                        current_time_nanos,
+                        plan[slot].tat_nanos,
+                        plan[slot].held_until_nanos,
+                        Priority::High,
+                        plan[slot].multiplier,
+                    )
+                };
+                match new_tat_nanos {
+                    Some(new_tat_nanos) => {
+                        plan[slot].tat_nanos = new_tat_nanos;
+                        plan[slot].count += 1;
+                    }
+                    None if denied_by.is_none() => denied_by = Some(client_id.clone()),
+                    None => {}
+                }
+                decisions.push((client_id.clone(), decision));
+            }
+
+            // all keys must fit, or none is checked
+            if let Err(err) = self.ensure_capacity(new_clients) {
+                return self.fail_all(client_ids, err);
+            }
+
+            if let Some(denied_key) = &denied_by {
+                // nothing was consumed, so every key but the bypassed ones counts as denied
+                decisions.iter().for_each(|(_, decision)| {
+                    let exempt = decision.reason == DecisionReason::Exempt;
+                    self.record_decision(exempt, decision.retry_after_seconds)
+                });
+                self.touch_planned(&plan, current_time_nanos);
+                if let Some(entry) = self.client_state.get(denied_key) {
+                    if let Some((_, decision)) = decisions.iter().find(|(_, d)| !d.allowed) {
+                        self.hold_if_exhausted(&entry, decision);
+                    }
+                    entry.record(false, 1);
+                    self.observe(&entry, 1, current_time_nanos);
+                }
+                self.publish_all(&decisions, current_time_nanos);
+                return Ok(MultiKeyDecision {
+                    allowed: false,
+                    denied_by,
+                    decisions,
+                });
+            }
+
+            if self.commit_plan(&plan, current_time_nanos) {
+                client_ids
+                    .iter()
+                    .for_each(|_| self.record_decision(true, None));
+                // observed once committed, so retried attempts are not counted twice
+                for planned in &plan {
+                    if let Some(entry) = self.client_state.get(planned.client_id) {
+                        entry.touch(current_time_nanos);
+                        self.observe(&entry, planned.count, current_time_nanos);
+                    }
+                }
+                self.publish_all(&decisions, current_time_nanos);
+                return Ok(MultiKeyDecision {
+                    allowed: true,
+                    denied_by: None,
+                    decisions,
+                });
+            }
+        }
+    }
+
+    // resolve a failure for every key of an all-or-nothing check
+    fn fail_all(
+        &self,
+        client_ids: &[T],
+        err: FluxLimiterError,
+    ) -> Result<MultiKeyDecision<T>, FluxLimiterError> {
+        let decision = self.fail(err)?;
+        // fail() counted one decision; count the remaining keys too
+        client_ids
+            .iter()
+            .skip(1)
+            .for_each(|_| self.record_decision(decision.allowed, decision.retry_after_seconds));
+        for client_id in client_ids {
+            self.publish(|| client_id.clone(), &decision, 0);
+        }
+        Ok(MultiKeyDecision {
+            allowed: decision.allowed,
+            denied_by: match decision.allowed {
+                true => None,
+                false => client_ids.first().cloned(),
+            },
+            decisions: client_ids
+                .iter()
+                .map(|client_id| (client_id.clone(), decision))
+                .collect(),
+        })
+    }
+
+    // write planned TATs if no entry changed since it was observed; on conflict,
+    // undo what was already written and report failure so the caller can retry
+    fn commit_plan(&self, plan: &[PlannedEntry<'_, T>], current_time_nanos: u64) -> bool {
+        for (committed, planned) in plan.iter().enumerate() {
+            let written = match planned.observed {
+                Some(observed) => self
+                    .client_state
+                    .get(planned.client_id)
+                    .is_some_and(|entry| {
+                        let swapped = entry
+                            .compare_exchange_tat(observed, planned.tat_nanos)
+                            .is_ok();
+                        if swapped {
+                            entry.record(true, planned.count);
+                        }
+                        swapped
+                    }),
+                None => match self.client_state.entry(planned.client_id.clone()) {
+                    Entry::Vacant(entry) => {
+                        let new_entry = ClientEntry::new(planned.tat_nanos, current_time_nanos);
+                        self.schedule_expiry(entry.key(), &new_entry);
+                        new_entry.record(true, planned.count);
+                        entry.insert(new_entry);
+                        true
+                    }
+                    Entry::Occupied(_) => false,
+                },
+            };
+
+            if !written {
+                for planned in &plan[..committed] {
+                    self.undo_commit(planned);
+                }
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // return the capacity a partial commit consumed
+    fn undo_commit(&self, planned: &PlannedEntry<'_, T>) {
+        let written = planned.tat_nanos;
+        if let Some(entry) = self.client_state.get(planned.client_id) {
+            entry.unrecord_allowed(planned.count);
+            match planned.observed {
+                Some(observed) => entry.update_tat(|tat| match tat == written {
+                    // untouched since our write: restore the previous value exactly
+                    true => observed,
+                    // someone built on our write: take back only what we added
+                    false => tat.saturating_sub(written.saturating_sub(observed)),
+                }),
+                None => {
+                    drop(entry);
+                    self.client_state
+                        .remove_if(planned.client_id, |_, entry| entry.tat_nanos() == written);
+                }
+            }
+        }
+    }
+
+    /// Return capacity consumed by requests that were never actually served.
+    ///
+    /// Rolls the client's TAT back by `cost` emission intervals (e.g. `1.0`
+    /// for a single request), clamped to the current time so a refund can
+    /// never grant more than a fully idle client would have. Unknown clients
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// * `FluxLimiterError::InvalidCost` - `cost` is negative or not finite
+    /// * `FluxLimiterError::ClockError` - the clock could not be read
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// assert!(limiter.check_request_ref("user_123").unwrap().allowed);
+    /// // upstream returned 503: give the request back
+    /// limiter.refund("user_123", 1.0).unwrap();
+    /// assert!(limiter.check_request_ref("user_123").unwrap().allowed);
+    /// ```
+    pub fn refund<Q>(&self, client_id: &Q, cost: f64) -> Result<(), FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !cost.is_finite() || cost < 0.0 {
+            return Err(FluxLimiterError::InvalidCost);
+        }
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let refund_nanos = (cost * self.increment_nanos() as f64) as u64;
+
+        if let Some(entry) = self.client_state.get(client_id) {
+            entry.update_tat(|tat| tat.saturating_sub(refund_nanos).max(current_time_nanos));
+        }
+
+        Ok(())
+    }
+
+    /// Consume `cost` requests of a client's capacity without a decision,
+    /// e.g. to punish abuse detected elsewhere.
+    ///
+    /// Pushes the client's TAT forward by `cost` emission intervals from
+    /// wherever it is (or from now, for idle and unknown clients), so a
+    /// penalty larger than the burst locks the client out until the excess
+    /// has drained at the configured rate. Penalties do not count in the
+    /// statistics.
+    ///
+    /// # Errors
+    ///
+    /// * `FluxLimiterError::InvalidCost` - `cost` is negative or not finite
+    /// * `FluxLimiterError::ClockError` - the clock could not be read
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// // credential stuffing detected: lock the client out for ~5 seconds
+    /// limiter.penalize("user_123".to_string(), 10.0).unwrap();
+    /// assert!(!limiter.check_request_ref("user_123").unwrap().allowed);
+    /// ```
+    pub fn penalize(&self, client_id: T, cost: f64) -> Result<(), FluxLimiterError> {
+        self.penalize_by(None, client_id, cost)
+    }
+
+    // penalize a client, auditing the penalty under `actor`
+    pub(crate) fn penalize_by(
+        &self,
+        actor: Option<&str>,
+        client_id: T,
+        cost: f64,
+    ) -> Result<(), FluxLimiterError> {
+        if !cost.is_finite() || cost < 0.0 {
+            return Err(FluxLimiterError::InvalidCost);
+        }
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let penalty_nanos = (cost * self.increment_nanos() as f64) as u64;
+
+        {
+            let entry = match self.client_state.get(&client_id) {
+                Some(entry) => entry,
+                None => {
+                    self.ensure_capacity(1)?;
+                    self.insert_client(client_id.clone(), current_time_nanos)
+                }
+            };
+            entry.update_tat(|tat| tat.max(current_time_nanos).saturating_add(penalty_nanos));
+            entry.touch(current_time_nanos);
+        }
+
+        let action = AuditAction::Penalize { client_id, cost };
+        self.audit(actor, action, Some(current_time_nanos));
+        Ok(())
+    }
+
+    /// Schedule a request instead of checking it: never rejects, but returns
+    /// the time at which the request should be executed to keep the client
+    /// within its limit.
+    ///
+    /// The client's schedule is advanced as if the request had been allowed at
+    /// that time, so successive calls hand out evenly spaced slots at the
+    /// configured rate. Up to the burst capacity may be scheduled immediately;
+    /// with a burst of `0.0` the output is perfectly smooth. This suits
+    /// outbound work that must eventually run, such as email sends or job
+    /// dispatch: sleep until the returned time, then execute.
+    ///
+    /// Paced requests count as allowed in the statistics. Callers that drop a
+    /// scheduled request can give its slot back with [`refund`](Self::refund).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails, and
+    /// [`FluxLimiterError::CapacityExceeded`] if the client is new and
+    /// `max_clients` clients are already tracked. There is no decision to
+    /// fall back to, so the failure policy does not apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(2.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let first = limiter.pace("smtp-relay").unwrap();
+    /// let second = limiter.pace("smtp-relay").unwrap();
+    /// assert_eq!(second.as_nanos() - first.as_nanos(), 500_000_000);
+    /// ```
+    pub fn pace(&self, client_id: T) -> Result<Timestamp, FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        self.pace_at(client_id, current_time_nanos)
+    }
+
+    /// Schedule a request at a caller-supplied time instead of reading the
+    /// clock; see [`pace`](Self::pace) and
+    /// [`check_request_at`](Self::check_request_at).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::CapacityExceeded`] if the client is new
+    /// and `max_clients` clients are already tracked.
+    pub fn pace_at(
+        &self,
+        client_id: T,
+        timestamp_nanos: u64,
+    ) -> Result<Timestamp, FluxLimiterError> {
+        self.expire_recovered(timestamp_nanos);
+
+        let entry = match self.client_state.get(&client_id) {
+            Some(entry) => entry,
+            None => {
+                self.ensure_capacity(1)?;
+                self.insert_client(client_id, timestamp_nanos)
+            }
+        };
+
+        let tolerance_nanos = self.tolerance_nanos();
+        let increment_nanos = self.increment_nanos();
+        let mut slot_nanos = timestamp_nanos;
+        entry.update_tat(|tat_nanos| {
+            // the earliest time the request conforms, and the schedule after it
+            slot_nanos = timestamp_nanos.max(tat_nanos.saturating_sub(tolerance_nanos));
+            timestamp_nanos
+                .max(tat_nanos)
+                .saturating_add(increment_nanos)
+        });
+        entry.touch(timestamp_nanos);
+        entry.record(true, 1);
+        self.observe(&entry, 1, timestamp_nanos);
+        self.record_decision(true, None);
+
+        Ok(Timestamp::from_nanos(slot_nanos))
+    }
+
+    // consume `cost` requests of a client's capacity that were spent elsewhere,
+    // without a decision; the schedule is pushed at most to an exhausted burst
+    #[cfg(feature = "gossip")]
+    pub(crate) fn charge(&self, client_id: &T, cost: f64) -> Result<(), FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let charge_nanos = (cost * self.increment_nanos() as f64) as u64;
+        let exhausted_nanos = current_time_nanos
+            .saturating_add(self.tolerance_nanos())
+            .saturating_add(self.increment_nanos());
+
+        let entry = match self.client_state.get(client_id) {
+            Some(entry) => entry,
+            None => {
+                self.ensure_capacity(1)?;
+                self.insert_client(client_id.clone(), current_time_nanos)
+            }
+        };
+        entry.update_tat(|tat| {
+            let charged = tat.max(current_time_nanos).saturating_add(charge_nanos);
+            // a client already running on debt keeps its schedule
+            charged.min(exhausted_nanos.max(tat))
+        });
+        Ok(())
+    }
+
+    /// Inspect a client's state without consuming capacity.
+    ///
+    /// Returns `None` for clients that are not currently tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// limiter.check_request_ref("user_123").unwrap();
+    /// let snapshot = limiter.client_snapshot("user_123").unwrap().unwrap();
+    /// assert!(snapshot.is_conforming());
+    /// ```
+    pub fn client_snapshot<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<Option<ClientSnapshot>, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let tat_nanos = match self.client_state.get(client_id) {
+            Some(entry) => entry.tat_nanos(),
+            None => return Ok(None),
+        };
+
+        Ok(Some(self.snapshot_at(current_time_nanos, tat_nanos)))
+    }
+
+    // the tracked clients furthest behind schedule, worst first, at most `limit`
+    #[cfg(feature = "admin")]
+    pub(crate) fn top_offenders(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<(T, ClientSnapshot)>, FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        // fully idle clients have not used anything, so they are not offenders
+        let mut clients: Vec<(T, u64)> = self
             .client_state
-            .get(&client_id)
-            .map(|entry| *entry.value())
-            .unwrap_or(current_time_nanos);
+            .iter()
+            .filter(|entry| entry.tat_nanos() > current_time_nanos)
+            .map(|entry| (entry.key().clone(), entry.tat_nanos()))
+            .collect();
+        clients.sort_unstable_by_key(|&(_, tat_nanos)| std::cmp::Reverse(tat_nanos));
+        clients.truncate(limit);
+
+        Ok(clients
+            .into_iter()
+            .map(|(client_id, tat_nanos)| {
+                (client_id, self.snapshot_at(current_time_nanos, tat_nanos))
+            })
+            .collect())
+    }
 
-        let is_conforming =
-            current_time_nanos >= previous_tat_nanos.saturating_sub(self.tolerance_nanos);
+    // a client's state as seen at `current_time_nanos`
+    fn snapshot_at(&self, current_time_nanos: u64, tat_nanos: u64) -> ClientSnapshot {
+        ClientSnapshot {
+            tat_nanos,
+            remaining_capacity: self.calculate_remaining_capacity(current_time_nanos, tat_nanos),
+            time_until_conforming_nanos: tat_nanos
+                .saturating_sub(self.tolerance_nanos())
+                .saturating_sub(current_time_nanos),
+            debt: self.debt_at(current_time_nanos, tat_nanos),
+        }
+    }
 
-        if is_conforming {
-            let new_tat_nanos = current_time_nanos.max(previous_tat_nanos) + self.rate_nanos;
-            self.client_state.insert(client_id, new_tat_nanos);
+    /// Forget a client's state, giving it its full burst again.
+    ///
+    /// Returns whether the client was tracked. Per-client statistics go
+    /// with the state; limiter-wide counters are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// limiter.check_request_ref("user_123").unwrap();
+    /// assert!(!limiter.check_request_ref("user_123").unwrap().allowed);
+    ///
+    /// assert!(limiter.reset_client("user_123"));
+    /// assert!(limiter.check_request_ref("user_123").unwrap().allowed);
+    /// ```
+    pub fn reset_client<Q>(&self, client_id: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.reset_client_by(None, client_id)
+    }
 
-            Ok(FluxLimiterDecision {
+    // reset a client, auditing the reset under `actor`
+    pub(crate) fn reset_client_by<Q>(&self, actor: Option<&str>, client_id: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.client_state.remove(client_id) {
+            Some((client_id, _)) => {
+                self.audit(actor, AuditAction::ResetClient { client_id }, None);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // change the rate, auditing the change under `actor`
+    pub(crate) fn set_rate_by(
+        &self,
+        actor: Option<&str>,
+        rate_per_second: f64,
+    ) -> Result<(), FluxLimiterError> {
+        let previous = self.rate();
+        self.set_rate(rate_per_second)?;
+        let action = AuditAction::SetRate {
+            previous,
+            rate: self.rate(),
+        };
+        self.audit(actor, action, None);
+        Ok(())
+    }
+
+    // hand an action to the audit sink, reading the clock unless the caller has
+    fn audit(&self, actor: Option<&str>, action: AuditAction<T>, timestamp_nanos: Option<u64>) {
+        if let Some(audit) = &self.audit {
+            let timestamp_nanos = timestamp_nanos.unwrap_or_else(|| self.now_nanos().unwrap_or(0));
+            audit.record(actor, action, timestamp_nanos);
+        }
+    }
+
+    // check a single client against an already-read timestamp
+    pub(crate) fn check_at(
+        &self,
+        client_id: T,
+        current_time_nanos: u64,
+        priority: Priority,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.check_costed_at(client_id, current_time_nanos, priority, 1.0)
+    }
+
+    // check a single client, charging `cost` requests' worth of its schedule
+    fn check_costed_at(
+        &self,
+        client_id: T,
+        current_time_nanos: u64,
+        priority: Priority,
+        cost: f64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.expire_recovered(current_time_nanos);
+        let Some(multiplier) = self.rate_multiplier(&client_id) else {
+            return Ok(self.bypass(|| client_id, current_time_nanos));
+        };
+        let multiplier = multiplier / cost;
+
+        // known clients only take the shard's read lock; the map is written on first sight
+        if let Some(entry) = self.client_state.get(&client_id) {
+            if self.drains(true) {
+                return Ok(self.turn_away(|| client_id, entry.tat_nanos(), current_time_nanos));
+            }
+            return Ok(self.apply(&entry, current_time_nanos, priority, multiplier));
+        }
+        if self.drains(false) {
+            return Ok(self.turn_away(|| client_id, current_time_nanos, current_time_nanos));
+        }
+        if let Err(err) = self.ensure_capacity(1) {
+            return self.fail_for(|| client_id, err);
+        }
+        let entry = self.insert_client(client_id, current_time_nanos);
+        Ok(self.apply(&entry, current_time_nanos, priority, multiplier))
+    }
+
+    // refuse `additional` new clients if they would take the map past `max_clients`;
+    // the map length is read before any shard is locked for writing
+    fn ensure_capacity(&self, additional: usize) -> Result<(), FluxLimiterError> {
+        let Some(max) = self.max_clients else {
+            return Ok(());
+        };
+        let current = self.client_state.len();
+        if additional > 0 && current.saturating_add(additional) > max {
+            return Err(FluxLimiterError::CapacityExceeded { current, max });
+        }
+        Ok(())
+    }
+
+    // add a first-seen client, or pick up the entry a concurrent check just added
+    fn insert_client(&self, client_id: T, current_time_nanos: u64) -> Ref<'_, T, ClientEntry> {
+        match self.client_state.entry(client_id) {
+            Entry::Occupied(entry) => entry.into_ref().downgrade(),
+            Entry::Vacant(entry) => {
+                // new clients start on the schedule, at the start of the step if stepwise
+                let tat_nanos = self.credited_nanos(current_time_nanos);
+                let new_entry = ClientEntry::new(tat_nanos, current_time_nanos);
+                self.schedule_expiry(entry.key(), &new_entry);
+                entry.insert(new_entry).downgrade()
+            }
+        }
+    }
+
+    // put a new client on the expiry wheel, if there is one
+    fn schedule_expiry(&self, client_id: &T, entry: &ClientEntry) {
+        if let Some(expiry) = &self.expiry {
+            expiry.schedule(client_id.clone(), self.expiry_deadline(entry));
+        }
+    }
+
+    // earliest time an entry can be removed: once recovered, or once its TTL runs out
+    fn expiry_deadline(&self, entry: &ClientEntry) -> u64 {
+        let recovered_nanos = entry.tat_nanos().saturating_add(self.tolerance_nanos());
+        match self.entry_ttl_nanos {
+            Some(ttl_nanos) => {
+                recovered_nanos.min(entry.last_access_nanos().saturating_add(ttl_nanos))
+            }
+            None => recovered_nanos,
+        }
+    }
+
+    // note a check of every key in a multi-key plan, for the entry TTL
+    fn touch_planned(&self, plan: &[PlannedEntry<'_, T>], current_time_nanos: u64) {
+        if self.entry_ttl_nanos.is_none() {
+            return;
+        }
+        for planned in plan {
+            if let Some(entry) = self.client_state.get(planned.client_id) {
+                entry.touch(current_time_nanos);
+            }
+        }
+    }
+
+    // remove clients the expiry wheel reports as recovered and put back the
+    // ones that were checked again since they were scheduled; must not be
+    // called while holding a reference into the client map
+    fn expire_recovered(&self, current_time_nanos: u64) {
+        let Some(expiry) = &self.expiry else {
+            return;
+        };
+        let due = expiry.expire(current_time_nanos);
+        if due.is_empty() {
+            return;
+        }
+
+        let mut removed = 0;
+        let mut rescheduled = Vec::new();
+        // a client removed and seen again can be due twice
+        for client_id in due.into_iter().collect::<HashSet<_>>() {
+            let expired = self.client_state.remove_if(&client_id, |_, entry| {
+                self.is_stale(entry, current_time_nanos, 0)
+            });
+            if expired.is_some() {
+                removed += 1;
+            } else if let Some(deadline_nanos) = self
+                .client_state
+                .get(&client_id)
+                .map(|entry| self.expiry_deadline(&entry))
+            {
+                rescheduled.push((client_id, deadline_nanos));
+            }
+        }
+        expiry.schedule_all(rescheduled);
+        if let Some(stats) = &self.stats {
+            stats.record_evictions(removed);
+        }
+    }
+
+    // run the GCRA step against an entry and record the outcome; concurrent
+    // checks for the same client race on the TAT and the losers re-decide
+    fn apply(
+        &self,
+        entry: &Ref<'_, T, ClientEntry>,
+        current_time_nanos: u64,
+        priority: Priority,
+        multiplier: f64,
+    ) -> FluxLimiterDecision {
+        self.apply_traced(entry, current_time_nanos, priority, multiplier)
+            .0
+    }
+
+    // apply the GCRA step as `apply` does, keeping the trace of the attempt that won
+    fn apply_traced(
+        &self,
+        entry: &Ref<'_, T, ClientEntry>,
+        current_time_nanos: u64,
+        priority: Priority,
+        multiplier: f64,
+    ) -> (FluxLimiterDecision, DecisionTrace) {
+        // read-mostly: denials are decided from a plain load and never write the TAT
+        let mut previous_tat_nanos = entry.tat_nanos();
+        let (decision, trace) = loop {
+            let (decision, trace) = self.decide_traced(
+                current_time_nanos,
+                previous_tat_nanos,
+                entry.held_until_nanos(),
+                priority,
+                multiplier,
+            );
+            let Some(new_tat_nanos) = trace.new_tat_nanos else {
+                self.hold_if_exhausted(entry, &decision);
+                break (decision, trace);
+            };
+            match entry.compare_exchange_tat(previous_tat_nanos, new_tat_nanos) {
+                Ok(()) => break (decision, trace),
+                Err(actual) => previous_tat_nanos = actual,
+            }
+        };
+        entry.touch(current_time_nanos);
+        entry.record(decision.allowed, 1);
+        self.observe(entry, 1, current_time_nanos);
+        self.record_decision(decision.allowed, decision.retry_after_seconds);
+        self.publish(|| entry.key().clone(), &decision, current_time_nanos);
+
+        (decision, trace)
+    }
+
+    // feed requests into a client's observed rate (no-op without per-key-stats)
+    #[cfg_attr(not(feature = "per-key-stats"), allow(unused_variables))]
+    fn observe(&self, entry: &ClientEntry, count: u64, current_time_nanos: u64) {
+        #[cfg(feature = "per-key-stats")]
+        entry.observe(count, current_time_nanos, self.observed_rate_window_nanos);
+    }
+
+    // run a check against an external store, applying the failure policy to errors
+    pub(crate) fn check_store<S, K>(
+        &self,
+        store: &S,
+        client_id: &K,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        S: StateStore<K> + ?Sized,
+        K: ?Sized,
+    {
+        self.try_check_store(store, client_id)
+            .or_else(|err| self.fail(err))
+    }
+
+    // GCRA step against a store, retrying when a concurrent writer wins
+    fn try_check_store<S, K>(
+        &self,
+        store: &S,
+        client_id: &K,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        S: StateStore<K> + ?Sized,
+        K: ?Sized,
+    {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+
+        loop {
+            let observed = store.get(client_id).map_err(FluxLimiterError::StoreError)?;
+            // stores keep only the TAT, so no hold applies
+            let (decision, new_tat_nanos) = self.decide(
+                current_time_nanos,
+                observed.unwrap_or(self.credited_nanos(current_time_nanos)),
+                0,
+                Priority::High,
+                1.0,
+            );
+
+            let committed = match new_tat_nanos {
+                Some(new_tat_nanos) => store
+                    .compare_and_swap(client_id, observed, new_tat_nanos)
+                    .map_err(FluxLimiterError::StoreError)?,
+                None => true,
+            };
+            if committed {
+                self.record_decision(decision.allowed, decision.retry_after_seconds);
+                return Ok(decision);
+            }
+        }
+    }
+
+    // async counterpart of check_store for stores that do network I/O
+    pub(crate) async fn check_store_async<S, K>(
+        &self,
+        store: &S,
+        client_id: &K,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        S: AsyncStateStore<K> + ?Sized,
+        K: ?Sized,
+    {
+        match self.try_check_store_async(store, client_id).await {
+            Ok(decision) => Ok(decision),
+            Err(err) => self.fail(err),
+        }
+    }
+
+    // same loop as try_check_store, awaiting each store call
+    async fn try_check_store_async<S, K>(
+        &self,
+        store: &S,
+        client_id: &K,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        S: AsyncStateStore<K> + ?Sized,
+        K: ?Sized,
+    {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+
+        loop {
+            let observed = store
+                .get(client_id)
+                .await
+                .map_err(FluxLimiterError::StoreError)?;
+            // stores keep only the TAT, so no hold applies
+            let (decision, new_tat_nanos) = self.decide(
+                current_time_nanos,
+                observed.unwrap_or(self.credited_nanos(current_time_nanos)),
+                0,
+                Priority::High,
+                1.0,
+            );
+
+            let committed = match new_tat_nanos {
+                Some(new_tat_nanos) => store
+                    .compare_and_swap(client_id, observed, new_tat_nanos)
+                    .await
+                    .map_err(FluxLimiterError::StoreError)?,
+                None => true,
+            };
+            if committed {
+                self.record_decision(decision.allowed, decision.retry_after_seconds);
+                return Ok(decision);
+            }
+        }
+    }
+
+    // resolve a clock failure for `client_id` and publish the outcome
+    fn fail_for(
+        &self,
+        client_id: impl FnOnce() -> T,
+        err: FluxLimiterError,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let decision = self.fail(err)?;
+        self.publish(client_id, &decision, 0);
+        Ok(decision)
+    }
+
+    // resolve a clock, store or capacity failure according to the failure policy
+    pub(crate) fn fail(
+        &self,
+        err: FluxLimiterError,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let allowed = match self.failure_policy {
+            FailurePolicy::Propagate => return Err(err),
+            FailurePolicy::FailOpen => true,
+            FailurePolicy::FailClosed => false,
+        };
+
+        // without a clock reading there is no TAT to report
+        let decision = FluxLimiterDecision {
+            allowed,
+            retry_after_seconds: (!allowed).then(|| self.jittered(self.increment())),
+            remaining_capacity: None,
+            reset_time_nanos: 0,
+            limit_per_second: self.rate(),
+            burst_capacity: self.burst(),
+            shedding: false,
+            debt: 0.0,
+            suggested_delay_seconds: None,
+            reason: DecisionReason::Fallback,
+        };
+        self.record_decision(allowed, decision.retry_after_seconds);
+        Ok(decision)
+    }
+
+    // the classifier's rate multiplier for `client_id`, or None if it bypasses the limiter
+    fn rate_multiplier(&self, client_id: &T) -> Option<f64> {
+        self.classifier.as_ref().map_or(Some(1.0), |classifier| {
+            classifier.rate_multiplier(client_id)
+        })
+    }
+
+    // the decision for a request the classifier exempted; it is unlimited, as a
+    // router's unlimited route reports
+    fn exempt_decision(&self) -> FluxLimiterDecision {
+        FluxLimiterDecision {
+            allowed: true,
+            retry_after_seconds: None,
+            remaining_capacity: None,
+            reset_time_nanos: 0,
+            limit_per_second: f64::INFINITY,
+            burst_capacity: f64::INFINITY,
+            shedding: false,
+            debt: 0.0,
+            suggested_delay_seconds: None,
+            reason: DecisionReason::Exempt,
+        }
+    }
+
+    // allow a bypassed request without touching client state, and publish the outcome
+    fn bypass(
+        &self,
+        client_id: impl FnOnce() -> T,
+        current_time_nanos: u64,
+    ) -> FluxLimiterDecision {
+        let decision = self.exempt_decision();
+        self.record_decision(true, None);
+        self.publish(client_id, &decision, current_time_nanos);
+        decision
+    }
+
+    // whether drain mode turns away a client that is tracked (`existing`) or new
+    fn drains(&self, existing: bool) -> bool {
+        self.draining.load(Ordering::Relaxed)
+            && existing == (self.drain_mode == DrainMode::ExistingClients)
+    }
+
+    // the denial for a drained client; retrying after one emission interval
+    // gives a load balancer time to move the client elsewhere
+    fn drain_decision(&self, reset_time_nanos: u64) -> FluxLimiterDecision {
+        FluxLimiterDecision {
+            allowed: false,
+            retry_after_seconds: Some(self.jittered(self.increment())),
+            remaining_capacity: Some(0.0),
+            reset_time_nanos,
+            limit_per_second: self.rate(),
+            burst_capacity: self.burst(),
+            shedding: false,
+            debt: 0.0,
+            suggested_delay_seconds: None,
+            reason: DecisionReason::Draining,
+        }
+    }
+
+    // deny a drained client without touching its state, and publish the outcome
+    fn turn_away(
+        &self,
+        client_id: impl FnOnce() -> T,
+        reset_time_nanos: u64,
+        current_time_nanos: u64,
+    ) -> FluxLimiterDecision {
+        let decision = self.drain_decision(reset_time_nanos);
+        self.record_decision(false, decision.retry_after_seconds);
+        self.publish(client_id, &decision, current_time_nanos);
+        decision
+    }
+
+    // core GCRA step: returns the decision and, when allowed, the new TAT to store
+    fn decide(
+        &self,
+        current_time_nanos: u64,
+        previous_tat_nanos: u64,
+        held_until_nanos: u64,
+        priority: Priority,
+        multiplier: f64,
+    ) -> (FluxLimiterDecision, Option<u64>) {
+        let (decision, trace) = self.decide_traced(
+            current_time_nanos,
+            previous_tat_nanos,
+            held_until_nanos,
+            priority,
+            multiplier,
+        );
+        (decision, trace.new_tat_nanos)
+    }
+
+    // the GCRA step, also returning the values it was computed from; `multiplier`
+    // is the share of the rate the request's classification and cost grant, and
+    // requests before `held_until_nanos` are denied while the client regains its burst
+    fn decide_traced(
+        &self,
+        current_time_nanos: u64,
+        previous_tat_nanos: u64,
+        held_until_nanos: u64,
+        priority: Priority,
+        multiplier: f64,
+    ) -> (FluxLimiterDecision, DecisionTrace) {
+        let pressure_factor = self
+            .pressure
+            .as_ref()
+            .map_or(1.0, PressureHook::rate_factor);
+        let shedding = pressure_factor < 1.0;
+        let rate_factor = pressure_factor * multiplier;
+        let limit_per_second = self.rate() * rate_factor;
+
+        // the debt ceiling extends how far ahead of the schedule a client may run
+        let allowance_nanos = self
+            .tolerance_nanos()
+            .saturating_add(self.intervals.debt_nanos.load(Ordering::Relaxed));
+        // low-priority requests must leave the reserved share of the burst window untouched
+        let reserved_nanos = match priority {
+            Priority::High => 0,
+            Priority::Low => {
+                let window_nanos = self
+                    .tolerance_nanos()
+                    .saturating_add(self.increment_nanos());
+                (self.priority_reserve * window_nanos as f64) as u64
+            }
+        };
+        let threshold_nanos = previous_tat_nanos.saturating_add(reserved_nanos);
+        // stepwise replenishment only credits the schedule with whole steps
+        let credited_nanos = self.credited_nanos(current_time_nanos);
+        let held = current_time_nanos < held_until_nanos;
+        let is_conforming = rate_factor > 0.0
+            && !held
+            && gcra::conforms(credited_nanos, threshold_nanos, allowance_nanos);
+
+        // under pressure, a strict classification or an operation cost each
+        // request takes a proportional share of the schedule
+        let increment_nanos = if rate_factor != 1.0 {
+            (self.increment_nanos() as f64 / rate_factor) as u64
+        } else {
+            self.increment_nanos()
+        };
+        // a TAT past the end of the time range cannot be scheduled, so such requests are denied
+        let new_tat_nanos = gcra::advance_tat(credited_nanos, previous_tat_nanos, increment_nanos)
+            .filter(|_| is_conforming);
+        let trace = DecisionTrace {
+            now_nanos: credited_nanos,
+            previous_tat_nanos,
+            new_tat_nanos,
+            emission_interval_nanos: increment_nanos,
+            tolerance_nanos: allowance_nanos,
+            reserved_nanos,
+            new_client: false,
+        };
+
+        if let Some(new_tat_nanos) = new_tat_nanos {
+            let decision = FluxLimiterDecision {
                 allowed: true,
                 retry_after_seconds: None,
                 remaining_capacity: Some(
-                    self.calculate_remaining_capacity(current_time_nanos, new_tat_nanos),
+                    self.calculate_remaining_capacity(credited_nanos, new_tat_nanos),
                 ),
                 reset_time_nanos: new_tat_nanos,
-            })
+                limit_per_second,
+                burst_capacity: self.burst(),
+                shedding,
+                debt: self.debt_at(current_time_nanos, new_tat_nanos),
+                // the next request is on schedule once the TAT has passed
+                suggested_delay_seconds: self.pacing_hints.then(|| {
+                    new_tat_nanos.saturating_sub(current_time_nanos) as f64 / 1_000_000_000.0
+                }),
+                reason: DecisionReason::Conforming,
+            };
+            (decision, trace)
         } else {
-            let retry_after_nanos = previous_tat_nanos
-                .saturating_sub(self.tolerance_nanos)
-                .saturating_sub(current_time_nanos);
+            // when fully shed there is no schedule to wait for; suggest one emission interval
+            let retry_after_nanos = if held {
+                held_until_nanos - current_time_nanos
+            } else if rate_factor > 0.0 {
+                let wait_nanos =
+                    gcra::retry_after_nanos(credited_nanos, threshold_nanos, allowance_nanos);
+                self.next_credit_nanos(credited_nanos.saturating_add(wait_nanos))
+                    .saturating_sub(current_time_nanos)
+            } else {
+                self.increment_nanos()
+            };
 
-            Ok(FluxLimiterDecision {
+            let decision = FluxLimiterDecision {
                 allowed: false,
-                retry_after_seconds: Some(retry_after_nanos as f64 / 1_000_000_000.0),
+                retry_after_seconds: Some(
+                    self.jittered(retry_after_nanos as f64 / 1_000_000_000.0),
+                ),
                 remaining_capacity: Some(0.0),
                 reset_time_nanos: previous_tat_nanos,
-            })
+                limit_per_second,
+                burst_capacity: self.burst(),
+                shedding,
+                debt: self.debt_at(current_time_nanos, previous_tat_nanos),
+                suggested_delay_seconds: None,
+                reason: match shedding {
+                    true => DecisionReason::Shed,
+                    false => DecisionReason::BurstExhausted,
+                },
+            };
+            (decision, trace)
+        }
+    }
+
+    /// Check a request and also return the GCRA values it was decided from.
+    ///
+    /// Behaves exactly like [`check_request`](Self::check_request), consuming
+    /// capacity the same way. The [`DecisionTrace`] shows the client's TAT
+    /// before and after the check, the time used and the effective interval
+    /// and tolerance, which answers "why was I blocked?" without
+    /// reconstructing the state by hand. Decisions made by the failure policy
+    /// or by [drain mode](Self::set_drain) have no trace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// limiter.check_request("user_123").unwrap();
+    /// let (decision, trace) = limiter.check_request_explained("user_123").unwrap();
+    /// let trace = trace.unwrap();
+    /// assert!(!decision.allowed);
+    /// // the previous request scheduled the client a second ahead
+    /// assert!(trace.previous_tat_nanos > trace.now_nanos + trace.tolerance_nanos);
+    /// assert_eq!(trace.new_tat_nanos, None);
+    /// ```
+    pub fn check_request_explained(
+        &self,
+        client_id: T,
+    ) -> Result<(FluxLimiterDecision, Option<DecisionTrace>), FluxLimiterError> {
+        let current_time_nanos = match self.now_nanos() {
+            Ok(now) => now,
+            Err(err) => {
+                return self
+                    .fail_for(|| client_id, FluxLimiterError::ClockError(err))
+                    .map(|decision| (decision, None));
+            }
+        };
+        self.expire_recovered(current_time_nanos);
+        let Some(multiplier) = self.rate_multiplier(&client_id) else {
+            return Ok((self.bypass(|| client_id, current_time_nanos), None));
+        };
+
+        let existing = self.client_state.get(&client_id);
+        if self.drains(existing.is_some()) {
+            let reset_time_nanos = existing.map_or(current_time_nanos, |entry| entry.tat_nanos());
+            let decision = self.turn_away(|| client_id, reset_time_nanos, current_time_nanos);
+            return Ok((decision, None));
         }
+        let (entry, new_client) = match existing {
+            Some(entry) => (entry, false),
+            None => {
+                if let Err(err) = self.ensure_capacity(1) {
+                    return self
+                        .fail_for(|| client_id, err)
+                        .map(|decision| (decision, None));
+                }
+                (self.insert_client(client_id, current_time_nanos), true)
+            }
+        };
+        let (decision, trace) =
+            self.apply_traced(&entry, current_time_nanos, Priority::High, multiplier);
+        Ok((
+            decision,
+            Some(DecisionTrace {
+                new_client,
+                ..trace
+            }),
+        ))
+    }
+
+    /// Check a request and return a typed [`Decision`].
+    ///
+    /// Equivalent to [`check_request`](Self::check_request), but the outcome is
+    /// split into `Decision::Allowed` / `Decision::Denied` so that the retry
+    /// delay is only present (and never optional) on denials.
+    pub fn check_request_typed(&self, client_id: T) -> Result<Decision, FluxLimiterError> {
+        self.check_request(client_id).map(Decision::from)
+    }
+
+    /// Check a request, returning denials as errors.
+    ///
+    /// Lets handlers bail out with `?`: allowed requests yield their
+    /// [`AllowedMeta`], denials yield [`RateLimitedError::Denied`] with the
+    /// retry delay and reset time, and clock or store failures yield
+    /// [`RateLimitedError::Failed`].
+    pub fn try_check(&self, client_id: T) -> Result<AllowedMeta, RateLimitedError> {
+        match self.check_request_typed(client_id)? {
+            Decision::Allowed(meta) => Ok(meta),
+            Decision::Denied(meta) => Err(RateLimitedError::Denied(meta)),
+        }
+    }
+
+    // spread a denial's retry delay when jitter is configured
+    fn jittered(&self, retry_after_seconds: f64) -> f64 {
+        if self.retry_jitter_seconds == 0.0 {
+            return retry_after_seconds;
+        }
+        self.jitter
+            .apply(retry_after_seconds, self.retry_jitter_seconds)
+    }
+
+    // requests a client owes beyond its limit, given its TAT
+    fn debt_at(&self, current_time: u64, tat: u64) -> f64 {
+        if self.debt_ceiling == 0.0 {
+            return 0.0;
+        }
+        // within the limit, a TAT runs at most tolerance + one increment ahead;
+        // a limit past the end of the time range leaves no room for debt
+        let limit_nanos = current_time
+            .saturating_add(self.tolerance_nanos())
+            .saturating_add(self.increment_nanos());
+        tat.saturating_sub(limit_nanos) as f64 / self.increment_nanos() as f64
     }
 
     fn calculate_remaining_capacity(&self, current_time: u64, tat: u64) -> f64 {
-        if current_time >= tat.saturating_sub(self.tolerance_nanos) {
-            let time_until_tat = tat.saturating_sub(current_time) as f64 / 1_000_000_000.0;
-            let rate_per_second = self.rate();
-            (self.burst() - (time_until_tat * rate_per_second)).max(0.0)
+        gcra::remaining_capacity(
+            current_time,
+            tat,
+            self.tolerance_nanos(),
+            self.rate(),
+            self.burst(),
+        )
+    }
+
+    // the replenishment step in nanoseconds, if capacity is regained stepwise
+    fn step_nanos(&self) -> Option<u64> {
+        match self.replenishment {
+            Replenishment::Stepwise(step) => Some(((step * 1_000_000_000.0) as u64).max(1)),
+            Replenishment::Linear | Replenishment::WhenIdle => None,
+        }
+    }
+
+    // the time the schedule is credited with at `nanos`: the start of its step
+    fn credited_nanos(&self, nanos: u64) -> u64 {
+        self.step_nanos().map_or(nanos, |step| nanos - nanos % step)
+    }
+
+    // the first time at or after `nanos` that the schedule is credited with
+    fn next_credit_nanos(&self, nanos: u64) -> u64 {
+        self.step_nanos().map_or(nanos, |step| match nanos % step {
+            0 => nanos,
+            rem => nanos.saturating_add(step - rem),
+        })
+    }
+
+    // under `WhenIdle` replenishment, hold a client that exhausted its burst
+    // until its schedule has caught up
+    fn hold_if_exhausted(&self, entry: &ClientEntry, decision: &FluxLimiterDecision) {
+        if self.replenishment == Replenishment::WhenIdle
+            && decision.reason == DecisionReason::BurstExhausted
+        {
+            entry.hold_until(decision.reset_time_nanos);
+        }
+    }
+
+    /// Remove clients that have been fully recovered for longer than `max_stale_nanos`.
+    ///
+    /// Returns how many entries were examined, removed and kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let one_hour_nanos = 60 * 60 * 1_000_000_000;
+    /// let report = limiter.cleanup_stale_clients(one_hour_nanos).unwrap();
+    /// assert_eq!((report.scanned, report.removed, report.remaining), (1, 0, 1));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let mut report = CleanupReport::default();
+        self.client_state.retain(|_, entry| {
+            let keep = !self.is_stale(entry, current_time_nanos, max_stale_nanos);
+            report.scanned += 1;
+            report.removed += usize::from(!keep);
+            keep
+        });
+        report.remaining = report.scanned - report.removed;
+        if let Some(stats) = &self.stats {
+            stats.record_evictions(report.removed as u64);
+        }
+
+        Ok(report)
+    }
+
+    /// Count what [`cleanup_stale_clients`](Self::cleanup_stale_clients) would
+    /// remove, without removing anything.
+    ///
+    /// Useful for checking that a cleanup threshold actually matches idle
+    /// clients before enabling it. Evictions are not counted in the stats.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients_dry_run(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let mut report = CleanupReport::default();
+        for entry in self.client_state.iter() {
+            report.scanned += 1;
+            report.removed +=
+                usize::from(self.is_stale(&entry, current_time_nanos, max_stale_nanos));
+        }
+        report.remaining = report.scanned - report.removed;
+
+        Ok(report)
+    }
+
+    /// Clean up stale clients incrementally, examining at most `max_scan`
+    /// entries per call.
+    ///
+    /// [`cleanup_stale_clients`](Self::cleanup_stale_clients) walks the whole
+    /// map, holding each shard's write lock while it goes, which stalls
+    /// requests on maps with millions of clients when run inline. This variant
+    /// resumes from a cursor shared by every clone, so repeated calls cycle
+    /// through the map a slice at a time. Entries are examined under shard
+    /// read locks and stale ones removed one key at a time afterwards;
+    /// reaching the cursor walks the entries before it, so later slices of a
+    /// cycle cost more than earlier ones. Clients inserted or moved by the map
+    /// growing between calls may be skipped or seen twice in a cycle and are
+    /// picked up by the next one. The report's `remaining` counts the
+    /// examined entries that were kept, not every client left in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    /// for client in 0..10u64 {
+    ///     limiter.check_request(client).unwrap();
+    /// }
+    ///
+    /// let report = limiter.cleanup_stale_clients_bounded(0, 4).unwrap();
+    /// assert_eq!(report.scanned, 4);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients_bounded(
+        &self,
+        max_stale_nanos: u64,
+        max_scan: usize,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        let mut report = CleanupReport::default();
+        let mut cursor = self
+            .cleanup_cursor
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+
+        // collect under the shard read locks, then remove one key at a time
+        let mut stale = Vec::new();
+        for entry in self
+            .client_state
+            .iter()
+            .skip(cursor.position)
+            .take(max_scan)
+        {
+            report.scanned += 1;
+            if self.is_stale(entry.value(), current_time_nanos, max_stale_nanos) {
+                stale.push(entry.key().clone());
+            }
+        }
+        for client_id in &stale {
+            // re-check, since the client may have been seen again in between
+            let removed = self.client_state.remove_if(client_id, |_, entry| {
+                self.is_stale(entry, current_time_nanos, max_stale_nanos)
+            });
+            report.removed += usize::from(removed.is_some());
+        }
+        // removed entries were behind the cursor, so only the kept ones move it
+        cursor.position = if report.scanned < max_scan {
+            0
         } else {
-            0.0
+            cursor.position + report.scanned - report.removed
+        };
+        report.remaining = report.scanned - report.removed;
+        if let Some(stats) = &self.stats {
+            stats.record_evictions(report.removed as u64);
         }
+
+        Ok(report)
+    }
+
+    // whether an entry has been fully recovered for longer than `max_stale_nanos`,
+    // or has gone unchecked for longer than the entry TTL
+    fn is_stale(&self, entry: &ClientEntry, current_time_nanos: u64, max_stale_nanos: u64) -> bool {
+        let recovered = gcra::is_recovered(
+            current_time_nanos,
+            entry.tat_nanos(),
+            self.tolerance_nanos(),
+            max_stale_nanos,
+        );
+        let idle = self.entry_ttl_nanos.is_some_and(|ttl_nanos| {
+            current_time_nanos.saturating_sub(entry.last_access_nanos()) >= ttl_nanos
+        });
+        recovered || idle
     }
 
-    // method to clean up stale clients
-    pub fn cleanup_stale_clients(&self, max_stale_nanos: u64) -> Result<(), FluxLimiterError> {
-        let current_time_nanos = self.clock.now().map_err(FluxLimiterError::ClockError)?;
-        self.client_state.retain(|_, &mut tat| {
-            tat + self.tolerance_nanos > current_time_nanos.saturating_sub(max_stale_nanos)
+    // forget clients whose TAT has fully recovered, without counting evictions
+    #[cfg(feature = "persistence")]
+    pub(crate) fn drop_recovered_clients(&self) -> Result<(), FluxLimiterError> {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        self.client_state.retain(|_, entry| {
+            entry.tat_nanos().saturating_add(self.tolerance_nanos()) > current_time_nanos
         });
 
         Ok(())
     }
+
+    /// Return the limiter's statistics.
+    ///
+    /// Counters are maintained only when enabled with
+    /// [`FluxLimiterConfig::stats`]; `current_clients` is always reported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// limiter.check_request("user_123").unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let stats = limiter.stats();
+    /// assert_eq!((stats.allowed, stats.denied), (1, 1));
+    /// ```
+    pub fn stats(&self) -> FluxLimiterStats {
+        let current_clients = self.client_state.len();
+        match &self.stats {
+            Some(stats) => stats.snapshot(current_clients),
+            None => FluxLimiterStats {
+                current_clients,
+                ..FluxLimiterStats::default()
+            },
+        }
+    }
+
+    /// Reset all statistics counters to zero.
+    pub fn reset_stats(&self) {
+        if let Some(stats) = &self.stats {
+            stats.reset();
+        }
+    }
+
+    /// Return the allowed/denied counts for a single client.
+    ///
+    /// Returns `None` for clients that are not currently tracked.
+    #[cfg(feature = "per-key-stats")]
+    pub fn client_stats<Q>(&self, client_id: &Q) -> Option<ClientStats>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.client_state.get(client_id).map(|entry| ClientStats {
+            allowed: entry.allowed(),
+            denied: entry.denied(),
+        })
+    }
+
+    /// Estimate a client's recent demand in requests per second.
+    ///
+    /// Every checked request counts, allowed or not, averaged with an
+    /// exponential decay over [`FluxLimiterConfig::observed_rate_window`].
+    /// Compare it with [`rate`](Self::rate) to see how close a client runs to
+    /// its limit. Returns `None` for clients that are not currently tracked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0).observed_rate_window(10.0);
+    /// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// for _ in 0..5 {
+    ///     limiter.check_request_ref("user_123").unwrap();
+    /// }
+    /// let rate = limiter.observed_rate("user_123").unwrap().unwrap();
+    /// assert!(rate > 0.0 && rate <= 0.5);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    #[cfg(feature = "per-key-stats")]
+    pub fn observed_rate<Q>(&self, client_id: &Q) -> Result<Option<f64>, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let current_time_nanos = self.now_nanos().map_err(FluxLimiterError::ClockError)?;
+        Ok(self
+            .client_state
+            .get(client_id)
+            .map(|entry| entry.observed_rate(current_time_nanos, self.observed_rate_window_nanos)))
+    }
+
+    // send a decision to subscribers, only building the owned key if someone listens
+    fn publish(
+        &self,
+        client_id: impl FnOnce() -> T,
+        decision: &FluxLimiterDecision,
+        timestamp_nanos: u64,
+    ) {
+        if self.subscribers.is_active() {
+            self.subscribers
+                .publish(client_id(), decision, timestamp_nanos);
+        }
+    }
+
+    // publish the per-key decisions of a multi-key check
+    fn publish_all(&self, decisions: &[(T, FluxLimiterDecision)], timestamp_nanos: u64) {
+        for (client_id, decision) in decisions {
+            self.publish(|| client_id.clone(), decision, timestamp_nanos);
+        }
+    }
+
+    // count a decision when statistics are enabled, and export it when otel is attached
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    fn record_decision(&self, allowed: bool, retry_after_seconds: Option<f64>) {
+        if let Some(stats) = &self.stats {
+            stats.record_decision(allowed);
+        }
+        #[cfg(feature = "otel")]
+        if let Some(otel) = &self.otel {
+            otel.record(allowed, retry_after_seconds);
+        }
+    }
 }
 
-/// Result of a rate limiting decision with metadata for HTTP responses
-#[derive(Debug, Clone)]
-pub struct FluxLimiterDecision {
-    /// Whether the request should be allowed
-    pub allowed: bool,
-    /// Seconds until the client can make another request (when denied)
-    pub retry_after_seconds: Option<f64>,
-    /// Approximate remaining burst capacity
-    pub remaining_capacity: Option<f64>,
-    /// When the rate limit window resets (nanoseconds since epoch)
-    pub reset_time_nanos: u64,
+// methods for limiters keyed by shared strings
+impl<C, H> FluxLimiter<Arc<str>, C, H>
+where
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    /// Check a request for a string key in a limiter keyed by `Arc<str>`.
+    ///
+    /// Like [`check_request_ref`](Self::check_request_ref) for `String`
+    /// keys: known clients are looked up by `&str` without allocating, and
+    /// the `Arc<str>` is created once, when a client is first seen. Every
+    /// later copy of the key, in the expiry wheel, decision events and state
+    /// snapshots, shares that allocation instead of duplicating the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use std::sync::Arc;
+    ///
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    /// let limiter = FluxLimiter::<Arc<str>, _>::with_config(config, SystemClock).unwrap();
+    ///
+    /// let decision = limiter.check_request_str("user_123").unwrap();
+    /// assert!(decision.allowed);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`check_request`](Self::check_request).
+    pub fn check_request_str(
+        &self,
+        client_id: &str,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.check_borrowed(client_id, |key: &str| Arc::from(key))
+    }
+}
+
+// a key's share of an all-or-nothing check
+struct PlannedEntry<'a, T> {
+    client_id: &'a T,
+    observed: Option<u64>, // TAT in the map when planning, None if absent
+    held_until_nanos: u64, // hold on the entry when planning, 0 if none
+    tat_nanos: u64,        // TAT after this operation
+    count: u64,            // requests consumed for this key
+    multiplier: f64,       // share of the rate the key's classification grants
 }
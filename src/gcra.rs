@@ -0,0 +1,375 @@
+// src/gcra.rs
+
+//! Core GCRA arithmetic shared by every limiter, free of `std` and of storage
+//!
+//! [`gcra_decide`] is the conformance step of the crate's single-threaded
+//! limiters, as a pure function over one client's theoretical arrival time
+//! (TAT). [`FluxLimiter`](crate::FluxLimiter) layers debt, priorities,
+//! pressure and replenishment on the same helpers and reaches the same
+//! decisions and TATs when none of them is configured. Use it to apply the
+//! algorithm to state kept elsewhere,
+//! such as a custom database or a limiter on the other side of an FFI
+//! boundary; [`intervals`] turns a rate and burst into its parameters, and
+//! [`Precision`] controls how they are rounded to whole nanoseconds.
+//!
+//! ```rust
+//! use flux_limiter::gcra::{gcra_decide, intervals};
+//!
+//! // 1 request per second with 1 extra
+//! let (emission_interval, tolerance) = intervals(1.0, 1.0).unwrap();
+//!
+//! // a client with no stored state starts from a TAT of 0
+//! let (decision, tat) = gcra_decide(0, 0, emission_interval, tolerance);
+//! assert!(decision.is_allowed());
+//! let (decision, tat) = gcra_decide(tat, 0, emission_interval, tolerance);
+//! assert!(decision.is_allowed());
+//!
+//! // denials leave the TAT unchanged
+//! let (decision, unchanged) = gcra_decide(tat, 0, emission_interval, tolerance);
+//! assert!(!decision.is_allowed());
+//! assert_eq!(unchanged, tat);
+//! ```
+
+// dependencies
+use crate::decision::{Decision, DecisionReason, FluxLimiterDecision};
+use crate::errors::FluxLimiterError;
+
+/// Highest representable rate: one request per nanosecond.
+pub(crate) const MAX_RATE_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// How rates and bursts are converted to whole nanoseconds.
+///
+/// Emission intervals rarely divide a second evenly: 3 requests per second
+/// is 333,333,333.3ns apart. Truncating, the default, makes the effective
+/// rate slightly higher than configured, which matters at high rates where
+/// every nanosecond is a large fraction of the interval. Pick the
+/// [`Rounding`] that suits the limit, and a minimum interval to reject rates
+/// that would be too coarse to enforce faithfully.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::gcra::{Precision, Rounding};
+///
+/// // 3 req/s: truncated to 333,333,333ns, rounded up to 333,333,334ns
+/// let exact = Precision::default().rounding(Rounding::Up);
+/// assert_eq!(exact.intervals(3.0, 0.0).unwrap(), (333_333_334, 0));
+///
+/// // refuse rates above 1M req/s rather than enforce them within 1ns
+/// let coarse = Precision::default().min_emission_interval_nanos(1_000);
+/// assert!(coarse.intervals(10_000_000.0, 0.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Precision {
+    rounding: Rounding,
+    min_emission_interval_nanos: u64,
+}
+
+/// Rounding of fractional nanoseconds, see [`Precision`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Rounding {
+    /// Drop the fraction: intervals may come out shorter, so the effective
+    /// rate is at or slightly above the configured one
+    #[default]
+    Truncate,
+    /// Round to the nearest nanosecond: the effective rate is as close to
+    /// the configured one as possible
+    Nearest,
+    /// Round up: the effective rate never exceeds the configured one
+    Up,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self {
+            rounding: Rounding::default(),
+            min_emission_interval_nanos: 1,
+        }
+    }
+}
+
+impl Precision {
+    /// Builder-style: set the rounding mode ([`Rounding::Truncate`] by default)
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Builder-style: reject rates whose emission interval is below `nanos` (1ns by default)
+    ///
+    /// Such rates fail with [`FluxLimiterError::RateTooHigh`]. A minimum of
+    /// 0 is treated as 1, since a zero interval would disable limiting.
+    pub fn min_emission_interval_nanos(mut self, nanos: u64) -> Self {
+        self.min_emission_interval_nanos = nanos;
+        self
+    }
+
+    /// The rounding mode
+    pub fn rounding_mode(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// The shortest accepted emission interval, in nanoseconds
+    pub fn min_interval_nanos(&self) -> u64 {
+        self.min_emission_interval_nanos.max(1)
+    }
+
+    /// The emission interval and tolerance, in nanoseconds, of a limiter
+    /// allowing `rate_per_second` requests with `burst_capacity` extra,
+    /// converted with this precision.
+    ///
+    /// # Errors
+    ///
+    /// Returns the rate and burst validation errors of
+    /// [`FluxLimiterConfig::validate`](crate::FluxLimiterConfig::validate),
+    /// and [`FluxLimiterError::RateTooHigh`] if the interval falls below the
+    /// minimum.
+    pub fn intervals(
+        &self,
+        rate_per_second: f64,
+        burst_capacity: f64,
+    ) -> Result<(u64, u64), FluxLimiterError> {
+        let limits = Limits::new(rate_per_second, burst_capacity, *self)?;
+        Ok((limits.increment_nanos, limits.tolerance_nanos))
+    }
+
+    // convert a non-negative number of nanoseconds to a whole one; casts saturate
+    fn round_nanos(&self, nanos: f64) -> u64 {
+        let truncated = nanos as u64;
+        match self.rounding {
+            Rounding::Truncate => truncated,
+            Rounding::Nearest => (nanos + 0.5) as u64,
+            Rounding::Up if (truncated as f64) < nanos => truncated.saturating_add(1),
+            Rounding::Up => truncated,
+        }
+    }
+}
+
+// reject rates and bursts the algorithm cannot represent
+pub(crate) fn validate(rate_per_second: f64, burst_capacity: f64) -> Result<(), FluxLimiterError> {
+    if !rate_per_second.is_finite() {
+        return Err(FluxLimiterError::NonFiniteRate);
+    }
+    if !burst_capacity.is_finite() {
+        return Err(FluxLimiterError::NonFiniteBurst);
+    }
+    if rate_per_second <= 0.0 {
+        return Err(FluxLimiterError::InvalidRate);
+    }
+    if burst_capacity < 0.0 {
+        return Err(FluxLimiterError::InvalidBurst);
+    }
+    if rate_per_second > MAX_RATE_PER_SECOND {
+        return Err(FluxLimiterError::RateTooHigh);
+    }
+    Ok(())
+}
+
+// nanoseconds between conforming requests at `rate_per_second`
+pub(crate) fn emission_interval_nanos(
+    rate_per_second: f64,
+    precision: Precision,
+) -> Result<u64, FluxLimiterError> {
+    let increment_nanos = precision.round_nanos(1_000_000_000.0 / rate_per_second);
+    if increment_nanos < precision.min_interval_nanos() {
+        // a zero interval would silently disable limiting
+        return Err(FluxLimiterError::RateTooHigh);
+    }
+    Ok(increment_nanos)
+}
+
+// how far ahead of the schedule `requests` extra requests may run
+pub(crate) fn allowance_nanos(requests: f64, increment_nanos: u64, precision: Precision) -> u64 {
+    precision.round_nanos(requests * increment_nanos as f64)
+}
+
+// a request at `now_nanos` conforms unless it arrives more than
+// `allowance_nanos` ahead of the schedule held in `tat_nanos`
+pub(crate) fn conforms(now_nanos: u64, tat_nanos: u64, allowance_nanos: u64) -> bool {
+    now_nanos.saturating_add(allowance_nanos) >= tat_nanos
+}
+
+// how long a non-conforming request must wait before it would conform
+pub(crate) fn retry_after_nanos(now_nanos: u64, tat_nanos: u64, allowance_nanos: u64) -> u64 {
+    tat_nanos.saturating_sub(now_nanos.saturating_add(allowance_nanos))
+}
+
+// the TAT after admitting a request; `None` past the end of the time range
+pub(crate) fn advance_tat(now_nanos: u64, tat_nanos: u64, increment_nanos: u64) -> Option<u64> {
+    now_nanos.max(tat_nanos).checked_add(increment_nanos)
+}
+
+// approximate burst capacity left to a client whose schedule is at `tat_nanos`
+pub(crate) fn remaining_capacity(
+    now_nanos: u64,
+    tat_nanos: u64,
+    tolerance_nanos: u64,
+    rate_per_second: f64,
+    burst_capacity: f64,
+) -> f64 {
+    if now_nanos >= tat_nanos.saturating_sub(tolerance_nanos) {
+        let time_until_tat = tat_nanos.saturating_sub(now_nanos) as f64 / 1_000_000_000.0;
+        (burst_capacity - (time_until_tat * rate_per_second)).max(0.0)
+    } else {
+        0.0
+    }
+}
+
+// validated rate and burst of a limiter without priorities, shedding or debt,
+// with the intervals derived from them
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    pub(crate) rate_per_second: f64,
+    pub(crate) burst_capacity: f64,
+    pub(crate) increment_nanos: u64,
+    pub(crate) tolerance_nanos: u64,
+}
+
+impl Limits {
+    pub(crate) fn new(
+        rate_per_second: f64,
+        burst_capacity: f64,
+        precision: Precision,
+    ) -> Result<Self, FluxLimiterError> {
+        validate(rate_per_second, burst_capacity)?;
+        let increment_nanos = emission_interval_nanos(rate_per_second, precision)?;
+        Ok(Self {
+            rate_per_second,
+            burst_capacity,
+            increment_nanos,
+            tolerance_nanos: allowance_nanos(burst_capacity, increment_nanos, precision),
+        })
+    }
+
+    // core GCRA step: returns the decision and, when allowed, the new TAT to store
+    pub(crate) fn decide(
+        &self,
+        now_nanos: u64,
+        tat_nanos: u64,
+    ) -> (FluxLimiterDecision, Option<u64>) {
+        // a TAT past the end of the time range cannot be scheduled, so such requests are denied
+        let new_tat_nanos = advance_tat(now_nanos, tat_nanos, self.increment_nanos)
+            .filter(|_| conforms(now_nanos, tat_nanos, self.tolerance_nanos));
+
+        let decision = match new_tat_nanos {
+            Some(new_tat_nanos) => FluxLimiterDecision {
+                allowed: true,
+                retry_after_seconds: None,
+                remaining_capacity: Some(remaining_capacity(
+                    now_nanos,
+                    new_tat_nanos,
+                    self.tolerance_nanos,
+                    self.rate_per_second,
+                    self.burst_capacity,
+                )),
+                reset_time_nanos: new_tat_nanos,
+                limit_per_second: self.rate_per_second,
+                burst_capacity: self.burst_capacity,
+                shedding: false,
+                debt: 0.0,
+                suggested_delay_seconds: None,
+                reason: DecisionReason::Conforming,
+            },
+            None => {
+                let retry_after_nanos =
+                    retry_after_nanos(now_nanos, tat_nanos, self.tolerance_nanos);
+                FluxLimiterDecision {
+                    allowed: false,
+                    retry_after_seconds: Some(retry_after_nanos as f64 / 1_000_000_000.0),
+                    remaining_capacity: Some(0.0),
+                    reset_time_nanos: tat_nanos,
+                    limit_per_second: self.rate_per_second,
+                    burst_capacity: self.burst_capacity,
+                    shedding: false,
+                    debt: 0.0,
+                    suggested_delay_seconds: None,
+                    reason: DecisionReason::BurstExhausted,
+                }
+            }
+        };
+        (decision, new_tat_nanos)
+    }
+}
+
+/// Decide one request arriving at `now_nanos` for a client whose stored TAT
+/// is `prev_tat_nanos`, returning the decision and the TAT to store.
+///
+/// `emission_interval_nanos` is the time between conforming requests and
+/// `tolerance_nanos` how far ahead of that schedule a client may run, both
+/// as returned by [`intervals`]; an interval of 0 is treated as 1ns, the
+/// shortest the limiters accept. Pass 0 as the TAT of a client with no state.
+///
+/// Allowed requests advance the TAT by one interval from `max(prev, now)`.
+/// Denied requests, including any whose new TAT would overflow `u64`, return
+/// `prev_tat_nanos` unchanged, so storing the result unconditionally is
+/// always correct. The decision reports the rate and burst implied by the
+/// intervals, and carries no shedding, debt or pacing information.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::Decision;
+/// use flux_limiter::gcra::gcra_decide;
+///
+/// // one request per second, no burst, last admitted at t = 0
+/// match gcra_decide(1_000_000_000, 500_000_000, 1_000_000_000, 0) {
+///     (Decision::Denied(meta), _) => assert_eq!(meta.retry_after_seconds, 0.5),
+///     (Decision::Allowed(_), _) => unreachable!(),
+/// }
+/// ```
+pub fn gcra_decide(
+    prev_tat_nanos: u64,
+    now_nanos: u64,
+    emission_interval_nanos: u64,
+    tolerance_nanos: u64,
+) -> (Decision, u64) {
+    let increment_nanos = emission_interval_nanos.max(1);
+    let limits = Limits {
+        rate_per_second: 1_000_000_000.0 / increment_nanos as f64,
+        burst_capacity: tolerance_nanos as f64 / increment_nanos as f64,
+        increment_nanos,
+        tolerance_nanos,
+    };
+    let (decision, new_tat_nanos) = limits.decide(now_nanos, prev_tat_nanos);
+    (
+        Decision::from(decision),
+        new_tat_nanos.unwrap_or(prev_tat_nanos),
+    )
+}
+
+/// The emission interval and tolerance, in nanoseconds, of a limiter
+/// allowing `rate_per_second` requests with `burst_capacity` extra.
+///
+/// These are the values every limiter in this crate derives from a config
+/// with the default [`Precision`]; see [`Precision::intervals`] for others.
+///
+/// # Errors
+///
+/// Returns the rate and burst validation errors of
+/// [`FluxLimiterConfig::validate`](crate::FluxLimiterConfig::validate).
+pub fn intervals(
+    rate_per_second: f64,
+    burst_capacity: f64,
+) -> Result<(u64, u64), FluxLimiterError> {
+    Precision::default().intervals(rate_per_second, burst_capacity)
+}
+
+// whether a client has been fully recovered for at least `max_stale_nanos`
+pub(crate) fn is_recovered(
+    now_nanos: u64,
+    tat_nanos: u64,
+    tolerance_nanos: u64,
+    max_stale_nanos: u64,
+) -> bool {
+    tat_nanos.saturating_add(tolerance_nanos) <= now_nanos.saturating_sub(max_stale_nanos)
+}
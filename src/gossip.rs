@@ -0,0 +1,315 @@
+// src/gossip.rs
+
+//! Approximate global limits by exchanging consumption with peers (gossip feature)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use dashmap::DashMap;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::io;
+use std::marker::PhantomData;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// keys per summary, keeping encoded UDP datagrams well below 64 KiB
+const DEFAULT_MAX_KEYS_PER_SUMMARY: usize = 256;
+
+/// Requests one node admitted per key since its previous summary.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConsumptionSummary<T> {
+    /// Identifier of the node that admitted the requests
+    pub node_id: u64,
+    /// Position of the summary among those sent by the node, starting at 1
+    pub sequence: u64,
+    /// Client keys and the number of requests admitted for each
+    pub requests: Vec<(T, u64)>,
+}
+
+/// Carries [`ConsumptionSummary`]s between the nodes of a [`GossipLimiter`]
+/// deployment.
+///
+/// Delivery may be lossy, duplicated or reordered: lost summaries only cause
+/// over-admission, and duplicates and summaries older than one already seen
+/// from the same node are ignored. [`UdpTransport`] sends to a fixed peer
+/// list; implement the trait to use a message bus or service mesh instead.
+pub trait GossipTransport<T>: Send + Sync {
+    /// Send `summary` to every peer
+    fn send(&self, summary: &ConsumptionSummary<T>) -> io::Result<()>;
+
+    /// Return the summaries received since the previous call, without blocking
+    fn receive(&self) -> io::Result<Vec<ConsumptionSummary<T>>>;
+}
+
+/// A rate limiter sharing one limit per key across several instances.
+///
+/// Every instance checks requests against its own [`FluxLimiter`], configured
+/// with the global rate and burst, and counts what it admits. Calling
+/// [`sync`](Self::sync) periodically sends those counts to the peers and
+/// charges the counts they sent against the local schedules, as if the
+/// peers' requests had been checked here. Between syncs each instance only
+/// sees its own traffic, so a deployment of `n` instances admits up to about
+/// `n - 1` extra sync intervals' worth of each key's traffic, plus anything
+/// lost in transit. Charges never push a key past an exhausted burst.
+///
+/// Use this when a central store is unwanted and slight over-admission is
+/// acceptable; see [`StoreBackedLimiter`](crate::StoreBackedLimiter) for
+/// exact global limits.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{
+///     FluxLimiter, FluxLimiterConfig, GossipLimiter, SystemClock, UdpTransport,
+/// };
+///
+/// let transport = UdpTransport::bind("127.0.0.1:0", Vec::<std::net::SocketAddr>::new()).unwrap();
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(100.0, 50.0), SystemClock).unwrap();
+/// let gossip = GossipLimiter::new(limiter, transport);
+///
+/// assert!(gossip.check_request("user_123".to_string()).unwrap().allowed);
+/// // e.g. every 100ms, from a background task
+/// gossip.sync().unwrap();
+/// ```
+pub struct GossipLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    limiter: FluxLimiter<T, C>,
+    transport: Box<dyn GossipTransport<T>>,
+    node_id: u64,
+    sequence: AtomicU64,
+    max_keys_per_summary: usize,
+    // requests admitted locally since the last sync
+    consumed: DashMap<T, u64>,
+    // latest sequence seen from each peer
+    peers: DashMap<u64, u64>,
+}
+
+impl<T, C> GossipLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    /// Share `limiter`'s limits with the peers reachable through `transport`.
+    ///
+    /// The node gets a random identifier, so restarted instances are never
+    /// mistaken for their earlier selves.
+    pub fn new(limiter: FluxLimiter<T, C>, transport: impl GossipTransport<T> + 'static) -> Self {
+        Self {
+            limiter,
+            transport: Box::new(transport),
+            node_id: RandomState::new().hash_one(std::process::id()),
+            sequence: AtomicU64::new(0),
+            max_keys_per_summary: DEFAULT_MAX_KEYS_PER_SUMMARY,
+            consumed: DashMap::new(),
+            peers: DashMap::new(),
+        }
+    }
+
+    /// Builder-style: split syncs into summaries of at most `max_keys` keys,
+    /// e.g. to fit a transport's message size. Values below 1 are treated as 1.
+    pub fn max_keys_per_summary(mut self, max_keys: usize) -> Self {
+        self.max_keys_per_summary = max_keys.max(1);
+        self
+    }
+
+    /// Builder-style: identify this node as `node_id` instead of a random
+    /// identifier, e.g. for reproducible summaries in tests. Every node
+    /// sharing a transport needs a distinct identifier.
+    pub fn with_node_id(mut self, node_id: u64) -> Self {
+        self.node_id = node_id;
+        self
+    }
+
+    /// This node's identifier in the summaries it sends
+    pub fn node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    /// The local limiter
+    pub fn limiter(&self) -> &FluxLimiter<T, C> {
+        &self.limiter
+    }
+
+    /// Check a request against the local limiter, counting it for the peers
+    /// if it is allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request`].
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let decision = self.limiter.check_request(client_id.clone())?;
+        if decision.allowed {
+            *self.consumed.entry(client_id).or_default() += 1;
+        }
+        Ok(decision)
+    }
+
+    /// Take the requests admitted since the previous call as summaries to
+    /// send, at most [`max_keys_per_summary`](Self::max_keys_per_summary)
+    /// keys each. Empty if nothing was admitted.
+    ///
+    /// [`sync`](Self::sync) calls this; use it directly with a transport that
+    /// does not fit [`GossipTransport`].
+    pub fn take_summaries(&self) -> Vec<ConsumptionSummary<T>> {
+        let keys: Vec<T> = self
+            .consumed
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        // remove one key at a time so checks in between are counted next time
+        let requests: Vec<(T, u64)> = keys
+            .into_iter()
+            .filter_map(|key| self.consumed.remove(&key))
+            .collect();
+
+        requests
+            .chunks(self.max_keys_per_summary)
+            .map(|chunk| ConsumptionSummary {
+                node_id: self.node_id,
+                sequence: self.sequence.fetch_add(1, Ordering::Relaxed) + 1,
+                requests: chunk.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Charge a peer's summary against the local limiter.
+    ///
+    /// Returns whether it was applied: summaries sent by this node, and
+    /// duplicates or older summaries from a peer whose later summary was
+    /// already applied, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails, and
+    /// [`FluxLimiterError::CapacityExceeded`] if the summary names a client
+    /// this node does not track and `max_clients` clients are already
+    /// tracked. Clients charged before the error keep their charge.
+    pub fn merge(&self, summary: &ConsumptionSummary<T>) -> Result<bool, FluxLimiterError> {
+        if summary.node_id == self.node_id {
+            return Ok(false);
+        }
+        {
+            let mut latest = self.peers.entry(summary.node_id).or_default();
+            if summary.sequence <= *latest {
+                return Ok(false);
+            }
+            *latest = summary.sequence;
+        }
+
+        for (client_id, requests) in &summary.requests {
+            self.limiter.charge(client_id, *requests as f64)?;
+        }
+        Ok(true)
+    }
+
+    /// Send this node's consumption to the peers and apply theirs.
+    ///
+    /// Call it periodically; the interval bounds how stale each node's view
+    /// of the others is. Returns the number of peer summaries applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::GossipError`] if the transport fails and
+    /// the errors of [`merge`](Self::merge) for received summaries. Summaries
+    /// not sent or applied because of an error are dropped.
+    pub fn sync(&self) -> Result<usize, FluxLimiterError> {
+        let mut send_result = Ok(());
+        for summary in self.take_summaries() {
+            if let Err(err) = self.transport.send(&summary) {
+                send_result = Err(FluxLimiterError::GossipError(err));
+            }
+        }
+
+        // apply what arrived even if sending failed
+        let received = self
+            .transport
+            .receive()
+            .map_err(FluxLimiterError::GossipError)?;
+        let mut applied = 0;
+        for summary in &received {
+            if self.merge(summary)? {
+                applied += 1;
+            }
+        }
+        send_result.map(|()| applied)
+    }
+}
+
+/// A [`GossipTransport`] exchanging JSON datagrams with a fixed list of peers.
+///
+/// Each summary is sent as one datagram to every peer; datagrams that cannot
+/// be decoded are skipped. UDP gives no delivery guarantees, which only costs
+/// accuracy (see [`GossipLimiter`]).
+#[derive(Debug)]
+pub struct UdpTransport<T> {
+    socket: UdpSocket,
+    peers: Vec<SocketAddr>,
+    _keys: PhantomData<fn(T) -> T>,
+}
+
+impl<T> UdpTransport<T> {
+    /// Listen on `addr` and send to `peers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the socket cannot be bound or made
+    /// non-blocking, or if a peer address cannot be resolved.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        peers: impl IntoIterator<Item = impl ToSocketAddrs>,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        let mut resolved = Vec::new();
+        for peer in peers {
+            resolved.extend(peer.to_socket_addrs()?);
+        }
+        Ok(Self {
+            socket,
+            peers: resolved,
+            _keys: PhantomData,
+        })
+    }
+
+    /// The address the transport listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
+
+impl<T> GossipTransport<T> for UdpTransport<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn send(&self, summary: &ConsumptionSummary<T>) -> io::Result<()> {
+        let datagram = serde_json::to_vec(summary)?;
+        for peer in &self.peers {
+            self.socket.send_to(&datagram, peer)?;
+        }
+        Ok(())
+    }
+
+    fn receive(&self) -> io::Result<Vec<ConsumptionSummary<T>>> {
+        let mut summaries = Vec::new();
+        let mut buffer = vec![0; 65_536];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, _)) => {
+                    if let Ok(summary) = serde_json::from_slice(&buffer[..len]) {
+                        summaries.push(summary);
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(summaries),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
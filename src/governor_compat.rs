@@ -0,0 +1,410 @@
+// src/governor_compat.rs
+
+//! `governor`-style quotas and limiter methods over `FluxLimiter`, for migrating call sites
+
+// dependencies
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::config::{FailurePolicy, FluxLimiterConfig};
+use crate::decision::FluxLimiterDecision;
+use crate::flux_limiter::FluxLimiter;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+/// A `governor`-style quota: a burst of `burst_size` requests, with one
+/// request's capacity replenished every `replenish_interval`.
+///
+/// Converts into the equivalent [`FluxLimiterConfig`]: a rate of one request
+/// per interval and a burst of `burst_size - 1` on top of the request that
+/// always fits at an idle client.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, Quota};
+/// use std::num::NonZeroU32;
+///
+/// let quota = Quota::per_second(NonZeroU32::new(10).unwrap())
+///     .allow_burst(NonZeroU32::new(20).unwrap());
+///
+/// assert_eq!(FluxLimiterConfig::from(quota), FluxLimiterConfig::new(10.0, 19.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quota {
+    max_burst: NonZeroU32,
+    replenish_1_per: Duration,
+}
+
+impl Quota {
+    /// `max_burst` requests per second, all available at once
+    pub const fn per_second(max_burst: NonZeroU32) -> Self {
+        Self::per(1_000_000_000, max_burst)
+    }
+
+    /// `max_burst` requests per minute, all available at once
+    pub const fn per_minute(max_burst: NonZeroU32) -> Self {
+        Self::per(60 * 1_000_000_000, max_burst)
+    }
+
+    /// `max_burst` requests per hour, all available at once
+    pub const fn per_hour(max_burst: NonZeroU32) -> Self {
+        Self::per(3_600 * 1_000_000_000, max_burst)
+    }
+
+    /// One request per `replenish_1_per`, with no burst; `None` for a zero
+    /// interval
+    pub const fn with_period(replenish_1_per: Duration) -> Option<Self> {
+        if replenish_1_per.is_zero() {
+            return None;
+        }
+        Some(Self {
+            max_burst: NonZeroU32::MIN,
+            replenish_1_per,
+        })
+    }
+
+    /// The same replenishment rate, allowing `max_burst` requests at once
+    pub const fn allow_burst(self, max_burst: NonZeroU32) -> Self {
+        Self { max_burst, ..self }
+    }
+
+    /// Requests allowed at once
+    pub const fn burst_size(&self) -> NonZeroU32 {
+        self.max_burst
+    }
+
+    /// Time to replenish one request's capacity
+    pub const fn replenish_interval(&self) -> Duration {
+        self.replenish_1_per
+    }
+
+    /// Time for an exhausted burst to be fully replenished
+    pub fn burst_size_replenished_in(&self) -> Duration {
+        self.replenish_1_per * self.max_burst.get()
+    }
+
+    // `max_burst` requests spread over `period_nanos`, at most one per nanosecond
+    const fn per(period_nanos: u64, max_burst: NonZeroU32) -> Self {
+        let replenish_nanos = period_nanos / max_burst.get() as u64;
+        Self {
+            max_burst,
+            replenish_1_per: Duration::from_nanos(if replenish_nanos == 0 {
+                1
+            } else {
+                replenish_nanos
+            }),
+        }
+    }
+}
+
+impl From<Quota> for FluxLimiterConfig {
+    fn from(quota: Quota) -> Self {
+        // governor's burst includes the request every idle client may make
+        FluxLimiterConfig::new(
+            1_000_000_000.0 / quota.replenish_1_per.as_nanos() as f64,
+            f64::from(quota.max_burst.get() - 1),
+        )
+    }
+}
+
+/// A denial from [`RateLimiter`]: the key may retry at
+/// [`earliest_possible`](Self::earliest_possible).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NotUntil {
+    quota: Quota,
+    earliest_possible: Timestamp,
+    wait_time: Duration,
+}
+
+impl NotUntil {
+    /// The quota the key was limited by
+    pub fn quota(&self) -> Quota {
+        self.quota
+    }
+
+    /// The earliest time the request would be allowed, on the limiter's
+    /// clock; the epoch if the clock failed
+    pub fn earliest_possible(&self) -> Timestamp {
+        self.earliest_possible
+    }
+
+    /// Time from `from` until [`earliest_possible`](Self::earliest_possible),
+    /// zero if that has passed
+    pub fn wait_time_from(&self, from: Timestamp) -> Duration {
+        self.earliest_possible
+            .checked_duration_since(from)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Time to wait as of the check, which stays meaningful when the clock
+    /// failed
+    pub fn wait_time(&self) -> Duration {
+        self.wait_time
+    }
+}
+
+impl fmt::Display for NotUntil {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rate-limited; retry after {:.3}s",
+            self.wait_time.as_secs_f64()
+        )
+    }
+}
+
+impl Error for NotUntil {}
+
+/// A request for more requests at once than the quota's burst can ever
+/// allow; holds the burst size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsufficientCapacity(pub u32);
+
+impl fmt::Display for InsufficientCapacity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "requested more requests than the burst of {} allows",
+            self.0
+        )
+    }
+}
+
+impl Error for InsufficientCapacity {}
+
+/// A limiter with `governor`'s call signatures, backed by a [`FluxLimiter`].
+///
+/// Lets code written against `governor` switch crates by swapping imports:
+/// [`keyed`](Self::keyed) and [`check_key`](Self::check_key) for keyed
+/// limiters, [`direct`](Self::direct) and [`check`](Self::check) for a
+/// single shared limit, `_n` variants for several requests at once, and,
+/// with the `tokio` feature, `until_*_ready` to wait for capacity. Both
+/// crates implement GCRA, so a [`Quota`] admits the same requests either
+/// way.
+///
+/// Unlike `governor`'s, the methods read the limiter's [`Clock`], and a
+/// clock failure counts as a denial for one replenish interval. Reach the
+/// underlying limiter with [`limiter`](Self::limiter) to adopt the rest of
+/// this crate's API incrementally.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{Quota, RateLimiter};
+/// use std::num::NonZeroU32;
+///
+/// let limiter = RateLimiter::keyed(Quota::per_second(NonZeroU32::new(2).unwrap()));
+///
+/// assert!(limiter.check_key(&"user_123").is_ok());
+/// assert!(limiter.check_key(&"user_123").is_ok());
+///
+/// let negative = limiter.check_key(&"user_123").unwrap_err();
+/// assert!(negative.wait_time().as_millis() > 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter<K, C = SystemClock>
+where
+    K: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    limiter: FluxLimiter<K, C>,
+    quota: Quota,
+    clock: C,
+}
+
+/// A [`RateLimiter`] enforcing one limit for all callers
+pub type DirectRateLimiter<C = SystemClock> = RateLimiter<(), C>;
+
+impl<K> RateLimiter<K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// A limiter applying `quota` to each key separately
+    pub fn keyed(quota: Quota) -> Self {
+        Self::keyed_with_clock(quota, SystemClock)
+    }
+}
+
+impl DirectRateLimiter {
+    /// A limiter applying `quota` to all callers together
+    pub fn direct(quota: Quota) -> Self {
+        Self::keyed(quota)
+    }
+}
+
+impl<C> DirectRateLimiter<C>
+where
+    C: Clock + Clone,
+{
+    /// A direct limiter reading `clock`
+    pub fn direct_with_clock(quota: Quota, clock: C) -> Self {
+        Self::keyed_with_clock(quota, clock)
+    }
+
+    /// Check one request against the shared limit
+    pub fn check(&self) -> Result<(), NotUntil> {
+        self.check_key(&())
+    }
+
+    /// Check `n` requests against the shared limit, all allowed or none.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InsufficientCapacity`] if `n` exceeds the burst size.
+    pub fn check_n(&self, n: NonZeroU32) -> Result<Result<(), NotUntil>, InsufficientCapacity> {
+        self.check_key_n(&(), n)
+    }
+
+    /// Wait until one request fits the shared limit, then take it
+    #[cfg(feature = "tokio")]
+    pub async fn until_ready(&self) {
+        self.until_key_ready(&()).await
+    }
+
+    /// Wait until `n` requests fit the shared limit, then take them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InsufficientCapacity`] at once if `n` exceeds the burst size.
+    #[cfg(feature = "tokio")]
+    pub async fn until_n_ready(&self, n: NonZeroU32) -> Result<(), InsufficientCapacity> {
+        self.until_key_n_ready(&(), n).await
+    }
+}
+
+impl<K, C> RateLimiter<K, C>
+where
+    K: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    /// A keyed limiter reading `clock`
+    pub fn keyed_with_clock(quota: Quota, clock: C) -> Self {
+        // denials carry their own retry time, so a failing clock denies
+        let config = FluxLimiterConfig::from(quota).failure_policy(FailurePolicy::FailClosed);
+        // a nonzero interval and burst always give a rate and burst in range
+        let limiter = FluxLimiter::with_config(config, clock.clone())
+            .expect("quotas convert to valid configs");
+        Self {
+            limiter,
+            quota,
+            clock,
+        }
+    }
+
+    /// The applied quota
+    pub fn quota(&self) -> Quota {
+        self.quota
+    }
+
+    /// The limiter doing the work
+    pub fn limiter(&self) -> &FluxLimiter<K, C> {
+        &self.limiter
+    }
+
+    /// Check one request for `key`
+    pub fn check_key(&self, key: &K) -> Result<(), NotUntil> {
+        let now = self.clock.now().ok();
+        let decision = now.and_then(|now| {
+            self.limiter
+                .check_request_at(key.clone(), now.as_nanos())
+                .ok()
+        });
+        self.outcome(now, decision)
+    }
+
+    /// Check `n` requests for `key`, all allowed or none.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InsufficientCapacity`] if `n` exceeds the burst size, as
+    /// such a request could never be allowed.
+    pub fn check_key_n(
+        &self,
+        key: &K,
+        n: NonZeroU32,
+    ) -> Result<Result<(), NotUntil>, InsufficientCapacity> {
+        if n > self.quota.max_burst {
+            return Err(InsufficientCapacity(self.quota.max_burst.get()));
+        }
+        let now = self.clock.now().ok();
+        let keys = vec![key.clone(); n.get() as usize];
+        let decision = now
+            .and_then(|_| self.limiter.check_all(&keys).ok())
+            .map(|multi| {
+                // the first denial says when the batch fits, else any allowance
+                let index = multi
+                    .decisions
+                    .iter()
+                    .position(|(_, decision)| !decision.allowed)
+                    .unwrap_or(0);
+                multi.decisions[index].1
+            });
+        Ok(self.outcome(now, decision))
+    }
+
+    /// Wait until a request for `key` is allowed, then take it
+    #[cfg(feature = "tokio")]
+    pub async fn until_key_ready(&self, key: &K) {
+        while let Err(negative) = self.check_key(key) {
+            tokio::time::sleep(negative.wait_time).await;
+        }
+    }
+
+    /// Wait until `n` requests for `key` are allowed, then take them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InsufficientCapacity`] at once if `n` exceeds the burst size.
+    #[cfg(feature = "tokio")]
+    pub async fn until_key_n_ready(
+        &self,
+        key: &K,
+        n: NonZeroU32,
+    ) -> Result<(), InsufficientCapacity> {
+        while let Err(negative) = self.check_key_n(key, n)? {
+            tokio::time::sleep(negative.wait_time).await;
+        }
+        Ok(())
+    }
+
+    /// Forget keys whose capacity has fully replenished
+    pub fn retain_recent(&self) {
+        // a failing clock only delays the cleanup to the next call
+        let _ = self.limiter.cleanup_stale_clients(0);
+    }
+
+    /// Number of keys tracked
+    pub fn len(&self) -> usize {
+        self.limiter.client_state.len()
+    }
+
+    /// Whether no keys are tracked
+    pub fn is_empty(&self) -> bool {
+        self.limiter.client_state.is_empty()
+    }
+
+    // turn a decision made at `now` into governor's result
+    fn outcome(
+        &self,
+        now: Option<Timestamp>,
+        decision: Option<FluxLimiterDecision>,
+    ) -> Result<(), NotUntil> {
+        let retry_after_seconds = match decision {
+            Some(decision) if decision.allowed => return Ok(()),
+            Some(decision) => decision.retry_after_seconds,
+            None => None,
+        };
+        let wait_time =
+            retry_after_seconds.map_or(self.quota.replenish_1_per, Duration::from_secs_f64);
+        let earliest_possible = now
+            .and_then(|now| now.checked_add(wait_time))
+            .unwrap_or_default();
+        Err(NotUntil {
+            quota: self.quota,
+            earliest_possible,
+            wait_time,
+        })
+    }
+}
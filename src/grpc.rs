@@ -0,0 +1,133 @@
+// src/grpc.rs
+
+//! tonic interceptor for gRPC servers (`tonic` feature)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::flux_limiter::FluxLimiter;
+use crate::key_extractor::{HeaderKey, KeyExtractor, PeerIp, RequestInfo};
+use std::net::IpAddr;
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::{Code, Request, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+/// Where the interceptor takes the client ID from.
+///
+/// For other keys, e.g. a peer address behind trusted proxies, use
+/// [`FluxLimiterInterceptor::with_extractor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrpcKey {
+    /// The peer's IP address
+    PeerAddr,
+    /// The value of an ASCII metadata header, e.g. `x-api-key`
+    Metadata(String),
+}
+
+impl<T> KeyExtractor<Request<T>> for GrpcKey {
+    fn extract(&self, request: &Request<T>) -> Option<String> {
+        match self {
+            GrpcKey::PeerAddr => PeerIp::new().extract(request),
+            GrpcKey::Metadata(name) => HeaderKey::new(name.as_str()).extract(request),
+        }
+    }
+}
+
+// metadata stands in for headers
+impl<T> RequestInfo for Request<T> {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.remote_addr().map(|addr| addr.ip())
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.metadata().get(name)?.to_str().ok()
+    }
+}
+
+/// A tonic [`Interceptor`] that rate limits incoming calls.
+///
+/// Denied calls fail with `RESOURCE_EXHAUSTED` and a `google.rpc.RetryInfo`
+/// detail carrying the retry delay. Allowed calls carry the
+/// [`FluxLimiterDecision`](crate::FluxLimiterDecision) in their request
+/// extensions so handlers can read it. Calls without a usable key (no peer
+/// address, missing or non-ASCII header) are passed through unlimited.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterInterceptor, GrpcKey, SystemClock};
+/// use std::sync::Arc;
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+/// let interceptor = FluxLimiterInterceptor::new(limiter, GrpcKey::Metadata("x-api-key".into()));
+/// // MyServiceServer::with_interceptor(service, interceptor)
+/// ```
+pub struct FluxLimiterInterceptor<C = SystemClock>
+where
+    C: Clock,
+{
+    limiter: Arc<FluxLimiter<String, C>>,
+    key: Arc<dyn KeyExtractor<Request<()>>>,
+}
+
+impl<C> FluxLimiterInterceptor<C>
+where
+    C: Clock,
+{
+    /// Create an interceptor that checks `limiter` using the given key source
+    pub fn new(limiter: Arc<FluxLimiter<String, C>>, key: GrpcKey) -> Self {
+        Self::with_extractor(limiter, key)
+    }
+
+    /// Create an interceptor that checks `limiter` with keys from `extractor`
+    pub fn with_extractor(
+        limiter: Arc<FluxLimiter<String, C>>,
+        extractor: impl KeyExtractor<Request<()>> + 'static,
+    ) -> Self {
+        Self {
+            limiter,
+            key: Arc::new(extractor),
+        }
+    }
+}
+
+impl<C> Clone for FluxLimiterInterceptor<C>
+where
+    C: Clock,
+{
+    fn clone(&self) -> Self {
+        Self {
+            limiter: Arc::clone(&self.limiter),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<C> Interceptor for FluxLimiterInterceptor<C>
+where
+    C: Clock,
+{
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let Some(client_id) = self.key.extract(&request) else {
+            return Ok(request);
+        };
+
+        let decision = self
+            .limiter
+            .check_request_ref(client_id.as_str())
+            .map_err(|_| Status::unavailable("rate limiter unavailable"))?;
+
+        if !decision.allowed {
+            let retry_delay = decision.retry_after().unwrap_or_default();
+            return Err(Status::with_error_details(
+                Code::ResourceExhausted,
+                "rate limit exceeded",
+                ErrorDetails::with_retry_info(Some(retry_delay)),
+            ));
+        }
+
+        request.extensions_mut().insert(decision);
+        Ok(request)
+    }
+}
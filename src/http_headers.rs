@@ -0,0 +1,85 @@
+// src/http_headers.rs
+
+//! Rate limit headers and 429 responses for `http` crate types (http feature)
+
+// dependencies
+use crate::decision::FluxLimiterDecision;
+use http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use http::{Response, StatusCode};
+
+const RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+impl FluxLimiterDecision {
+    /// Write the decision's rate limit headers into `headers`.
+    ///
+    /// Sets `X-RateLimit-Limit` (requests per second), `X-RateLimit-Remaining`
+    /// (whole requests left in the burst), `X-RateLimit-Reset` (Unix time in
+    /// seconds, rounded up, assuming a clock on the Unix epoch such as
+    /// [`SystemClock`](crate::SystemClock)) and, for denials,
+    /// `Retry-After` in whole seconds, rounded up so clients never retry
+    /// early. Existing values are replaced; headers without a value to report
+    /// (e.g. after a clock failure) are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use http::HeaderMap;
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// limiter.check_request("user_123").unwrap().apply_headers(&mut headers);
+    /// assert_eq!(headers["retry-after"], "1");
+    /// assert_eq!(headers["x-ratelimit-remaining"], "0");
+    /// ```
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        if let Ok(limit) = HeaderValue::try_from(self.limit_per_second.to_string()) {
+            headers.insert(RATE_LIMIT_LIMIT, limit);
+        }
+        if let Some(remaining) = self.remaining_capacity {
+            headers.insert(
+                RATE_LIMIT_REMAINING,
+                HeaderValue::from(remaining.floor() as u64),
+            );
+        }
+        // decisions made without a clock reading carry no reset time
+        if self.reset_time_nanos > 0 {
+            let reset_seconds = self.reset_time_nanos.div_ceil(1_000_000_000);
+            headers.insert(RATE_LIMIT_RESET, HeaderValue::from(reset_seconds));
+        }
+        if let Some(retry_after) = self.retry_after_delta_seconds() {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+    }
+
+    /// Build a `429 Too Many Requests` response carrying the decision's headers.
+    ///
+    /// `body` receives the decision, so the body can describe the limit (e.g.
+    /// as JSON with the `serde` feature).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), SystemClock).unwrap();
+    /// limiter.check_request("user_123").unwrap();
+    ///
+    /// let decision = limiter.check_request("user_123").unwrap();
+    /// let response = decision.to_429_response(|decision| {
+    ///     format!("retry in {:.1}s", decision.retry_after_seconds.unwrap_or(0.0))
+    /// });
+    /// assert_eq!(response.status(), 429);
+    /// assert_eq!(response.headers()["retry-after"], "1");
+    /// ```
+    pub fn to_429_response<B>(&self, body: impl FnOnce(&Self) -> B) -> Response<B> {
+        let mut response = Response::new(body(self));
+        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+        self.apply_headers(response.headers_mut());
+        response
+    }
+}
@@ -0,0 +1,76 @@
+// src/intern.rs
+
+//! Shared string keys for limiters keyed by `Arc<str>`
+
+// dependencies
+use dashmap::DashMap;
+use std::sync::Arc;
+
+/// Hands out one shared `Arc<str>` per distinct key.
+///
+/// Limiters keyed by `Arc<str>` store a pointer per client instead of a
+/// string, and every copy of a key (in other limiters, shards, the expiry
+/// wheel, events and snapshots) shares one allocation. Interning pays off
+/// when the same long keys, such as API tokens, are tracked by several
+/// limiters; for a single limiter,
+/// [`FluxLimiter::check_request_str`](crate::FluxLimiter::check_request_str)
+/// already allocates each key once.
+///
+/// The interner keeps every key it has handed out until
+/// [`prune`](Self::prune) drops those no limiter holds any more, so run it
+/// after cleaning up stale clients.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, KeyInterner, SystemClock};
+/// use std::sync::Arc;
+///
+/// let interner = KeyInterner::new();
+/// let per_second = FluxLimiter::<Arc<str>, _>::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock).unwrap();
+/// let per_hour = FluxLimiter::<Arc<str>, _>::with_config(FluxLimiterConfig::per_hour(1000), SystemClock).unwrap();
+///
+/// let key = interner.intern("tok_3b1f...");
+/// per_second.check_request(key.clone()).unwrap();
+/// per_hour.check_request(key).unwrap();
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyInterner {
+    keys: DashMap<Arc<str>, ()>,
+}
+
+impl KeyInterner {
+    /// Create an empty interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The shared allocation for `key`, created on first use
+    pub fn intern(&self, key: &str) -> Arc<str> {
+        if let Some(entry) = self.keys.get(key) {
+            return Arc::clone(entry.key());
+        }
+        // another thread may have interned the key meanwhile; keep the first
+        let entry = self.keys.entry(Arc::from(key)).or_insert(());
+        Arc::clone(entry.key())
+    }
+
+    /// Drop keys held by nobody but the interner, returning how many were
+    /// removed
+    pub fn prune(&self) -> usize {
+        let before = self.keys.len();
+        self.keys.retain(|key, _| Arc::strong_count(key) > 1);
+        before.saturating_sub(self.keys.len())
+    }
+
+    /// Number of interned keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether no keys are interned
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
@@ -0,0 +1,238 @@
+// src/ip_prefix.rs
+
+//! IP network prefixes used as client keys
+
+// dependencies
+use crate::errors::FluxLimiterError;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// An IP network, e.g. `203.0.113.0/24`, usable as a client key.
+///
+/// Limiting by prefix instead of by address stops clients from dodging a
+/// limit by rotating addresses: an IPv6 host typically controls a whole /64,
+/// and a NAT pool or small hosting block shares a /24. Host bits are cleared
+/// on construction, so every address in the network yields an equal key.
+/// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) are treated as IPv4.
+///
+/// Use [`IpPrefixer`] to map addresses to their prefix with per-family
+/// lengths.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, IpPrefix, SystemClock};
+///
+/// let limiter =
+///     FluxLimiter::<IpPrefix, _>::with_config(FluxLimiterConfig::new(10.0, 5.0), SystemClock)
+///         .unwrap();
+///
+/// let first = IpPrefix::new("203.0.113.7".parse().unwrap(), 24).unwrap();
+/// let second = IpPrefix::new("203.0.113.200".parse().unwrap(), 24).unwrap();
+/// assert_eq!(first, second);
+/// assert_eq!(first.to_string(), "203.0.113.0/24");
+///
+/// limiter.check_request(first).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct IpPrefix {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpPrefix {
+    /// The network of the first `prefix_len` bits of `addr`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::InvalidIpPrefix`] if `prefix_len` is longer
+    /// than the address (32 bits for IPv4, 128 for IPv6).
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, FluxLimiterError> {
+        let addr = addr.to_canonical();
+        if prefix_len > max_prefix_len(addr) {
+            return Err(FluxLimiterError::InvalidIpPrefix(format!(
+                "{addr}/{prefix_len}"
+            )));
+        }
+        Ok(Self::clamped(addr, prefix_len))
+    }
+
+    // like `new`, treating overlong prefixes as a single address
+    pub(crate) fn clamped(addr: IpAddr, prefix_len: u8) -> Self {
+        let addr = addr.to_canonical();
+        let prefix_len = prefix_len.min(max_prefix_len(addr));
+        let network = match addr {
+            IpAddr::V4(addr) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len));
+                IpAddr::V4(Ipv4Addr::from(u32::from(addr) & mask.unwrap_or(0)))
+            }
+            IpAddr::V6(addr) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len));
+                IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask.unwrap_or(0)))
+            }
+        };
+        Self {
+            network,
+            prefix_len,
+        }
+    }
+
+    /// The network address, with host bits cleared
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    /// Number of leading bits that identify the network
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Whether `addr` lies in this network
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        let addr = addr.to_canonical();
+        addr.is_ipv4() == self.network.is_ipv4()
+            && Self::clamped(addr, self.prefix_len).network == self.network
+    }
+}
+
+// address length in bits
+fn max_prefix_len(addr: IpAddr) -> u8 {
+    if addr.is_ipv4() { 32 } else { 128 }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+impl FromStr for IpPrefix {
+    type Err = FluxLimiterError;
+
+    /// Parse `address/prefix_len`, or a bare address as a single-address
+    /// network. Host bits may be set and are cleared.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || FluxLimiterError::InvalidIpPrefix(s.to_owned());
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len.parse().map_err(|_| invalid())?,
+            None => max_prefix_len(addr.to_canonical()),
+        };
+        Self::new(addr, prefix_len).map_err(|_| invalid())
+    }
+}
+
+impl From<IpAddr> for IpPrefix {
+    /// A network holding just `addr`
+    fn from(addr: IpAddr) -> Self {
+        Self::clamped(addr, 128)
+    }
+}
+
+impl TryFrom<String> for IpPrefix {
+    type Error = FluxLimiterError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<IpPrefix> for String {
+    fn from(prefix: IpPrefix) -> Self {
+        prefix.to_string()
+    }
+}
+
+#[cfg(feature = "ipnet")]
+impl From<ipnet::IpNet> for IpPrefix {
+    fn from(net: ipnet::IpNet) -> Self {
+        Self::clamped(net.addr(), net.prefix_len())
+    }
+}
+
+#[cfg(feature = "ipnet")]
+impl From<IpPrefix> for ipnet::IpNet {
+    fn from(prefix: IpPrefix) -> Self {
+        // the prefix length always fits the address family
+        ipnet::IpNet::new_assert(prefix.network, prefix.prefix_len)
+    }
+}
+
+/// Maps addresses to the network they are limited as.
+///
+/// Defaults to /24 for IPv4 and /64 for IPv6, the usual allocation to a
+/// single customer. Use [`PeerIp::aggregate`](crate::PeerIp::aggregate) to
+/// apply it to request keys.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::IpPrefixer;
+///
+/// let prefixer = IpPrefixer::default();
+/// let key = prefixer.prefix("2001:db8:1:2:aaaa::1".parse().unwrap());
+/// assert_eq!(key.to_string(), "2001:db8:1:2::/64");
+///
+/// // coarser IPv6 grouping, per-address IPv4
+/// let prefixer = IpPrefixer::new(32, 56).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpPrefixer {
+    ipv4_prefix_len: u8,
+    ipv6_prefix_len: u8,
+}
+
+impl Default for IpPrefixer {
+    fn default() -> Self {
+        Self {
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 64,
+        }
+    }
+}
+
+impl IpPrefixer {
+    /// Map IPv4 addresses to `/ipv4_prefix_len` and IPv6 to `/ipv6_prefix_len`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::InvalidIpPrefix`] if a length exceeds its
+    /// address family (32 or 128 bits).
+    pub fn new(ipv4_prefix_len: u8, ipv6_prefix_len: u8) -> Result<Self, FluxLimiterError> {
+        if ipv4_prefix_len > 32 {
+            return Err(FluxLimiterError::InvalidIpPrefix(format!(
+                "/{ipv4_prefix_len} for IPv4"
+            )));
+        }
+        if ipv6_prefix_len > 128 {
+            return Err(FluxLimiterError::InvalidIpPrefix(format!(
+                "/{ipv6_prefix_len} for IPv6"
+            )));
+        }
+        Ok(Self {
+            ipv4_prefix_len,
+            ipv6_prefix_len,
+        })
+    }
+
+    /// The network `addr` is limited as
+    pub fn prefix(&self, addr: IpAddr) -> IpPrefix {
+        let addr = addr.to_canonical();
+        let prefix_len = if addr.is_ipv4() {
+            self.ipv4_prefix_len
+        } else {
+            self.ipv6_prefix_len
+        };
+        IpPrefix::clamped(addr, prefix_len)
+    }
+}
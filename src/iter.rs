@@ -0,0 +1,92 @@
+// src/iter.rs
+
+//! Blocking throttle adapter for iterators
+
+// dependencies
+use crate::clock::Clock;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use std::hash::Hash;
+
+/// Extension trait adding [`throttle`](IteratorExt::throttle) to iterators.
+pub trait IteratorExt: Iterator + Sized {
+    /// Pace the iterator, sleeping the current thread until each item
+    /// conforms under the key returned by `key_fn`.
+    ///
+    /// Meant for synchronous batch jobs; no async runtime is involved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, IteratorExt, SystemClock};
+    ///
+    /// // at most 100 rows per second per table, bursts of 10
+    /// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(100.0, 10.0), SystemClock).unwrap();
+    /// let rows = vec![("users", 1), ("users", 2), ("orders", 3)];
+    ///
+    /// for row in rows.into_iter().throttle(&limiter, |(table, _)| *table) {
+    ///     let (table, id) = row.unwrap();
+    ///     println!("loading {table} #{id}");
+    /// }
+    /// ```
+    fn throttle<K, F, C>(
+        self,
+        limiter: &FluxLimiter<K, C>,
+        key_fn: F,
+    ) -> Throttle<'_, Self, K, F, C>
+    where
+        K: Hash + Eq + Clone,
+        F: FnMut(&Self::Item) -> K,
+        C: Clock,
+    {
+        Throttle {
+            iter: self,
+            limiter,
+            key_fn,
+        }
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
+
+/// Iterator returned by [`IteratorExt::throttle`].
+///
+/// Yields `Ok(item)` once the item conforms and `Err` if the limiter fails.
+pub struct Throttle<'a, I, K, F, C>
+where
+    K: Hash + Eq + Clone,
+    C: Clock,
+{
+    iter: I,
+    limiter: &'a FluxLimiter<K, C>,
+    key_fn: F,
+}
+
+impl<I, K, F, C> Iterator for Throttle<'_, I, K, F, C>
+where
+    I: Iterator,
+    K: Hash + Eq + Clone,
+    F: FnMut(&I::Item) -> K,
+    C: Clock,
+{
+    type Item = Result<I::Item, FluxLimiterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let key = (self.key_fn)(&item);
+
+        loop {
+            match self.limiter.check_request(key.clone()) {
+                Ok(decision) if decision.allowed => return Some(Ok(item)),
+                Ok(decision) => {
+                    std::thread::sleep(decision.retry_after().unwrap_or_default());
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
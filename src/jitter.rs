@@ -0,0 +1,165 @@
+// src/jitter.rs
+
+//! Randomized retry-after delays to spread out retries
+
+// dependencies
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Chooses how much extra delay to add to a denial's `retry_after_seconds`.
+///
+/// Enable jitter with [`FluxLimiterConfig::retry_jitter`](crate::FluxLimiterConfig::retry_jitter);
+/// the limiter then asks the strategy for an extra delay on every denial and
+/// clamps the answer to `0.0..=max_seconds`, so retries are never advised
+/// earlier than the request could succeed. [`UniformJitter`] is used unless
+/// another strategy is set with
+/// [`FluxLimiter::with_jitter_strategy`](crate::FluxLimiter::with_jitter_strategy).
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, JitterStrategy, SystemClock};
+///
+/// // always add half of the allowed jitter
+/// struct HalfJitter;
+///
+/// impl JitterStrategy for HalfJitter {
+///     fn jitter(&self, _retry_after_seconds: f64, max_seconds: f64) -> f64 {
+///         max_seconds / 2.0
+///     }
+/// }
+///
+/// let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(0.5);
+/// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock)
+///     .unwrap()
+///     .with_jitter_strategy(HalfJitter);
+/// ```
+pub trait JitterStrategy: Send + Sync {
+    /// Extra delay in seconds for a denial that could retry after
+    /// `retry_after_seconds`, between `0.0` and `max_seconds`
+    fn jitter(&self, retry_after_seconds: f64, max_seconds: f64) -> f64;
+}
+
+/// Adds a uniformly distributed delay between zero and the configured maximum.
+///
+/// Uses a small per-thread generator seeded from the standard library's
+/// random hasher keys; it is not suitable for anything security sensitive.
+/// Use [`SeededJitter`] for delays that repeat from run to run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformJitter;
+
+impl JitterStrategy for UniformJitter {
+    fn jitter(&self, _retry_after_seconds: f64, max_seconds: f64) -> f64 {
+        unit_random() * max_seconds
+    }
+}
+
+/// Adds a uniformly distributed delay drawn from a generator with a fixed
+/// seed, so tests and simulations see the same delays on every run.
+///
+/// The generator is shared by every thread using the strategy: a given
+/// sequence of denials always gets the same sequence of delays, though
+/// concurrent denials may take them in any order.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SeededJitter, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(0.5);
+/// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock)
+///     .unwrap()
+///     .with_jitter_strategy(SeededJitter::new(42));
+/// ```
+#[derive(Debug)]
+pub struct SeededJitter {
+    state: AtomicU64,
+}
+
+impl SeededJitter {
+    /// Create a strategy whose delays are determined by `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            // xorshift gets stuck at zero
+            state: AtomicU64::new(seed | 1),
+        }
+    }
+}
+
+impl JitterStrategy for SeededJitter {
+    fn jitter(&self, _retry_after_seconds: f64, max_seconds: f64) -> f64 {
+        let mut next = 0;
+        // the closure always returns Some, so the update cannot fail
+        let _ = self
+            .state
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                next = xorshift(x);
+                Some(next)
+            });
+        to_unit(next) * max_seconds
+    }
+}
+
+thread_local! {
+    static RNG_STATE: Cell<u64> = Cell::new(RandomState::new().build_hasher().finish() | 1);
+}
+
+// per-thread xorshift64* draw from [0, 1)
+fn unit_random() -> f64 {
+    RNG_STATE.with(|state| {
+        let x = xorshift(state.get());
+        state.set(x);
+        to_unit(x)
+    })
+}
+
+// xorshift64 state step
+fn xorshift(mut x: u64) -> u64 {
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    x
+}
+
+// xorshift64* output mapped onto [0, 1)
+fn to_unit(x: u64) -> f64 {
+    (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// shared handle to a strategy, so the limiter can keep deriving Debug
+#[derive(Clone)]
+pub(crate) struct JitterHook(Arc<dyn JitterStrategy>);
+
+impl JitterHook {
+    pub(crate) fn new(strategy: impl JitterStrategy + 'static) -> Self {
+        Self(Arc::new(strategy))
+    }
+
+    // add bounded jitter to a retry delay
+    pub(crate) fn apply(&self, retry_after_seconds: f64, max_seconds: f64) -> f64 {
+        let extra = self.0.jitter(retry_after_seconds, max_seconds);
+        // a NaN from a custom strategy falls back to no jitter
+        let extra = if extra.is_nan() {
+            0.0
+        } else {
+            extra.clamp(0.0, max_seconds)
+        };
+        retry_after_seconds + extra
+    }
+}
+
+impl Default for JitterHook {
+    fn default() -> Self {
+        Self::new(UniformJitter)
+    }
+}
+
+impl fmt::Debug for JitterHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JitterHook")
+    }
+}
@@ -0,0 +1,268 @@
+// src/key_extractor.rs
+
+//! Request keying shared by the framework integrations
+
+// dependencies
+use crate::ip_prefix::{IpPrefix, IpPrefixer};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::IpAddr;
+
+/// What key extractors can read from a request.
+///
+/// Implemented for the request types of the framework integrations, so the
+/// built-in extractors ([`PeerIp`], [`HeaderKey`], [`BearerTokenHash`] and
+/// [`RouteTemplate`]) work with all of them. Implement it for your own
+/// request type to reuse them elsewhere.
+pub trait RequestInfo {
+    /// IP address of the connection's peer, if known
+    fn peer_ip(&self) -> Option<IpAddr>;
+
+    /// Value of the header `name`, if present and valid text
+    fn header(&self, name: &str) -> Option<&str>;
+
+    /// The route template the framework matched, e.g. `GET /users/<id>`.
+    ///
+    /// Defaults to `None` for request types without routing information.
+    fn route_template(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Derives the limiter key for a request.
+///
+/// Returning `None` means the request has no usable key; the integrations
+/// pass such requests through unlimited. Closures taking `&Req` and
+/// returning `Option<String>` are extractors, and a pair `(a, b)` keys by
+/// both parts joined with `|`, e.g. `(RouteTemplate, PeerIp::new())` for a
+/// separate budget per route and client.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{KeyExtractor, PeerIp, RequestInfo, RouteTemplate};
+/// use std::net::IpAddr;
+///
+/// struct Incoming {
+///     peer: IpAddr,
+/// }
+///
+/// impl RequestInfo for Incoming {
+///     fn peer_ip(&self) -> Option<IpAddr> {
+///         Some(self.peer)
+///     }
+///
+///     fn header(&self, _name: &str) -> Option<&str> {
+///         None
+///     }
+///
+///     fn route_template(&self) -> Option<String> {
+///         Some("GET /search".into())
+///     }
+/// }
+///
+/// let request = Incoming { peer: "203.0.113.7".parse().unwrap() };
+/// let key = (RouteTemplate, PeerIp::new()).extract(&request);
+/// assert_eq!(key.as_deref(), Some("GET /search|203.0.113.7"));
+/// ```
+pub trait KeyExtractor<Req: ?Sized>: Send + Sync {
+    /// The key for `request`, or `None` if it has none
+    fn extract(&self, request: &Req) -> Option<String>;
+}
+
+impl<Req, F> KeyExtractor<Req> for F
+where
+    Req: ?Sized,
+    F: Fn(&Req) -> Option<String> + Send + Sync,
+{
+    fn extract(&self, request: &Req) -> Option<String> {
+        self(request)
+    }
+}
+
+impl<Req, A, B> KeyExtractor<Req> for (A, B)
+where
+    Req: ?Sized,
+    A: KeyExtractor<Req>,
+    B: KeyExtractor<Req>,
+{
+    fn extract(&self, request: &Req) -> Option<String> {
+        let first = self.0.extract(request)?;
+        let second = self.1.extract(request)?;
+        Some(format!("{first}|{second}"))
+    }
+}
+
+/// Keys requests by client IP address.
+///
+/// By default the peer address of the connection is the client. Behind a
+/// load balancer or reverse proxy, mark the proxies' networks with
+/// [`trust_proxies`](Self::trust_proxies): for requests arriving from a
+/// trusted peer, `X-Forwarded-For` is walked from the right, skipping
+/// trusted hops, and the first untrusted address is the client. Entries
+/// left of that are supplied by the client and ignored, so they cannot be
+/// used to pick another client's budget.
+///
+/// With [`aggregate`](Self::aggregate), clients are keyed by their network
+/// rather than their exact address.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::PeerIp;
+///
+/// // clients connect through load balancers in 10.0.0.0/8
+/// let extractor = PeerIp::new().trust_proxies("10.0.0.0".parse().unwrap(), 8);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerIp {
+    trusted_proxies: Vec<IpPrefix>,
+    aggregate: Option<IpPrefixer>,
+}
+
+impl PeerIp {
+    /// Key by the connection's peer address, trusting no proxies
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust `X-Forwarded-For` from peers in `network/prefix_len`.
+    ///
+    /// Prefix lengths longer than the address are treated as a single
+    /// address (`/32` or `/128`).
+    pub fn trust_proxies(mut self, network: IpAddr, prefix_len: u8) -> Self {
+        self.trusted_proxies
+            .push(IpPrefix::clamped(network, prefix_len));
+        self
+    }
+
+    /// Key clients by the network `prefixer` maps their address to, e.g.
+    /// `203.0.113.0/24`, so rotating addresses within it shares one budget.
+    pub fn aggregate(mut self, prefixer: IpPrefixer) -> Self {
+        self.aggregate = Some(prefixer);
+        self
+    }
+
+    /// The client address of `request`, following trusted proxies
+    pub fn client_ip<R>(&self, request: &R) -> Option<IpAddr>
+    where
+        R: RequestInfo + ?Sized,
+    {
+        let mut client = request.peer_ip()?;
+        if !self.is_trusted(client) {
+            return Some(client);
+        }
+
+        if let Some(forwarded) = request.header("x-forwarded-for") {
+            for hop in forwarded.rsplit(',') {
+                // a malformed entry ends the chain at the hop that reported it
+                let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+                    break;
+                };
+                client = hop;
+                if !self.is_trusted(hop) {
+                    break;
+                }
+            }
+        }
+        Some(client)
+    }
+
+    // whether `addr` lies in one of the trusted proxy networks
+    fn is_trusted(&self, addr: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|network| network.contains(addr))
+    }
+}
+
+impl<R> KeyExtractor<R> for PeerIp
+where
+    R: RequestInfo + ?Sized,
+{
+    fn extract(&self, request: &R) -> Option<String> {
+        let ip = self.client_ip(request)?;
+        Some(match self.aggregate {
+            Some(prefixer) => prefixer.prefix(ip).to_string(),
+            None => ip.to_string(),
+        })
+    }
+}
+
+/// Keys requests by the value of a header, e.g. `x-api-key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderKey(pub String);
+
+impl HeaderKey {
+    /// Key by the header `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+}
+
+impl<R> KeyExtractor<R> for HeaderKey
+where
+    R: RequestInfo + ?Sized,
+{
+    fn extract(&self, request: &R) -> Option<String> {
+        request.header(&self.0).map(str::to_owned)
+    }
+}
+
+/// Keys requests by a hash of their `Authorization: Bearer` token.
+///
+/// The raw token never becomes a limiter key, so it is not held in client
+/// state or snapshots. The hash is SipHash with fixed keys: stable across
+/// processes built with the same Rust version, which keeps keys consistent
+/// for shared state stores, but not a cryptographic digest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BearerTokenHash;
+
+impl<R> KeyExtractor<R> for BearerTokenHash
+where
+    R: RequestInfo + ?Sized,
+{
+    fn extract(&self, request: &R) -> Option<String> {
+        let authorization = request.header("authorization")?;
+        let (scheme, token) = authorization.trim().split_once(' ')?;
+        let token = token.trim();
+        if !scheme.eq_ignore_ascii_case("bearer") || token.is_empty() {
+            return None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        Some(format!("bearer:{:016x}", hasher.finish()))
+    }
+}
+
+/// Keys requests by the route template the framework matched.
+///
+/// All clients of a route share one budget; pair it with a client extractor,
+/// e.g. `(RouteTemplate, PeerIp::new())`, for per-client route limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteTemplate;
+
+impl<R> KeyExtractor<R> for RouteTemplate
+where
+    R: RequestInfo + ?Sized,
+{
+    fn extract(&self, request: &R) -> Option<String> {
+        request.route_template()
+    }
+}
+
+// plain `http` requests, as seen by tower and axum middleware; servers that
+// record the peer put its `SocketAddr` in the request extensions
+#[cfg(feature = "http")]
+impl<B> RequestInfo for http::Request<B> {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.extensions()
+            .get::<std::net::SocketAddr>()
+            .map(|addr| addr.ip())
+            .or_else(|| self.extensions().get::<IpAddr>().copied())
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers().get(name)?.to_str().ok()
+    }
+}
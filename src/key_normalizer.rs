@@ -0,0 +1,161 @@
+// src/key_normalizer.rs
+
+//! Client key cleanup applied before keys reach a limiter
+
+// dependencies
+use crate::key_extractor::KeyExtractor;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+// one step of a normalizer chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Lowercase,
+    Trim,
+    StripPrefix(String),
+    Hash,
+}
+
+/// A chain of steps that turns noisy identifiers into stable client keys.
+///
+/// Identifiers such as `User-Agent` values or hand-typed API keys arrive in
+/// many spellings of the same client: different case, stray whitespace, a
+/// `Bearer ` or version prefix. Each spelling would otherwise get its own
+/// entry and its own budget. The steps run in the order they were added;
+/// normalize with the same chain before checks and before lookups such as
+/// [`client_snapshot`](crate::FluxLimiter::client_snapshot) so both see the
+/// same key. Wrap an extractor with [`extractor`](Self::extractor) to apply
+/// it in the framework integrations.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::KeyNormalizer;
+///
+/// let normalizer = KeyNormalizer::new().trim().strip_prefix("key-").lowercase();
+/// assert_eq!(normalizer.normalize("  key-ABC123 "), "abc123");
+/// assert_eq!(normalizer.normalize("abc123"), "abc123");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyNormalizer {
+    steps: Vec<Step>,
+}
+
+impl KeyNormalizer {
+    /// A normalizer without steps, which leaves keys unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lowercase the key (Unicode-aware)
+    pub fn lowercase(self) -> Self {
+        self.then(Step::Lowercase)
+    }
+
+    /// Remove leading and trailing whitespace
+    pub fn trim(self) -> Self {
+        self.then(Step::Trim)
+    }
+
+    /// Remove `prefix` from the start of the key, if present.
+    ///
+    /// The match is exact, so add this step after
+    /// [`lowercase`](Self::lowercase) to strip a prefix in any case.
+    pub fn strip_prefix(self, prefix: impl Into<String>) -> Self {
+        self.then(Step::StripPrefix(prefix.into()))
+    }
+
+    /// Replace the key with a 64-bit hash of it, as 16 hex digits.
+    ///
+    /// Bounds the size of long keys and keeps the raw value out of client
+    /// state. Like [`BearerTokenHash`](crate::BearerTokenHash), the hash is
+    /// SipHash with fixed keys: stable across processes built with the same
+    /// Rust version, but not a cryptographic digest. Use a
+    /// [`KeyAnonymizer`](crate::KeyAnonymizer) when keys must not be
+    /// recomputable from known identifiers.
+    pub fn hash(self) -> Self {
+        self.then(Step::Hash)
+    }
+
+    /// Run the steps on `key`
+    pub fn normalize(&self, key: &str) -> String {
+        let mut key = key.to_owned();
+        for step in &self.steps {
+            key = match step {
+                Step::Lowercase => key.to_lowercase(),
+                Step::Trim => key.trim().to_owned(),
+                Step::StripPrefix(prefix) => match key.strip_prefix(prefix.as_str()) {
+                    Some(rest) => rest.to_owned(),
+                    None => key,
+                },
+                Step::Hash => {
+                    let mut hasher = DefaultHasher::new();
+                    key.hash(&mut hasher);
+                    format!("{:016x}", hasher.finish())
+                }
+            };
+        }
+        key
+    }
+
+    /// Wrap `extractor` so its keys are normalized by this chain.
+    ///
+    /// Keys that normalize to an empty string, e.g. a header of only
+    /// whitespace, count as missing, like a request the extractor has no
+    /// key for.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{HeaderKey, KeyNormalizer};
+    ///
+    /// let extractor = KeyNormalizer::new()
+    ///     .trim()
+    ///     .lowercase()
+    ///     .hash()
+    ///     .extractor(HeaderKey::new("user-agent"));
+    /// ```
+    pub fn extractor<E>(self, extractor: E) -> Normalized<E> {
+        Normalized {
+            extractor,
+            normalizer: self,
+        }
+    }
+
+    // append `step` to the chain
+    fn then(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+}
+
+/// A key extractor whose keys pass through a [`KeyNormalizer`].
+///
+/// Built by [`KeyNormalizer::extractor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalized<E> {
+    extractor: E,
+    normalizer: KeyNormalizer,
+}
+
+impl<E> Normalized<E> {
+    /// The wrapped extractor
+    pub fn inner(&self) -> &E {
+        &self.extractor
+    }
+
+    /// The normalizer applied to its keys
+    pub fn normalizer(&self) -> &KeyNormalizer {
+        &self.normalizer
+    }
+}
+
+impl<Req, E> KeyExtractor<Req> for Normalized<E>
+where
+    Req: ?Sized,
+    E: KeyExtractor<Req>,
+{
+    fn extract(&self, request: &Req) -> Option<String> {
+        let key = self.normalizer.normalize(&self.extractor.extract(request)?);
+        (!key.is_empty()).then_some(key)
+    }
+}
@@ -21,14 +21,256 @@
 //! }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// public modules
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub mod gcra;
+
 // private modules
+#[cfg(feature = "std")]
+mod adaptive;
+#[cfg(feature = "admin")]
+mod admin;
+#[cfg(feature = "std")]
+mod anonymize;
+#[cfg(feature = "std")]
+mod audit;
+#[cfg(feature = "std")]
+mod cached_store;
+#[cfg(feature = "std")]
+mod classify;
+mod clock;
+#[cfg(feature = "std")]
 mod config;
+mod decision;
+#[cfg(feature = "std")]
+mod entry;
+#[cfg(feature = "envoy")]
+mod envoy;
 mod errors;
+#[cfg(feature = "std")]
+mod events;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "std")]
 mod flux_limiter;
-mod clock;
+#[cfg(feature = "gossip")]
+mod gossip;
+#[cfg(feature = "std")]
+mod governor_compat;
+#[cfg(feature = "tonic")]
+mod grpc;
+#[cfg(feature = "http")]
+mod http_headers;
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "std")]
+mod ip_prefix;
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "std")]
+mod jitter;
+#[cfg(feature = "std")]
+mod key_extractor;
+#[cfg(feature = "std")]
+mod key_normalizer;
+#[cfg(feature = "hashbrown")]
+mod local;
+#[cfg(feature = "std")]
+mod local_flux_limiter;
+#[cfg(feature = "std")]
+mod matrix;
+#[cfg(feature = "memcached")]
+mod memcached_store;
+#[cfg(feature = "std")]
+mod namespace;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "std")]
+mod permit;
+#[cfg(feature = "persistence")]
+mod persistence;
+#[cfg(feature = "postgres")]
+mod postgres_store;
+#[cfg(feature = "std")]
+mod pressure;
+#[cfg(feature = "pyo3")]
+mod python;
+#[cfg(feature = "std")]
+mod registry;
+#[cfg(feature = "hot-reload")]
+mod reload;
+#[cfg(feature = "std")]
+mod replay;
+#[cfg(feature = "reqwest-middleware")]
+mod reqwest_throttle;
+#[cfg(feature = "rocket")]
+mod rocket_fairing;
+#[cfg(feature = "std")]
+mod router;
+#[cfg(feature = "std")]
+mod same_instant;
+#[cfg(feature = "std")]
+mod schedule;
+#[cfg(feature = "std")]
+mod sharded;
+#[cfg(feature = "shm")]
+mod shm_store;
+#[cfg(feature = "sidecar")]
+mod sidecar;
+#[cfg(feature = "std")]
+mod simulation;
+#[cfg(feature = "sled")]
+mod sled_store;
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod store;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "tower")]
+mod tower_throttle;
+#[cfg(feature = "std")]
+mod two_rate;
+#[cfg(feature = "tokio")]
+mod wait_queue;
+#[cfg(feature = "std")]
+mod wheel;
 
 // public API exports
-pub use clock::{Clock, SystemClock, ClockError};
-pub use config::FluxLimiterConfig;
-pub use errors::FluxLimiterError;
-pub use flux_limiter::{FluxLimiter, FluxLimiterDecision};
+#[cfg(feature = "std")]
+pub use adaptive::{AdaptiveLimiter, AimdConfig, Outcome};
+#[cfg(feature = "admin")]
+pub use admin::{ACTOR_HEADER, admin_router};
+#[cfg(feature = "std")]
+pub use anonymize::{AnonymizedKey, KeyAnonymizer};
+#[cfg(feature = "std")]
+pub use audit::{AuditAction, AuditEvent, AuditSink, Operator};
+#[cfg(feature = "std")]
+pub use cached_store::CachedStore;
+#[cfg(feature = "std")]
+pub use classify::{Classification, Classifier};
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+#[cfg(feature = "tokio")]
+pub use clock::TokioClock;
+#[cfg(feature = "wasm")]
+pub use clock::WasmClock;
+pub use clock::{Clock, ClockError, Timestamp};
+#[cfg(feature = "std")]
+pub use config::{DrainMode, FailurePolicy, FluxLimiterConfig, Priority, Replenishment};
+pub use decision::{
+    AllowedMeta, Decision, DecisionReason, DecisionTrace, DeniedMeta, FluxLimiterDecision,
+    MultiKeyDecision,
+};
+#[cfg(feature = "std")]
+pub use entry::ClientEntry;
+#[cfg(feature = "envoy")]
+pub use envoy::{
+    CurrentLimit, DescriptorEntry, DescriptorStatus, EnvoyRateLimitService, RateLimitCode,
+    RateLimitDescriptor, RateLimitRequest, RateLimitResponse, RateLimitUnit, ResponseHeader,
+};
+#[cfg(feature = "tokio")]
+pub use errors::AcquireError;
+pub use errors::{FluxLimiterError, RateLimitedError};
+#[cfg(feature = "std")]
+pub use events::DecisionEvent;
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    FLUX_LIMITER_ERR_LIMITER, FLUX_LIMITER_ERR_NULL, FLUX_LIMITER_OK, FfiDecision, FfiLimiter,
+    flux_limiter_check, flux_limiter_cleanup, flux_limiter_free, flux_limiter_new,
+};
+#[cfg(feature = "std")]
+pub use flux_limiter::FluxLimiter;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use gcra::{Precision, Rounding};
+#[cfg(feature = "gossip")]
+pub use gossip::{ConsumptionSummary, GossipLimiter, GossipTransport, UdpTransport};
+#[cfg(feature = "std")]
+pub use governor_compat::{DirectRateLimiter, InsufficientCapacity, NotUntil, Quota, RateLimiter};
+#[cfg(feature = "tonic")]
+pub use grpc::{FluxLimiterInterceptor, GrpcKey};
+#[cfg(feature = "std")]
+pub use intern::KeyInterner;
+#[cfg(feature = "std")]
+pub use ip_prefix::{IpPrefix, IpPrefixer};
+#[cfg(feature = "std")]
+pub use iter::{IteratorExt, Throttle};
+#[cfg(feature = "std")]
+pub use jitter::{JitterStrategy, SeededJitter, UniformJitter};
+#[cfg(feature = "std")]
+pub use key_extractor::{
+    BearerTokenHash, HeaderKey, KeyExtractor, PeerIp, RequestInfo, RouteTemplate,
+};
+#[cfg(feature = "std")]
+pub use key_normalizer::{KeyNormalizer, Normalized};
+#[cfg(feature = "hashbrown")]
+pub use local::LocalLimiter;
+#[cfg(feature = "std")]
+pub use local_flux_limiter::LocalFluxLimiter;
+#[cfg(feature = "std")]
+pub use matrix::{MatrixBuilder, MatrixLimiter};
+#[cfg(feature = "memcached")]
+pub use memcached_store::MemcachedStore;
+#[cfg(feature = "std")]
+pub use namespace::NamespacedLimiter;
+#[cfg(feature = "std")]
+pub use permit::Permit;
+#[cfg(feature = "persistence")]
+pub use persistence::PersistentLimiter;
+#[cfg(feature = "postgres")]
+pub use postgres_store::PostgresStore;
+#[cfg(feature = "std")]
+pub use pressure::PressureProvider;
+#[cfg(feature = "pyo3")]
+pub use python::{PyDecision, PyFluxLimiter, python_module};
+#[cfg(feature = "std")]
+pub use registry::LimiterRegistry;
+#[cfg(feature = "hot-reload")]
+pub use reload::PolicyWatcher;
+#[cfg(feature = "std")]
+pub use replay::ReplayLoader;
+#[cfg(feature = "reqwest-middleware")]
+pub use reqwest_throttle::ThrottleMiddleware;
+#[cfg(feature = "rocket")]
+pub use rocket_fairing::{FluxLimiterFairing, RateLimit, RateLimitRejection, RocketKey};
+#[cfg(feature = "std")]
+pub use router::{Policy, PolicyRouter};
+#[cfg(feature = "std")]
+pub use same_instant::SameInstant;
+#[cfg(feature = "std")]
+pub use schedule::{ScheduledLimiter, TimeWindow, Weekday};
+#[cfg(feature = "std")]
+pub use sharded::ShardedFluxLimiter;
+#[cfg(feature = "shm")]
+pub use shm_store::SharedMemoryStore;
+#[cfg(feature = "sidecar")]
+pub use sidecar::{
+    CheckRequest, CheckResponse, PeekRequest, PeekResponse, RateLimiterService, ResetRequest,
+    ResetResponse, StatsRequest, StatsResponse,
+};
+#[cfg(feature = "std")]
+pub use simulation::simulate;
+#[cfg(feature = "simulation")]
+pub use simulation::{ArrivalPattern, SimulationReport, Simulator};
+#[cfg(feature = "sled")]
+pub use sled_store::SledStore;
+#[cfg(feature = "std")]
+pub use snapshot::{ClientSnapshot, StateSnapshot};
+#[cfg(feature = "per-key-stats")]
+pub use stats::ClientStats;
+#[cfg(feature = "std")]
+pub use stats::{CleanupReport, FluxLimiterStats};
+#[cfg(feature = "std")]
+pub use store::{AsyncStateStore, StateStore, StoreBackedLimiter, StoreError};
+#[cfg(feature = "stream")]
+pub use stream::{FluxLimit, FluxLimitStreamExt, StreamMode};
+#[cfg(feature = "tower")]
+pub use tower_throttle::{Authority, ThrottleLayer, ThrottleService};
+#[cfg(feature = "std")]
+pub use two_rate::TwoRateLimiter;
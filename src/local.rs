@@ -0,0 +1,169 @@
+// src/local.rs
+
+//! Single-owner limiter for `no_std` targets, backed by a hashbrown map
+
+// dependencies
+use crate::clock::Clock;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::gcra::{self, Limits, Precision};
+use core::hash::{BuildHasher, Hash};
+use hashbrown::HashMap;
+
+/// A rate limiter owned by a single caller, usable without the standard library.
+///
+/// Runs the same GCRA step as `FluxLimiter` and gives the same decisions for
+/// the same arrivals, but keeps each client's TAT in a plain `hashbrown` map
+/// updated through `&mut self`, so it needs only `alloc`: no threads, locks or
+/// atomics. This suits embedded gateways and other single-threaded event
+/// loops; wrap it in the platform's own lock to share it.
+///
+/// Both the clock and the map's hasher are supplied by the caller, since
+/// there is no system clock or randomized hasher without `std`. Only rate and
+/// burst are configurable, validated as by
+/// `FluxLimiterConfig::validate`.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{Clock, ClockError, LocalLimiter, Timestamp};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::BuildHasherDefault;
+///
+/// // e.g. a hardware tick counter
+/// struct TickClock;
+///
+/// impl Clock for TickClock {
+///     fn now(&self) -> Result<Timestamp, ClockError> {
+///         Ok(Timestamp::from_nanos(1_000_000_000))
+///     }
+/// }
+///
+/// let mut limiter =
+///     LocalLimiter::<u8, _, BuildHasherDefault<DefaultHasher>>::new(1.0, 1.0, TickClock).unwrap();
+///
+/// assert!(limiter.check_request(7).unwrap().allowed);
+/// assert!(limiter.check_request(7).unwrap().allowed);
+/// assert!(!limiter.check_request(7).unwrap().allowed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalLimiter<K, C, S> {
+    client_state: HashMap<K, u64, S>,
+    clock: C,
+    limits: Limits,
+}
+
+impl<K, C, S> LocalLimiter<K, C, S>
+where
+    K: Hash + Eq,
+    C: Clock,
+    S: BuildHasher,
+{
+    /// Create a limiter allowing `rate_per_second` with a burst of `burst_capacity`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same rate and burst errors as
+    /// `FluxLimiterConfig::validate`, and [`FluxLimiterError::RateTooHigh`]
+    /// for rates whose emission interval rounds to 0ns.
+    pub fn new(
+        rate_per_second: f64,
+        burst_capacity: f64,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError>
+    where
+        S: Default,
+    {
+        Self::with_hasher(rate_per_second, burst_capacity, clock, S::default())
+    }
+
+    /// Create a limiter whose client map uses `hasher`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`LocalLimiter::new`].
+    pub fn with_hasher(
+        rate_per_second: f64,
+        burst_capacity: f64,
+        clock: C,
+        hasher: S,
+    ) -> Result<Self, FluxLimiterError> {
+        Ok(Self {
+            client_state: HashMap::with_hasher(hasher),
+            clock,
+            limits: Limits::new(rate_per_second, burst_capacity, Precision::default())?,
+        })
+    }
+
+    /// The sustained rate every key is limited to, in requests per second
+    pub fn rate(&self) -> f64 {
+        self.limits.rate_per_second
+    }
+
+    /// The burst every key is allowed
+    pub fn burst(&self) -> f64 {
+        self.limits.burst_capacity
+    }
+
+    /// Number of clients currently tracked
+    pub fn len(&self) -> usize {
+        self.client_state.len()
+    }
+
+    /// Whether no clients are tracked
+    pub fn is_empty(&self) -> bool {
+        self.client_state.is_empty()
+    }
+
+    /// Check a request for `client_id` at the clock's current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn check_request(&mut self, client_id: K) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let now_nanos = self
+            .clock
+            .now()
+            .map_err(FluxLimiterError::ClockError)?
+            .as_nanos();
+        Ok(self.check_request_at(client_id, now_nanos))
+    }
+
+    /// Check a request at a caller-supplied time in nanoseconds, without
+    /// reading the clock.
+    pub fn check_request_at(&mut self, client_id: K, timestamp_nanos: u64) -> FluxLimiterDecision {
+        // new clients start on schedule
+        let tat_nanos = self
+            .client_state
+            .entry(client_id)
+            .or_insert(timestamp_nanos);
+        let (decision, new_tat_nanos) = self.limits.decide(timestamp_nanos, *tat_nanos);
+        if let Some(new_tat_nanos) = new_tat_nanos {
+            *tat_nanos = new_tat_nanos;
+        }
+        decision
+    }
+
+    /// Remove clients that have been fully recovered for longer than
+    /// `max_stale_nanos`, returning how many were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients(
+        &mut self,
+        max_stale_nanos: u64,
+    ) -> Result<usize, FluxLimiterError> {
+        let now_nanos = self
+            .clock
+            .now()
+            .map_err(FluxLimiterError::ClockError)?
+            .as_nanos();
+        let before = self.client_state.len();
+        let tolerance_nanos = self.limits.tolerance_nanos;
+        self.client_state.retain(|_, tat_nanos| {
+            !gcra::is_recovered(now_nanos, *tat_nanos, tolerance_nanos, max_stale_nanos)
+        });
+        Ok(before - self.client_state.len())
+    }
+}
@@ -0,0 +1,248 @@
+// src/local_flux_limiter.rs
+
+//! Single-threaded limiter for thread-per-core runtimes, free of atomics and locks
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::{FailurePolicy, FluxLimiterConfig};
+use crate::decision::{DecisionReason, FluxLimiterDecision};
+use crate::errors::FluxLimiterError;
+use crate::gcra::{self, Limits};
+use crate::stats::CleanupReport;
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::rc::Rc;
+
+/// A rate limiter confined to a single thread.
+///
+/// Makes the same decisions as [`FluxLimiter`](crate::FluxLimiter), but keeps
+/// client TATs in a plain `HashMap` behind `Rc<RefCell<..>>` instead of an
+/// `Arc<DashMap>` of atomic entries. In thread-per-core runtimes such as
+/// glommio or monoio, where each core owns its keys, this removes the shard
+/// locks and atomic operations from every check. The limiter is neither
+/// `Send` nor `Sync`; cloning yields another handle to the same state on the
+/// same thread.
+///
+/// Rate, burst, [`capacity`](FluxLimiterConfig::capacity) and the
+/// [`failure_policy`](FluxLimiterConfig::failure_policy) apply. Debt,
+/// priority reserves, pacing hints, retry jitter, statistics, the expiry
+/// wheel and entry TTLs are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, LocalFluxLimiter, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(1.0, 1.0);
+/// let limiter = LocalFluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+///
+/// assert!(limiter.check_request_ref("user_123").unwrap().allowed);
+/// assert!(limiter.check_request_ref("user_123").unwrap().allowed);
+/// assert!(!limiter.check_request_ref("user_123").unwrap().allowed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalFluxLimiter<T, C = SystemClock, H = RandomState>
+where
+    T: Hash + Eq,
+    C: Clock,
+    H: BuildHasher,
+{
+    limits: Limits,
+    failure_policy: FailurePolicy,
+    client_state: Rc<RefCell<HashMap<T, u64, H>>>,
+    clock: C,
+}
+
+// constructors for limiters using the default hasher
+impl<T, C> LocalFluxLimiter<T, C>
+where
+    T: Hash + Eq,
+    C: Clock,
+{
+    /// Create a limiter from `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error, or
+    /// [`FluxLimiterError::RateTooHigh`] if the emission interval rounds to 0ns.
+    pub fn with_config(config: FluxLimiterConfig, clock: C) -> Result<Self, FluxLimiterError> {
+        Self::with_config_and_hasher(config, clock, RandomState::new())
+    }
+}
+
+impl<T, C, H> LocalFluxLimiter<T, C, H>
+where
+    T: Hash + Eq,
+    C: Clock,
+    H: BuildHasher,
+{
+    /// Create a limiter whose client map uses `hasher`; see
+    /// [`FluxLimiter::with_config_and_hasher`](crate::FluxLimiter::with_config_and_hasher).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`LocalFluxLimiter::with_config`].
+    pub fn with_config_and_hasher(
+        config: FluxLimiterConfig,
+        clock: C,
+        hasher: H,
+    ) -> Result<Self, FluxLimiterError> {
+        config.validate()?;
+        Ok(Self {
+            limits: Limits::new(
+                config.rate_per_second,
+                config.burst_capacity,
+                config.precision,
+            )?,
+            failure_policy: config.failure_policy,
+            client_state: Rc::new(RefCell::new(HashMap::with_capacity_and_hasher(
+                config.initial_capacity,
+                hasher,
+            ))),
+            clock,
+        })
+    }
+
+    /// The sustained rate every key is limited to, in requests per second
+    pub fn rate(&self) -> f64 {
+        self.limits.rate_per_second
+    }
+
+    /// The burst every key is allowed
+    pub fn burst(&self) -> f64 {
+        self.limits.burst_capacity
+    }
+
+    /// Number of clients currently tracked
+    pub fn len(&self) -> usize {
+        RefCell::borrow(&self.client_state).len()
+    }
+
+    /// Whether no clients are tracked
+    pub fn is_empty(&self) -> bool {
+        RefCell::borrow(&self.client_state).is_empty()
+    }
+
+    /// Check a request for `client_id` at the clock's current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails and the
+    /// failure policy is [`FailurePolicy::Propagate`].
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let current_time_nanos = match self.clock.now() {
+            Ok(now) => now.as_nanos(),
+            Err(err) => return self.fail(FluxLimiterError::ClockError(err)),
+        };
+        self.check_request_at(client_id, current_time_nanos)
+    }
+
+    /// Check a request at a caller-supplied time; see
+    /// [`FluxLimiter::check_request_at`](crate::FluxLimiter::check_request_at).
+    pub fn check_request_at(
+        &self,
+        client_id: T,
+        timestamp_nanos: u64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let mut client_state = self.client_state.borrow_mut();
+        // new clients start on schedule
+        let tat_nanos = client_state.entry(client_id).or_insert(timestamp_nanos);
+        Ok(self.apply(tat_nanos, timestamp_nanos))
+    }
+
+    /// Check a request using a borrowed form of the client ID, only
+    /// allocating an owned key the first time a client is seen.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`check_request`](Self::check_request).
+    pub fn check_request_ref<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    {
+        let current_time_nanos = match self.clock.now() {
+            Ok(now) => now.as_nanos(),
+            Err(err) => return self.fail(FluxLimiterError::ClockError(err)),
+        };
+
+        let mut client_state = self.client_state.borrow_mut();
+        if let Some(tat_nanos) = client_state.get_mut(client_id) {
+            return Ok(self.apply(tat_nanos, current_time_nanos));
+        }
+        let tat_nanos = client_state
+            .entry(client_id.to_owned())
+            .or_insert(current_time_nanos);
+        Ok(self.apply(tat_nanos, current_time_nanos))
+    }
+
+    /// Remove clients that have been fully recovered for longer than `max_stale_nanos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let current_time_nanos = self
+            .clock
+            .now()
+            .map_err(FluxLimiterError::ClockError)?
+            .as_nanos();
+        let mut client_state = self.client_state.borrow_mut();
+        let scanned = client_state.len();
+        client_state.retain(|_, tat_nanos| {
+            !gcra::is_recovered(
+                current_time_nanos,
+                *tat_nanos,
+                self.limits.tolerance_nanos,
+                max_stale_nanos,
+            )
+        });
+
+        Ok(CleanupReport {
+            scanned,
+            removed: scanned - client_state.len(),
+            remaining: client_state.len(),
+        })
+    }
+
+    // run the GCRA step against a client's TAT, storing the new TAT when allowed
+    fn apply(&self, tat_nanos: &mut u64, current_time_nanos: u64) -> FluxLimiterDecision {
+        let (decision, new_tat_nanos) = self.limits.decide(current_time_nanos, *tat_nanos);
+        if let Some(new_tat_nanos) = new_tat_nanos {
+            *tat_nanos = new_tat_nanos;
+        }
+        decision
+    }
+
+    // resolve a clock failure according to the failure policy
+    fn fail(&self, err: FluxLimiterError) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let allowed = match self.failure_policy {
+            FailurePolicy::Propagate => return Err(err),
+            FailurePolicy::FailOpen => true,
+            FailurePolicy::FailClosed => false,
+        };
+
+        // without a clock reading there is no TAT to report
+        Ok(FluxLimiterDecision {
+            allowed,
+            retry_after_seconds: (!allowed)
+                .then(|| self.limits.increment_nanos as f64 / 1_000_000_000.0),
+            remaining_capacity: None,
+            reset_time_nanos: 0,
+            limit_per_second: self.rate(),
+            burst_capacity: self.burst(),
+            shedding: false,
+            debt: 0.0,
+            suggested_delay_seconds: None,
+            reason: DecisionReason::Fallback,
+        })
+    }
+}
@@ -0,0 +1,203 @@
+// src/matrix.rs
+
+//! Per-endpoint, per-client limiting with one config per endpoint group
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::CleanupReport;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Rate limits every (endpoint, client) pair, with a config per endpoint
+/// group.
+///
+/// The common "each client may search 5 times a second but write once a
+/// second" setup, without formatting `"search:user_123"` keys by hand:
+/// each endpoint group owns a limiter keyed by client, so a client's
+/// budget in one group never affects another, and
+/// [`check`](Self::check) picks the group and checks the client in one
+/// call. Endpoints with no group of their own use the
+/// [`fallback`](MatrixBuilder::fallback) config if one is set, and are
+/// rejected otherwise.
+///
+/// Build one with [`MatrixLimiter::new`] and [`MatrixBuilder::build`].
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{MatrixLimiter, SystemClock};
+///
+/// let limiter = MatrixLimiter::new()
+///     .endpoint("search", 5.0, 2.0)
+///     .endpoint("write", 1.0, 0.0)
+///     .build(SystemClock)
+///     .unwrap();
+///
+/// assert!(limiter.check("write", "user_123").unwrap().allowed);
+/// assert!(!limiter.check("write", "user_123").unwrap().allowed);
+/// // the same client still has its search budget
+/// assert!(limiter.check("search", "user_123").unwrap().allowed);
+/// ```
+#[derive(Debug)]
+pub struct MatrixLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    endpoints: HashMap<String, FluxLimiter<T, C>>,
+    fallback: Option<FluxLimiter<T, C>>,
+}
+
+impl MatrixLimiter<String> {
+    /// Start building a limiter with no endpoint groups
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> MatrixBuilder {
+        MatrixBuilder::default()
+    }
+}
+
+impl<T, C> MatrixLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+{
+    /// Check a request for `client_id` against `endpoint`'s group.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::UnknownEndpoint`] if `endpoint` has no
+    /// group and no fallback is set, and otherwise the same errors as
+    /// [`FluxLimiter::check_request`].
+    pub fn check(
+        &self,
+        endpoint: &str,
+        client_id: T,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.resolve(endpoint)?.check_request(client_id)
+    }
+
+    /// The limiter checks against `endpoint` go to, or `None` if it has no
+    /// group and no fallback is set
+    pub fn limiter(&self, endpoint: &str) -> Option<&FluxLimiter<T, C>> {
+        self.endpoints.get(endpoint).or(self.fallback.as_ref())
+    }
+
+    /// Names of the configured endpoint groups, in no particular order
+    pub fn endpoints(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.keys().map(String::as_str)
+    }
+
+    /// Forget `client_id` in every endpoint group, e.g. after a plan
+    /// upgrade. Returns whether any group tracked it.
+    pub fn reset_client<Q>(&self, client_id: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        // reset every limiter rather than stopping at the first hit
+        let mut removed = false;
+        for limiter in self.limiters() {
+            removed |= limiter.reset_client(client_id);
+        }
+        removed
+    }
+
+    /// Clean up stale clients in every endpoint group.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a group; the remaining groups
+    /// are still cleaned. On success the report covers every group.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        for limiter in self.limiters() {
+            match limiter.cleanup_stale_clients(max_stale_nanos) {
+                Ok(cleaned) => report = report.merge(cleaned),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    // the group's limiter, the fallback, or an error naming the endpoint
+    fn resolve(&self, endpoint: &str) -> Result<&FluxLimiter<T, C>, FluxLimiterError> {
+        self.limiter(endpoint)
+            .ok_or_else(|| FluxLimiterError::UnknownEndpoint(endpoint.to_owned()))
+    }
+
+    // every group's limiter, then the fallback's
+    fn limiters(&self) -> impl Iterator<Item = &FluxLimiter<T, C>> {
+        self.endpoints.values().chain(self.fallback.as_ref())
+    }
+}
+
+/// Endpoint groups for a [`MatrixLimiter`], created by
+/// [`MatrixLimiter::new`].
+///
+/// Configs are validated by [`build`](Self::build), so the chain itself
+/// cannot fail.
+#[derive(Debug, Clone, Default)]
+pub struct MatrixBuilder {
+    endpoints: Vec<(String, FluxLimiterConfig)>,
+    fallback: Option<FluxLimiterConfig>,
+}
+
+impl MatrixBuilder {
+    /// Add an endpoint group allowing `rate` requests per second with
+    /// `burst` extra, replacing any group with the same name
+    pub fn endpoint(self, name: impl Into<String>, rate: f64, burst: f64) -> Self {
+        self.endpoint_config(name, FluxLimiterConfig::new(rate, burst))
+    }
+
+    /// Add an endpoint group with a full config, e.g. one with stats or a
+    /// failure policy, replacing any group with the same name
+    pub fn endpoint_config(mut self, name: impl Into<String>, config: FluxLimiterConfig) -> Self {
+        let name = name.into();
+        self.endpoints.retain(|(existing, _)| *existing != name);
+        self.endpoints.push((name, config));
+        self
+    }
+
+    /// Limit endpoints without a group of their own with `config`
+    pub fn fallback(mut self, config: FluxLimiterConfig) -> Self {
+        self.fallback = Some(config);
+        self
+    }
+
+    /// Create the limiter, with a limiter per group sharing clones of
+    /// `clock`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first validation error among the configs.
+    pub fn build<T, C>(self, clock: C) -> Result<MatrixLimiter<T, C>, FluxLimiterError>
+    where
+        T: Hash + Eq + Clone,
+        C: Clock + Clone,
+    {
+        let endpoints = self
+            .endpoints
+            .into_iter()
+            .map(|(name, config)| Ok((name, FluxLimiter::with_config(config, clock.clone())?)))
+            .collect::<Result<_, FluxLimiterError>>()?;
+        let fallback = self
+            .fallback
+            .map(|config| FluxLimiter::with_config(config, clock))
+            .transpose()?;
+        Ok(MatrixLimiter {
+            endpoints,
+            fallback,
+        })
+    }
+}
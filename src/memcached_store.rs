@@ -0,0 +1,111 @@
+// src/memcached_store.rs
+
+//! [`StateStore`] backed by memcached (`memcached` feature)
+
+// dependencies
+use crate::store::{StateStore, StoreError};
+use memcache::{Client, CommandError, MemcacheError};
+
+/// Client TATs stored in memcached, using CAS tokens for the atomic update.
+///
+/// A first write is an `add`, which fails if another instance created the
+/// key first; later writes are `cas` calls with the token returned by the
+/// preceding `gets`. Use the binary protocol (the `memcache` crate default):
+/// over the ASCII protocol a conflicting `add` is not reported.
+///
+/// Connection and server failures surface as [`StoreError`]s, so the
+/// limiter's failure handling applies to them like any other store error.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use flux_limiter::{FluxLimiterConfig, MemcachedStore, StoreBackedLimiter, SystemClock};
+///
+/// let client = memcache::Client::connect("memcache://127.0.0.1:11211").unwrap();
+/// let store = MemcachedStore::new(client).expiration(3600);
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = StoreBackedLimiter::with_config(config, SystemClock, store).unwrap();
+/// limiter.check_request(&"client1".to_string()).unwrap();
+/// ```
+pub struct MemcachedStore {
+    client: Client,
+    expiration: u32,
+}
+
+impl MemcachedStore {
+    /// Create a store using an existing client; entries never expire
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            expiration: 0,
+        }
+    }
+
+    /// Let memcached expire entries `seconds` after their last write.
+    ///
+    /// Pick a value longer than the time a client needs to fully recover its
+    /// burst, or expiry will reset clients early.
+    pub fn expiration(mut self, seconds: u32) -> Self {
+        self.expiration = seconds;
+        self
+    }
+
+    // read the TAT and the CAS token for a key
+    fn gets(&self, key: &str) -> Result<Option<(u64, Option<u64>)>, StoreError> {
+        let mut values = self
+            .client
+            .gets::<(Vec<u8>, u32, Option<u64>)>(&[key])
+            .map_err(StoreError::new)?;
+
+        values
+            .remove(key)
+            .map(|(bytes, _, cas)| Ok((decode(&bytes)?, cas)))
+            .transpose()
+    }
+}
+
+impl<K> StateStore<K> for MemcachedStore
+where
+    K: AsRef<str> + ?Sized,
+{
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError> {
+        Ok(self.gets(key.as_ref())?.map(|(tat_nanos, _)| tat_nanos))
+    }
+
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError> {
+        let key = key.as_ref();
+        let value = new.to_string();
+
+        let Some(expected) = expected else {
+            return match self.client.add(key, value.as_str(), self.expiration) {
+                Ok(()) => Ok(true),
+                Err(MemcacheError::CommandError(CommandError::KeyExists)) => Ok(false),
+                Err(err) => Err(StoreError::new(err)),
+            };
+        };
+
+        match self.gets(key)? {
+            Some((tat_nanos, Some(cas))) if tat_nanos == expected => self
+                .client
+                .cas(key, value.as_str(), self.expiration, cas)
+                .map_err(StoreError::new),
+            Some((_, None)) => Err(StoreError::new("memcached returned no CAS token")),
+            // changed or expired since it was read
+            _ => Ok(false),
+        }
+    }
+}
+
+// TATs are stored as decimal strings so they stay readable with memcached tools
+fn decode(bytes: &[u8]) -> Result<u64, StoreError> {
+    std::str::from_utf8(bytes)
+        .map_err(StoreError::new)?
+        .parse()
+        .map_err(StoreError::new)
+}
@@ -0,0 +1,197 @@
+// src/namespace.rs
+
+//! Per-tenant namespaces with isolated limiter state
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::{CleanupReport, FluxLimiterStats};
+use dashmap::DashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Rate limits keys within namespaces, e.g. one namespace per tenant.
+///
+/// Every namespace gets its own limiter, so keys from different namespaces
+/// never share state: the same key has a separate budget in each namespace,
+/// one tenant's client count does not grow another's map, and cleanup or
+/// [`remove_namespace`](Self::remove_namespace) only touches the namespace it
+/// is asked about.
+///
+/// Namespaces use the default config unless one is set for them with
+/// [`set_config`](Self::set_config). Each namespace's limiter is created the
+/// first time it is checked, and all of them share a clone of the clock.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, NamespacedLimiter, SystemClock};
+///
+/// let limiter = NamespacedLimiter::new(FluxLimiterConfig::new(10.0, 5.0), SystemClock).unwrap();
+/// limiter
+///     .set_config("enterprise", FluxLimiterConfig::new(1000.0, 500.0))
+///     .unwrap();
+///
+/// assert!(limiter.check("acme", "user_123").unwrap().allowed);
+/// assert!(limiter.check("enterprise", "user_123").unwrap().allowed);
+/// ```
+pub struct NamespacedLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    default_config: FluxLimiterConfig,
+    configs: DashMap<String, FluxLimiterConfig>,
+    limiters: DashMap<String, Arc<FluxLimiter<T, C>>>,
+    clock: C,
+}
+
+impl<T, C> NamespacedLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    /// Create a limiter whose namespaces use `default_config` unless
+    /// configured otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error.
+    pub fn new(default_config: FluxLimiterConfig, clock: C) -> Result<Self, FluxLimiterError> {
+        default_config.validate()?;
+        Ok(Self {
+            default_config,
+            configs: DashMap::new(),
+            limiters: DashMap::new(),
+            clock,
+        })
+    }
+
+    /// Set (or replace) the config for `namespace`.
+    ///
+    /// Replacing a config discards the namespace's client state.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error, leaving the namespace unchanged.
+    pub fn set_config(
+        &self,
+        namespace: impl Into<String>,
+        config: FluxLimiterConfig,
+    ) -> Result<(), FluxLimiterError> {
+        config.validate()?;
+        let namespace = namespace.into();
+        // swap the config first so a concurrent check cannot rebuild the old one
+        self.configs.insert(namespace.clone(), config);
+        self.limiters.remove(&namespace);
+        Ok(())
+    }
+
+    /// The config checks in `namespace` are made against
+    pub fn config(&self, namespace: &str) -> FluxLimiterConfig {
+        self.configs
+            .get(namespace)
+            .map_or_else(|| self.default_config.clone(), |config| config.clone())
+    }
+
+    /// Return the limiter for `namespace`, creating it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error; configs are validated when set,
+    /// so this does not happen in practice.
+    pub fn namespace(&self, namespace: &str) -> Result<Arc<FluxLimiter<T, C>>, FluxLimiterError> {
+        if let Some(limiter) = self.limiters.get(namespace) {
+            return Ok(Arc::clone(&limiter));
+        }
+
+        let config = self.config(namespace);
+        let limiter = self
+            .limiters
+            .entry(namespace.to_owned())
+            .or_try_insert_with(|| {
+                FluxLimiter::with_config(config, self.clock.clone()).map(Arc::new)
+            })?;
+        Ok(Arc::clone(&limiter))
+    }
+
+    /// Check a request for `client_id` within `namespace`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request`].
+    pub fn check(
+        &self,
+        namespace: &str,
+        client_id: T,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.namespace(namespace)?.check_request(client_id)
+    }
+
+    /// Statistics for `namespace`, or `None` if it has not been checked yet.
+    pub fn stats(&self, namespace: &str) -> Option<FluxLimiterStats> {
+        self.limiters.get(namespace).map(|limiter| limiter.stats())
+    }
+
+    /// Clean up stale clients in `namespace` only.
+    ///
+    /// Namespaces that have not been checked yet report nothing to clean.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::cleanup_stale_clients`].
+    pub fn cleanup_namespace(
+        &self,
+        namespace: &str,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        // clone the handle so the map is not locked while cleaning
+        let limiter = self.limiters.get(namespace).map(|limiter| limiter.clone());
+        limiter.map_or(Ok(CleanupReport::default()), |limiter| {
+            limiter.cleanup_stale_clients(max_stale_nanos)
+        })
+    }
+
+    /// Clean up stale clients in every namespace checked so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a namespace; the remaining
+    /// namespaces are still cleaned. On success the report covers every
+    /// namespace.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        for limiter in self.limiters.iter() {
+            match limiter.cleanup_stale_clients(max_stale_nanos) {
+                Ok(cleaned) => report = report.merge(cleaned),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    /// Drop all client state for `namespace`, e.g. when a tenant is removed.
+    ///
+    /// Its config, if one was set, is kept. Returns whether the namespace had
+    /// any state.
+    pub fn remove_namespace(&self, namespace: &str) -> bool {
+        self.limiters.remove(namespace).is_some()
+    }
+
+    /// Namespaces that have been checked and hold state
+    pub fn namespaces(&self) -> Vec<String> {
+        self.limiters
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+}
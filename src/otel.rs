@@ -0,0 +1,53 @@
+// src/otel.rs
+
+//! OpenTelemetry metrics and span attributes for decisions (otel feature)
+
+// dependencies
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::trace::get_active_span;
+
+/// Name of the counter of decisions, split by the `rate_limit.allowed` attribute
+pub(crate) const DECISIONS_METRIC: &str = "flux_limiter.decisions";
+
+/// Name of the histogram of retry delays handed to denied requests
+pub(crate) const RETRY_AFTER_METRIC: &str = "flux_limiter.retry_after";
+
+// instruments created from the meter passed to `with_otel_meter`
+#[derive(Debug, Clone)]
+pub(crate) struct OtelHook {
+    decisions: Counter<u64>,
+    retry_after: Histogram<f64>,
+}
+
+impl OtelHook {
+    pub(crate) fn new(meter: &Meter) -> Self {
+        Self {
+            decisions: meter
+                .u64_counter(DECISIONS_METRIC)
+                .with_description("Rate limit decisions made")
+                .build(),
+            retry_after: meter
+                .f64_histogram(RETRY_AFTER_METRIC)
+                .with_description("Retry delay suggested to denied requests")
+                .with_unit("s")
+                .build(),
+        }
+    }
+
+    // export one decision and annotate the caller's active span with it
+    pub(crate) fn record(&self, allowed: bool, retry_after_seconds: Option<f64>) {
+        self.decisions
+            .add(1, &[KeyValue::new("rate_limit.allowed", allowed)]);
+        if let Some(retry_after) = retry_after_seconds {
+            self.retry_after.record(retry_after, &[]);
+        }
+
+        get_active_span(|span| {
+            span.set_attribute(KeyValue::new("rate_limit.allowed", allowed));
+            if let Some(retry_after) = retry_after_seconds {
+                span.set_attribute(KeyValue::new("rate_limit.retry_after", retry_after));
+            }
+        });
+    }
+}
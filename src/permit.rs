@@ -0,0 +1,361 @@
+// src/permit.rs
+
+//! Scoped permits that can hand capacity back when dropped
+
+// dependencies
+use crate::clock::Clock;
+use crate::decision::{AllowedMeta, Decision};
+#[cfg(feature = "tokio")]
+use crate::decision::{DecisionReason, DeniedMeta};
+#[cfg(feature = "tokio")]
+use crate::errors::AcquireError;
+use crate::errors::RateLimitedError;
+use crate::flux_limiter::FluxLimiter;
+#[cfg(feature = "tokio")]
+use crate::snapshot::ClientSnapshot;
+use std::fmt;
+#[cfg(feature = "tokio")]
+use std::future::{Future, poll_fn};
+use std::hash::{BuildHasher, Hash};
+#[cfg(feature = "tokio")]
+use std::pin::pin;
+#[cfg(feature = "tokio")]
+use std::task::Poll;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::time::Instant;
+#[cfg(feature = "cancellation")]
+use tokio_util::sync::CancellationToken;
+
+/// Proof that a request was admitted by [`FluxLimiter::acquire`].
+///
+/// A permit holds one request's worth of the client's capacity for as long
+/// as the request runs. By default the capacity stays consumed when the
+/// permit is dropped, exactly as with
+/// [`check_request`](FluxLimiter::check_request). Mark it with
+/// [`refund_on_drop`](Self::refund_on_drop) and dropping it gives the
+/// capacity back, unless [`commit`](Self::commit) is called first: early
+/// returns, `?` and panics between admission and completion then refund
+/// automatically, and only requests that ran to completion count.
+///
+/// Refunds follow [`FluxLimiter::refund`]; one that fails because the clock
+/// cannot be read is skipped, since a drop has no way to report it.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(1.0, 0.0);
+/// let limiter = FluxLimiter::<String, _>::with_config(config, SystemClock).unwrap();
+///
+/// let permit = limiter.acquire("user_123".to_string()).unwrap().refund_on_drop();
+/// // the handler bails out before doing any work
+/// drop(permit);
+///
+/// let permit = limiter.acquire("user_123".to_string()).unwrap().refund_on_drop();
+/// // the request was served: keep the capacity consumed
+/// permit.commit();
+/// assert!(limiter.acquire("user_123".to_string()).is_err());
+/// ```
+#[must_use = "dropping a permit ends the request it admits"]
+pub struct Permit<'a, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    limiter: &'a FluxLimiter<T, C, H>,
+    client_id: T,
+    meta: AllowedMeta,
+    // cost handed back on drop
+    unused: f64,
+}
+
+impl<'a, T, C, H> Permit<'a, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    /// The client the permit was acquired for
+    pub fn client_id(&self) -> &T {
+        &self.client_id
+    }
+
+    /// Metadata of the decision that admitted the request
+    pub fn meta(&self) -> &AllowedMeta {
+        &self.meta
+    }
+
+    /// Refund the permit's full cost if it is dropped without
+    /// [`commit`](Self::commit).
+    pub fn refund_on_drop(self) -> Self {
+        self.refund_unused(1.0)
+    }
+
+    /// Refund `cost` when the permit is dropped, e.g. `0.5` if the request
+    /// turned out to need only half its work. Replaces any earlier amount;
+    /// negative and non-finite costs refund nothing.
+    pub fn refund_unused(mut self, cost: f64) -> Self {
+        self.unused = if cost.is_finite() { cost.max(0.0) } else { 0.0 };
+        self
+    }
+
+    /// Keep the capacity consumed: the request ran, nothing is refunded.
+    pub fn commit(mut self) {
+        self.unused = 0.0;
+    }
+}
+
+impl<T, C, H> Drop for Permit<'_, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    fn drop(&mut self) {
+        if self.unused > 0.0 {
+            let _ = self.limiter.refund(&self.client_id, self.unused);
+        }
+    }
+}
+
+impl<T, C, H> fmt::Debug for Permit<'_, T, C, H>
+where
+    T: Hash + Eq + Clone + fmt::Debug,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Permit")
+            .field("client_id", &self.client_id)
+            .field("meta", &self.meta)
+            .field("unused", &self.unused)
+            .finish()
+    }
+}
+
+impl<T, C, H> FluxLimiter<T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    /// Check a request and, if it is allowed, return a [`Permit`] scoped to
+    /// its lifetime.
+    ///
+    /// Denials and failures are reported as by [`try_check`](Self::try_check).
+    ///
+    /// # Errors
+    ///
+    /// * `RateLimitedError::Denied` - the client is over its limit
+    /// * `RateLimitedError::Failed` - the clock failed under
+    ///   [`FailurePolicy::Propagate`](crate::FailurePolicy::Propagate)
+    pub fn acquire(&self, client_id: T) -> Result<Permit<'_, T, C, H>, RateLimitedError> {
+        match self.check_request_typed(client_id.clone())? {
+            Decision::Allowed(meta) => Ok(Permit {
+                limiter: self,
+                client_id,
+                meta,
+                unused: 0.0,
+            }),
+            Decision::Denied(meta) => Err(RateLimitedError::Denied(meta)),
+        }
+    }
+
+    /// Wait until a request conforms and return its [`Permit`], giving up
+    /// once `max_wait` has passed.
+    ///
+    /// Each denial's retry delay is slept on the tokio timer before checking
+    /// again, so this must run inside a tokio runtime. A wait that would end
+    /// after the deadline is not started: the error is returned at once,
+    /// rather than after sleeping through the remaining time. The limiter's
+    /// clock should run in step with tokio time, as [`SystemClock`] and
+    /// [`TokioClock`](crate::TokioClock) do.
+    ///
+    /// # Cancel safety
+    ///
+    /// Capacity is only consumed by the check that admits the request, and
+    /// the permit is returned without awaiting anything after it. Dropping
+    /// the future while it waits, e.g. in `tokio::select!` or because its
+    /// task was aborted, therefore reserves nothing and leaks nothing.
+    ///
+    /// # Errors
+    ///
+    /// * `AcquireError::Elapsed` - the request would not conform within
+    ///   `max_wait`; holds the latest denial
+    /// * `AcquireError::Failed` - the clock failed under
+    ///   [`FailurePolicy::Propagate`](crate::FailurePolicy::Propagate)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{AcquireError, FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    ///
+    /// let permit = limiter.acquire_timeout("job-queue", Duration::from_millis(10)).await;
+    /// assert!(permit.is_ok());
+    ///
+    /// // the next slot is a second away
+    /// let err = limiter
+    ///     .acquire_timeout("job-queue", Duration::from_millis(10))
+    ///     .await
+    ///     .unwrap_err();
+    /// assert!(matches!(err, AcquireError::Elapsed(_)));
+    /// # }
+    /// ```
+    ///
+    /// [`SystemClock`]: crate::SystemClock
+    #[cfg(feature = "tokio")]
+    pub async fn acquire_timeout(
+        &self,
+        client_id: T,
+        max_wait: Duration,
+    ) -> Result<Permit<'_, T, C, H>, AcquireError> {
+        self.acquire_until(client_id, max_wait, std::future::pending())
+            .await
+    }
+
+    /// Like [`acquire_timeout`](Self::acquire_timeout), but also gives up
+    /// with [`AcquireError::Cancelled`] as soon as `token` is cancelled.
+    ///
+    /// Sharing one token between all waiters lets a shutting-down server
+    /// release them at once instead of waiting out their sleeps. A waiter
+    /// whose token is already cancelled returns without checking, so it
+    /// consumes no capacity; one cancelled mid-wait holds none, as with
+    /// `acquire_timeout`.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`acquire_timeout`](Self::acquire_timeout), and
+    /// `AcquireError::Cancelled` once `token` is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{AcquireError, FluxLimiter, FluxLimiterConfig, SystemClock};
+    /// use std::time::Duration;
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let config = FluxLimiterConfig::new(1.0, 0.0);
+    /// let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+    /// let shutdown = CancellationToken::new();
+    ///
+    /// shutdown.cancel();
+    /// let result = limiter
+    ///     .acquire_cancellable("job-queue", Duration::from_secs(60), &shutdown)
+    ///     .await;
+    /// assert!(matches!(result, Err(AcquireError::Cancelled)));
+    /// # }
+    /// ```
+    #[cfg(feature = "cancellation")]
+    pub async fn acquire_cancellable(
+        &self,
+        client_id: T,
+        max_wait: Duration,
+        token: &CancellationToken,
+    ) -> Result<Permit<'_, T, C, H>, AcquireError> {
+        if token.is_cancelled() {
+            return Err(AcquireError::Cancelled);
+        }
+        self.acquire_until(client_id, max_wait, token.cancelled())
+            .await
+    }
+
+    // check and sleep until admitted, past the deadline, or until `cancelled` completes
+    #[cfg(feature = "tokio")]
+    async fn acquire_until(
+        &self,
+        client_id: T,
+        max_wait: Duration,
+        cancelled: impl Future<Output = ()>,
+    ) -> Result<Permit<'_, T, C, H>, AcquireError> {
+        let mut cancelled = pin!(cancelled);
+        let deadline = Instant::now().checked_add(max_wait);
+
+        // with FIFO waiters, wait until every earlier waiter for the key was served
+        let mut place = self
+            .wait_queues()
+            .map(|queues| queues.join(client_id.clone()));
+        if let Some(place) = place.as_mut() {
+            let mut turn = pin!(place.queue().lock_owned());
+            let mut expired = pin!(async {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            });
+            let turn = poll_fn(|cx| {
+                if cancelled.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Err(AcquireError::Cancelled));
+                }
+                if let Poll::Ready(turn) = turn.as_mut().poll(cx) {
+                    return Poll::Ready(Ok(turn));
+                }
+                expired
+                    .as_mut()
+                    .poll(cx)
+                    .map(|()| Err(self.elapsed_in_queue(&client_id)))
+            })
+            .await?;
+            place.hold(turn);
+        }
+
+        loop {
+            let meta = match self.acquire(client_id.clone()) {
+                Ok(permit) => return Ok(permit),
+                Err(RateLimitedError::Denied(meta)) => meta,
+                Err(RateLimitedError::Failed(err)) => return Err(AcquireError::Failed(err)),
+            };
+            let wake = Instant::now().checked_add(meta.retry_after());
+            let wake = match (wake, deadline) {
+                (Some(wake), Some(deadline)) if wake <= deadline => wake,
+                // no deadline within the timer's range: effectively unbounded
+                (Some(wake), None) => wake,
+                _ => return Err(AcquireError::Elapsed(meta)),
+            };
+
+            let mut sleep = pin!(tokio::time::sleep_until(wake));
+            let woke = poll_fn(|cx| {
+                if cancelled.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(false);
+                }
+                sleep.as_mut().poll(cx).map(|()| true)
+            })
+            .await;
+            if !woke {
+                return Err(AcquireError::Cancelled);
+            }
+        }
+    }
+
+    // the error for a waiter whose deadline passed before its turn came
+    #[cfg(feature = "tokio")]
+    fn elapsed_in_queue(&self, client_id: &T) -> AcquireError {
+        // the waiters ahead hold the next slots, so report the client's schedule
+        let snapshot = match self.client_snapshot(client_id) {
+            Ok(snapshot) => snapshot,
+            Err(err) => return AcquireError::Failed(err),
+        };
+        AcquireError::Elapsed(DeniedMeta {
+            retry_after_seconds: snapshot
+                .as_ref()
+                .map_or(0.0, ClientSnapshot::time_until_conforming_seconds),
+            reset_time_nanos: snapshot.as_ref().map_or(0, |snapshot| snapshot.tat_nanos),
+            limit_per_second: self.rate(),
+            burst_capacity: self.burst(),
+            shedding: false,
+            debt: snapshot.as_ref().map_or(0.0, |snapshot| snapshot.debt),
+            reason: DecisionReason::BurstExhausted,
+        })
+    }
+}
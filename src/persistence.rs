@@ -0,0 +1,182 @@
+// src/persistence.rs
+
+//! File persistence for limiter state (`persistence` feature)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::snapshot::StateSnapshot;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{self, BufWriter, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// methods for saving and loading state files
+impl<T, C> FluxLimiter<T, C>
+where
+    T: Hash + Eq + Clone + Serialize + DeserializeOwned,
+    C: Clock,
+{
+    /// Write the limiter state to `path` as JSON.
+    ///
+    /// The snapshot is written to a temporary file next to `path` and renamed
+    /// into place, so a crash mid-write never leaves a truncated state file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::PersistenceError`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FluxLimiterError> {
+        write_atomically(path.as_ref(), &self.export_state())
+            .map_err(FluxLimiterError::PersistenceError)
+    }
+
+    /// Create a limiter from a state file written by [`FluxLimiter::save`].
+    ///
+    /// A missing file yields a fresh limiter, so the same call works on first
+    /// start. Clients whose TAT has already recovered by the current clock
+    /// time are dropped rather than restored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+    ///
+    /// let path = std::env::temp_dir().join("flux-limiter-doc-load.json");
+    /// let config = FluxLimiterConfig::new(10.0, 5.0);
+    ///
+    /// let limiter = FluxLimiter::<String>::load(config.clone(), SystemClock, &path).unwrap();
+    /// limiter.check_request("client1".to_string()).unwrap();
+    /// limiter.save(&path).unwrap();
+    ///
+    /// let restored = FluxLimiter::<String>::load(config, SystemClock, &path).unwrap();
+    /// assert!(restored.client_state.contains_key("client1"));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::PersistenceError`] if the file exists but cannot
+    /// be read or parsed, plus the errors of [`FluxLimiter::with_state`].
+    pub fn load(
+        config: FluxLimiterConfig,
+        clock: C,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, FluxLimiterError> {
+        let snapshot = match fs::read(path.as_ref()) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|err| FluxLimiterError::PersistenceError(err.into()))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => StateSnapshot::new(Vec::new()),
+            Err(err) => return Err(FluxLimiterError::PersistenceError(err)),
+        };
+
+        let limiter = Self::with_state(config, clock, snapshot)?;
+        limiter.drop_recovered_clients()?;
+
+        Ok(limiter)
+    }
+}
+
+/// A limiter that restores its state from a file and saves it back on drop.
+///
+/// Dereferences to [`FluxLimiter`], so it is used exactly like the limiter it
+/// wraps. Saving on drop is best effort; call [`PersistentLimiter::save`]
+/// during graceful shutdown to observe errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, PersistentLimiter, SystemClock};
+///
+/// let path = std::env::temp_dir().join("flux-limiter-doc-persistent.json");
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+///
+/// {
+///     let limiter = PersistentLimiter::<String>::open(config.clone(), SystemClock, &path).unwrap();
+///     limiter.check_request("client1".to_string()).unwrap();
+/// } // state written here
+///
+/// let limiter = PersistentLimiter::<String>::open(config, SystemClock, &path).unwrap();
+/// assert!(limiter.client_state.contains_key("client1"));
+/// # drop(limiter);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct PersistentLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone + Serialize + DeserializeOwned,
+    C: Clock,
+{
+    limiter: FluxLimiter<T, C>,
+    path: PathBuf,
+}
+
+impl<T, C> PersistentLimiter<T, C>
+where
+    T: Hash + Eq + Clone + Serialize + DeserializeOwned,
+    C: Clock,
+{
+    /// Load the limiter from `path` (see [`FluxLimiter::load`]) and keep the
+    /// path for saving.
+    pub fn open(
+        config: FluxLimiterConfig,
+        clock: C,
+        path: impl Into<PathBuf>,
+    ) -> Result<Self, FluxLimiterError> {
+        let path = path.into();
+        let limiter = FluxLimiter::load(config, clock, &path)?;
+        Ok(Self { limiter, path })
+    }
+
+    /// Save the current state to the limiter's file.
+    pub fn save(&self) -> Result<(), FluxLimiterError> {
+        self.limiter.save(&self.path)
+    }
+
+    /// The file state is saved to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<T, C> Deref for PersistentLimiter<T, C>
+where
+    T: Hash + Eq + Clone + Serialize + DeserializeOwned,
+    C: Clock,
+{
+    type Target = FluxLimiter<T, C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.limiter
+    }
+}
+
+impl<T, C> Drop for PersistentLimiter<T, C>
+where
+    T: Hash + Eq + Clone + Serialize + DeserializeOwned,
+    C: Clock,
+{
+    fn drop(&mut self) {
+        // nowhere to report an error from drop; explicit save() surfaces it
+        let _ = self.save();
+    }
+}
+
+// write to a sibling temp file, sync it, then rename over the target
+fn write_atomically<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let mut tmp_name = OsString::from(path.file_name().unwrap_or_default());
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let file = File::create(&tmp_path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, value)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+
+    fs::rename(&tmp_path, path)
+}
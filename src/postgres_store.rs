@@ -0,0 +1,121 @@
+// src/postgres_store.rs
+
+//! [`StateStore`] backed by a PostgreSQL table (`postgres` feature)
+
+// dependencies
+use crate::store::{StateStore, StoreError};
+use postgres::Client;
+use std::sync::Mutex;
+
+/// Client TATs stored in a PostgreSQL table of `(key, tat)` rows, shared by
+/// every instance that points at the same table.
+///
+/// Each compare-and-swap is a single statement, so no transaction or
+/// advisory lock is held between the read and the write: a first write is an
+/// `INSERT ... ON CONFLICT DO NOTHING`, a later one an `UPDATE ... WHERE tat =
+/// <expected>`. Either affects no row if another instance won the race, and
+/// the limiter retries.
+///
+/// The table name is interpolated into SQL and must come from trusted
+/// configuration.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use flux_limiter::{FluxLimiterConfig, PostgresStore, StoreBackedLimiter, SystemClock};
+///
+/// let client = postgres::Client::connect("host=localhost user=postgres", postgres::NoTls).unwrap();
+/// let store = PostgresStore::new(client, "rate_limits");
+/// store.create_table().unwrap();
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = StoreBackedLimiter::with_config(config, SystemClock, store).unwrap();
+/// limiter.check_request(&"client1".to_string()).unwrap();
+/// ```
+pub struct PostgresStore {
+    client: Mutex<Client>,
+    table: String,
+}
+
+impl PostgresStore {
+    /// Create a store over `table` using an existing connection
+    pub fn new(client: Client, table: impl Into<String>) -> Self {
+        Self {
+            client: Mutex::new(client),
+            table: table.into(),
+        }
+    }
+
+    /// Create the state table if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StoreError`] if the statement fails.
+    pub fn create_table(&self) -> Result<(), StoreError> {
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS \"{}\" (key TEXT PRIMARY KEY, tat BIGINT NOT NULL)",
+            self.table
+        );
+        self.lock()?.batch_execute(&sql).map_err(StoreError::new)
+    }
+
+    // lock the connection; postgres::Client needs &mut for every query
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Client>, StoreError> {
+        self.client
+            .lock()
+            .map_err(|_| StoreError::new("postgres connection mutex poisoned"))
+    }
+}
+
+impl<K> StateStore<K> for PostgresStore
+where
+    K: AsRef<str> + ?Sized,
+{
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError> {
+        let sql = format!("SELECT tat FROM \"{}\" WHERE key = $1", self.table);
+        let row = self
+            .lock()?
+            .query_opt(&sql, &[&key.as_ref()])
+            .map_err(StoreError::new)?;
+
+        row.map(|row| from_sql(row.get(0))).transpose()
+    }
+
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError> {
+        let new = to_sql(new)?;
+        let mut client = self.lock()?;
+        let rows = match expected {
+            None => {
+                let sql = format!(
+                    "INSERT INTO \"{}\" (key, tat) VALUES ($1, $2) ON CONFLICT (key) DO NOTHING",
+                    self.table
+                );
+                client.execute(&sql, &[&key.as_ref(), &new])
+            }
+            Some(expected) => {
+                let sql = format!(
+                    "UPDATE \"{}\" SET tat = $2 WHERE key = $1 AND tat = $3",
+                    self.table
+                );
+                client.execute(&sql, &[&key.as_ref(), &new, &to_sql(expected)?])
+            }
+        }
+        .map_err(StoreError::new)?;
+
+        Ok(rows == 1)
+    }
+}
+
+// TATs are stored as BIGINT, which covers nanosecond timestamps until 2262
+fn to_sql(tat_nanos: u64) -> Result<i64, StoreError> {
+    i64::try_from(tat_nanos).map_err(StoreError::new)
+}
+
+fn from_sql(tat_nanos: i64) -> Result<u64, StoreError> {
+    u64::try_from(tat_nanos).map_err(StoreError::new)
+}
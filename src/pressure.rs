@@ -0,0 +1,77 @@
+// src/pressure.rs
+
+//! System pressure signals for load shedding
+
+// dependencies
+use std::fmt;
+use std::sync::Arc;
+
+/// Reports how loaded the system is, from `0.0` (idle) to `1.0` (saturated).
+///
+/// Attach a provider with
+/// [`FluxLimiter::with_pressure_provider`](crate::FluxLimiter::with_pressure_provider)
+/// and the limiter scales its effective rate by `1.0 - pressure`: at 0.25
+/// pressure clients get 75% of the configured rate, and at 1.0 every request
+/// is shed. Values outside the range are clamped and NaN counts as no
+/// pressure.
+///
+/// The provider is consulted once per decision, so it should be cheap, e.g.
+/// reading a CPU or queue-depth gauge that is sampled elsewhere. Closures
+/// returning `f64` implement the trait.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock};
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let queue_depth = Arc::new(AtomicUsize::new(0));
+/// let depth = queue_depth.clone();
+///
+/// let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(100.0, 10.0), SystemClock)
+///     .unwrap()
+///     .with_pressure_provider(move || depth.load(Ordering::Relaxed) as f64 / 1_000.0);
+///
+/// queue_depth.store(500, Ordering::Relaxed); // half full: rate drops to 50 req/s
+/// let decision = limiter.check_request("user_123").unwrap();
+/// assert!(decision.shedding);
+/// ```
+pub trait PressureProvider: Send + Sync {
+    /// Current pressure between `0.0` and `1.0`
+    fn pressure(&self) -> f64;
+}
+
+impl<F> PressureProvider for F
+where
+    F: Fn() -> f64 + Send + Sync,
+{
+    fn pressure(&self) -> f64 {
+        self()
+    }
+}
+
+// shared handle to a provider, so the limiter can keep deriving Debug
+#[derive(Clone)]
+pub(crate) struct PressureHook(Arc<dyn PressureProvider>);
+
+impl PressureHook {
+    pub(crate) fn new(provider: impl PressureProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
+    // share of the configured rate to grant right now, between 0.0 and 1.0
+    pub(crate) fn rate_factor(&self) -> f64 {
+        let pressure = self.0.pressure();
+        if pressure.is_nan() {
+            return 1.0;
+        }
+        1.0 - pressure.clamp(0.0, 1.0)
+    }
+}
+
+impl fmt::Debug for PressureHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PressureHook")
+    }
+}
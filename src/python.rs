@@ -0,0 +1,165 @@
+// src/python.rs
+
+//! Python bindings through PyO3 (`pyo3` feature)
+
+// dependencies
+use crate::clock::SystemClock;
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+/// `flux_limiter.FluxLimiter` in Python: a [`FluxLimiter`] keyed by `str`
+/// on the system clock.
+///
+/// Checks release the GIL while they run, so Python threads checking
+/// different clients proceed in parallel. Invalid configs raise
+/// `ValueError` and limiter failures `RuntimeError`.
+///
+/// ```python
+/// from flux_limiter import FluxLimiter
+///
+/// limiter = FluxLimiter(10.0, 5.0)
+/// decision = limiter.check("user_123")
+/// if not decision:
+///     print(f"retry after {decision.retry_after_seconds:.2f}s")
+/// ```
+#[pyclass(name = "FluxLimiter", module = "flux_limiter", frozen)]
+pub struct PyFluxLimiter {
+    inner: FluxLimiter<String, SystemClock>,
+}
+
+#[pymethods]
+impl PyFluxLimiter {
+    /// Create a limiter allowing `rate_per_second` with `burst_capacity` extra
+    #[new]
+    #[pyo3(signature = (rate_per_second, burst_capacity = 0.0))]
+    fn new(rate_per_second: f64, burst_capacity: f64) -> PyResult<Self> {
+        FluxLimiter::with_config(
+            FluxLimiterConfig::new(rate_per_second, burst_capacity),
+            SystemClock,
+        )
+        .map(|inner| Self { inner })
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Check a request for `client_id` now
+    fn check(&self, py: Python<'_>, client_id: String) -> PyResult<PyDecision> {
+        py.allow_threads(|| self.inner.check_request(client_id))
+            .map(PyDecision::from)
+            .map_err(runtime_error)
+    }
+
+    /// Check a request for `client_id` at `timestamp_nanos`, e.g. an event
+    /// time when replaying a log
+    fn check_at(
+        &self,
+        py: Python<'_>,
+        client_id: String,
+        timestamp_nanos: u64,
+    ) -> PyResult<PyDecision> {
+        py.allow_threads(|| self.inner.check_request_at(client_id, timestamp_nanos))
+            .map(PyDecision::from)
+            .map_err(runtime_error)
+    }
+
+    /// Forget `client_id`, returning whether it was tracked
+    fn reset(&self, client_id: &str) -> bool {
+        self.inner.reset_client(client_id)
+    }
+
+    /// Drop clients recovered for at least `max_stale_nanos`, returning how
+    /// many were removed
+    fn cleanup(&self, py: Python<'_>, max_stale_nanos: u64) -> PyResult<usize> {
+        py.allow_threads(|| self.inner.cleanup_stale_clients(max_stale_nanos))
+            .map(|report| report.removed)
+            .map_err(runtime_error)
+    }
+
+    /// Configured rate, in requests per second
+    #[getter]
+    fn rate(&self) -> f64 {
+        self.inner.rate()
+    }
+
+    /// Configured burst capacity
+    #[getter]
+    fn burst(&self) -> f64 {
+        self.inner.burst()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FluxLimiter(rate_per_second={:?}, burst_capacity={:?})",
+            self.inner.rate(),
+            self.inner.burst()
+        )
+    }
+}
+
+/// `flux_limiter.Decision` in Python: the fields of a
+/// [`FluxLimiterDecision`], truthy when the request is allowed
+#[pyclass(name = "Decision", module = "flux_limiter", frozen, get_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyDecision {
+    /// Whether the request is allowed
+    pub allowed: bool,
+    /// Seconds to wait before retrying, for denied requests
+    pub retry_after_seconds: Option<f64>,
+    /// Requests still available in the burst
+    pub remaining_capacity: Option<f64>,
+    /// When the client's capacity is fully restored (nanoseconds since epoch)
+    pub reset_time_nanos: u64,
+    /// Configured rate, in requests per second
+    pub limit_per_second: f64,
+    /// Configured burst capacity
+    pub burst_capacity: f64,
+    /// [`DecisionReason::as_str`](crate::DecisionReason::as_str) of the decision
+    pub reason: &'static str,
+}
+
+#[pymethods]
+impl PyDecision {
+    fn __bool__(&self) -> bool {
+        self.allowed
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Decision(allowed={}, reason='{}', retry_after_seconds={:?})",
+            if self.allowed { "True" } else { "False" },
+            self.reason,
+            self.retry_after_seconds
+        )
+    }
+}
+
+impl From<FluxLimiterDecision> for PyDecision {
+    fn from(decision: FluxLimiterDecision) -> Self {
+        Self {
+            allowed: decision.allowed,
+            retry_after_seconds: decision.retry_after_seconds,
+            remaining_capacity: decision.remaining_capacity,
+            reset_time_nanos: decision.reset_time_nanos,
+            limit_per_second: decision.limit_per_second,
+            burst_capacity: decision.burst_capacity,
+            reason: decision.reason.as_str(),
+        }
+    }
+}
+
+/// The `flux_limiter` Python module, exported as `PyInit_flux_limiter`
+#[pymodule]
+#[pyo3(name = "flux_limiter")]
+pub fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyFluxLimiter>()?;
+    module.add_class::<PyDecision>()?;
+    Ok(())
+}
+
+// limiter errors at check time are the clock failing
+fn runtime_error(err: FluxLimiterError) -> PyErr {
+    PyRuntimeError::new_err(err.to_string())
+}
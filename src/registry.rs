@@ -0,0 +1,162 @@
+// src/registry.rs
+
+//! Named collection of rate limiters
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::CleanupReport;
+use dashmap::DashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Manages several named limiters, e.g. one per route or API product.
+///
+/// Configs are registered up front; each limiter is created the first time
+/// its name is used. All limiters share a clone of the registry's clock.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, LimiterRegistry, SystemClock};
+///
+/// let registry = LimiterRegistry::with_configs(
+///     [
+///         ("search", FluxLimiterConfig::new(5.0, 10.0)),
+///         ("upload", FluxLimiterConfig::per_minute(10)),
+///     ],
+///     SystemClock,
+/// )
+/// .unwrap();
+///
+/// let decision = registry.check("search", "user_123").unwrap();
+/// assert!(decision.allowed);
+/// ```
+pub struct LimiterRegistry<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    configs: DashMap<String, FluxLimiterConfig>,
+    limiters: DashMap<String, Arc<FluxLimiter<T, C>>>,
+    clock: C,
+}
+
+impl<T, C> LimiterRegistry<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    /// Create an empty registry
+    pub fn new(clock: C) -> Self {
+        Self {
+            configs: DashMap::new(),
+            limiters: DashMap::new(),
+            clock,
+        }
+    }
+
+    /// Create a registry from `(name, config)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first validation error among the configs.
+    pub fn with_configs<N>(
+        configs: impl IntoIterator<Item = (N, FluxLimiterConfig)>,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError>
+    where
+        N: Into<String>,
+    {
+        let registry = Self::new(clock);
+        for (name, config) in configs {
+            registry.register(name, config)?;
+        }
+        Ok(registry)
+    }
+
+    /// Register (or replace) the config for `name`.
+    ///
+    /// Replacing a config discards the existing limiter and its client state.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error, leaving the registry unchanged.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        config: FluxLimiterConfig,
+    ) -> Result<(), FluxLimiterError> {
+        config.validate()?;
+        let name = name.into();
+        // swap the config first so a concurrent get() cannot rebuild the old one
+        self.configs.insert(name.clone(), config);
+        self.limiters.remove(&name);
+        Ok(())
+    }
+
+    /// Return the limiter for `name`, creating it on first use.
+    ///
+    /// Returns `None` if no config is registered under `name`.
+    pub fn get(&self, name: &str) -> Option<Arc<FluxLimiter<T, C>>> {
+        if let Some(limiter) = self.limiters.get(name) {
+            return Some(Arc::clone(&limiter));
+        }
+
+        let config = self.configs.get(name)?.clone();
+        let limiter = self
+            .limiters
+            .entry(name.to_owned())
+            .or_try_insert_with(|| {
+                FluxLimiter::with_config(config, self.clock.clone()).map(Arc::new)
+            })
+            .ok()?;
+        Some(Arc::clone(&limiter))
+    }
+
+    /// Check a request against the limiter registered under `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::UnknownLimiter`] for unregistered names,
+    /// otherwise the same errors as [`FluxLimiter::check_request`].
+    pub fn check(&self, name: &str, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.get(name)
+            .ok_or_else(|| FluxLimiterError::UnknownLimiter(name.to_owned()))?
+            .check_request(client_id)
+    }
+
+    /// Clean up stale clients in every limiter created so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a limiter; the remaining limiters
+    /// are still cleaned. On success the report covers every limiter.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        for limiter in self.limiters.iter() {
+            match limiter.cleanup_stale_clients(max_stale_nanos) {
+                Ok(cleaned) => report = report.merge(cleaned),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    /// Names with a registered config
+    pub fn names(&self) -> Vec<String> {
+        self.configs
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+}
@@ -0,0 +1,297 @@
+// src/reload.rs
+
+//! Policy files loaded into a router and reloaded when they change (hot-reload feature)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::router::{Policy, PolicyRouter};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+// contents of a policy file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    #[serde(default = "unlimited")]
+    fallback: Policy,
+    #[serde(default)]
+    routes: Vec<RouteEntry>,
+}
+
+// one `[[routes]]` table
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RouteEntry {
+    pattern: String,
+    policy: Policy,
+}
+
+// serde default matching `PolicyRouter::new`
+fn unlimited() -> Policy {
+    Policy::Unlimited
+}
+
+// read and parse a policy file, naming the file in errors
+fn read_policy_file(path: &Path) -> Result<PolicyFile, FluxLimiterError> {
+    let policy_file_error = |err: &dyn std::fmt::Display| {
+        FluxLimiterError::PolicyFileError(format!("{}: {}", path.display(), err))
+    };
+    let contents = fs::read_to_string(path).map_err(|err| policy_file_error(&err))?;
+    toml::from_str(&contents).map_err(|err| policy_file_error(&err))
+}
+
+// methods for building routers from policy files
+impl<C> PolicyRouter<C>
+where
+    C: Clock + Clone,
+{
+    /// Create a router from a TOML policy file.
+    ///
+    /// The file sets an optional `fallback` policy, [`Policy::Unlimited`]
+    /// when omitted, followed by `[[routes]]` tables in the order they are
+    /// added. A policy is either `"unlimited"` or a `limit` table holding a
+    /// [`FluxLimiterConfig`](crate::FluxLimiterConfig) as serialized with
+    /// the `serde` feature:
+    ///
+    /// ```toml
+    /// fallback = { limit = { rate_per_second = 100.0, burst_capacity = 20.0 } }
+    ///
+    /// [[routes]]
+    /// pattern = "/api/v1/search*"
+    /// policy = { limit = { rate_per_second = 5.0, burst_capacity = 10.0 } }
+    ///
+    /// [[routes]]
+    /// pattern = "admin:*"
+    /// policy = "unlimited"
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::PolicyFileError`] if the file cannot be
+    /// read or parsed, and the first config validation error otherwise.
+    pub fn from_policy_file(path: impl AsRef<Path>, clock: C) -> Result<Self, FluxLimiterError> {
+        Self::new(clock).reload_from(path.as_ref())
+    }
+
+    // the router described by the file at `path`, keeping unchanged routes' state
+    fn reload_from(&self, path: &Path) -> Result<Self, FluxLimiterError> {
+        let file = read_policy_file(path)?;
+        self.reconfigure(
+            file.fallback,
+            file.routes
+                .into_iter()
+                .map(|route| (route.pattern, route.policy)),
+        )
+    }
+}
+
+type ReloadListener = Box<dyn Fn(&Result<(), FluxLimiterError>) + Send + Sync>;
+
+// state shared between the watcher handle and the file watcher's thread
+struct Shared<C>
+where
+    C: Clock + Clone,
+{
+    path: PathBuf,
+    router: RwLock<Arc<PolicyRouter<C>>>,
+    listener: RwLock<Option<ReloadListener>>,
+}
+
+impl<C> Shared<C>
+where
+    C: Clock + Clone,
+{
+    fn current(&self) -> Arc<PolicyRouter<C>> {
+        self.router
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    // load the file and swap the new router in; on failure the current one stays
+    fn reload(&self) -> Result<(), FluxLimiterError> {
+        let router = self.current().reload_from(&self.path)?;
+        *self.router.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(router);
+        Ok(())
+    }
+
+    // reload after a change on disk and tell the listener how it went
+    fn reload_and_notify(&self) {
+        let result = self.reload();
+        let listener = self.listener.read().unwrap_or_else(|err| err.into_inner());
+        if let Some(listener) = listener.as_ref() {
+            listener(&result);
+        }
+    }
+
+    // whether a file system event touches the policy file
+    fn is_relevant(&self, event: &Event) -> bool {
+        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == self.path.file_name())
+    }
+}
+
+/// A [`PolicyRouter`] that follows a policy file on disk.
+///
+/// The file is loaded once when the watcher is created and again whenever it
+/// changes, in the format described at
+/// [`PolicyRouter::from_policy_file`]. Each reload builds a complete new
+/// router and swaps it in atomically, so a check sees either the old policies
+/// or the new ones, never a mix. Routes whose pattern and policy are unchanged
+/// keep their client state; changed and new routes start fresh.
+///
+/// A file that fails to read, parse or validate leaves the current policies in
+/// place, so a half-written or mistyped file does not take limits down.
+/// Attach a listener with [`with_reload_listener`](Self::with_reload_listener)
+/// to log reload outcomes.
+///
+/// The directory containing the file is watched rather than the file itself,
+/// so editors and deploy tools that replace the file by renaming over it are
+/// picked up. Writing the file in place can be noticed halfway through, and a
+/// truncated file may still parse, so prefer writing a new file and renaming it
+/// over the old one. Watching stops when the watcher is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{PolicyWatcher, SystemClock};
+///
+/// let path = std::env::temp_dir().join("flux-limiter-doc-policies.toml");
+/// std::fs::write(
+///     &path,
+///     r#"
+///     [[routes]]
+///     pattern = "/api/upload*"
+///     policy = { limit = { rate_per_second = 1.0, burst_capacity = 0.0 } }
+///     "#,
+/// )
+/// .unwrap();
+///
+/// let policies = PolicyWatcher::watch(&path, SystemClock).unwrap();
+/// assert!(policies.check("/api/upload/1").unwrap().allowed);
+/// assert!(!policies.check("/api/upload/1").unwrap().allowed);
+/// assert!(policies.check("/api/health").unwrap().allowed); // unlimited fallback
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct PolicyWatcher<C = SystemClock>
+where
+    C: Clock + Clone,
+{
+    shared: Arc<Shared<C>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl<C> PolicyWatcher<C>
+where
+    C: Clock + Clone + Send + Sync + 'static,
+{
+    /// Load the policy file at `path` and start watching it for changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`PolicyRouter::from_policy_file`], and
+    /// [`FluxLimiterError::PolicyFileError`] if the file cannot be watched.
+    pub fn watch(path: impl Into<PathBuf>, clock: C) -> Result<Self, FluxLimiterError> {
+        let path = path.into();
+        let router = PolicyRouter::from_policy_file(&path, clock)?;
+        let shared = Arc::new(Shared {
+            path,
+            router: RwLock::new(Arc::new(router)),
+            listener: RwLock::new(None),
+        });
+
+        let watch_error = |err: notify::Error| {
+            FluxLimiterError::PolicyFileError(format!("{}: {}", shared.path.display(), err))
+        };
+        let mut watcher = notify::recommended_watcher({
+            let shared = shared.clone();
+            move |event: notify::Result<Event>| {
+                if event.is_ok_and(|event| shared.is_relevant(&event)) {
+                    shared.reload_and_notify();
+                }
+            }
+        })
+        .map_err(watch_error)?;
+
+        // a bare file name lives in the current directory
+        let directory = match shared.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        Ok(Self {
+            shared,
+            _watcher: watcher,
+        })
+    }
+
+    /// Call `listener` with the outcome of every reload triggered by a change
+    /// on disk, e.g. to log files that failed to load.
+    pub fn with_reload_listener(
+        self,
+        listener: impl Fn(&Result<(), FluxLimiterError>) + Send + Sync + 'static,
+    ) -> Self {
+        *self
+            .shared
+            .listener
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Box::new(listener));
+        self
+    }
+}
+
+impl<C> PolicyWatcher<C>
+where
+    C: Clock + Clone,
+{
+    /// The file being watched
+    pub fn path(&self) -> &Path {
+        &self.shared.path
+    }
+
+    /// The router currently in effect.
+    ///
+    /// The returned router stays valid, and keeps applying the policies it was
+    /// loaded with, after later reloads.
+    pub fn router(&self) -> Arc<PolicyRouter<C>> {
+        self.shared.current()
+    }
+
+    /// Check a request for `key` against the current policies; see
+    /// [`PolicyRouter::check`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`PolicyRouter::check`].
+    pub fn check(&self, key: &str) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.router().check(key)
+    }
+
+    /// The config the current policies apply to `key`; see
+    /// [`PolicyRouter::effective_config`].
+    pub fn effective_config(&self, key: &str) -> Option<FluxLimiterConfig> {
+        self.router().effective_config(key)
+    }
+
+    /// Load the file now instead of waiting for a change notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`PolicyRouter::from_policy_file`]; the
+    /// current policies stay in effect.
+    pub fn reload(&self) -> Result<(), FluxLimiterError> {
+        self.shared.reload()
+    }
+}
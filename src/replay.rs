@@ -0,0 +1,152 @@
+// src/replay.rs
+
+//! Warm-starting a limiter by replaying recorded requests
+
+// dependencies
+use crate::clock::Clock;
+use crate::config::FluxLimiterConfig;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::gcra::{self, Limits};
+use crate::snapshot::StateSnapshot;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Rebuilds client TATs from `(key, timestamp)` records, such as a recent
+/// window of access logs, so a freshly deployed limiter does not give every
+/// client a full burst at rollout.
+///
+/// Each record is decided with the same GCRA step a limiter would have run
+/// at its timestamp, so requests the old limiter would have denied do not
+/// advance the client's TAT. Records are expected in time order per key;
+/// nothing is checked against a clock, and stats and events are untouched.
+/// Once every record is in, [`load`](Self::load) builds the limiter, or
+/// [`into_snapshot`](Self::into_snapshot) hands the state to
+/// [`FluxLimiter::with_state`] or another store.
+///
+/// Only the config's rate and burst are used for the replay.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{Clock, FluxLimiterConfig, ReplayLoader, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(1.0, 1.0);
+/// let now = SystemClock.now().unwrap().as_nanos();
+///
+/// // user_123 used its burst just before the deploy
+/// let mut loader = ReplayLoader::new(&config).unwrap();
+/// loader.extend([("user_123", now), ("user_123", now)]);
+///
+/// let limiter = loader.load(config, SystemClock).unwrap();
+/// assert!(!limiter.check_request("user_123").unwrap().allowed);
+/// assert!(limiter.check_request("user_456").unwrap().allowed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplayLoader<T> {
+    limits: Limits,
+    tats: HashMap<T, u64>,
+    replayed: u64,
+    denied: u64,
+}
+
+impl<T> ReplayLoader<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Create a loader replaying against `config`'s rate and burst.
+    ///
+    /// # Errors
+    ///
+    /// Returns the rate and burst validation errors of
+    /// [`FluxLimiterConfig::validate`].
+    pub fn new(config: &FluxLimiterConfig) -> Result<Self, FluxLimiterError> {
+        Ok(Self {
+            limits: Limits::new(
+                config.rate_per_second,
+                config.burst_capacity,
+                config.precision,
+            )?,
+            tats: HashMap::new(),
+            replayed: 0,
+            denied: 0,
+        })
+    }
+
+    /// Replay one request for `client_id` at `timestamp_nanos`, returning
+    /// whether the limiter would have allowed it
+    pub fn record(&mut self, client_id: T, timestamp_nanos: u64) -> bool {
+        let tat_nanos = self.tats.entry(client_id).or_insert(timestamp_nanos);
+        let new_tat_nanos =
+            gcra::advance_tat(timestamp_nanos, *tat_nanos, self.limits.increment_nanos).filter(
+                |_| gcra::conforms(timestamp_nanos, *tat_nanos, self.limits.tolerance_nanos),
+            );
+
+        self.replayed += 1;
+        match new_tat_nanos {
+            Some(new_tat_nanos) => {
+                *tat_nanos = new_tat_nanos;
+                true
+            }
+            None => {
+                self.denied += 1;
+                false
+            }
+        }
+    }
+
+    /// Number of records replayed so far
+    pub fn replayed(&self) -> u64 {
+        self.replayed
+    }
+
+    /// Number of replayed records the limiter would have denied
+    pub fn denied(&self) -> u64 {
+        self.denied
+    }
+
+    /// Number of distinct clients seen
+    pub fn len(&self) -> usize {
+        self.tats.len()
+    }
+
+    /// Whether no records have been replayed
+    pub fn is_empty(&self) -> bool {
+        self.tats.is_empty()
+    }
+
+    /// The replayed TATs, for [`FluxLimiter::with_state`]
+    pub fn into_snapshot(self) -> StateSnapshot<T> {
+        StateSnapshot::new(self.tats.into_iter().collect())
+    }
+
+    /// Create a limiter from `config` starting from the replayed state.
+    ///
+    /// Clients whose replayed requests have long since recovered are loaded
+    /// too, and are dropped by the limiter's usual cleanup.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::with_state`].
+    pub fn load<C>(
+        self,
+        config: FluxLimiterConfig,
+        clock: C,
+    ) -> Result<FluxLimiter<T, C>, FluxLimiterError>
+    where
+        C: Clock,
+    {
+        FluxLimiter::with_state(config, clock, self.into_snapshot())
+    }
+}
+
+impl<T> Extend<(T, u64)> for ReplayLoader<T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn extend<I: IntoIterator<Item = (T, u64)>>(&mut self, records: I) {
+        for (client_id, timestamp_nanos) in records {
+            self.record(client_id, timestamp_nanos);
+        }
+    }
+}
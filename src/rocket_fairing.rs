@@ -0,0 +1,223 @@
+// src/rocket_fairing.rs
+
+//! Rocket fairing and request guard (`rocket` feature)
+
+// dependencies
+use crate::clock::Clock;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::key_extractor::{KeyExtractor, RequestInfo, RouteTemplate};
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::{Build, Response, Rocket};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// What the guard keys its checks on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RocketKey {
+    /// The client IP (`Request::client_ip`)
+    ClientIp,
+    /// The matched route, shared by all clients
+    Route,
+    /// The matched route, separately for each client IP
+    RouteAndClientIp,
+}
+
+impl<'r> KeyExtractor<Request<'r>> for RocketKey {
+    fn extract(&self, request: &Request<'r>) -> Option<String> {
+        let ip = || request.client_ip().map(|ip| ip.to_string());
+        match self {
+            RocketKey::ClientIp => ip(),
+            RocketKey::Route => RouteTemplate.extract(request),
+            RocketKey::RouteAndClientIp => RouteTemplate
+                .extract(request)
+                .zip(ip())
+                .map(|(route, ip)| format!("{route}|{ip}")),
+        }
+    }
+}
+
+// the route template is the matched route's method and URI pattern
+impl<'r> RequestInfo for Request<'r> {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.remote().map(|addr| addr.ip())
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers().get_one(name)
+    }
+
+    fn route_template(&self) -> Option<String> {
+        self.route()
+            .map(|route| format!("{} {}", route.method, route.uri))
+    }
+}
+
+// limiter with its clock type erased, so the guard is not generic
+type CheckFn = dyn Fn(&str) -> Result<FluxLimiterDecision, FluxLimiterError> + Send + Sync;
+
+// key extractor usable for requests of any lifetime
+type KeyFn = dyn for<'r> KeyExtractor<Request<'r>>;
+
+// managed state installed by the fairing
+struct RocketLimiter {
+    check: Arc<CheckFn>,
+    key: Arc<KeyFn>,
+}
+
+// request-local copy of the decision, read back when writing headers
+struct CachedDecision(Option<FluxLimiterDecision>);
+
+/// Fairing that installs a limiter for the [`RateLimit`] guard and adds
+/// rate limit headers to responses.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterFairing, RocketKey, SystemClock};
+/// use std::sync::Arc;
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+/// let rocket = rocket::build().attach(FluxLimiterFairing::new(limiter, RocketKey::ClientIp));
+/// ```
+pub struct FluxLimiterFairing {
+    check: Arc<CheckFn>,
+    key: Arc<KeyFn>,
+}
+
+impl FluxLimiterFairing {
+    /// Create a fairing that checks `limiter`, keyed as described by `key`
+    pub fn new<C>(limiter: Arc<FluxLimiter<String, C>>, key: RocketKey) -> Self
+    where
+        C: Clock + 'static,
+    {
+        Self::with_extractor(limiter, key)
+    }
+
+    /// Create a fairing that checks `limiter` with keys from `extractor`,
+    /// e.g. a [`BearerTokenHash`](crate::BearerTokenHash)
+    pub fn with_extractor<C>(
+        limiter: Arc<FluxLimiter<String, C>>,
+        extractor: impl for<'r> KeyExtractor<Request<'r>> + 'static,
+    ) -> Self
+    where
+        C: Clock + 'static,
+    {
+        Self {
+            check: Arc::new(move |client_id| limiter.check_request_ref(client_id)),
+            key: Arc::new(extractor),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for FluxLimiterFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Flux Limiter",
+            kind: Kind::Ignite | Kind::Response,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        Ok(rocket.manage(RocketLimiter {
+            check: Arc::clone(&self.check),
+            key: Arc::clone(&self.key),
+        }))
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let CachedDecision(Some(decision)) = request.local_cache(|| CachedDecision(None)) else {
+            return;
+        };
+
+        response.set_header(Header::new(
+            "X-RateLimit-Limit",
+            decision.limit_per_second.to_string(),
+        ));
+        if let Some(remaining) = decision.remaining_capacity {
+            response.set_header(Header::new(
+                "X-RateLimit-Remaining",
+                remaining.floor().to_string(),
+            ));
+        }
+        if let Some(retry_after) = decision.retry_after_seconds {
+            response.set_header(Header::new("Retry-After", retry_after.ceil().to_string()));
+        }
+    }
+}
+
+/// Request guard that enforces the limit installed by [`FluxLimiterFairing`].
+///
+/// Add it to a handler's arguments to limit that route. Denied requests fail
+/// with `429 Too Many Requests`; allowed ones give the handler the decision.
+/// `decision` is `None` when no key could be derived (e.g. no client IP), in
+/// which case the request is not limited.
+///
+/// ```rust
+/// use flux_limiter::RateLimit;
+///
+/// #[rocket::get("/search")]
+/// fn search(limit: RateLimit) -> String {
+///     let remaining = limit.decision.and_then(|d| d.remaining_capacity);
+///     format!("remaining: {:?}", remaining)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// The decision for this request
+    pub decision: Option<FluxLimiterDecision>,
+}
+
+/// Why the [`RateLimit`] guard rejected a request.
+#[derive(Debug)]
+pub enum RateLimitRejection {
+    /// The client is over its limit (`429`)
+    Limited(FluxLimiterDecision),
+    /// The limiter failed (`503`)
+    Unavailable(FluxLimiterError),
+    /// No [`FluxLimiterFairing`] is attached (`500`)
+    NotConfigured,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RateLimit {
+    type Error = RateLimitRejection;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(limiter) = request.rocket().state::<RocketLimiter>() else {
+            return Outcome::Error((
+                Status::InternalServerError,
+                RateLimitRejection::NotConfigured,
+            ));
+        };
+
+        let Some(client_id) = limiter.key.extract(request) else {
+            return Outcome::Success(RateLimit { decision: None });
+        };
+
+        match (limiter.check)(&client_id) {
+            Ok(decision) => {
+                request.local_cache(|| CachedDecision(Some(decision)));
+                if decision.allowed {
+                    Outcome::Success(RateLimit {
+                        decision: Some(decision),
+                    })
+                } else {
+                    Outcome::Error((
+                        Status::TooManyRequests,
+                        RateLimitRejection::Limited(decision),
+                    ))
+                }
+            }
+            Err(err) => Outcome::Error((
+                Status::ServiceUnavailable,
+                RateLimitRejection::Unavailable(err),
+            )),
+        }
+    }
+}
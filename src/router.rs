@@ -0,0 +1,401 @@
+// src/router.rs
+
+//! Pattern-based policy selection in front of rate limiters
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::{DecisionReason, FluxLimiterDecision};
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::CleanupReport;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Policy applied to the keys matched by a route
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Policy {
+    /// Rate limit matching keys with this config
+    Limit(FluxLimiterConfig),
+    /// Always allow matching keys
+    Unlimited,
+}
+
+impl From<FluxLimiterConfig> for Policy {
+    fn from(config: FluxLimiterConfig) -> Self {
+        Policy::Limit(config)
+    }
+}
+
+// how a route pattern is matched against keys
+enum Pattern {
+    Prefix(String),
+    Glob(String),
+}
+
+impl Pattern {
+    // classify a pattern; None means it has no wildcards and is matched exactly
+    fn parse(pattern: &str) -> Option<Self> {
+        if !pattern.contains(['*', '?']) {
+            return None;
+        }
+        match pattern.strip_suffix('*') {
+            Some(prefix) if !prefix.contains(['*', '?']) => {
+                Some(Pattern::Prefix(prefix.to_owned()))
+            }
+            _ => Some(Pattern::Glob(pattern.to_owned())),
+        }
+    }
+}
+
+// `*` matches any run of characters, `?` matches exactly one
+fn glob_matches(glob: &str, key: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let (mut g, mut k) = (0, 0);
+    // position of the last `*` and the key index it currently absorbs up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while k < key.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, k));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == key[k] => {
+                g += 1;
+                k += 1;
+            }
+            _ => match backtrack {
+                Some((star, absorbed)) => {
+                    g = star + 1;
+                    k = absorbed + 1;
+                    backtrack = Some((star, k));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+// a resolved policy with its limiter, and the config it was built from
+enum Route<C: Clock> {
+    Limited(Box<FluxLimiter<String, C>>, Box<FluxLimiterConfig>),
+    Unlimited,
+}
+
+impl<C: Clock + Clone> Route<C> {
+    // the same route sharing this one's state, if it applies `policy`
+    fn reuse(&self, policy: &Policy) -> Option<Self> {
+        match (self, policy) {
+            (Route::Limited(limiter, config), Policy::Limit(new_config))
+                if **config == *new_config =>
+            {
+                Some(Route::Limited(limiter.clone(), config.clone()))
+            }
+            (Route::Unlimited, Policy::Unlimited) => Some(Route::Unlimited),
+            _ => None,
+        }
+    }
+}
+
+/// Selects a rate limit policy by key pattern.
+///
+/// Patterns are matched against the whole key:
+///
+/// - no wildcards: exact match, e.g. `"/health"`
+/// - a single trailing `*`: prefix match, e.g. `"/api/v1/search*"`
+/// - anything else: glob where `*` matches any run of characters and `?`
+///   matches one, e.g. `"/api/*/upload"`
+///
+/// Exact routes win, then the longest matching prefix, then globs in the
+/// order they were added. Keys matching no route use the fallback policy,
+/// which is [`Policy::Unlimited`] unless set with
+/// [`fallback`](Self::fallback).
+///
+/// Each limited route owns its own limiter, tracked per full key.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, Policy, PolicyRouter, SystemClock};
+///
+/// let router = PolicyRouter::with_routes(
+///     [
+///         ("/api/v1/search*", Policy::Limit(FluxLimiterConfig::new(5.0, 0.0))),
+///         ("admin:*", Policy::Unlimited),
+///     ],
+///     SystemClock,
+/// )
+/// .unwrap();
+///
+/// assert!(router.check("/api/v1/search?q=rust").unwrap().allowed);
+/// assert!(router.check("admin:alice").unwrap().allowed);
+/// ```
+pub struct PolicyRouter<C = SystemClock>
+where
+    C: Clock + Clone,
+{
+    exact: HashMap<String, Route<C>>,
+    prefixes: Vec<(String, Route<C>)>,
+    globs: Vec<(String, Route<C>)>,
+    fallback: Route<C>,
+    clock: C,
+}
+
+impl<C> PolicyRouter<C>
+where
+    C: Clock + Clone,
+{
+    /// Create a router with no routes and an unlimited fallback
+    pub fn new(clock: C) -> Self {
+        Self {
+            exact: HashMap::new(),
+            prefixes: Vec::new(),
+            globs: Vec::new(),
+            fallback: Route::Unlimited,
+            clock,
+        }
+    }
+
+    /// Create a router from `(pattern, policy)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first validation error among the policies' configs.
+    pub fn with_routes<P>(
+        routes: impl IntoIterator<Item = (P, Policy)>,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError>
+    where
+        P: Into<String>,
+    {
+        let mut router = Self::new(clock);
+        for (pattern, policy) in routes {
+            router.route(pattern, policy)?;
+        }
+        Ok(router)
+    }
+
+    /// Add a route, replacing any existing route with the same pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns the policy's config validation error, leaving the router unchanged.
+    pub fn route(
+        &mut self,
+        pattern: impl Into<String>,
+        policy: impl Into<Policy>,
+    ) -> Result<(), FluxLimiterError> {
+        let route = self.build(policy.into())?;
+        self.insert(pattern.into(), route);
+        Ok(())
+    }
+
+    // file a route under its pattern, replacing any route with the same pattern
+    fn insert(&mut self, pattern: String, route: Route<C>) {
+        match Pattern::parse(&pattern) {
+            None => {
+                self.exact.insert(pattern, route);
+            }
+            Some(Pattern::Prefix(prefix)) => {
+                Self::upsert(&mut self.prefixes, prefix, route);
+                // longest prefix first so the first match is the most specific
+                self.prefixes
+                    .sort_by_key(|(prefix, _)| Reverse(prefix.chars().count()));
+            }
+            Some(Pattern::Glob(glob)) => Self::upsert(&mut self.globs, glob, route),
+        }
+    }
+
+    /// Set the policy for keys that match no route.
+    ///
+    /// # Errors
+    ///
+    /// Returns the policy's config validation error, leaving the router unchanged.
+    pub fn fallback(&mut self, policy: impl Into<Policy>) -> Result<(), FluxLimiterError> {
+        self.fallback = self.build(policy.into())?;
+        Ok(())
+    }
+
+    /// Return the limiter that governs `key`, or `None` if it is unlimited
+    pub fn limiter_for(&self, key: &str) -> Option<&FluxLimiter<String, C>> {
+        match self.resolve(key) {
+            Route::Limited(limiter, _) => Some(limiter.as_ref()),
+            Route::Unlimited => None,
+        }
+    }
+
+    /// The config applied to `key`, or `None` if it is unlimited.
+    ///
+    /// Resolves `key` like [`check`](Self::check) and reports the
+    /// [`effective_config`](FluxLimiter::effective_config) of the route's
+    /// limiter, so rate changes made through
+    /// [`limiter_for`](Self::limiter_for) show.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use flux_limiter::{FluxLimiterConfig, Policy, PolicyRouter, SystemClock};
+    ///
+    /// let mut router = PolicyRouter::new(SystemClock);
+    /// router.route("/search*", FluxLimiterConfig::new(5.0, 0.0)).unwrap();
+    /// router.fallback(FluxLimiterConfig::new(100.0, 10.0)).unwrap();
+    /// router.route("/health", Policy::Unlimited).unwrap();
+    ///
+    /// let search = router.effective_config("/search?q=rust").unwrap();
+    /// assert_eq!(search.rate_per_second(), 5.0);
+    /// assert_eq!(router.effective_config("/users").unwrap().rate_per_second(), 100.0);
+    /// assert!(router.effective_config("/health").is_none());
+    /// ```
+    pub fn effective_config(&self, key: &str) -> Option<FluxLimiterConfig> {
+        self.limiter_for(key).map(FluxLimiter::effective_config)
+    }
+
+    /// Check a request for `key` against the policy it resolves to.
+    ///
+    /// Unlimited keys are always allowed and report an infinite rate and burst.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_ref`].
+    pub fn check(&self, key: &str) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        match self.resolve(key) {
+            Route::Limited(limiter, _) => limiter.check_request_ref(key),
+            Route::Unlimited => Ok(FluxLimiterDecision {
+                allowed: true,
+                retry_after_seconds: None,
+                remaining_capacity: None,
+                reset_time_nanos: 0,
+                limit_per_second: f64::INFINITY,
+                burst_capacity: f64::INFINITY,
+                shedding: false,
+                debt: 0.0,
+                suggested_delay_seconds: None,
+                reason: DecisionReason::Exempt,
+            }),
+        }
+    }
+
+    /// Clean up stale clients in every route's limiter.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a limiter; the remaining limiters
+    /// are still cleaned. On success the report covers every limiter.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let routes = self
+            .exact
+            .values()
+            .chain(self.prefixes.iter().map(|(_, route)| route))
+            .chain(self.globs.iter().map(|(_, route)| route))
+            .chain(std::iter::once(&self.fallback));
+
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        for route in routes {
+            if let Route::Limited(limiter, _) = route {
+                match limiter.cleanup_stale_clients(max_stale_nanos) {
+                    Ok(cleaned) => report = report.merge(cleaned),
+                    Err(err) => {
+                        first_error.get_or_insert(err);
+                    }
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    // find the most specific route for a key
+    fn resolve(&self, key: &str) -> &Route<C> {
+        if let Some(route) = self.exact.get(key) {
+            return route;
+        }
+        self.prefixes
+            .iter()
+            .find(|(prefix, _)| key.starts_with(prefix.as_str()))
+            .or_else(|| self.globs.iter().find(|(glob, _)| glob_matches(glob, key)))
+            .map_or(&self.fallback, |(_, route)| route)
+    }
+
+    /// Build a router with a new set of routes, keeping the client state of
+    /// every route whose pattern and policy are unchanged.
+    ///
+    /// Routes whose policy changed, or that are new, start with fresh state,
+    /// as with [`route`](Self::route). `self` is left untouched, so callers
+    /// can swap the result in atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first validation error among the policies' configs.
+    pub fn reconfigure<P>(
+        &self,
+        fallback: Policy,
+        routes: impl IntoIterator<Item = (P, Policy)>,
+    ) -> Result<Self, FluxLimiterError>
+    where
+        P: Into<String>,
+    {
+        let mut router = Self::new(self.clock.clone());
+        router.fallback = match self.fallback.reuse(&fallback) {
+            Some(route) => route,
+            None => router.build(fallback)?,
+        };
+        for (pattern, policy) in routes {
+            let pattern = pattern.into();
+            let route = match self.find(&pattern).and_then(|route| route.reuse(&policy)) {
+                Some(route) => route,
+                None => router.build(policy)?,
+            };
+            router.insert(pattern, route);
+        }
+        Ok(router)
+    }
+
+    // the route registered under exactly `pattern`, if any
+    fn find(&self, pattern: &str) -> Option<&Route<C>> {
+        match Pattern::parse(pattern) {
+            None => self.exact.get(pattern),
+            Some(Pattern::Prefix(prefix)) => Self::lookup(&self.prefixes, &prefix),
+            Some(Pattern::Glob(glob)) => Self::lookup(&self.globs, &glob),
+        }
+    }
+
+    fn lookup<'a>(routes: &'a [(String, Route<C>)], pattern: &str) -> Option<&'a Route<C>> {
+        routes
+            .iter()
+            .find(|(existing, _)| existing == pattern)
+            .map(|(_, route)| route)
+    }
+
+    // validate a policy and build its limiter
+    fn build(&self, policy: Policy) -> Result<Route<C>, FluxLimiterError> {
+        match policy {
+            Policy::Limit(config) => Ok(Route::Limited(
+                Box::new(FluxLimiter::with_config(
+                    config.clone(),
+                    self.clock.clone(),
+                )?),
+                Box::new(config),
+            )),
+            Policy::Unlimited => Ok(Route::Unlimited),
+        }
+    }
+
+    // replace the route for an existing pattern in place, otherwise append it
+    fn upsert(routes: &mut Vec<(String, Route<C>)>, pattern: String, route: Route<C>) {
+        match routes.iter_mut().find(|(existing, _)| *existing == pattern) {
+            Some((_, existing)) => *existing = route,
+            None => routes.push((pattern, route)),
+        }
+    }
+}
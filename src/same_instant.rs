@@ -0,0 +1,71 @@
+// src/same_instant.rs
+
+//! Several checks sharing one clock reading
+
+// dependencies
+use crate::clock::{Clock, Timestamp};
+use crate::config::Priority;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+/// Checks against a limiter that share a single clock reading.
+///
+/// Returned by [`FluxLimiter::same_instant`]. A handler that checks several
+/// limit dimensions per request, such as the user, the IP and the route,
+/// reads the clock once instead of once per check, and every check sees
+/// exactly the same time. Keep it for one request only: a reading that has
+/// grown old only ever makes checks stricter, but the limits drift from
+/// wall-clock time.
+///
+/// If the clock failed when the handle was created, each check reads the
+/// clock again and resolves a repeated failure with the limiter's
+/// [`FailurePolicy`](crate::FailurePolicy), as
+/// [`check_request`](FluxLimiter::check_request) would.
+#[derive(Debug)]
+pub struct SameInstant<'a, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    pub(crate) limiter: &'a FluxLimiter<T, C, H>,
+    pub(crate) timestamp_nanos: Option<u64>,
+}
+
+impl<T, C, H> SameInstant<'_, T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock,
+    H: BuildHasher + Clone,
+{
+    /// The shared clock reading, or `None` if the clock failed
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp_nanos.map(Timestamp::from_nanos)
+    }
+
+    /// [`FluxLimiter::check_request`] at the shared reading
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        match self.timestamp_nanos {
+            Some(now) => self.limiter.check_at(client_id, now, Priority::High),
+            None => self.limiter.check_request(client_id),
+        }
+    }
+
+    /// [`FluxLimiter::check_request_ref`] at the shared reading
+    pub fn check_request_ref<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    {
+        match self.timestamp_nanos {
+            Some(now) => self.limiter.check_borrowed_at(client_id, Q::to_owned, now),
+            None => self.limiter.check_request_ref(client_id),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// src/schedule.rs
+
+//! Rate profiles that switch on a time-of-day schedule
+
+// dependencies
+use crate::clock::{Clock, SystemClock, Timestamp};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::CleanupReport;
+use std::hash::Hash;
+
+const NANOS_PER_MINUTE: i128 = 60_000_000_000;
+const MINUTES_PER_DAY: i128 = 24 * 60;
+
+/// Day of the week, used to restrict a [`TimeWindow`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    // bit in a TimeWindow's day mask (Monday = bit 0)
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A recurring window of wall-clock time, e.g. weekdays 09:00–17:00.
+///
+/// Windows are half-open (`start` is inside, `end` is not) and may wrap past
+/// midnight: `22:00–06:00` covers the late evening and the following early
+/// morning. For a wrapping window, the day restriction applies to the day
+/// the window starts on.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::TimeWindow;
+///
+/// let business_hours = TimeWindow::daily((9, 0), (17, 0)).unwrap().weekdays();
+/// let overnight = TimeWindow::daily((22, 0), (6, 0)).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    start_minute: u16,
+    end_minute: u16,
+    days: u8,
+}
+
+impl TimeWindow {
+    /// Create a window active every day from `start` to `end`, given as
+    /// `(hour, minute)`. An `end` of `(24, 0)` means midnight at the end of
+    /// the day.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::InvalidTimeWindow`] for times outside
+    /// 00:00–24:00 or an empty window (`start == end`).
+    pub fn daily(start: (u8, u8), end: (u8, u8)) -> Result<Self, FluxLimiterError> {
+        let start_minute = Self::minute_of_day(start)?;
+        let end_minute = Self::minute_of_day(end)?;
+        if start_minute == end_minute || start_minute == MINUTES_PER_DAY as u16 {
+            return Err(FluxLimiterError::InvalidTimeWindow);
+        }
+        Ok(Self {
+            start_minute,
+            end_minute,
+            days: 0b111_1111,
+        })
+    }
+
+    /// Restrict the window to the given days
+    pub fn on(mut self, days: &[Weekday]) -> Self {
+        self.days = days.iter().fold(0, |mask, day| mask | day.bit());
+        self
+    }
+
+    /// Restrict the window to Monday through Friday
+    pub fn weekdays(self) -> Self {
+        self.on(&[
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ])
+    }
+
+    /// Restrict the window to Saturday and Sunday
+    pub fn weekends(self) -> Self {
+        self.on(&[Weekday::Saturday, Weekday::Sunday])
+    }
+
+    // whether the window covers a local minute of the day on the given weekday
+    fn contains(&self, weekday: u8, minute: u16) -> bool {
+        let active_on = |day: u8| self.days & (1 << day) != 0;
+        if self.start_minute < self.end_minute {
+            active_on(weekday) && (self.start_minute..self.end_minute).contains(&minute)
+        } else if minute >= self.start_minute {
+            active_on(weekday)
+        } else {
+            // the early part of a wrapping window belongs to the previous day
+            minute < self.end_minute && active_on((weekday + 6) % 7)
+        }
+    }
+
+    // validate an (hour, minute) pair and convert it to minutes since midnight
+    fn minute_of_day((hour, minute): (u8, u8)) -> Result<u16, FluxLimiterError> {
+        match (hour, minute) {
+            (0..=23, 0..=59) | (24, 0) => Ok(hour as u16 * 60 + minute as u16),
+            _ => Err(FluxLimiterError::InvalidTimeWindow),
+        }
+    }
+}
+
+/// A rate limiter that switches between rate profiles on a schedule.
+///
+/// Each profile pairs a [`TimeWindow`] with a config. On every check the
+/// limiter reads its clock, picks the first profile whose window covers the
+/// current local time, and falls back to the default config otherwise.
+/// Times are evaluated in UTC unless an offset is set with
+/// [`utc_offset_minutes`](Self::utc_offset_minutes).
+///
+/// Every profile keeps its own client state, so a client's usage under the
+/// peak profile does not carry over into the off-peak one.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, ScheduledLimiter, SystemClock, TimeWindow};
+///
+/// // 100 req/s off-peak, 20 req/s during weekday business hours
+/// let limiter = ScheduledLimiter::new(FluxLimiterConfig::new(100.0, 50.0), SystemClock)
+///     .unwrap()
+///     .profile(
+///         TimeWindow::daily((9, 0), (17, 0)).unwrap().weekdays(),
+///         FluxLimiterConfig::new(20.0, 10.0),
+///     )
+///     .unwrap();
+///
+/// let decision = limiter.check_request("user_123").unwrap();
+/// assert!(decision.allowed);
+/// ```
+pub struct ScheduledLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    default: FluxLimiter<T, C>,
+    profiles: Vec<(TimeWindow, FluxLimiter<T, C>)>,
+    utc_offset_minutes: i32,
+    clock: C,
+}
+
+impl<T, C> ScheduledLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    /// Create a scheduled limiter that applies `default` outside every profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error.
+    pub fn new(default: FluxLimiterConfig, clock: C) -> Result<Self, FluxLimiterError> {
+        Ok(Self {
+            default: FluxLimiter::with_config(default, clock.clone())?,
+            profiles: Vec::new(),
+            utc_offset_minutes: 0,
+            clock,
+        })
+    }
+
+    /// Add a profile that applies `config` during `window`.
+    ///
+    /// Profiles are evaluated in the order they were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error.
+    pub fn profile(
+        mut self,
+        window: TimeWindow,
+        config: FluxLimiterConfig,
+    ) -> Result<Self, FluxLimiterError> {
+        let limiter = FluxLimiter::with_config(config, self.clock.clone())?;
+        self.profiles.push((window, limiter));
+        Ok(self)
+    }
+
+    /// Evaluate windows in local time `minutes` east of UTC (e.g. `-300` for UTC-5)
+    pub fn utc_offset_minutes(mut self, minutes: i32) -> Self {
+        self.utc_offset_minutes = minutes;
+        self
+    }
+
+    /// Check a request against the profile active at the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request`]. When the
+    /// clock fails, the default profile's failure policy decides.
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let now = match self.clock.now().map(Timestamp::as_nanos) {
+            Ok(now) => now,
+            Err(err) => return self.default.fail(FluxLimiterError::ClockError(err)),
+        };
+        self.limiter_at(now).check_request_at(client_id, now)
+    }
+
+    /// Return the limiter for the profile active at the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn active_limiter(&self) -> Result<&FluxLimiter<T, C>, FluxLimiterError> {
+        let now = self
+            .clock
+            .now()
+            .map(Timestamp::as_nanos)
+            .map_err(FluxLimiterError::ClockError)?;
+        Ok(self.limiter_at(now))
+    }
+
+    /// The config of the profile active at the current time.
+    ///
+    /// The [`effective_config`](FluxLimiter::effective_config) of
+    /// [`active_limiter`](Self::active_limiter); every client gets the same
+    /// profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn effective_config(&self) -> Result<FluxLimiterConfig, FluxLimiterError> {
+        self.active_limiter().map(FluxLimiter::effective_config)
+    }
+
+    /// Clean up stale clients in every profile's limiter.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a limiter; the remaining limiters
+    /// are still cleaned. On success the report covers every limiter.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        let limiters =
+            std::iter::once(&self.default).chain(self.profiles.iter().map(|(_, limiter)| limiter));
+        for limiter in limiters {
+            match limiter.cleanup_stale_clients(max_stale_nanos) {
+                Ok(cleaned) => report = report.merge(cleaned),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    // pick the first profile covering a clock reading, or the default
+    fn limiter_at(&self, now_nanos: u64) -> &FluxLimiter<T, C> {
+        let local_minutes = now_nanos as i128 / NANOS_PER_MINUTE + self.utc_offset_minutes as i128;
+        let minute = local_minutes.rem_euclid(MINUTES_PER_DAY) as u16;
+        // the Unix epoch fell on a Thursday (Monday = 0)
+        let weekday = (local_minutes.div_euclid(MINUTES_PER_DAY) + 3).rem_euclid(7) as u8;
+
+        self.profiles
+            .iter()
+            .find(|(window, _)| window.contains(weekday, minute))
+            .map_or(&self.default, |(_, limiter)| limiter)
+    }
+}
@@ -0,0 +1,374 @@
+// src/sharded.rs
+
+//! Hash-partitioned limiter that spreads keys over independent inner limiters
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::{FluxLimiterConfig, Priority};
+use crate::decision::{AllowedMeta, Decision, FluxLimiterDecision};
+use crate::errors::{FluxLimiterError, RateLimitedError};
+use crate::flux_limiter::FluxLimiter;
+use crate::snapshot::ClientSnapshot;
+#[cfg(feature = "per-key-stats")]
+use crate::stats::ClientStats;
+use crate::stats::{CleanupReport, FluxLimiterStats};
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::sync::Arc;
+
+/// A rate limiter that partitions keys across independent inner limiters.
+///
+/// Every key is hashed to exactly one of N inner [`FluxLimiter`]s, each with
+/// its own client map, statistics counters and rate, so cores working on
+/// different keys rarely touch the same cache lines. Per-key behaviour is
+/// identical to a single limiter with the same config.
+///
+/// All single-key checks are available; [`FluxLimiter::check_all`] is not,
+/// since an all-or-nothing check cannot span several inner limiters. Cleanup
+/// and statistics cover every shard. Cloning yields another handle to the
+/// same shards.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, ShardedFluxLimiter, SystemClock};
+///
+/// let config = FluxLimiterConfig::new(100.0, 20.0).stats(true);
+/// let limiter = ShardedFluxLimiter::new(config, 8, SystemClock).unwrap();
+///
+/// assert!(limiter.check_request("user_123").unwrap().allowed);
+/// assert_eq!(limiter.stats().allowed, 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShardedFluxLimiter<T, C = SystemClock, H = RandomState>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+    H: BuildHasher + Clone,
+{
+    shards: Vec<FluxLimiter<T, C>>,
+    hasher: H,
+}
+
+impl<T, C> ShardedFluxLimiter<T, C>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+{
+    /// Create a limiter with `shards` inner limiters built from `config`.
+    ///
+    /// The config's [`capacity`](FluxLimiterConfig::capacity) and
+    /// [`max_clients`](FluxLimiterConfig::max_clients) are totals across all
+    /// shards and are split evenly between them, rounding up.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::InvalidShardAmount`] unless `shards` is a
+    /// power of two above 1, and the config's validation error otherwise.
+    pub fn new(
+        config: FluxLimiterConfig,
+        shards: usize,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError> {
+        Self::with_hasher(config, shards, clock, RandomState::new())
+    }
+}
+
+impl<T, C, H> ShardedFluxLimiter<T, C, H>
+where
+    T: Hash + Eq + Clone,
+    C: Clock + Clone,
+    H: BuildHasher + Clone,
+{
+    /// Create a limiter that assigns keys to shards with `hasher`.
+    ///
+    /// [`new`](ShardedFluxLimiter::new) uses a randomly keyed hasher, so which
+    /// shard a key lands on differs from run to run; a fixed hasher such as
+    /// `BuildHasherDefault<DefaultHasher>` makes the assignment, and with it
+    /// per-shard stats and capacity, reproducible in tests and simulations.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`new`](ShardedFluxLimiter::new).
+    pub fn with_hasher(
+        config: FluxLimiterConfig,
+        shards: usize,
+        clock: C,
+        hasher: H,
+    ) -> Result<Self, FluxLimiterError> {
+        if shards < 2 || !shards.is_power_of_two() {
+            return Err(FluxLimiterError::InvalidShardAmount(shards));
+        }
+        config.validate()?;
+
+        let per_shard = config.initial_capacity.div_ceil(shards);
+        let mut config = config.capacity(per_shard);
+        config.max_clients = config.max_clients.map(|max| max.div_ceil(shards));
+        let shards = (0..shards)
+            .map(|_| FluxLimiter::with_config(config.clone(), clock.clone()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { shards, hasher })
+    }
+
+    /// The inner limiters, in shard order
+    pub fn shards(&self) -> &[FluxLimiter<T, C>] {
+        &self.shards
+    }
+
+    /// Return the inner limiter responsible for `client_id`
+    pub fn shard_for<Q>(&self, client_id: &Q) -> &FluxLimiter<T, C>
+    where
+        T: Borrow<Q>,
+        Q: Hash + ?Sized,
+    {
+        // the shard count is a power of two, so masking picks a uniform shard
+        let index = self.hasher.hash_one(client_id) as usize & (self.shards.len() - 1);
+        &self.shards[index]
+    }
+
+    /// The sustained rate every key is limited to, in requests per second
+    pub fn rate(&self) -> f64 {
+        self.shards[0].rate()
+    }
+
+    /// The burst every key is allowed
+    pub fn burst(&self) -> f64 {
+        self.shards[0].burst()
+    }
+
+    /// Start or stop draining every shard; see [`FluxLimiter::set_drain`]
+    pub fn set_drain(&self, draining: bool) {
+        self.shards
+            .iter()
+            .for_each(|shard| shard.set_drain(draining));
+    }
+
+    /// Whether the shards are draining
+    pub fn is_draining(&self) -> bool {
+        self.shards[0].is_draining()
+    }
+
+    /// Check a request on the shard owning `client_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request`].
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.shard_for(&client_id).check_request(client_id)
+    }
+
+    /// Check a request at a caller-supplied time; see [`FluxLimiter::check_request_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_at`].
+    pub fn check_request_at(
+        &self,
+        client_id: T,
+        timestamp_nanos: u64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.shard_for(&client_id)
+            .check_request_at(client_id, timestamp_nanos)
+    }
+
+    /// Check a batch of requests, returning results in input order.
+    ///
+    /// Unlike [`FluxLimiter::check_requests`], the clock is read once per key.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a shard.
+    pub fn check_requests(
+        &self,
+        client_ids: impl IntoIterator<Item = T>,
+    ) -> Result<Vec<(T, FluxLimiterDecision)>, FluxLimiterError> {
+        client_ids
+            .into_iter()
+            .map(|client_id| {
+                let decision = self.check_request(client_id.clone())?;
+                Ok((client_id, decision))
+            })
+            .collect()
+    }
+
+    /// Check a request using a borrowed form of the client ID; see
+    /// [`FluxLimiter::check_request_ref`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_ref`].
+    pub fn check_request_ref<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    {
+        self.shard_for(client_id).check_request_ref(client_id)
+    }
+
+    /// Check a request in a priority class; see
+    /// [`FluxLimiter::check_request_with_priority`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_with_priority`].
+    pub fn check_request_with_priority(
+        &self,
+        client_id: T,
+        priority: Priority,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.shard_for(&client_id)
+            .check_request_with_priority(client_id, priority)
+    }
+
+    /// Check a request and return a typed [`Decision`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_typed`].
+    pub fn check_request_typed(&self, client_id: T) -> Result<Decision, FluxLimiterError> {
+        self.shard_for(&client_id).check_request_typed(client_id)
+    }
+
+    /// Check a request, returning denials as errors; see [`FluxLimiter::try_check`].
+    pub fn try_check(&self, client_id: T) -> Result<AllowedMeta, RateLimitedError> {
+        self.shard_for(&client_id).try_check(client_id)
+    }
+
+    /// Return capacity consumed by requests that were never served; see
+    /// [`FluxLimiter::refund`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::refund`].
+    pub fn refund<Q>(&self, client_id: &Q, cost: f64) -> Result<(), FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_for(client_id).refund(client_id, cost)
+    }
+
+    /// Inspect a client's state without consuming capacity.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::client_snapshot`].
+    pub fn client_snapshot<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<Option<ClientSnapshot>, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_for(client_id).client_snapshot(client_id)
+    }
+
+    /// Clean up stale clients in every shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a shard; the remaining shards are
+    /// still cleaned. On success the report covers every shard.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let mut report = CleanupReport::default();
+        let mut first_error = None;
+        for shard in &self.shards {
+            match shard.cleanup_stale_clients(max_stale_nanos) {
+                Ok(cleaned) => report = report.merge(cleaned),
+                Err(err) => {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        first_error.map_or(Ok(report), Err)
+    }
+
+    /// Count what [`cleanup_stale_clients`](Self::cleanup_stale_clients)
+    /// would remove across every shard, without removing anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error reported by a shard.
+    pub fn cleanup_stale_clients_dry_run(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        self.shards
+            .iter()
+            .try_fold(CleanupReport::default(), |report, shard| {
+                Ok(report.merge(shard.cleanup_stale_clients_dry_run(max_stale_nanos)?))
+            })
+    }
+
+    /// Return the statistics of all shards combined
+    pub fn stats(&self) -> FluxLimiterStats {
+        self.shards.iter().map(FluxLimiter::stats).fold(
+            FluxLimiterStats::default(),
+            |total, shard| FluxLimiterStats {
+                total_checks: total.total_checks + shard.total_checks,
+                allowed: total.allowed + shard.allowed,
+                denied: total.denied + shard.denied,
+                evictions: total.evictions + shard.evictions,
+                current_clients: total.current_clients + shard.current_clients,
+            },
+        )
+    }
+
+    /// Reset the statistics counters of every shard to zero
+    pub fn reset_stats(&self) {
+        self.shards.iter().for_each(FluxLimiter::reset_stats);
+    }
+
+    /// Return the allowed/denied counts for a single client.
+    ///
+    /// Returns `None` for clients that are not currently tracked.
+    #[cfg(feature = "per-key-stats")]
+    pub fn client_stats<Q>(&self, client_id: &Q) -> Option<ClientStats>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_for(client_id).client_stats(client_id)
+    }
+
+    /// Estimate a client's recent demand; see [`FluxLimiter::observed_rate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    #[cfg(feature = "per-key-stats")]
+    pub fn observed_rate<Q>(&self, client_id: &Q) -> Result<Option<f64>, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.shard_for(client_id).observed_rate(client_id)
+    }
+}
+
+// methods for limiters keyed by shared strings
+impl<C, H> ShardedFluxLimiter<Arc<str>, C, H>
+where
+    C: Clock + Clone,
+    H: BuildHasher + Clone,
+{
+    /// Check a request for a string key; see [`FluxLimiter::check_request_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`FluxLimiter::check_request_str`].
+    pub fn check_request_str(
+        &self,
+        client_id: &str,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.shard_for(client_id).check_request_str(client_id)
+    }
+}
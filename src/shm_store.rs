@@ -0,0 +1,258 @@
+// src/shm_store.rs
+
+//! [`StateStore`] in a memory-mapped file shared between processes (`shm` feature)
+
+// dependencies
+use crate::store::{StateStore, StoreError};
+use memmap2::MmapRaw;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// "FLUXSHM1", marking a file laid out by this module
+const MAGIC: u64 = u64::from_be_bytes(*b"FLUXSHM1");
+
+// start of the file: the magic number, then the slot count
+#[repr(C)]
+struct Header {
+    magic: AtomicU64,
+    capacity: AtomicU64,
+}
+
+// one client; `key_hash` is 0 while free and `tat` 0 until first written,
+// otherwise the TAT plus one
+#[repr(C)]
+struct Slot {
+    key_hash: AtomicU64,
+    tat: AtomicU64,
+}
+
+/// Client TATs in a fixed-size hash table in a memory-mapped file, shared by
+/// every process that opens the same path.
+///
+/// Pre-fork servers run several worker processes per host; with a
+/// [`FluxLimiter`](crate::FluxLimiter) in each, every worker enforces its own
+/// copy of the limit. Opening one `SharedMemoryStore` per worker (or once
+/// before forking) makes them share a single TAT per client instead, updated
+/// with atomic compare-and-swaps on the mapped memory, without a server
+/// process. Put the file on a RAM-backed file system such as `/dev/shm` to
+/// keep it off the disk.
+///
+/// The table never shrinks: a client keeps its slot for the file's lifetime,
+/// and checks of new clients fail with a [`StoreError`] once all `capacity`
+/// slots are taken, so size it for every client expected before the file is
+/// recreated (e.g. on deploy). Clients are identified by a 64-bit FNV-1a hash
+/// of their key, which is stable across processes; two keys with the same
+/// hash share a limit.
+///
+/// The file must not be truncated while mapped.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, SharedMemoryStore, StoreBackedLimiter, SystemClock};
+///
+/// let path = std::env::temp_dir().join("flux-limiter-doc-shm");
+/// # let _ = std::fs::remove_file(&path);
+/// let store = SharedMemoryStore::open(&path, 10_000).unwrap();
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = StoreBackedLimiter::with_config(config, SystemClock, store).unwrap();
+/// assert!(limiter.check_request(&"client1".to_string()).unwrap().allowed);
+/// ```
+pub struct SharedMemoryStore {
+    map: MmapRaw,
+    capacity: usize,
+}
+
+impl SharedMemoryStore {
+    /// Map the table in the file at `path`, creating it with room for
+    /// `capacity` clients if it does not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the file cannot be opened, resized or mapped,
+    /// and an [`io::ErrorKind::InvalidData`] error if it was not created by
+    /// this store or was created with a different capacity.
+    pub fn open(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        if capacity == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "capacity must be at least 1",
+            ));
+        }
+        let len = capacity
+            .checked_mul(size_of::<Slot>())
+            .and_then(|slots| slots.checked_add(size_of::<Header>()))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "capacity is too large"))?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        // new files are zero-filled, which is an empty table
+        if file.metadata()?.len() < len as u64 {
+            file.set_len(len as u64)?;
+        }
+        let map = MmapRaw::map_raw(&file)?;
+        if map.len() < len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "shared memory file is too short",
+            ));
+        }
+
+        let store = Self { map, capacity };
+        // the first process to open the file claims it; the rest check it
+        let header = store.header();
+        match header
+            .magic
+            .compare_exchange(0, MAGIC, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) | Err(MAGIC) => {}
+            Err(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "not a shared memory store file",
+                ));
+            }
+        }
+        let capacity = capacity as u64;
+        match header
+            .capacity
+            .compare_exchange(0, capacity, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => {}
+            Err(existing) if existing == capacity => {}
+            Err(existing) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("shared memory store was created with capacity {existing}"),
+                ));
+            }
+        }
+        Ok(store)
+    }
+
+    /// Number of clients the table holds
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of slots taken, which scans the whole table
+    pub fn len(&self) -> usize {
+        self.slots()
+            .iter()
+            .filter(|slot| slot.key_hash.load(Ordering::Relaxed) != 0)
+            .count()
+    }
+
+    /// Whether no slots are taken
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // the header at the start of the map
+    fn header(&self) -> &Header {
+        // SAFETY: the map is page-aligned and at least a header plus `capacity`
+        // slots long (checked in `open`), lives as long as `self`, and is only
+        // accessed through atomics
+        unsafe { &*self.map.as_mut_ptr().cast::<Header>() }
+    }
+
+    // the slot table following the header
+    fn slots(&self) -> &[Slot] {
+        // SAFETY: as for `header`; slots start right after it and are 8-byte aligned
+        unsafe {
+            let first = self
+                .map
+                .as_mut_ptr()
+                .add(size_of::<Header>())
+                .cast::<Slot>();
+            std::slice::from_raw_parts(first, self.capacity)
+        }
+    }
+
+    // the slot holding `key`, taking a free one for it if `claim` is set
+    fn find(&self, key: &[u8], claim: bool) -> Result<Option<&Slot>, StoreError> {
+        let key_hash = fnv1a(key).max(1);
+        let slots = self.slots();
+        let start = (key_hash % slots.len() as u64) as usize;
+
+        // linear probing; slots are never freed, so a free slot ends the chain
+        for offset in 0..slots.len() {
+            let slot = &slots[(start + offset) % slots.len()];
+            match slot.key_hash.load(Ordering::Acquire) {
+                hash if hash == key_hash => return Ok(Some(slot)),
+                0 if !claim => return Ok(None),
+                0 => match slot.key_hash.compare_exchange(
+                    0,
+                    key_hash,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => return Ok(Some(slot)),
+                    // another process claimed it, perhaps for the same key
+                    Err(hash) if hash == key_hash => return Ok(Some(slot)),
+                    Err(_) => {}
+                },
+                _ => {}
+            }
+        }
+        if claim {
+            return Err(StoreError::new("shared memory store is full"));
+        }
+        Ok(None)
+    }
+}
+
+impl<K> StateStore<K> for SharedMemoryStore
+where
+    K: AsRef<[u8]> + ?Sized,
+{
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError> {
+        Ok(self
+            .find(key.as_ref(), false)?
+            .and_then(|slot| decode(slot.tat.load(Ordering::Acquire))))
+    }
+
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError> {
+        // only a first write needs a slot of its own
+        let Some(slot) = self.find(key.as_ref(), expected.is_none())? else {
+            return Ok(false);
+        };
+        Ok(slot
+            .tat
+            .compare_exchange(
+                encode(expected),
+                encode(Some(new)),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok())
+    }
+}
+
+// 64-bit FNV-1a, fixed so every process maps a key to the same slot
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// TATs are stored plus one, keeping 0 for "never written"
+fn encode(tat_nanos: Option<u64>) -> u64 {
+    tat_nanos.map_or(0, |tat_nanos| tat_nanos.saturating_add(1))
+}
+
+fn decode(stored: u64) -> Option<u64> {
+    stored.checked_sub(1)
+}
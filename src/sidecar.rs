@@ -0,0 +1,344 @@
+// src/sidecar.rs
+
+//! gRPC service exposing a limiter to other processes (`sidecar` feature)
+
+// handlers return tonic's `Status`, large as it is, like generated services
+#![allow(clippy::result_large_err)]
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{Body, BoxFuture, Service, StdError, http};
+use tonic::server::{Grpc, NamedService, UnaryService};
+use tonic::{Request, Response, Status};
+
+/// Request for `Check`: the client to check a request for
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckRequest {
+    /// Client key, e.g. an API key or IP address
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+/// Response to `Check`: the fields of a
+/// [`FluxLimiterDecision`](crate::FluxLimiterDecision)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CheckResponse {
+    /// Whether the request is allowed
+    #[prost(bool, tag = "1")]
+    pub allowed: bool,
+    /// Seconds to wait before retrying, for denied requests
+    #[prost(double, optional, tag = "2")]
+    pub retry_after_seconds: Option<f64>,
+    /// Requests still available in the burst
+    #[prost(double, optional, tag = "3")]
+    pub remaining_capacity: Option<f64>,
+    /// When the client's capacity is fully restored (nanoseconds since epoch)
+    #[prost(uint64, tag = "4")]
+    pub reset_time_nanos: u64,
+    /// Configured rate, in requests per second
+    #[prost(double, tag = "5")]
+    pub limit_per_second: f64,
+    /// Configured burst capacity
+    #[prost(double, tag = "6")]
+    pub burst_capacity: f64,
+    /// [`DecisionReason::as_str`](crate::DecisionReason::as_str) of the decision
+    #[prost(string, tag = "7")]
+    pub reason: String,
+}
+
+/// Request for `Peek`: the client to inspect
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PeekRequest {
+    /// Client key
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+/// Response to `Peek`: the fields of a [`ClientSnapshot`](crate::ClientSnapshot)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PeekResponse {
+    /// Whether the client is tracked; the other fields are zero if not
+    #[prost(bool, tag = "1")]
+    pub tracked: bool,
+    /// Stored theoretical arrival time (nanoseconds since epoch)
+    #[prost(uint64, tag = "2")]
+    pub tat_nanos: u64,
+    /// Approximate remaining burst capacity
+    #[prost(double, tag = "3")]
+    pub remaining_capacity: f64,
+    /// Nanoseconds until the client's next request would conform
+    #[prost(uint64, tag = "4")]
+    pub time_until_conforming_nanos: u64,
+    /// Requests owed beyond the limit
+    #[prost(double, tag = "5")]
+    pub debt: f64,
+}
+
+/// Request for `Reset`: the client to forget
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResetRequest {
+    /// Client key
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+/// Response to `Reset`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResetResponse {
+    /// Whether the client was tracked
+    #[prost(bool, tag = "1")]
+    pub removed: bool,
+}
+
+/// Request for `Stats`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StatsRequest {}
+
+/// Response to `Stats`: the fields of a [`FluxLimiterStats`](crate::FluxLimiterStats)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StatsResponse {
+    /// Number of requests checked
+    #[prost(uint64, tag = "1")]
+    pub total_checks: u64,
+    /// Number of requests allowed
+    #[prost(uint64, tag = "2")]
+    pub allowed: u64,
+    /// Number of requests denied
+    #[prost(uint64, tag = "3")]
+    pub denied: u64,
+    /// Number of client entries removed by cleanup
+    #[prost(uint64, tag = "4")]
+    pub evictions: u64,
+    /// Number of clients currently tracked
+    #[prost(uint64, tag = "5")]
+    pub current_clients: u64,
+}
+
+/// The `flux_limiter.v1.RateLimiter` gRPC service over a [`FluxLimiter`].
+///
+/// Lets services written in other languages use the limiter as a local
+/// sidecar: `Check` runs [`check_request`](FluxLimiter::check_request),
+/// `Peek` [`client_snapshot`](FluxLimiter::client_snapshot), `Reset`
+/// [`reset_client`](FluxLimiter::reset_client) and `Stats`
+/// [`stats`](FluxLimiter::stats). Generate clients from
+/// `proto/flux_limiter/v1/rate_limiter.proto` in the repository. Calls
+/// with an empty `client_id` fail with `INVALID_ARGUMENT`, and limiter
+/// errors with `UNAVAILABLE`.
+///
+/// Add it to a tonic server next to other services, or run it on its own
+/// with [`serve`](Self::serve) or the `flux-limiter-sidecar` binary.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, RateLimiterService, SystemClock};
+/// use std::sync::Arc;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = FluxLimiterConfig::new(100.0, 50.0).stats(true);
+/// let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock)?);
+///
+/// RateLimiterService::new(limiter)
+///     .serve("127.0.0.1:50051".parse()?)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RateLimiterService<C = SystemClock>
+where
+    C: Clock,
+{
+    limiter: Arc<FluxLimiter<String, C>>,
+}
+
+// derived Clone would require C: Clone
+impl<C> Clone for RateLimiterService<C>
+where
+    C: Clock,
+{
+    fn clone(&self) -> Self {
+        Self {
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+impl<C> RateLimiterService<C>
+where
+    C: Clock + 'static,
+{
+    /// Serve `limiter`, which may be shared with in-process callers
+    pub fn new(limiter: Arc<FluxLimiter<String, C>>) -> Self {
+        Self { limiter }
+    }
+
+    /// The served limiter
+    pub fn limiter(&self) -> &Arc<FluxLimiter<String, C>> {
+        &self.limiter
+    }
+
+    /// Serve this service alone on `addr` until the server fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the transport error if `addr` cannot be bound or the server
+    /// fails.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), tonic::transport::Error> {
+        tonic::transport::Server::builder()
+            .add_service(self)
+            .serve(addr)
+            .await
+    }
+
+    // run `Check`
+    fn check(&self, request: CheckRequest) -> Result<CheckResponse, Status> {
+        let decision = self
+            .limiter
+            .check_request(client_id(request.client_id)?)
+            .map_err(unavailable)?;
+        Ok(CheckResponse {
+            allowed: decision.allowed,
+            retry_after_seconds: decision.retry_after_seconds,
+            remaining_capacity: decision.remaining_capacity,
+            reset_time_nanos: decision.reset_time_nanos,
+            limit_per_second: decision.limit_per_second,
+            burst_capacity: decision.burst_capacity,
+            reason: decision.reason.as_str().to_string(),
+        })
+    }
+
+    // run `Peek`
+    fn peek(&self, request: PeekRequest) -> Result<PeekResponse, Status> {
+        let snapshot = self
+            .limiter
+            .client_snapshot(client_id(request.client_id)?.as_str())
+            .map_err(unavailable)?;
+        Ok(
+            snapshot.map_or_else(PeekResponse::default, |snapshot| PeekResponse {
+                tracked: true,
+                tat_nanos: snapshot.tat_nanos,
+                remaining_capacity: snapshot.remaining_capacity,
+                time_until_conforming_nanos: snapshot.time_until_conforming_nanos,
+                debt: snapshot.debt,
+            }),
+        )
+    }
+
+    // run `Reset`
+    fn reset(&self, request: ResetRequest) -> Result<ResetResponse, Status> {
+        Ok(ResetResponse {
+            removed: self
+                .limiter
+                .reset_client(client_id(request.client_id)?.as_str()),
+        })
+    }
+
+    // run `Stats`
+    fn stats(&self, _request: StatsRequest) -> Result<StatsResponse, Status> {
+        let stats = self.limiter.stats();
+        Ok(StatsResponse {
+            total_checks: stats.total_checks,
+            allowed: stats.allowed,
+            denied: stats.denied,
+            evictions: stats.evictions,
+            current_clients: stats.current_clients as u64,
+        })
+    }
+}
+
+impl<C> NamedService for RateLimiterService<C>
+where
+    C: Clock,
+{
+    const NAME: &'static str = "flux_limiter.v1.RateLimiter";
+}
+
+// route gRPC calls by path, as tonic-build generated servers do
+impl<C, B> Service<http::Request<B>> for RateLimiterService<C>
+where
+    C: Clock + 'static,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let service = self.clone();
+        match request.uri().path() {
+            "/flux_limiter.v1.RateLimiter/Check" => {
+                unary(request, move |message| service.check(message))
+            }
+            "/flux_limiter.v1.RateLimiter/Peek" => {
+                unary(request, move |message| service.peek(message))
+            }
+            "/flux_limiter.v1.RateLimiter/Reset" => {
+                unary(request, move |message| service.reset(message))
+            }
+            "/flux_limiter.v1.RateLimiter/Stats" => {
+                unary(request, move |message| service.stats(message))
+            }
+            _ => Box::pin(async { Ok(Status::unimplemented("unknown method").into_http()) }),
+        }
+    }
+}
+
+// a synchronous handler for one unary method
+pub(crate) struct Handler<F>(F);
+
+impl<F, M, R> UnaryService<M> for Handler<F>
+where
+    F: FnMut(M) -> Result<R, Status>,
+{
+    type Response = R;
+    type Future = std::future::Ready<Result<Response<R>, Status>>;
+
+    fn call(&mut self, request: Request<M>) -> Self::Future {
+        std::future::ready((self.0)(request.into_inner()).map(Response::new))
+    }
+}
+
+// decode a unary call, run `handler` on it and encode the result
+pub(crate) fn unary<B, M, R, F>(
+    request: http::Request<B>,
+    handler: F,
+) -> BoxFuture<http::Response<BoxBody>, Infallible>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    M: prost::Message + Default + Send + 'static,
+    R: prost::Message + Send + 'static,
+    F: FnMut(M) -> Result<R, Status> + Send + 'static,
+{
+    Box::pin(async move {
+        let mut grpc = Grpc::new(ProstCodec::<R, M>::default());
+        Ok(grpc.unary(Handler(handler), request).await)
+    })
+}
+
+// reject calls without a client key
+fn client_id(client_id: String) -> Result<String, Status> {
+    if client_id.is_empty() {
+        return Err(Status::invalid_argument("client_id must not be empty"));
+    }
+    Ok(client_id)
+}
+
+// limiter errors are the clock failing, which a retry may get past
+pub(crate) fn unavailable(err: FluxLimiterError) -> Status {
+    Status::unavailable(err.to_string())
+}
@@ -0,0 +1,253 @@
+// src/simulation.rs
+
+//! Offline replay and synthetic traffic simulation against a config
+
+// dependencies
+use crate::clock::SystemClock;
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+
+/// Replay request arrival times for a single client and return every decision.
+///
+/// `arrivals_nanos` are timestamps in nanoseconds on any time base, checked
+/// in the order given with [`FluxLimiter::check_request_at`]; the system
+/// clock is never read. Useful for checking what a config would do to a
+/// recorded or synthetic traffic pattern, and for property tests of the
+/// algorithm itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, simulate};
+///
+/// // 1 req/s with a burst of 1: three requests at once, then one a second later
+/// let decisions = simulate(
+///     FluxLimiterConfig::new(1.0, 1.0),
+///     [0, 0, 0, 1_000_000_000],
+/// )
+/// .unwrap();
+///
+/// let allowed: Vec<bool> = decisions.iter().map(|decision| decision.allowed).collect();
+/// assert_eq!(allowed, [true, true, false, true]);
+/// ```
+///
+/// # Errors
+///
+/// Returns the config's validation error.
+pub fn simulate(
+    config: FluxLimiterConfig,
+    arrivals_nanos: impl IntoIterator<Item = u64>,
+) -> Result<Vec<FluxLimiterDecision>, FluxLimiterError> {
+    let limiter = FluxLimiter::with_config(config, SystemClock)?;
+    arrivals_nanos
+        .into_iter()
+        .map(|arrival_nanos| limiter.check_request_at((), arrival_nanos))
+        .collect()
+}
+
+/// Synthetic arrival pattern for a [`Simulator`] run.
+#[cfg(feature = "simulation")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrivalPattern {
+    /// Requests evenly spaced at `rate_per_second`
+    Constant { rate_per_second: f64 },
+    /// Independent arrivals averaging `rate_per_second` (exponential gaps)
+    Poisson { rate_per_second: f64 },
+    /// `burst_size` simultaneous requests every `period_seconds`
+    Bursty {
+        burst_size: u32,
+        period_seconds: f64,
+    },
+}
+
+#[cfg(feature = "simulation")]
+impl ArrivalPattern {
+    // arrival times in nanoseconds within `duration_nanos`, drawing randomness from `rng`
+    fn arrivals(&self, duration_nanos: u64, rng: &mut SimRng) -> Vec<u64> {
+        let mut arrivals = Vec::new();
+        match *self {
+            ArrivalPattern::Constant { rate_per_second } => {
+                let gap = 1_000_000_000.0 / rate_per_second;
+                let count = (duration_nanos as f64 / gap).ceil() as u64;
+                arrivals.extend((0..count).map(|n| (n as f64 * gap) as u64));
+            }
+            ArrivalPattern::Poisson { rate_per_second } => {
+                let mut now = 0.0;
+                loop {
+                    // inverse-transform sample of an exponential gap
+                    now += -(1.0 - rng.next_unit()).ln() / rate_per_second * 1_000_000_000.0;
+                    if now >= duration_nanos as f64 {
+                        break;
+                    }
+                    arrivals.push(now as u64);
+                }
+            }
+            ArrivalPattern::Bursty {
+                burst_size,
+                period_seconds,
+            } => {
+                let period = period_seconds * 1_000_000_000.0;
+                let bursts = (duration_nanos as f64 / period).ceil() as u64;
+                for n in 0..bursts {
+                    let at = (n as f64 * period) as u64;
+                    arrivals.extend(std::iter::repeat_n(at, burst_size as usize));
+                }
+            }
+        }
+        arrivals
+    }
+
+    // whether the pattern describes a finite, non-empty stream of arrivals
+    fn is_valid(&self) -> bool {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        match *self {
+            ArrivalPattern::Constant { rate_per_second }
+            | ArrivalPattern::Poisson { rate_per_second } => positive(rate_per_second),
+            ArrivalPattern::Bursty {
+                burst_size,
+                period_seconds,
+            } => burst_size > 0 && positive(period_seconds),
+        }
+    }
+}
+
+/// Aggregate outcome of a [`Simulator`] run.
+///
+/// Percentiles are taken over the `retry_after_seconds` of denied requests
+/// and are `None` when nothing was denied.
+#[cfg(feature = "simulation")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationReport {
+    /// Number of requests generated
+    pub total: u64,
+    /// Number of requests allowed
+    pub allowed: u64,
+    /// Number of requests denied
+    pub denied: u64,
+    /// Median advised retry delay in seconds
+    pub retry_after_p50: Option<f64>,
+    /// 90th percentile advised retry delay in seconds
+    pub retry_after_p90: Option<f64>,
+    /// 99th percentile advised retry delay in seconds
+    pub retry_after_p99: Option<f64>,
+    /// Longest advised retry delay in seconds
+    pub retry_after_max: Option<f64>,
+}
+
+/// Runs synthetic traffic against a config to help tune rate and burst.
+///
+/// Each run feeds one client's requests from an [`ArrivalPattern`] through a
+/// fresh limiter, as [`simulate`] does, and summarizes the decisions. Random
+/// patterns are reproducible: the same seed yields the same arrivals.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{ArrivalPattern, FluxLimiterConfig, Simulator};
+///
+/// let simulator = Simulator::new(FluxLimiterConfig::new(10.0, 5.0)).unwrap();
+///
+/// // 20 req/s of steady demand against a 10 req/s limit, for a minute
+/// let report = simulator
+///     .run(ArrivalPattern::Constant { rate_per_second: 20.0 }, 60.0)
+///     .unwrap();
+/// assert_eq!(report.total, 1_200);
+/// assert!(report.allowed <= 606);
+/// assert!(report.retry_after_p99.unwrap() <= 0.1);
+/// ```
+#[cfg(feature = "simulation")]
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    config: FluxLimiterConfig,
+    seed: u64,
+}
+
+#[cfg(feature = "simulation")]
+impl Simulator {
+    /// Create a simulator for `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the config's validation error.
+    pub fn new(config: FluxLimiterConfig) -> Result<Self, FluxLimiterError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        })
+    }
+
+    /// Seed the generator used by random arrival patterns
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Simulate `duration_seconds` of traffic following `pattern`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::InvalidSimulation`] for a duration,
+    /// rate, period or burst size that is not positive and finite.
+    pub fn run(
+        &self,
+        pattern: ArrivalPattern,
+        duration_seconds: f64,
+    ) -> Result<SimulationReport, FluxLimiterError> {
+        if !pattern.is_valid() || !duration_seconds.is_finite() || duration_seconds <= 0.0 {
+            return Err(FluxLimiterError::InvalidSimulation);
+        }
+
+        let mut rng = SimRng::new(self.seed);
+        let arrivals = pattern.arrivals((duration_seconds * 1_000_000_000.0) as u64, &mut rng);
+        let decisions = simulate(self.config.clone(), arrivals)?;
+
+        let mut retry_afters: Vec<f64> = decisions
+            .iter()
+            .filter_map(|decision| decision.retry_after_seconds)
+            .collect();
+        retry_afters.sort_unstable_by(f64::total_cmp);
+        let allowed = decisions.iter().filter(|decision| decision.allowed).count() as u64;
+
+        Ok(SimulationReport {
+            total: decisions.len() as u64,
+            allowed,
+            denied: decisions.len() as u64 - allowed,
+            retry_after_p50: percentile(&retry_afters, 0.50),
+            retry_after_p90: percentile(&retry_afters, 0.90),
+            retry_after_p99: percentile(&retry_afters, 0.99),
+            retry_after_max: retry_afters.last().copied(),
+        })
+    }
+}
+
+// nearest-rank percentile of sorted values
+#[cfg(feature = "simulation")]
+fn percentile(sorted: &[f64], fraction: f64) -> Option<f64> {
+    let rank = (fraction * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+// seedable xorshift64* generator, so runs are reproducible
+#[cfg(feature = "simulation")]
+struct SimRng(u64);
+
+#[cfg(feature = "simulation")]
+impl SimRng {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        Self(seed | 1)
+    }
+
+    // next value in [0, 1)
+    fn next_unit(&mut self) -> f64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,146 @@
+// src/sled_store.rs
+
+//! [`StateStore`] backed by the `sled` embedded database (`sled` feature)
+
+// dependencies
+use crate::store::{StateStore, StoreError};
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+
+/// Client TATs stored in a `sled` tree, so state survives restarts without an
+/// external server.
+///
+/// In the default write-through mode every allowed request is a sled
+/// compare-and-swap. In [write-behind](SledStore::write_behind) mode checks run
+/// against an in-memory cache and changed TATs reach sled only on
+/// [`flush`](SledStore::flush) (and on drop), trading a window of lost updates
+/// on crash for an in-memory hot path.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiterConfig, SledStore, StoreBackedLimiter, SystemClock};
+///
+/// let db = sled::Config::new().temporary(true).open().unwrap();
+/// let store = SledStore::new(db.open_tree("rate-limits").unwrap());
+///
+/// let config = FluxLimiterConfig::new(10.0, 5.0);
+/// let limiter = StoreBackedLimiter::with_config(config, SystemClock, store).unwrap();
+/// assert!(limiter.check_request(&"client1".to_string()).unwrap().allowed);
+/// ```
+pub struct SledStore {
+    tree: sled::Tree,
+    // write-behind cache: key -> (TAT, not yet flushed)
+    cache: Option<DashMap<Vec<u8>, (u64, bool)>>,
+}
+
+impl SledStore {
+    /// Create a write-through store over `tree`
+    pub fn new(tree: sled::Tree) -> Self {
+        Self { tree, cache: None }
+    }
+
+    /// Create a write-behind store over `tree`; call [`SledStore::flush`]
+    /// periodically to persist changes.
+    pub fn write_behind(tree: sled::Tree) -> Self {
+        Self {
+            tree,
+            cache: Some(DashMap::new()),
+        }
+    }
+
+    /// Write cached changes to sled and flush sled to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StoreError`] if sled fails to write or flush.
+    pub fn flush(&self) -> Result<(), StoreError> {
+        if let Some(cache) = &self.cache {
+            for mut entry in cache.iter_mut() {
+                let (key, (tat_nanos, dirty)) = entry.pair_mut();
+                if *dirty {
+                    self.tree
+                        .insert(key.as_slice(), &tat_nanos.to_be_bytes())
+                        .map_err(StoreError::new)?;
+                    *dirty = false;
+                }
+            }
+        }
+        self.tree.flush().map_err(StoreError::new)?;
+
+        Ok(())
+    }
+
+    // read a TAT straight from sled
+    fn read_tree(&self, key: &[u8]) -> Result<Option<u64>, StoreError> {
+        self.tree
+            .get(key)
+            .map_err(StoreError::new)?
+            .map(|bytes| decode(&bytes))
+            .transpose()
+    }
+}
+
+impl<K> StateStore<K> for SledStore
+where
+    K: AsRef<[u8]> + ?Sized,
+{
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError> {
+        let key = key.as_ref();
+        if let Some(cached) = self.cache.as_ref().and_then(|cache| cache.get(key)) {
+            return Ok(Some(cached.0));
+        }
+        self.read_tree(key)
+    }
+
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError> {
+        let key = key.as_ref();
+        let Some(cache) = &self.cache else {
+            let expected = expected.map(u64::to_be_bytes);
+            return Ok(self
+                .tree
+                .compare_and_swap(key, expected.as_ref(), Some(&new.to_be_bytes()))
+                .map_err(StoreError::new)?
+                .is_ok());
+        };
+
+        match cache.entry(key.to_vec()) {
+            Entry::Occupied(mut entry) if Some(entry.get().0) == expected => {
+                entry.insert((new, true));
+                Ok(true)
+            }
+            Entry::Occupied(_) => Ok(false),
+            // not cached yet: sled holds the current value
+            Entry::Vacant(entry) => {
+                if self.read_tree(key)? == expected {
+                    entry.insert((new, true));
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SledStore {
+    fn drop(&mut self) {
+        // best effort; call flush() explicitly to observe errors
+        if self.cache.is_some() {
+            let _ = self.flush();
+        }
+    }
+}
+
+// TATs are stored as 8 big-endian bytes
+fn decode(bytes: &[u8]) -> Result<u64, StoreError> {
+    let bytes: [u8; 8] = bytes
+        .try_into()
+        .map_err(|_| StoreError::new("stored TAT is not 8 bytes"))?;
+    Ok(u64::from_be_bytes(bytes))
+}
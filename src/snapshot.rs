@@ -0,0 +1,61 @@
+// src/snapshot.rs
+
+//! Read-only views of limiter state
+
+/// Point-in-time view of a single client's rate limiting state.
+///
+/// Returned by [`FluxLimiter::client_snapshot`](crate::FluxLimiter::client_snapshot)
+/// for admin and debug endpoints. Taking a snapshot never modifies state.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientSnapshot {
+    /// Stored theoretical arrival time (nanoseconds since epoch)
+    pub tat_nanos: u64,
+    /// Approximate remaining burst capacity at the time of the snapshot
+    pub remaining_capacity: f64,
+    /// Nanoseconds until the client's next request would conform (0 if it already would)
+    pub time_until_conforming_nanos: u64,
+    /// Requests owed beyond the limit (always 0 without a debt ceiling)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub debt: f64,
+}
+
+impl ClientSnapshot {
+    /// Whether a request made at the time of the snapshot would be allowed
+    pub fn is_conforming(&self) -> bool {
+        self.time_until_conforming_nanos == 0
+    }
+
+    /// Seconds until the client's next request would conform
+    pub fn time_until_conforming_seconds(&self) -> f64 {
+        self.time_until_conforming_nanos as f64 / 1_000_000_000.0
+    }
+}
+
+/// Serializable copy of every client's TAT, used to carry state across restarts.
+///
+/// Produced by [`FluxLimiter::export_state`](crate::FluxLimiter::export_state) and
+/// loaded with [`FluxLimiter::with_state`](crate::FluxLimiter::with_state). TATs are
+/// absolute timestamps, so a snapshot stays meaningful across a restart as long
+/// as the new limiter's clock shares the same epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateSnapshot<T> {
+    /// Snapshot format version
+    pub version: u32,
+    /// Client identifiers and their theoretical arrival times (nanoseconds since epoch)
+    pub clients: Vec<(T, u64)>,
+}
+
+impl<T> StateSnapshot<T> {
+    /// Format version written by this release
+    pub const VERSION: u32 = 1;
+
+    /// Create a snapshot in the current format
+    pub fn new(clients: Vec<(T, u64)>) -> Self {
+        Self {
+            version: Self::VERSION,
+            clients,
+        }
+    }
+}
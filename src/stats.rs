@@ -0,0 +1,112 @@
+// src/stats.rs
+
+//! Per-limiter statistics counters
+
+// dependencies
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Snapshot of a limiter's statistics.
+///
+/// Returned by [`FluxLimiter::stats`](crate::FluxLimiter::stats). Counters are
+/// only maintained when enabled with [`FluxLimiterConfig::stats`](crate::FluxLimiterConfig::stats);
+/// otherwise they stay at zero and only `current_clients` is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FluxLimiterStats {
+    /// Number of requests checked
+    pub total_checks: u64,
+    /// Number of requests allowed
+    pub allowed: u64,
+    /// Number of requests denied
+    pub denied: u64,
+    /// Number of client entries removed by cleanup
+    pub evictions: u64,
+    /// Number of clients currently tracked
+    pub current_clients: usize,
+}
+
+/// Outcome of a cleanup pass.
+///
+/// Returned by [`FluxLimiter::cleanup_stale_clients`](crate::FluxLimiter::cleanup_stale_clients)
+/// and its dry-run variant, which reports what would have been removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CleanupReport {
+    /// Number of client entries examined
+    pub scanned: usize,
+    /// Number of stale entries removed (or that would be, for a dry run)
+    pub removed: usize,
+    /// Number of entries kept. A full pass examines every client, so this is
+    /// the number left in the map; for
+    /// [`cleanup_stale_clients_bounded`](crate::FluxLimiter::cleanup_stale_clients_bounded)
+    /// it only counts the examined entries that were kept.
+    pub remaining: usize,
+}
+
+impl CleanupReport {
+    // add up the reports of several limiters
+    pub(crate) fn merge(self, other: Self) -> Self {
+        Self {
+            scanned: self.scanned + other.scanned,
+            removed: self.removed + other.removed,
+            remaining: self.remaining + other.remaining,
+        }
+    }
+}
+
+/// Allowed/denied counts for a single client.
+///
+/// Returned by [`FluxLimiter::client_stats`](crate::FluxLimiter::client_stats)
+/// when the `per-key-stats` feature is enabled.
+#[cfg(feature = "per-key-stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientStats {
+    /// Requests allowed for this client
+    pub allowed: u64,
+    /// Requests denied for this client
+    pub denied: u64,
+}
+
+// relaxed atomic counters backing FluxLimiterStats
+#[derive(Debug, Default)]
+pub(crate) struct StatsCounters {
+    allowed: AtomicU64,
+    denied: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl StatsCounters {
+    // count the outcome of a single check
+    pub(crate) fn record_decision(&self, allowed: bool) {
+        let counter = if allowed { &self.allowed } else { &self.denied };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // count entries removed by cleanup
+    pub(crate) fn record_evictions(&self, count: u64) {
+        if count > 0 {
+            self.evictions.fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    // read the counters into a snapshot
+    pub(crate) fn snapshot(&self, current_clients: usize) -> FluxLimiterStats {
+        let allowed = self.allowed.load(Ordering::Relaxed);
+        let denied = self.denied.load(Ordering::Relaxed);
+        FluxLimiterStats {
+            total_checks: allowed + denied,
+            allowed,
+            denied,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            current_clients,
+        }
+    }
+
+    // zero all counters
+    pub(crate) fn reset(&self) {
+        self.allowed.store(0, Ordering::Relaxed);
+        self.denied.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,172 @@
+// src/store.rs
+
+//! Pluggable storage for client TATs
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::FluxLimiterConfig;
+use crate::decision::FluxLimiterDecision;
+use crate::errors::FluxLimiterError;
+use crate::flux_limiter::FluxLimiter;
+use crate::stats::FluxLimiterStats;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+/// Storage for client theoretical arrival times.
+///
+/// Implement this to keep limiter state outside the process (an embedded
+/// KV store, a database, a cache cluster). The limiter reads a client's TAT,
+/// runs the GCRA step, and writes the new TAT back with
+/// [`compare_and_swap`](StateStore::compare_and_swap), retrying when another
+/// writer got there first. Denied requests never write.
+pub trait StateStore<K: ?Sized> {
+    /// Return the stored TAT for `key`, or `None` if the key is unknown.
+    fn get(&self, key: &K) -> Result<Option<u64>, StoreError>;
+
+    /// Store `new` for `key` only if the current value is still `expected`
+    /// (`None` meaning absent). Returns whether the swap happened.
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> Result<bool, StoreError>;
+}
+
+/// Asynchronous counterpart of [`StateStore`] for network-backed stores.
+///
+/// Used by [`StoreBackedLimiter::check_request_async`] so that a round trip
+/// to Redis or a database does not block the executor thread. The semantics
+/// of each method match [`StateStore`].
+pub trait AsyncStateStore<K: ?Sized> {
+    /// Return the stored TAT for `key`, or `None` if the key is unknown.
+    fn get(&self, key: &K) -> impl Future<Output = Result<Option<u64>, StoreError>> + Send;
+
+    /// Store `new` for `key` only if the current value is still `expected`
+    /// (`None` meaning absent). Returns whether the swap happened.
+    fn compare_and_swap(
+        &self,
+        key: &K,
+        expected: Option<u64>,
+        new: u64,
+    ) -> impl Future<Output = Result<bool, StoreError>> + Send;
+}
+
+/// Error raised by a [`StateStore`] or [`AsyncStateStore`] implementation.
+#[derive(Debug)]
+pub struct StoreError(Box<dyn Error + Send + Sync>);
+
+impl StoreError {
+    /// Wrap a backend error
+    pub fn new(err: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self(err.into())
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "State store error: {}", self.0)
+    }
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// A rate limiter whose client state lives in a [`StateStore`] or an
+/// [`AsyncStateStore`].
+///
+/// Uses the same GCRA parameters, clock and statistics as [`FluxLimiter`];
+/// only the storage differs.
+pub struct StoreBackedLimiter<K, S, C = SystemClock>
+where
+    K: Hash + Eq + Clone,
+    C: Clock,
+{
+    limiter: FluxLimiter<K, C>,
+    store: S,
+}
+
+impl<K, S, C> StoreBackedLimiter<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    C: Clock,
+{
+    /// Create a store-backed limiter from a config object.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same configuration errors as [`FluxLimiter::with_config`].
+    pub fn with_config(
+        config: FluxLimiterConfig,
+        clock: C,
+        store: S,
+    ) -> Result<Self, FluxLimiterError> {
+        Ok(Self {
+            limiter: FluxLimiter::with_config(config, clock)?,
+            store,
+        })
+    }
+
+    /// The underlying store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Return the limiter's statistics (`current_clients` is always 0, as
+    /// clients live in the store).
+    pub fn stats(&self) -> FluxLimiterStats {
+        self.limiter.stats()
+    }
+
+    // accessor method to return the rate in requests per second
+    pub fn rate(&self) -> f64 {
+        self.limiter.rate()
+    }
+
+    // accessor method to return the burst capacity
+    pub fn burst(&self) -> f64 {
+        self.limiter.burst()
+    }
+}
+
+impl<K, S, C> StoreBackedLimiter<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: StateStore<K>,
+    C: Clock,
+{
+    /// Check if a request from the given client should be allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails and
+    /// [`FluxLimiterError::StoreError`] if the store does.
+    pub fn check_request(&self, client_id: &K) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.limiter.check_store(&self.store, client_id)
+    }
+}
+
+impl<K, S, C> StoreBackedLimiter<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: AsyncStateStore<K>,
+    C: Clock,
+{
+    /// Check if a request from the given client should be allowed, without
+    /// blocking on the store.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails and
+    /// [`FluxLimiterError::StoreError`] if the store does.
+    pub async fn check_request_async(
+        &self,
+        client_id: &K,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        self.limiter.check_store_async(&self.store, client_id).await
+    }
+}
@@ -0,0 +1,194 @@
+// src/tower_throttle.rs
+
+//! Client-side pacing for tower services (`tower` feature)
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::flux_limiter::FluxLimiter;
+use crate::key_extractor::KeyExtractor;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// Keys outgoing requests by destination authority: the URI's host, plus
+/// the port if it has one, falling back to the `Host` header for requests
+/// with a relative URI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Authority;
+
+impl<B> KeyExtractor<http::Request<B>> for Authority {
+    fn extract(&self, request: &http::Request<B>) -> Option<String> {
+        let uri = request.uri();
+        match (uri.host(), uri.port_u16()) {
+            (Some(host), Some(port)) => Some(format!("{host}:{port}")),
+            (Some(host), None) => Some(host.to_owned()),
+            (None, _) => request
+                .headers()
+                .get(http::header::HOST)?
+                .to_str()
+                .ok()
+                .map(str::to_owned),
+        }
+    }
+}
+
+/// Tower layer that paces outgoing requests, waiting for capacity instead
+/// of failing.
+///
+/// The client-side counterpart of the inbound integrations, for staying
+/// within third-party API quotas: each request is checked against the
+/// limiter before it is passed on, and while its key is over the limit the
+/// service sleeps for `retry_after_seconds` and checks again. Requests are
+/// keyed by [`Authority`] by default; requests without a key are sent
+/// immediately. Pass the same `Arc` to other integrations to share one
+/// budget between them.
+///
+/// Waiting holds no limiter capacity, so dropping a response future while it
+/// is delayed frees nothing because nothing was taken. Limiter errors, which
+/// only occur under [`FailurePolicy::Propagate`](crate::FailurePolicy::Propagate),
+/// fail the call.
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock, ThrottleLayer};
+/// use std::sync::Arc;
+/// use tower::ServiceBuilder;
+///
+/// // at most 5 requests per second to each upstream API
+/// let limiter = Arc::new(FluxLimiter::with_config(FluxLimiterConfig::new(5.0, 0.0), SystemClock).unwrap());
+/// let client = ServiceBuilder::new()
+///     .layer(ThrottleLayer::per_authority(limiter))
+///     .service_fn(|request: http::Request<String>| async move {
+///         Ok::<_, std::convert::Infallible>(http::Response::new(request.into_body()))
+///     });
+/// ```
+pub struct ThrottleLayer<E = Authority, C = SystemClock>
+where
+    C: Clock,
+{
+    limiter: Arc<FluxLimiter<String, C>>,
+    key: Arc<E>,
+}
+
+impl<C> ThrottleLayer<Authority, C>
+where
+    C: Clock,
+{
+    /// Pace requests separately for each destination authority
+    pub fn per_authority(limiter: Arc<FluxLimiter<String, C>>) -> Self {
+        Self::with_key(limiter, Authority)
+    }
+}
+
+impl<E, C> ThrottleLayer<E, C>
+where
+    C: Clock,
+{
+    /// Pace requests using keys from `key`, e.g. a closure returning an API
+    /// token or a [`HeaderKey`](crate::HeaderKey) naming a tenant header
+    pub fn with_key(limiter: Arc<FluxLimiter<String, C>>, key: E) -> Self {
+        Self {
+            limiter,
+            key: Arc::new(key),
+        }
+    }
+}
+
+// derived Clone would require E: Clone and C: Clone
+impl<E, C> Clone for ThrottleLayer<E, C>
+where
+    C: Clock,
+{
+    fn clone(&self) -> Self {
+        Self {
+            limiter: Arc::clone(&self.limiter),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<S, E, C> Layer<S> for ThrottleLayer<E, C>
+where
+    C: Clock,
+{
+    type Service = ThrottleService<S, E, C>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ThrottleService {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+/// Service produced by [`ThrottleLayer`]
+pub struct ThrottleService<S, E = Authority, C = SystemClock>
+where
+    C: Clock,
+{
+    inner: S,
+    limiter: Arc<FluxLimiter<String, C>>,
+    key: Arc<E>,
+}
+
+// derived Clone would require E: Clone and C: Clone
+impl<S, E, C> Clone for ThrottleService<S, E, C>
+where
+    S: Clone,
+    C: Clock,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limiter: Arc::clone(&self.limiter),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<S, E, C, B> Service<http::Request<B>> for ThrottleService<S, E, C>
+where
+    S: Service<http::Request<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    E: KeyExtractor<http::Request<B>> + 'static,
+    C: Clock + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let key = self.key.extract(&request);
+        let limiter = Arc::clone(&self.limiter);
+        // the inner service polled ready goes with the request; keep a fresh clone
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            if let Some(key) = key {
+                loop {
+                    let decision = limiter.check_request_ref(key.as_str())?;
+                    if decision.allowed {
+                        break;
+                    }
+                    tokio::time::sleep(decision.retry_after().unwrap_or_default()).await;
+                }
+            }
+            inner.call(request).await.map_err(Into::into)
+        })
+    }
+}
@@ -0,0 +1,274 @@
+// src/two_rate.rs
+
+//! Two-rate limiter enforcing a sustained and a peak rate per client
+
+// dependencies
+use crate::clock::{Clock, SystemClock};
+use crate::config::{FailurePolicy, FluxLimiterConfig};
+use crate::decision::{DecisionReason, FluxLimiterDecision};
+use crate::errors::FluxLimiterError;
+use crate::gcra::{self, Limits};
+use crate::stats::CleanupReport;
+use dashmap::DashMap;
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// A rate limiter holding every client to two rates at once, in the style of
+/// a two-rate three-color marker (RFC 2698).
+///
+/// The sustained rate and burst bound long-run throughput, while the peak
+/// rate and its (usually small) burst bound how fast that throughput may be
+/// drawn down: with a sustained 100/s and burst 1000, and a peak of 500/s
+/// with burst 10, a client may send 1000 extra requests, but no faster than
+/// 500/s. Each client keeps a TAT per rate; a request is admitted only if it
+/// conforms to both, and then both advance together under the entry's lock.
+///
+/// Denials say which constraint was violated:
+/// [`DecisionReason::BurstExhausted`] for the sustained rate and
+/// [`DecisionReason::PeakExceeded`] for the peak. `retry_after_seconds`
+/// waits for both, `remaining_capacity` is the smaller of the two, and
+/// `limit_per_second` / `burst_capacity` report the sustained config.
+///
+/// Only the rate and burst of each config apply, plus the sustained config's
+/// [`failure_policy`](FluxLimiterConfig::failure_policy).
+///
+/// # Examples
+///
+/// ```rust
+/// use flux_limiter::{DecisionReason, FluxLimiterConfig, SystemClock, TwoRateLimiter};
+///
+/// let limiter = TwoRateLimiter::new(
+///     FluxLimiterConfig::new(1.0, 10.0),  // sustained: 1/s, 10 extra
+///     FluxLimiterConfig::new(100.0, 1.0), // peak: 100/s, 1 extra
+///     SystemClock,
+/// )
+/// .unwrap();
+///
+/// assert!(limiter.check_request("trunk-1").unwrap().allowed);
+/// assert!(limiter.check_request("trunk-1").unwrap().allowed);
+/// let decision = limiter.check_request("trunk-1").unwrap();
+/// assert_eq!(decision.reason, DecisionReason::PeakExceeded);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TwoRateLimiter<T, C = SystemClock>
+where
+    T: Hash + Eq,
+    C: Clock,
+{
+    sustained: Limits,
+    peak: Limits,
+    failure_policy: FailurePolicy,
+    // (sustained TAT, peak TAT)
+    client_state: Arc<DashMap<T, (u64, u64)>>,
+    clock: C,
+}
+
+impl<T, C> TwoRateLimiter<T, C>
+where
+    T: Hash + Eq,
+    C: Clock,
+{
+    /// Create a limiter from a sustained and a peak config.
+    ///
+    /// # Errors
+    ///
+    /// Returns either config's validation error, and
+    /// [`FluxLimiterError::InvalidPeakRate`] if the peak rate is below the
+    /// sustained rate.
+    pub fn new(
+        sustained: FluxLimiterConfig,
+        peak: FluxLimiterConfig,
+        clock: C,
+    ) -> Result<Self, FluxLimiterError> {
+        sustained.validate()?;
+        peak.validate()?;
+        if peak.rate_per_second < sustained.rate_per_second {
+            return Err(FluxLimiterError::InvalidPeakRate);
+        }
+        Ok(Self {
+            sustained: Limits::new(
+                sustained.rate_per_second,
+                sustained.burst_capacity,
+                sustained.precision,
+            )?,
+            peak: Limits::new(peak.rate_per_second, peak.burst_capacity, peak.precision)?,
+            failure_policy: sustained.failure_policy,
+            client_state: Arc::new(DashMap::with_capacity(sustained.initial_capacity)),
+            clock,
+        })
+    }
+
+    /// The sustained rate, in requests per second
+    pub fn sustained_rate(&self) -> f64 {
+        self.sustained.rate_per_second
+    }
+
+    /// The peak rate, in requests per second
+    pub fn peak_rate(&self) -> f64 {
+        self.peak.rate_per_second
+    }
+
+    /// Number of clients currently tracked
+    pub fn len(&self) -> usize {
+        self.client_state.len()
+    }
+
+    /// Whether no clients are tracked
+    pub fn is_empty(&self) -> bool {
+        self.client_state.is_empty()
+    }
+
+    /// Check a request for `client_id` at the clock's current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails and the
+    /// failure policy is [`FailurePolicy::Propagate`].
+    pub fn check_request(&self, client_id: T) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let current_time_nanos = match self.clock.now() {
+            Ok(now) => now.as_nanos(),
+            Err(err) => return self.fail(FluxLimiterError::ClockError(err)),
+        };
+        self.check_request_at(client_id, current_time_nanos)
+    }
+
+    /// Check a request at a caller-supplied time; see
+    /// [`FluxLimiter::check_request_at`](crate::FluxLimiter::check_request_at).
+    pub fn check_request_at(
+        &self,
+        client_id: T,
+        timestamp_nanos: u64,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        // new clients start on schedule for both rates
+        let mut tats = self
+            .client_state
+            .entry(client_id)
+            .or_insert((timestamp_nanos, timestamp_nanos));
+        Ok(self.apply(&mut tats, timestamp_nanos))
+    }
+
+    /// Check a request using a borrowed form of the client ID, only
+    /// allocating an owned key the first time a client is seen.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`check_request`](Self::check_request).
+    pub fn check_request_ref<Q>(
+        &self,
+        client_id: &Q,
+    ) -> Result<FluxLimiterDecision, FluxLimiterError>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    {
+        let current_time_nanos = match self.clock.now() {
+            Ok(now) => now.as_nanos(),
+            Err(err) => return self.fail(FluxLimiterError::ClockError(err)),
+        };
+        if let Some(mut tats) = self.client_state.get_mut(client_id) {
+            return Ok(self.apply(&mut tats, current_time_nanos));
+        }
+        self.check_request_at(client_id.to_owned(), current_time_nanos)
+    }
+
+    /// Remove clients that have been fully recovered, on both rates, for
+    /// longer than `max_stale_nanos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FluxLimiterError::ClockError`] if the clock fails.
+    pub fn cleanup_stale_clients(
+        &self,
+        max_stale_nanos: u64,
+    ) -> Result<CleanupReport, FluxLimiterError> {
+        let current_time_nanos = self
+            .clock
+            .now()
+            .map_err(FluxLimiterError::ClockError)?
+            .as_nanos();
+        let scanned = self.client_state.len();
+        self.client_state
+            .retain(|_, &mut (sustained_tat_nanos, peak_tat_nanos)| {
+                let recovered = |tat_nanos, limits: &Limits| {
+                    gcra::is_recovered(
+                        current_time_nanos,
+                        tat_nanos,
+                        limits.tolerance_nanos,
+                        max_stale_nanos,
+                    )
+                };
+                !(recovered(sustained_tat_nanos, &self.sustained)
+                    && recovered(peak_tat_nanos, &self.peak))
+            });
+
+        let remaining = self.client_state.len();
+        Ok(CleanupReport {
+            scanned,
+            removed: scanned.saturating_sub(remaining),
+            remaining,
+        })
+    }
+
+    // run the GCRA step for both rates, advancing both TATs only when both conform
+    fn apply(&self, tats: &mut (u64, u64), current_time_nanos: u64) -> FluxLimiterDecision {
+        let (mut sustained, new_sustained_tat) = self.sustained.decide(current_time_nanos, tats.0);
+        let (peak, new_peak_tat) = self.peak.decide(current_time_nanos, tats.1);
+
+        match (new_sustained_tat, new_peak_tat) {
+            (Some(new_sustained_tat), Some(new_peak_tat)) => {
+                *tats = (new_sustained_tat, new_peak_tat);
+                sustained.remaining_capacity = sustained
+                    .remaining_capacity
+                    .zip(peak.remaining_capacity)
+                    .map(|(sustained_remaining, peak_remaining)| {
+                        sustained_remaining.min(peak_remaining)
+                    });
+                sustained
+            }
+            (sustained_tat, _) => {
+                // the request must wait until it conforms to both rates
+                let retry_after_seconds = [sustained.retry_after_seconds, peak.retry_after_seconds]
+                    .into_iter()
+                    .flatten()
+                    .reduce(f64::max);
+                let reason = match sustained_tat {
+                    None => DecisionReason::BurstExhausted,
+                    Some(_) => DecisionReason::PeakExceeded,
+                };
+                FluxLimiterDecision {
+                    allowed: false,
+                    retry_after_seconds,
+                    remaining_capacity: Some(0.0),
+                    reset_time_nanos: tats.0.max(tats.1),
+                    reason,
+                    ..sustained
+                }
+            }
+        }
+    }
+
+    // resolve a clock failure according to the failure policy
+    fn fail(&self, err: FluxLimiterError) -> Result<FluxLimiterDecision, FluxLimiterError> {
+        let allowed = match self.failure_policy {
+            FailurePolicy::Propagate => return Err(err),
+            FailurePolicy::FailOpen => true,
+            FailurePolicy::FailClosed => false,
+        };
+
+        // without a clock reading there is no TAT to report
+        Ok(FluxLimiterDecision {
+            allowed,
+            retry_after_seconds: (!allowed)
+                .then(|| self.sustained.increment_nanos as f64 / 1_000_000_000.0),
+            remaining_capacity: None,
+            reset_time_nanos: 0,
+            limit_per_second: self.sustained.rate_per_second,
+            burst_capacity: self.sustained.burst_capacity,
+            shedding: false,
+            debt: 0.0,
+            suggested_delay_seconds: None,
+            reason: DecisionReason::Fallback,
+        })
+    }
+}
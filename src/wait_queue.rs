@@ -0,0 +1,90 @@
+// src/wait_queue.rs
+
+//! Per-key FIFO queues for waiting acquires
+
+// dependencies
+use dashmap::DashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+// one fair mutex per key with waiters; tokio's mutex hands the lock over in
+// the order it was requested, so holding it is a place at the head of the line
+#[derive(Debug)]
+pub(crate) struct WaitQueues<T>
+where
+    T: Hash + Eq,
+{
+    queues: DashMap<T, Arc<Mutex<()>>>,
+}
+
+impl<T> Default for WaitQueues<T>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self {
+            queues: DashMap::new(),
+        }
+    }
+}
+
+impl<T> WaitQueues<T>
+where
+    T: Hash + Eq + Clone,
+{
+    // join the back of `key`'s queue, creating it if nobody is waiting yet
+    pub(crate) fn join(&self, key: T) -> Place<'_, T> {
+        let queue = match self.queues.get(&key) {
+            Some(queue) => Arc::clone(&queue),
+            None => Arc::clone(&self.queues.entry(key.clone()).or_default()),
+        };
+        Place {
+            queues: self,
+            key,
+            queue,
+            turn: None,
+        }
+    }
+}
+
+// a waiter's place in a key's queue; dropping it, turn or not, leaves the queue
+pub(crate) struct Place<'a, T>
+where
+    T: Hash + Eq + Clone,
+{
+    queues: &'a WaitQueues<T>,
+    key: T,
+    queue: Arc<Mutex<()>>,
+    turn: Option<OwnedMutexGuard<()>>,
+}
+
+impl<T> Place<'_, T>
+where
+    T: Hash + Eq + Clone,
+{
+    // the queue to wait on for this place's turn
+    pub(crate) fn queue(&self) -> Arc<Mutex<()>> {
+        Arc::clone(&self.queue)
+    }
+
+    // keep the turn until the place is dropped
+    pub(crate) fn hold(&mut self, turn: OwnedMutexGuard<()>) {
+        self.turn = Some(turn);
+    }
+}
+
+impl<T> Drop for Place<'_, T>
+where
+    T: Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        // hand the turn to the next waiter
+        self.turn = None;
+        // drop the queue once only the map and this place hold it; removal
+        // holds the shard lock, so nobody can join in between
+        self.queues
+            .queues
+            .remove_if(&self.key, |_, queue| Arc::strong_count(queue) == 2);
+    }
+}
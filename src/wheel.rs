@@ -0,0 +1,168 @@
+// src/wheel.rs
+
+//! Hierarchical timer wheel that schedules client entries for expiration
+
+// dependencies
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 4;
+
+// keys scheduled for expiration, shared by every clone of a limiter
+//
+// Expiring is opportunistic: the limiter calls `expire` after its checks, the
+// call costs one atomic load until the next tick is due, and a caller that
+// finds another thread already advancing the wheel skips instead of waiting.
+pub(crate) struct ExpiryWheel<K> {
+    tick_nanos: u64,
+    next_tick_nanos: AtomicU64,
+    wheel: Mutex<TimerWheel<K>>,
+}
+
+impl<K> ExpiryWheel<K> {
+    pub(crate) fn new(tick_nanos: u64) -> Self {
+        Self {
+            tick_nanos,
+            next_tick_nanos: AtomicU64::new(0),
+            wheel: Mutex::new(TimerWheel::new()),
+        }
+    }
+
+    // schedule `key` to be reported once `deadline_nanos` has passed
+    pub(crate) fn schedule(&self, key: K, deadline_nanos: u64) {
+        let deadline_tick = deadline_nanos.div_ceil(self.tick_nanos);
+        self.lock().insert(key, deadline_tick);
+    }
+
+    // schedule several keys while taking the lock once
+    pub(crate) fn schedule_all(&self, keys: impl IntoIterator<Item = (K, u64)>) {
+        let mut wheel = self.lock();
+        for (key, deadline_nanos) in keys {
+            wheel.insert(key, deadline_nanos.div_ceil(self.tick_nanos));
+        }
+    }
+
+    // advance to `now_nanos` and return the keys whose deadline has passed;
+    // empty when no tick is due yet or another thread is advancing the wheel
+    pub(crate) fn expire(&self, now_nanos: u64) -> Vec<K> {
+        if now_nanos < self.next_tick_nanos.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+        let Ok(mut wheel) = self.wheel.try_lock() else {
+            return Vec::new();
+        };
+        let due = wheel.advance(now_nanos / self.tick_nanos);
+        self.next_tick_nanos.store(
+            (wheel.current_tick + 1).saturating_mul(self.tick_nanos),
+            Ordering::Relaxed,
+        );
+        due
+    }
+
+    // a panic while holding the lock leaves the wheel consistent, so ignore poisoning
+    fn lock(&self) -> std::sync::MutexGuard<'_, TimerWheel<K>> {
+        self.wheel.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<K> fmt::Debug for ExpiryWheel<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExpiryWheel")
+            .field("tick_nanos", &self.tick_nanos)
+            .field("scheduled", &self.lock().len)
+            .finish()
+    }
+}
+
+// LEVELS wheels of SLOTS slots each; a slot on level k spans SLOTS^k ticks, so
+// a key sits on the coarsest level that still resolves its deadline and moves
+// down a level each time its slot comes round
+struct TimerWheel<K> {
+    current_tick: u64,
+    levels: Vec<Vec<Vec<(K, u64)>>>, // [level][slot] -> (key, deadline tick)
+    len: usize,
+}
+
+impl<K> TimerWheel<K> {
+    // the wheel starts at tick 0; the first advance jumps to the present
+    fn new() -> Self {
+        Self {
+            current_tick: 0,
+            levels: (0..LEVELS)
+                .map(|_| (0..SLOTS).map(|_| Vec::new()).collect())
+                .collect(),
+            len: 0,
+        }
+    }
+
+    // place a key in the slot that comes round next before its deadline
+    fn insert(&mut self, key: K, deadline_tick: u64) {
+        // deadlines already passed fire on the next tick
+        let deadline_tick = deadline_tick.max(self.current_tick + 1);
+        let delta = deadline_tick - self.current_tick;
+        // deadlines beyond the top level wait in it and are re-placed when their slot comes round
+        let level = ((delta.ilog2() / SLOT_BITS) as usize).min(LEVELS - 1);
+        let slot = (deadline_tick >> (SLOT_BITS * level as u32)) as usize & (SLOTS - 1);
+        self.levels[level][slot].push((key, deadline_tick));
+        self.len += 1;
+    }
+
+    // move the wheel to `target_tick`, returning every key that came due
+    fn advance(&mut self, target_tick: u64) -> Vec<K> {
+        let mut due = Vec::new();
+        if target_tick <= self.current_tick {
+            return due;
+        }
+
+        // turning the wheel tick by tick would cost more than re-placing every key
+        if target_tick - self.current_tick > (self.len + SLOTS) as u64 {
+            let scheduled: Vec<_> = self
+                .levels
+                .iter_mut()
+                .flatten()
+                .flat_map(std::mem::take)
+                .collect();
+            self.len = 0;
+            self.current_tick = target_tick;
+            for (key, deadline_tick) in scheduled {
+                if deadline_tick <= target_tick {
+                    due.push(key);
+                } else {
+                    self.insert(key, deadline_tick);
+                }
+            }
+            return due;
+        }
+
+        while self.current_tick < target_tick {
+            self.current_tick += 1;
+            let tick = self.current_tick;
+
+            // bring down every coarser slot that starts at this tick, coarsest first
+            for level in (1..LEVELS).rev() {
+                let span_bits = SLOT_BITS * level as u32;
+                if tick & ((1 << span_bits) - 1) != 0 {
+                    continue;
+                }
+                let slot = (tick >> span_bits) as usize & (SLOTS - 1);
+                let cascaded = std::mem::take(&mut self.levels[level][slot]);
+                self.len -= cascaded.len();
+                for (key, deadline_tick) in cascaded {
+                    if deadline_tick <= tick {
+                        due.push(key);
+                    } else {
+                        self.insert(key, deadline_tick);
+                    }
+                }
+            }
+
+            let fired = std::mem::take(&mut self.levels[0][tick as usize & (SLOTS - 1)]);
+            self.len -= fired.len();
+            due.extend(fired.into_iter().map(|(key, _)| key));
+        }
+        due
+    }
+}
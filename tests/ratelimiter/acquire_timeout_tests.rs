@@ -0,0 +1,80 @@
+// tests/ratelimiter/acquire_timeout_tests.rs
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        AcquireError, FailurePolicy, FluxLimiter, FluxLimiterConfig, FluxLimiterError, TokioClock,
+    };
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn limiter() -> FluxLimiter<&'static str, TokioClock> {
+        let config = FluxLimiterConfig::new(2.0, 0.0); // one slot every 500ms
+        FluxLimiter::with_config(config, TokioClock::new()).unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_the_next_slot() {
+        let limiter = limiter();
+        limiter.acquire("client1").unwrap().commit();
+
+        let start = Instant::now();
+        let permit = limiter
+            .acquire_timeout("client1", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(*permit.client_id(), "client1");
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_at_once_when_the_slot_is_past_the_deadline() {
+        let limiter = limiter();
+        limiter.acquire("client1").unwrap().commit();
+
+        let start = Instant::now();
+        let err = limiter
+            .acquire_timeout("client1", Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        match err {
+            AcquireError::Elapsed(meta) => assert_eq!(meta.retry_after_seconds, 0.5),
+            other => panic!("expected Elapsed, got {other:?}"),
+        }
+        // no time was spent sleeping towards an unreachable slot
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_wait_only_admits_conforming_requests() {
+        let limiter = limiter();
+
+        assert!(
+            limiter
+                .acquire_timeout("client1", Duration::ZERO)
+                .await
+                .is_ok()
+        );
+        assert!(matches!(
+            limiter.acquire_timeout("client1", Duration::ZERO).await,
+            Err(AcquireError::Elapsed(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reports_clock_failures() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::Propagate);
+        let limiter = FluxLimiter::<&str, _>::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter
+                .acquire_timeout("client1", Duration::from_secs(1))
+                .await,
+            Err(AcquireError::Failed(FluxLimiterError::ClockError(_)))
+        ));
+    }
+}
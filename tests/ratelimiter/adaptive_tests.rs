@@ -0,0 +1,131 @@
+// tests/ratelimiter/adaptive_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{AdaptiveLimiter, AimdConfig, FluxLimiterConfig, FluxLimiterError, Outcome};
+
+    fn limiter(clock: &TestClock) -> AdaptiveLimiter<&'static str, TestClock> {
+        AdaptiveLimiter::new(
+            FluxLimiterConfig::new(10.0, 0.0),
+            AimdConfig::new(2.0, 12.0)
+                .additive_increase(1.0)
+                .multiplicative_decrease(0.5),
+            clock.clone(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn success_increases_additively_up_to_max() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        assert_eq!(limiter.report_outcome(Outcome::Success), 11.0);
+        assert_eq!(limiter.report_outcome(Outcome::Success), 12.0);
+        assert_eq!(limiter.report_outcome(Outcome::Success), 12.0);
+        assert!((limiter.rate() - 12.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn overload_decreases_multiplicatively_down_to_min() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        assert_eq!(limiter.report_outcome(Outcome::Overload), 5.0);
+        assert_eq!(limiter.report_outcome(Outcome::Overload), 2.5);
+        assert_eq!(limiter.report_outcome(Outcome::Overload), 2.0);
+        assert_eq!(limiter.current_rate(), 2.0);
+        assert!((limiter.rate() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn adjusted_rate_governs_checks_and_keeps_state() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        assert!(limiter.check_request("client").unwrap().allowed);
+
+        // 10 req/s -> 5 req/s: the next slot moves from 0.1s to 0.2s out
+        limiter.report_outcome(Outcome::Overload);
+        clock.advance(0.1);
+        assert!(limiter.check_request("client").unwrap().allowed);
+        clock.advance(0.1);
+        assert!(!limiter.check_request("client").unwrap().allowed);
+        clock.advance(0.1);
+        assert!(limiter.check_request("client").unwrap().allowed);
+
+        let decision = limiter.check_request("client").unwrap();
+        assert_eq!(decision.limit_per_second, 5.0);
+    }
+
+    #[test]
+    fn burst_stays_constant_across_adjustments() {
+        let clock = TestClock::new(0.0);
+        let limiter = AdaptiveLimiter::<&str, _>::new(
+            FluxLimiterConfig::new(10.0, 4.0),
+            AimdConfig::new(1.0, 100.0),
+            clock,
+        )
+        .unwrap();
+
+        limiter.report_outcome(Outcome::Overload);
+        assert!((limiter.burst() - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn starting_rate_is_clamped_to_bounds() {
+        let clock = TestClock::new(0.0);
+        let limiter = AdaptiveLimiter::<&str, _>::new(
+            FluxLimiterConfig::new(100.0, 0.0),
+            AimdConfig::new(1.0, 20.0),
+            clock,
+        )
+        .unwrap();
+
+        assert_eq!(limiter.current_rate(), 20.0);
+    }
+
+    #[test]
+    fn invalid_aimd_configs_are_rejected() {
+        let invalid = [
+            AimdConfig::new(10.0, 5.0),
+            AimdConfig::new(1.0, 10.0).additive_increase(-1.0),
+            AimdConfig::new(1.0, 10.0).multiplicative_decrease(1.0),
+            AimdConfig::new(1.0, 10.0).multiplicative_decrease(0.0),
+        ];
+        for aimd in invalid {
+            assert!(matches!(
+                aimd.validate(),
+                Err(FluxLimiterError::InvalidAdaptiveConfig)
+            ));
+        }
+
+        assert!(matches!(
+            AimdConfig::new(0.0, 10.0).validate(),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+    }
+
+    #[test]
+    fn clones_of_the_inner_limiter_follow_rate_changes() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let handle = (*limiter).clone();
+
+        limiter.report_outcome(Outcome::Overload);
+        assert!((handle.rate() - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn effective_config_follows_the_adjusted_rate() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        limiter.report_outcome(Outcome::Overload);
+        let config = limiter.effective_config();
+        assert_eq!(config.rate_per_second(), 5.0);
+        assert_eq!(config, FluxLimiterConfig::new(5.0, 0.0));
+    }
+}
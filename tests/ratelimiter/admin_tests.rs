@@ -0,0 +1,229 @@
+// tests/ratelimiter/admin_tests.rs
+
+#[cfg(all(test, feature = "admin"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use flux_limiter::{
+        ACTOR_HEADER, AuditAction, AuditEvent, FluxLimiter, FluxLimiterConfig, admin_router,
+    };
+    use serde_json::{Value, json};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    fn limiter(clock: &TestClock) -> Arc<FluxLimiter<String, TestClock>> {
+        // 1 req/sec, burst of 1
+        let config = FluxLimiterConfig::new(1.0, 1.0).stats(true);
+        Arc::new(FluxLimiter::with_config(config, clock.clone()).unwrap())
+    }
+
+    // send one request to `router`, returning the status and JSON body
+    async fn send(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = match bytes.is_empty() {
+            true => Value::Null,
+            false => serde_json::from_slice(&bytes).unwrap(),
+        };
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn clients_can_be_inspected_penalized_and_reset() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let router = admin_router(Arc::clone(&limiter));
+
+        let (status, body) = send(&router, Method::GET, "/clients/client1", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "client is not tracked");
+
+        limiter.check_request("client1".to_string()).unwrap();
+        let (status, body) = send(&router, Method::GET, "/clients/client1", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tat_nanos"], 1_000_000_000u64);
+
+        let (status, body) = send(
+            &router,
+            Method::POST,
+            "/clients/client1/penalize",
+            Some(json!({ "cost": 5.0 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tat_nanos"], 6_000_000_000u64);
+        assert_eq!(body["time_until_conforming_nanos"], 5_000_000_000u64);
+        assert!(
+            !limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+
+        let (status, _) = send(&router, Method::DELETE, "/clients/client1", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(
+            limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+
+        let (status, _) = send(
+            &router,
+            Method::POST,
+            "/clients/client1/penalize",
+            Some(json!({ "cost": -1.0 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn offenders_are_listed_worst_first() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let router = admin_router(Arc::clone(&limiter));
+
+        limiter.check_request("light".to_string()).unwrap();
+        limiter.penalize("heavy".to_string(), 10.0).unwrap();
+        limiter.penalize("medium".to_string(), 3.0).unwrap();
+        // a fully recovered client is not an offender
+        limiter.penalize("idle".to_string(), 0.0).unwrap();
+
+        let (status, body) = send(&router, Method::GET, "/offenders?limit=2", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|offender| offender["client_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["heavy", "medium"]);
+
+        let (_, body) = send(&router, Method::GET, "/offenders", None).await;
+        assert_eq!(body.as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn the_rate_can_be_changed_live() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let router = admin_router(Arc::clone(&limiter));
+
+        let (status, body) = send(&router, Method::GET, "/config", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "rate": 1.0, "burst": 1.0 }));
+
+        let (status, body) = send(
+            &router,
+            Method::PUT,
+            "/config",
+            Some(json!({ "rate": 4.0 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["rate"], 4.0);
+        assert_eq!(limiter.rate(), 4.0);
+
+        for update in [
+            json!({ "rate": -1.0 }),
+            json!({ "rate": 2.0, "burst": 5.0 }),
+        ] {
+            let (status, _) = send(&router, Method::PUT, "/config", Some(update)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(limiter.rate(), 4.0);
+    }
+
+    #[tokio::test]
+    async fn cleanup_and_stats_are_exposed() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let router = admin_router(Arc::clone(&limiter));
+
+        limiter.check_request("client1".to_string()).unwrap();
+        clock.advance(10.0);
+
+        let (status, body) = send(
+            &router,
+            Method::POST,
+            "/cleanup?max_stale_seconds=5&dry_run=true",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["removed"], 1);
+        assert_eq!(limiter.stats().current_clients, 1);
+
+        let (_, body) = send(&router, Method::POST, "/cleanup?max_stale_seconds=5", None).await;
+        assert_eq!(body["removed"], 1);
+
+        let (status, body) = send(&router, Method::GET, "/stats", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_checks"], 1);
+        assert_eq!(body["evictions"], 1);
+        assert_eq!(body["current_clients"], 0);
+
+        clock.fail_next_call();
+        let (status, _) = send(&router, Method::GET, "/offenders", None).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn admin_calls_are_audited_under_the_actor_header() {
+        let clock = TestClock::new(0.0);
+        let trail = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&trail);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), clock.clone())
+            .unwrap()
+            .with_audit_sink(move |event: AuditEvent<String>| sink.lock().unwrap().push(event));
+        let router = admin_router(Arc::new(limiter));
+
+        let request = Request::builder()
+            .method(Method::PUT)
+            .uri("/config")
+            .header("content-type", "application/json")
+            .header(ACTOR_HEADER, "alice")
+            .body(Body::from(json!({ "rate": 2.0 }).to_string()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        send(
+            &router,
+            Method::POST,
+            "/clients/bot/penalize",
+            Some(json!({ "cost": 1.0 })),
+        )
+        .await;
+
+        let trail = trail.lock().unwrap();
+        assert_eq!(trail[0].actor.as_deref(), Some("alice"));
+        assert_eq!(
+            trail[0].action,
+            AuditAction::SetRate {
+                previous: 1.0,
+                rate: 2.0
+            }
+        );
+        assert_eq!(trail[1].actor, None);
+    }
+}
@@ -0,0 +1,69 @@
+// tests/ratelimiter/anonymize_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{AnonymizedKey, FluxLimiter, FluxLimiterConfig, KeyAnonymizer};
+
+    fn limiter(clock: &TestClock) -> FluxLimiter<AnonymizedKey, TestClock> {
+        FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap()
+    }
+
+    #[test]
+    fn anonymized_keys_limit_each_client_exactly() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let anonymizer = KeyAnonymizer::new();
+
+        assert!(
+            limiter
+                .check_request(anonymizer.key("alice@example.com"))
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            !limiter
+                .check_request(anonymizer.key("alice@example.com"))
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            limiter
+                .check_request(anonymizer.key("bob@example.com"))
+                .unwrap()
+                .allowed
+        );
+
+        // clones share the secret, owned and borrowed identifiers agree
+        let clone = anonymizer.clone();
+        assert_eq!(
+            clone.key(&String::from("alice@example.com")),
+            anonymizer.key("alice@example.com")
+        );
+    }
+
+    #[test]
+    fn separate_anonymizers_use_separate_secrets() {
+        let first = KeyAnonymizer::new();
+        let second = KeyAnonymizer::new();
+        assert_ne!(
+            first.key("alice@example.com"),
+            second.key("alice@example.com")
+        );
+    }
+
+    #[test]
+    fn raw_identifiers_never_reach_state_or_snapshots() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let anonymizer = KeyAnonymizer::new();
+        let key = anonymizer.key("203.0.113.7");
+        limiter.check_request(key).unwrap();
+
+        let snapshot = format!("{:?}", limiter.export_state());
+        assert!(!snapshot.contains("203.0.113.7"));
+        assert!(snapshot.contains(&key.to_string()));
+        assert_eq!(key.to_string().len(), 32);
+    }
+}
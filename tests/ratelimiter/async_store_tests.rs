@@ -0,0 +1,89 @@
+// tests/ratelimiter/async_store_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        AsyncStateStore, FailurePolicy, FluxLimiterConfig, FluxLimiterError, StoreBackedLimiter,
+        StoreError,
+    };
+    use futures::executor::block_on;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // in-process stand-in for a network store
+    #[derive(Default)]
+    struct AsyncMapStore {
+        tats: Mutex<HashMap<String, u64>>,
+        fail: AtomicBool,
+    }
+
+    impl AsyncStateStore<String> for AsyncMapStore {
+        async fn get(&self, key: &String) -> Result<Option<u64>, StoreError> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(StoreError::new("timed out"));
+            }
+            Ok(self.tats.lock().unwrap().get(key).copied())
+        }
+
+        async fn compare_and_swap(
+            &self,
+            key: &String,
+            expected: Option<u64>,
+            new: u64,
+        ) -> Result<bool, StoreError> {
+            let mut tats = self.tats.lock().unwrap();
+            if tats.get(key).copied() != expected {
+                return Ok(false);
+            }
+            tats.insert(key.clone(), new);
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn async_checks_apply_gcra() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 1 req/sec, burst of 1
+        let limiter =
+            StoreBackedLimiter::with_config(config, clock.clone(), AsyncMapStore::default())
+                .unwrap();
+        let client = "client1".to_string();
+
+        block_on(async {
+            assert!(limiter.check_request_async(&client).await.unwrap().allowed);
+            assert!(limiter.check_request_async(&client).await.unwrap().allowed);
+            assert!(!limiter.check_request_async(&client).await.unwrap().allowed);
+
+            clock.advance(1.0);
+            assert!(limiter.check_request_async(&client).await.unwrap().allowed);
+        });
+    }
+
+    #[test]
+    fn async_store_errors_follow_the_failure_policy() {
+        let clock = TestClock::new(0.0);
+        let client = "client1".to_string();
+        let store = AsyncMapStore::default();
+        store.fail.store(true, Ordering::Relaxed);
+
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = StoreBackedLimiter::with_config(config, clock.clone(), store).unwrap();
+        assert!(matches!(
+            block_on(limiter.check_request_async(&client)),
+            Err(FluxLimiterError::StoreError(_))
+        ));
+
+        let store = AsyncMapStore::default();
+        store.fail.store(true, Ordering::Relaxed);
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailOpen);
+        let limiter = StoreBackedLimiter::with_config(config, clock, store).unwrap();
+        assert!(
+            block_on(limiter.check_request_async(&client))
+                .unwrap()
+                .allowed
+        );
+    }
+}
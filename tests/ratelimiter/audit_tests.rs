@@ -0,0 +1,116 @@
+// tests/ratelimiter/audit_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{AuditAction, AuditEvent, FluxLimiter, FluxLimiterConfig};
+    use std::sync::{Arc, Mutex};
+
+    type Trail = Arc<Mutex<Vec<AuditEvent<String>>>>;
+
+    // a limiter whose audit events land in the returned trail
+    fn audited(clock: &TestClock) -> (FluxLimiter<String, TestClock>, Trail) {
+        let trail: Trail = Arc::default();
+        let sink = Arc::clone(&trail);
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone())
+            .unwrap()
+            .with_audit_sink(move |event| sink.lock().unwrap().push(event));
+        (limiter, trail)
+    }
+
+    #[test]
+    fn operator_actions_are_recorded_with_the_actor() {
+        let clock = TestClock::new(5.0);
+        let (limiter, trail) = audited(&clock);
+        let alice = limiter.operator("alice");
+        assert_eq!(alice.actor(), "alice");
+
+        alice.penalize("bot".to_string(), 3.0).unwrap();
+        clock.advance(1.0);
+        assert!(alice.reset_client("bot"));
+        alice.set_rate(2.0).unwrap();
+        assert_eq!(limiter.rate(), 2.0);
+
+        let trail = trail.lock().unwrap();
+        let events: Vec<_> = trail
+            .iter()
+            .map(|event| {
+                (
+                    event.actor.as_deref(),
+                    event.action.clone(),
+                    event.timestamp_nanos,
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            [
+                (
+                    Some("alice"),
+                    AuditAction::Penalize {
+                        client_id: "bot".to_string(),
+                        cost: 3.0
+                    },
+                    5_000_000_000
+                ),
+                (
+                    Some("alice"),
+                    AuditAction::ResetClient {
+                        client_id: "bot".to_string()
+                    },
+                    6_000_000_000
+                ),
+                (
+                    Some("alice"),
+                    AuditAction::SetRate {
+                        previous: 1.0,
+                        rate: 2.0
+                    },
+                    6_000_000_000
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn direct_calls_are_recorded_without_an_actor() {
+        let clock = TestClock::new(0.0);
+        let (limiter, trail) = audited(&clock);
+
+        limiter.penalize("bot".to_string(), 1.0).unwrap();
+        assert!(limiter.reset_client("bot"));
+
+        let trail = trail.lock().unwrap();
+        assert_eq!(trail.len(), 2);
+        assert!(trail.iter().all(|event| event.actor.is_none()));
+    }
+
+    #[test]
+    fn actions_that_change_nothing_are_not_recorded() {
+        let clock = TestClock::new(0.0);
+        let (limiter, trail) = audited(&clock);
+        let alice = limiter.operator("alice");
+
+        assert!(!alice.reset_client("unknown"));
+        assert!(alice.penalize("bot".to_string(), -1.0).is_err());
+        assert!(alice.set_rate(0.0).is_err());
+        assert_eq!(limiter.rate(), 1.0);
+
+        // checks are not administrative actions
+        limiter.check_request("bot".to_string()).unwrap();
+        assert!(trail.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_clock_failure_does_not_block_the_audit() {
+        let clock = TestClock::new(0.0);
+        let (limiter, trail) = audited(&clock);
+        limiter.check_request("bot".to_string()).unwrap();
+
+        clock.fail_next_call();
+        assert!(limiter.operator("alice").reset_client("bot"));
+        assert_eq!(trail.lock().unwrap()[0].timestamp_nanos, 0);
+    }
+}
@@ -0,0 +1,145 @@
+// tests/ratelimiter/batch_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+
+    #[test]
+    fn batch_returns_decisions_in_input_order() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let keys: Vec<String> = (0..32).map(|i| format!("client{}", i)).collect();
+        let decisions = limiter.check_requests(keys.clone()).unwrap();
+
+        assert_eq!(decisions.len(), keys.len());
+        for ((key, decision), expected) in decisions.iter().zip(&keys) {
+            assert_eq!(key, expected);
+            assert!(decision.allowed);
+        }
+        assert_eq!(limiter.client_state.len(), 32);
+    }
+
+    #[test]
+    fn batch_counts_duplicate_keys_in_order() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let decisions = limiter
+            .check_requests(["client1", "client2", "client1", "client1"])
+            .unwrap();
+        let allowed: Vec<bool> = decisions.iter().map(|(_, d)| d.allowed).collect();
+        assert_eq!(allowed, vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn batch_shares_state_with_single_checks() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        let decisions = limiter.check_requests(["client1", "client2"]).unwrap();
+        assert!(!decisions[0].1.allowed);
+        assert!(decisions[1].1.allowed);
+    }
+
+    #[test]
+    fn batch_propagates_clock_error_without_side_effects() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let result = limiter.check_requests(["client1", "client2"]);
+        assert!(matches!(result, Err(FluxLimiterError::ClockError(_))));
+        assert_eq!(limiter.client_state.len(), 0);
+    }
+
+    // All-or-nothing multi-key checks
+    #[test]
+    fn check_all_consumes_every_key_when_all_conform() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let outcome = limiter.check_all(&["user", "endpoint"]).unwrap();
+        assert!(outcome.allowed);
+        assert!(outcome.denied_by.is_none());
+        assert_eq!(outcome.decisions.len(), 2);
+        assert!(outcome.decisions.iter().all(|(_, d)| d.allowed));
+
+        // both quotas were consumed
+        assert!(!limiter.check_request("user").unwrap().allowed);
+        assert!(!limiter.check_request("endpoint").unwrap().allowed);
+    }
+
+    #[test]
+    fn check_all_consumes_nothing_when_one_key_denies() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        // exhaust the endpoint quota and remember the user's state
+        assert!(limiter.check_request("endpoint").unwrap().allowed);
+        assert!(limiter.check_request("user").unwrap().allowed);
+        clock.advance(1.0);
+
+        let outcome = limiter.check_all(&["user", "endpoint", "other"]).unwrap();
+        assert!(outcome.allowed); // both recovered after 1s
+
+        let outcome = limiter.check_all(&["fresh", "endpoint"]).unwrap();
+        assert!(!outcome.allowed);
+        assert_eq!(outcome.denied_by, Some("endpoint"));
+        assert!(!limiter.client_state.contains_key("fresh"));
+
+        // the conforming key kept its full allowance
+        assert!(limiter.check_request("fresh").unwrap().allowed);
+    }
+
+    #[test]
+    fn check_all_counts_repeated_keys() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let outcome = limiter.check_all(&["user", "user", "user"]).unwrap();
+        assert!(!outcome.allowed);
+        assert_eq!(outcome.denied_by, Some("user"));
+        assert!(!limiter.client_state.contains_key("user"));
+
+        let outcome = limiter.check_all(&["user", "user"]).unwrap();
+        assert!(outcome.allowed);
+        assert!(!limiter.check_request("user").unwrap().allowed);
+    }
+
+    #[test]
+    fn check_all_is_atomic_under_contention() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 4.0); // 5 immediate requests
+        let limiter = std::sync::Arc::new(FluxLimiter::with_config(config, clock).unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    (0..20)
+                        .filter(|_| limiter.check_all(&["a", "b"]).unwrap().allowed)
+                        .count()
+                })
+            })
+            .collect();
+        let allowed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(allowed, 5);
+
+        // both keys were consumed in lock-step
+        assert_eq!(
+            limiter.client_state.get("a").unwrap().tat_nanos(),
+            limiter.client_state.get("b").unwrap().tat_nanos()
+        );
+    }
+}
@@ -0,0 +1,156 @@
+// tests/ratelimiter/cached_store_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        CachedStore, FluxLimiterConfig, StateStore, StoreBackedLimiter, StoreError,
+    };
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // remote store counting round trips; clones share the same map
+    #[derive(Clone, Default)]
+    struct CountingStore {
+        tats: Arc<Mutex<HashMap<String, u64>>>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl CountingStore {
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::Relaxed)
+        }
+
+        fn tat(&self, key: &str) -> Option<u64> {
+            self.tats.lock().unwrap().get(key).copied()
+        }
+    }
+
+    impl StateStore<String> for CountingStore {
+        fn get(&self, key: &String) -> Result<Option<u64>, StoreError> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(self.tats.lock().unwrap().get(key).copied())
+        }
+
+        fn compare_and_swap(
+            &self,
+            key: &String,
+            expected: Option<u64>,
+            new: u64,
+        ) -> Result<bool, StoreError> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let mut tats = self.tats.lock().unwrap();
+            if tats.get(key).copied() != expected {
+                return Ok(false);
+            }
+            tats.insert(key.clone(), new);
+            Ok(true)
+        }
+    }
+
+    fn limiter(
+        remote: &CountingStore,
+        clock: &TestClock,
+        lease: Duration,
+    ) -> StoreBackedLimiter<String, CachedStore<String, CountingStore, TestClock>, TestClock> {
+        // 10 req/sec, burst of 4
+        let config = FluxLimiterConfig::new(10.0, 4.0);
+        let store = CachedStore::new(remote.clone(), clock.clone(), lease);
+        StoreBackedLimiter::with_config(config, clock.clone(), store).unwrap()
+    }
+
+    #[test]
+    fn checks_within_a_lease_skip_the_remote() {
+        let clock = TestClock::new(0.0);
+        let remote = CountingStore::default();
+        let limiter = limiter(&remote, &clock, Duration::from_secs(1));
+        let client = "client1".to_string();
+
+        for _ in 0..5 {
+            assert!(limiter.check_request(&client).unwrap().allowed);
+        }
+        assert!(!limiter.check_request(&client).unwrap().allowed);
+
+        // one read to take the lease, nothing written yet
+        assert_eq!(remote.calls(), 1);
+        assert_eq!(remote.tat("client1"), None);
+        assert_eq!(limiter.store().len(), 1);
+    }
+
+    #[test]
+    fn expired_lease_adds_the_tally_to_the_remote() {
+        let clock = TestClock::new(0.0);
+        let remote = CountingStore::default();
+        let limiter = limiter(&remote, &clock, Duration::from_millis(100));
+        let client = "client1".to_string();
+
+        for _ in 0..3 {
+            assert!(limiter.check_request(&client).unwrap().allowed);
+        }
+        clock.advance(0.1);
+        assert!(limiter.check_request(&client).unwrap().allowed);
+
+        // the three requests from t=0 were pushed as 300ms of schedule
+        assert_eq!(remote.tat("client1"), Some(300_000_000));
+        // read to lease, then read and swap to reconcile
+        assert_eq!(remote.calls(), 3);
+    }
+
+    #[test]
+    fn instances_sharing_a_remote_see_each_others_consumption() {
+        let clock = TestClock::new(0.0);
+        let remote = CountingStore::default();
+        let first = limiter(&remote, &clock, Duration::from_millis(100));
+        let second = limiter(&remote, &clock, Duration::from_millis(100));
+        let client = "client1".to_string();
+
+        for _ in 0..5 {
+            assert!(first.check_request(&client).unwrap().allowed);
+        }
+        first.store().flush().unwrap();
+
+        clock.advance(0.1);
+        // one request's worth has been restored; the burst went to the first
+        assert!(second.check_request(&client).unwrap().allowed);
+        assert!(!second.check_request(&client).unwrap().allowed);
+    }
+
+    #[test]
+    fn flush_drops_expired_leases_after_pushing_their_tally() {
+        let clock = TestClock::new(0.0);
+        let remote = CountingStore::default();
+        let limiter = limiter(&remote, &clock, Duration::from_millis(100));
+
+        assert!(limiter.check_request(&"a".to_string()).unwrap().allowed);
+        clock.advance(0.05);
+        assert!(limiter.check_request(&"b".to_string()).unwrap().allowed);
+
+        clock.advance(0.06);
+        limiter.store().flush().unwrap();
+
+        // "a" expired and went; "b" is still leased but its tally was pushed
+        assert_eq!(limiter.store().len(), 1);
+        assert_eq!(remote.tat("a"), Some(100_000_000));
+        assert_eq!(remote.tat("b"), Some(150_000_000));
+    }
+
+    #[test]
+    fn dropping_the_store_flushes_it() {
+        let clock = TestClock::new(0.0);
+        let remote = CountingStore::default();
+        let limiter = limiter(&remote, &clock, Duration::from_secs(1));
+
+        assert!(
+            limiter
+                .check_request(&"client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        drop(limiter);
+
+        assert_eq!(remote.tat("client1"), Some(100_000_000));
+    }
+}
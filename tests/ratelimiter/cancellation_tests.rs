@@ -0,0 +1,92 @@
+// tests/ratelimiter/cancellation_tests.rs
+
+#[cfg(all(test, feature = "cancellation"))]
+mod tests {
+
+    use flux_limiter::{AcquireError, FluxLimiter, FluxLimiterConfig, TokioClock};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tokio_util::sync::CancellationToken;
+
+    fn limiter() -> Arc<FluxLimiter<&'static str, TokioClock>> {
+        let config = FluxLimiterConfig::new(1.0, 0.0); // one slot per second
+        Arc::new(FluxLimiter::with_config(config, TokioClock::new()).unwrap())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelling_releases_every_waiter_promptly() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+        let shutdown = CancellationToken::new();
+
+        let waiters: Vec<_> = (0..100)
+            .map(|_| {
+                let limiter = Arc::clone(&limiter);
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move {
+                    limiter
+                        .acquire_cancellable("queue", Duration::from_secs(3600), &shutdown)
+                        .await
+                        .map(|permit| permit.commit())
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let cancelled_at = Instant::now();
+        shutdown.cancel();
+        for waiter in waiters {
+            assert!(matches!(
+                waiter.await.unwrap(),
+                Err(AcquireError::Cancelled)
+            ));
+        }
+        assert_eq!(cancelled_at.elapsed(), Duration::ZERO);
+
+        // none of the waiters held capacity: the next slot is still one second after the first
+        tokio::time::sleep(Duration::from_millis(900)).await;
+        assert!(limiter.acquire("queue").is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelled_token_consumes_nothing() {
+        let limiter = limiter();
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+
+        let result = limiter
+            .acquire_cancellable("queue", Duration::from_secs(1), &shutdown)
+            .await;
+        assert!(matches!(result, Err(AcquireError::Cancelled)));
+        assert!(limiter.acquire("queue").is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn uncancelled_waiter_is_admitted() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+        let shutdown = CancellationToken::new();
+
+        let permit = limiter
+            .acquire_cancellable("queue", Duration::from_secs(2), &shutdown)
+            .await;
+        assert!(permit.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropped_wait_leaks_no_capacity() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+
+        let wait = limiter.acquire_timeout("queue", Duration::from_secs(5));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(500), wait)
+                .await
+                .is_err()
+        );
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(limiter.acquire("queue").is_ok());
+    }
+}
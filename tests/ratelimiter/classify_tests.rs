@@ -0,0 +1,125 @@
+// tests/ratelimiter/classify_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{Classification, DecisionReason, FluxLimiter, FluxLimiterConfig};
+
+    // internal clients are exempt, anonymous ones get half the rate
+    fn classify(client_id: &str) -> Classification {
+        match client_id.split_once(':') {
+            Some(("internal", _)) => Classification::Bypass,
+            Some(("anon", _)) => Classification::Strict(0.5),
+            _ => Classification::Standard,
+        }
+    }
+
+    // 2 req/sec, burst of 1
+    fn limiter(clock: &TestClock) -> FluxLimiter<String, TestClock> {
+        let config = FluxLimiterConfig::new(2.0, 1.0).stats(true);
+        FluxLimiter::with_config(config, clock.clone())
+            .unwrap()
+            .with_classifier(|client_id: &String| classify(client_id))
+    }
+
+    #[test]
+    fn bypassed_requests_are_allowed_without_state() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        for _ in 0..10 {
+            let decision = limiter.check_request_ref("internal:billing").unwrap();
+            assert!(decision.allowed);
+            assert_eq!(decision.reason, DecisionReason::Exempt);
+        }
+        assert!(limiter.client_state.is_empty());
+        assert_eq!(limiter.stats().allowed, 10);
+
+        // exemption also holds while draining
+        limiter.set_drain(true);
+        assert!(
+            limiter
+                .check_request("internal:ops".to_string())
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn strict_requests_get_a_share_of_the_rate() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        // standard clients are spaced 0.5s apart, anonymous ones 1s
+        let anonymous = limiter.check_request("anon:1".to_string()).unwrap();
+        assert_eq!(anonymous.limit_per_second, 1.0);
+        assert!(!anonymous.shedding);
+        assert_eq!(
+            limiter
+                .client_snapshot("anon:1")
+                .unwrap()
+                .unwrap()
+                .tat_nanos,
+            1_000_000_000
+        );
+
+        let standard = limiter.check_request("user:1".to_string()).unwrap();
+        assert_eq!(standard.limit_per_second, 2.0);
+        assert_eq!(
+            limiter
+                .client_snapshot("user:1")
+                .unwrap()
+                .unwrap()
+                .tat_nanos,
+            500_000_000
+        );
+
+        // a strict class stacks with load shedding
+        let shed = limiter
+            .clone()
+            .with_pressure_provider(|| 0.5)
+            .check_request("anon:2".to_string())
+            .unwrap();
+        assert_eq!(shed.limit_per_second, 0.5);
+        assert!(shed.shedding);
+    }
+
+    #[test]
+    fn multipliers_are_clamped() {
+        assert_eq!(Classification::Strict(2.0).rate_multiplier(), Some(1.0));
+        assert_eq!(Classification::Strict(-1.0).rate_multiplier(), Some(0.0));
+        assert_eq!(
+            Classification::Strict(f64::NAN).rate_multiplier(),
+            Some(1.0)
+        );
+
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(2.0, 1.0), clock)
+            .unwrap()
+            .with_classifier(|_: &String| Classification::Strict(0.0));
+        let denied = limiter.check_request("anyone".to_string()).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.reason, DecisionReason::BurstExhausted);
+        assert_eq!(denied.retry_after_seconds, Some(0.5));
+    }
+
+    #[test]
+    fn bypassed_keys_do_not_block_all_or_nothing_checks() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        let keys = ["internal:svc".to_string(), "user:1".to_string()];
+        assert!(limiter.check_all(&keys).unwrap().allowed);
+        assert!(limiter.check_all(&keys).unwrap().allowed);
+
+        let outcome = limiter.check_all(&keys).unwrap();
+        assert!(!outcome.allowed);
+        assert_eq!(outcome.denied_by.as_deref(), Some("user:1"));
+        assert_eq!(outcome.decisions[0].1.reason, DecisionReason::Exempt);
+        assert!(!limiter.client_state.contains_key("internal:svc"));
+
+        let stats = limiter.stats();
+        assert_eq!((stats.allowed, stats.denied), (5, 1));
+    }
+}
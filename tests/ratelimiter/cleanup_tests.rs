@@ -4,7 +4,7 @@
 mod tests {
 
     use crate::fixtures::test_clock::TestClock;
-    use flux_limiter::{FluxLimiter, FluxLimiterConfig};
+    use flux_limiter::{CleanupReport, FluxLimiter, FluxLimiterConfig};
 
     #[test]
     fn cleanup_removes_stale_clients() {
@@ -95,4 +95,133 @@ mod tests {
 
         assert_eq!(limiter.client_state.len(), initial_count);
     }
+
+    #[test]
+    fn cleanup_reports_scanned_removed_and_remaining() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1".to_string()).unwrap(); // TAT = t=1
+        limiter.check_request("client2".to_string()).unwrap(); // TAT = t=1
+        clock.set_time(10.0);
+        limiter.check_request("client3".to_string()).unwrap(); // TAT = t=11
+
+        // Remove clients stale for more than 5 seconds
+        let report = limiter.cleanup_stale_clients(5_000_000_000).unwrap();
+
+        assert_eq!(
+            report,
+            CleanupReport {
+                scanned: 3,
+                removed: 2,
+                remaining: 1,
+            }
+        );
+        assert_eq!(limiter.client_state.len(), 1);
+        assert_eq!(limiter.stats().evictions, 2);
+    }
+
+    #[test]
+    fn cleanup_dry_run_counts_without_removing() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1".to_string()).unwrap(); // TAT = t=1
+        clock.set_time(10.0);
+        limiter.check_request("client2".to_string()).unwrap(); // TAT = t=11
+
+        let dry_run = limiter
+            .cleanup_stale_clients_dry_run(5_000_000_000)
+            .unwrap();
+        assert_eq!(
+            (dry_run.scanned, dry_run.removed, dry_run.remaining),
+            (2, 1, 1)
+        );
+        assert_eq!(limiter.client_state.len(), 2);
+        assert_eq!(limiter.stats().evictions, 0);
+
+        // The real cleanup removes exactly what the dry run predicted
+        assert_eq!(
+            limiter.cleanup_stale_clients(5_000_000_000).unwrap(),
+            dry_run
+        );
+    }
+
+    #[test]
+    fn cleanup_dry_run_propagates_clock_errors() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(limiter.cleanup_stale_clients_dry_run(1000).is_err());
+    }
+
+    #[test]
+    fn bounded_cleanup_removes_stale_clients_over_several_calls() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for client in 0..10u64 {
+            limiter.check_request(client).unwrap();
+        }
+        clock.set_time(10.0);
+
+        let mut removed = 0;
+        for _ in 0..10 {
+            let report = limiter
+                .cleanup_stale_clients_bounded(1_000_000_000, 3)
+                .unwrap();
+            assert!(report.scanned <= 3);
+            // only the examined entries count as remaining
+            assert_eq!(report.remaining, report.scanned - report.removed);
+            removed += report.removed;
+        }
+
+        assert_eq!(removed, 10);
+        assert_eq!(limiter.client_state.len(), 0);
+        assert_eq!(limiter.stats().evictions, 10);
+    }
+
+    #[test]
+    fn bounded_cleanup_resumes_where_the_last_call_stopped() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).shards(4);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for client in 0..10u64 {
+            limiter.check_request(client).unwrap();
+        }
+
+        // Nothing is stale, so one full cycle scans every client exactly once
+        let clone = limiter.clone();
+        let mut scanned = 0;
+        for call in 0..5 {
+            let limiter = if call % 2 == 0 { &limiter } else { &clone };
+            scanned += limiter
+                .cleanup_stale_clients_bounded(3_600_000_000_000, 2)
+                .unwrap()
+                .scanned;
+        }
+
+        assert_eq!(scanned, 10);
+        assert_eq!(limiter.client_state.len(), 10);
+    }
+
+    #[test]
+    fn bounded_cleanup_with_zero_budget_scans_nothing() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1".to_string()).unwrap();
+        clock.set_time(10.0);
+
+        let report = limiter.cleanup_stale_clients_bounded(0, 0).unwrap();
+        assert_eq!(report, CleanupReport::default());
+        assert_eq!(limiter.client_state.len(), 1);
+    }
 }
@@ -33,6 +33,50 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn config_rejects_nan_and_infinite_rate() {
+        for rate in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let config = FluxLimiterConfig::new(rate, 1.0);
+            assert!(matches!(
+                config.validate().unwrap_err(),
+                FluxLimiterError::NonFiniteRate
+            ));
+        }
+    }
+
+    #[test]
+    fn config_rejects_nan_and_infinite_burst() {
+        for burst in [f64::NAN, f64::INFINITY] {
+            let config = FluxLimiterConfig::new(1.0, burst);
+            assert!(matches!(
+                config.validate().unwrap_err(),
+                FluxLimiterError::NonFiniteBurst
+            ));
+        }
+    }
+
+    #[test]
+    fn config_rejects_rate_that_truncates_to_zero_interval() {
+        let config = FluxLimiterConfig::new(2_000_000_000.0, 0.0);
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            FluxLimiterError::RateTooHigh
+        ));
+
+        let clock = TestClock::new(0.0);
+        let result = FluxLimiter::<String, _>::with_config(config, clock);
+        assert!(matches!(result.unwrap_err(), FluxLimiterError::RateTooHigh));
+    }
+
+    #[test]
+    fn config_accepts_one_request_per_nanosecond() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1_000_000_000.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+    }
+
     #[test]
     fn config_accepts_valid_parameters() {
         let config = FluxLimiterConfig::new(10.0, 5.0);
@@ -60,6 +104,59 @@ mod tests {
         assert_eq!(limiter.burst(), 5.0);
     }
 
+    // Quota-style constructor tests
+    #[test]
+    fn config_per_minute_spaces_requests_evenly() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::per_minute(60); // one request per second
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        assert!((limiter.rate() - 1.0).abs() < 1e-9);
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn config_per_hour_and_per_day_compute_rate() {
+        let clock = TestClock::new(0.0);
+
+        let hourly = FluxLimiter::<String, _>::with_config(
+            FluxLimiterConfig::per_hour(3_600),
+            clock.clone(),
+        )
+        .unwrap();
+        assert!((hourly.rate() - 1.0).abs() < 1e-9);
+
+        let daily = FluxLimiter::<String, _>::with_config(FluxLimiterConfig::per_day(8_640), clock)
+            .unwrap();
+        assert!((daily.rate() - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn config_quota_constructors_compose_with_burst() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::per_minute(30).burst(2.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        // 1 + burst requests are allowed immediately
+        for _ in 0..3 {
+            assert!(limiter.check_request("client1").unwrap().allowed);
+        }
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn config_per_minute_zero_is_invalid() {
+        let config = FluxLimiterConfig::per_minute(0);
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            FluxLimiterError::InvalidRate
+        ));
+    }
+
     // Constructor tests with config
     #[test]
     fn constructor_with_invalid_config_fails() {
@@ -77,4 +174,169 @@ mod tests {
         let result = FluxLimiter::<String, _>::with_config(config, clock);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn config_rejects_invalid_shard_amounts() {
+        for shards in [0, 1, 3, 48] {
+            let result = FluxLimiterConfig::new(10.0, 5.0).shards(shards).validate();
+            assert!(matches!(
+                result.unwrap_err(),
+                FluxLimiterError::InvalidShardAmount(n) if n == shards
+            ));
+        }
+    }
+
+    #[test]
+    fn config_rejects_invalid_observed_rate_windows() {
+        for window in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let result = FluxLimiterConfig::new(10.0, 5.0)
+                .observed_rate_window(window)
+                .validate();
+            assert!(matches!(
+                result.unwrap_err(),
+                FluxLimiterError::InvalidObservedRateWindow
+            ));
+        }
+    }
+
+    #[test]
+    fn config_rejects_invalid_expiry_resolutions() {
+        for resolution in [0.0, -1.0, 1e-10, f64::NAN, f64::INFINITY] {
+            let result = FluxLimiterConfig::new(10.0, 5.0)
+                .expiry_wheel(resolution)
+                .validate();
+            assert!(matches!(
+                result.unwrap_err(),
+                FluxLimiterError::InvalidExpiryResolution
+            ));
+        }
+        assert!(
+            FluxLimiterConfig::new(10.0, 5.0)
+                .expiry_wheel(0.5)
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn config_rejects_invalid_entry_ttls() {
+        for ttl in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let result = FluxLimiterConfig::new(10.0, 5.0).entry_ttl(ttl).validate();
+            assert!(matches!(
+                result.unwrap_err(),
+                FluxLimiterError::InvalidEntryTtl
+            ));
+        }
+    }
+
+    #[test]
+    fn constructor_applies_capacity_and_shards() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(10.0, 5.0)
+            .capacity(10_000)
+            .shards(64);
+        let limiter = FluxLimiter::<u64, _>::with_config(config, clock).unwrap();
+
+        assert!(limiter.client_state.capacity() >= 10_000);
+        assert_eq!(limiter.client_state.shards().len(), 64);
+        assert!(limiter.check_request(1).unwrap().allowed);
+    }
+
+    // Environment loading tests (each test uses its own prefix)
+    #[test]
+    fn config_from_env_reads_rate_and_burst() {
+        // SAFETY: the variables are unique to this test
+        unsafe {
+            std::env::set_var("FLUX_ENV_OK_RATE", "20");
+            std::env::set_var("FLUX_ENV_OK_BURST", "4.5");
+        }
+        let config = FluxLimiterConfig::from_env("FLUX_ENV_OK").unwrap();
+
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+        assert_eq!(limiter.rate(), 20.0);
+        assert_eq!(limiter.burst(), 4.5);
+    }
+
+    #[test]
+    fn config_from_env_defaults_burst_to_zero() {
+        // SAFETY: the variables are unique to this test
+        unsafe {
+            std::env::set_var("FLUX_ENV_NOBURST_RATE", "5");
+        }
+        let config = FluxLimiterConfig::from_env("FLUX_ENV_NOBURST").unwrap();
+
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+        assert_eq!(limiter.burst(), 0.0);
+    }
+
+    #[test]
+    fn config_from_env_reads_max_clients() {
+        // SAFETY: the variables are unique to this test
+        unsafe {
+            std::env::set_var("FLUX_ENV_CAP_RATE", "5");
+            std::env::set_var("FLUX_ENV_CAP_MAX_CLIENTS", " 2 ");
+            std::env::set_var("FLUX_ENV_BADCAP_RATE", "5");
+            std::env::set_var("FLUX_ENV_BADCAP_MAX_CLIENTS", "-3");
+        }
+        let config = FluxLimiterConfig::from_env("FLUX_ENV_CAP").unwrap();
+        assert_eq!(config, FluxLimiterConfig::new(5.0, 0.0).max_clients(2));
+
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+        limiter.check_request("a").unwrap();
+        limiter.check_request("b").unwrap();
+        assert!(matches!(
+            limiter.check_request("c"),
+            Err(FluxLimiterError::CapacityExceeded { current: 2, max: 2 })
+        ));
+
+        match FluxLimiterConfig::from_env("FLUX_ENV_BADCAP").unwrap_err() {
+            FluxLimiterError::InvalidEnvVar { name, value } => {
+                assert_eq!(name, "FLUX_ENV_BADCAP_MAX_CLIENTS");
+                assert_eq!(value, "-3");
+            }
+            other => panic!("Expected InvalidEnvVar, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn config_from_env_requires_rate() {
+        let result = FluxLimiterConfig::from_env("FLUX_ENV_MISSING");
+        match result.unwrap_err() {
+            FluxLimiterError::MissingEnvVar(name) => assert_eq!(name, "FLUX_ENV_MISSING_RATE"),
+            other => panic!("Expected MissingEnvVar, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn config_from_env_rejects_unparseable_values() {
+        // SAFETY: the variables are unique to this test
+        unsafe {
+            std::env::set_var("FLUX_ENV_BAD_RATE", "ten");
+        }
+        let result = FluxLimiterConfig::from_env("FLUX_ENV_BAD");
+        match result.unwrap_err() {
+            FluxLimiterError::InvalidEnvVar { name, value } => {
+                assert_eq!(name, "FLUX_ENV_BAD_RATE");
+                assert_eq!(value, "ten");
+            }
+            other => panic!("Expected InvalidEnvVar, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn config_from_env_validates_values() {
+        // SAFETY: the variables are unique to this test
+        unsafe {
+            std::env::set_var("FLUX_ENV_NEG_RATE", "10");
+            std::env::set_var("FLUX_ENV_NEG_BURST", "-1");
+        }
+        let result = FluxLimiterConfig::from_env("FLUX_ENV_NEG");
+        assert!(matches!(
+            result.unwrap_err(),
+            FluxLimiterError::InvalidBurst
+        ));
+    }
 }
@@ -0,0 +1,105 @@
+// tests/ratelimiter/debt_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+
+    #[test]
+    fn requests_over_the_limit_are_allowed_up_to_the_ceiling() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).debt_ceiling(2.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        // burst of 1 plus the first request
+        assert_eq!(limiter.check_request("client").unwrap().debt, 0.0);
+        assert_eq!(limiter.check_request("client").unwrap().debt, 0.0);
+
+        // borrowed requests
+        let decision = limiter.check_request("client").unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.debt, 1.0);
+        let decision = limiter.check_request("client").unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.debt, 2.0);
+
+        // the ceiling is reached
+        let decision = limiter.check_request("client").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.debt, 2.0);
+        assert_eq!(decision.retry_after_seconds, Some(1.0));
+    }
+
+    #[test]
+    fn debt_is_repaid_before_capacity_accrues() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).debt_ceiling(2.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for _ in 0..3 {
+            assert!(limiter.check_request("client").unwrap().allowed);
+        }
+        assert!(!limiter.check_request("client").unwrap().allowed);
+
+        let snapshot = limiter.client_snapshot("client").unwrap().unwrap();
+        assert_eq!(snapshot.debt, 2.0);
+        assert_eq!(snapshot.remaining_capacity, 0.0);
+
+        // one second repays one request of debt
+        clock.advance(1.0);
+        let snapshot = limiter.client_snapshot("client").unwrap().unwrap();
+        assert_eq!(snapshot.debt, 1.0);
+        assert!(!snapshot.is_conforming());
+
+        // once repaid, the client is back within its limit
+        clock.advance(1.0);
+        let snapshot = limiter.client_snapshot("client").unwrap().unwrap();
+        assert_eq!(snapshot.debt, 0.0);
+        assert!(!snapshot.is_conforming());
+        clock.advance(1.0);
+        assert!(
+            limiter
+                .client_snapshot("client")
+                .unwrap()
+                .unwrap()
+                .is_conforming()
+        );
+    }
+
+    #[test]
+    fn no_ceiling_keeps_hard_limits() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock).unwrap();
+
+        assert!(limiter.check_request("client").unwrap().allowed);
+        let decision = limiter.check_request("client").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.debt, 0.0);
+    }
+
+    #[test]
+    fn invalid_ceilings_are_rejected() {
+        for ceiling in [-1.0, f64::NAN, f64::INFINITY] {
+            let config = FluxLimiterConfig::new(1.0, 0.0).debt_ceiling(ceiling);
+            assert!(matches!(
+                config.validate(),
+                Err(FluxLimiterError::InvalidDebtCeiling)
+            ));
+        }
+    }
+
+    #[test]
+    fn debt_near_the_end_of_the_time_range_does_not_overflow() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).debt_ceiling(1.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let decision = limiter.check_request_at("a", u64::MAX - 10).unwrap();
+        assert_eq!(decision.debt, 0.0);
+
+        // tolerance plus debt allowance past u64::MAX saturates
+        let config = FluxLimiterConfig::new(1.0, 1e12).debt_ceiling(1e12);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+        assert!(limiter.check_request("a").unwrap().allowed);
+    }
+}
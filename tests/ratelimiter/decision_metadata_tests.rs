@@ -4,7 +4,11 @@
 mod tests {
 
     use crate::fixtures::test_clock::TestClock;
-    use flux_limiter::{FluxLimiter, FluxLimiterConfig};
+    use flux_limiter::{
+        Decision, DecisionReason, FailurePolicy, FluxLimiter, FluxLimiterConfig,
+        FluxLimiterDecision, Policy, PolicyRouter,
+    };
+    use std::time::{Duration, UNIX_EPOCH};
 
     #[test]
     fn check_request_returns_detailed_decision() {
@@ -20,4 +24,305 @@ mod tests {
         assert!(decision.remaining_capacity.is_some());
         assert!(decision.reset_time_nanos > 0);
     }
+
+    #[test]
+    fn decision_reports_applied_policy() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(4.0, 2.0); // 4 req/sec, burst of 2
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+
+        // Allowed decisions carry the effective limit
+        let allowed = limiter.check_request(client).unwrap();
+        assert!(allowed.allowed);
+        assert_eq!(allowed.limit_per_second, 4.0);
+        assert_eq!(allowed.burst_capacity, 2.0);
+
+        // Exhaust the burst; denied decisions carry it too
+        limiter.check_request(client).unwrap();
+        limiter.check_request(client).unwrap();
+        let denied = limiter.check_request(client).unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.limit_per_second, 4.0);
+        assert_eq!(denied.burst_capacity, 2.0);
+    }
+
+    #[test]
+    fn typed_decision_separates_allowed_and_denied() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 0.0); // 2 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+
+        match limiter.check_request_typed(client).unwrap() {
+            Decision::Allowed(meta) => {
+                assert_eq!(meta.limit_per_second, 2.0);
+                assert!(meta.reset_time_nanos > 0);
+            }
+            Decision::Denied(_) => panic!("first request should be allowed"),
+        }
+
+        // Second request is denied with a non-optional retry delay
+        let decision = limiter.check_request_typed(client).unwrap();
+        assert!(!decision.is_allowed());
+        match decision {
+            Decision::Denied(meta) => {
+                assert!(meta.retry_after_seconds > 0.4 && meta.retry_after_seconds < 0.6);
+            }
+            Decision::Allowed(_) => panic!("second request should be denied"),
+        }
+    }
+
+    #[test]
+    fn pacing_hints_spread_requests_over_the_schedule() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 2.0).pacing_hints(true); // 0.5s interval
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        // each burst request pushes the on-schedule point further out
+        let delays: Vec<_> = (0..3)
+            .map(|_| {
+                limiter
+                    .check_request("client1")
+                    .unwrap()
+                    .suggested_delay_seconds
+            })
+            .collect();
+        assert_eq!(delays, [Some(0.5), Some(1.0), Some(1.5)]);
+
+        // denials carry retry_after instead
+        let decision = limiter.check_request("client1").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.suggested_delay_seconds, None);
+
+        // a client following the hint never touches its burst
+        clock.advance(1.5);
+        for _ in 0..4 {
+            let decision = limiter.check_request("client1").unwrap();
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining_capacity, Some(1.0)); // as for a fresh client
+            clock.advance(decision.suggested_delay_seconds.unwrap());
+        }
+    }
+
+    #[test]
+    fn pacing_hints_are_off_by_default() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(2.0, 2.0), clock).unwrap();
+
+        let decision = limiter.check_request("client1").unwrap();
+        assert_eq!(decision.suggested_delay_seconds, None);
+        match Decision::from(decision) {
+            Decision::Allowed(meta) => assert_eq!(meta.suggested_delay_seconds, None),
+            Decision::Denied(_) => panic!("first request should be allowed"),
+        }
+    }
+
+    #[test]
+    fn decisions_explain_why_they_were_made() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), clock.clone()).unwrap();
+
+        assert_eq!(
+            limiter.check_request("client1").unwrap().reason,
+            DecisionReason::Conforming
+        );
+        assert_eq!(
+            limiter.check_request("client1").unwrap().reason,
+            DecisionReason::Conforming
+        );
+        let denied = limiter.check_request("client1").unwrap();
+        assert_eq!(denied.reason, DecisionReason::BurstExhausted);
+
+        // the typed decision carries the same reason
+        match Decision::from(denied) {
+            Decision::Denied(meta) => assert_eq!(meta.reason, DecisionReason::BurstExhausted),
+            Decision::Allowed(_) => panic!("request should be denied"),
+        }
+    }
+
+    #[test]
+    fn shed_and_failure_policy_decisions_have_their_own_reasons() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(10.0, 5.0).failure_policy(FailurePolicy::FailOpen);
+        let limiter = FluxLimiter::with_config(config, clock.clone())
+            .unwrap()
+            .with_pressure_provider(|| 1.0);
+
+        assert_eq!(
+            limiter.check_request("client1").unwrap().reason,
+            DecisionReason::Shed
+        );
+
+        clock.fail_next_call();
+        let decision = limiter.check_request("client1").unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.reason, DecisionReason::Fallback);
+    }
+
+    #[test]
+    fn unlimited_routes_are_exempt() {
+        let mut router = PolicyRouter::new(TestClock::new(0.0));
+        router
+            .route("user:*", Policy::Limit(FluxLimiterConfig::new(1.0, 0.0)))
+            .unwrap();
+
+        assert_eq!(
+            router.check("/health").unwrap().reason,
+            DecisionReason::Exempt
+        );
+        assert_eq!(
+            router.check("user:1").unwrap().reason,
+            DecisionReason::Conforming
+        );
+        assert_eq!(DecisionReason::Exempt.as_str(), "exempt");
+    }
+
+    #[test]
+    fn retry_after_and_reset_time_are_exposed_as_std_time_types() {
+        let clock = TestClock::new(100.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(4.0, 0.0), clock.clone()).unwrap();
+
+        let allowed = limiter.check_request("client1").unwrap();
+        assert_eq!(allowed.retry_after(), None);
+        assert_eq!(
+            allowed.reset_time(),
+            UNIX_EPOCH + Duration::from_millis(100_250)
+        );
+
+        clock.advance(0.1);
+        let denied = limiter.check_request("client1").unwrap();
+        assert_eq!(denied.retry_after(), Some(Duration::from_millis(150)));
+
+        match Decision::from(denied) {
+            Decision::Denied(meta) => {
+                assert_eq!(meta.retry_after(), Duration::from_millis(150));
+                assert_eq!(
+                    meta.reset_time(),
+                    UNIX_EPOCH + Duration::from_millis(100_250)
+                );
+            }
+            Decision::Allowed(_) => panic!("request should be denied"),
+        }
+
+        let err = limiter.try_check("client1").unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn decisions_compare_and_display_compactly() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(4.0, 2.0), clock.clone()).unwrap();
+
+        let allowed = limiter.check_request("client1").unwrap();
+        assert_eq!(allowed.to_string(), "allowed, 1 remaining");
+        // decisions are Copy, so both conversions can use the same value
+        let typed = Decision::from(allowed);
+        assert_eq!(typed, Decision::from(allowed));
+        assert_eq!(typed.to_string(), allowed.to_string());
+
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client1").unwrap();
+        clock.advance(0.02);
+        let denied = limiter.check_request("client1").unwrap();
+        assert_ne!(denied, allowed);
+        assert_eq!(denied.to_string(), "denied, retry in 0.23s, 0 remaining");
+        assert_eq!(Decision::from(denied).to_string(), denied.to_string());
+
+        let shed = FluxLimiterDecision {
+            reason: DecisionReason::Shed,
+            ..denied
+        };
+        assert_eq!(
+            shed.to_string(),
+            "denied (shed), retry in 0.23s, 0 remaining"
+        );
+    }
+
+    #[test]
+    fn explained_checks_trace_the_gcra_values() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), clock.clone()).unwrap();
+
+        let (decision, trace) = limiter.check_request_explained("client1").unwrap();
+        let trace = trace.unwrap();
+        assert!(decision.allowed);
+        assert!(trace.new_client);
+        assert_eq!(
+            (
+                trace.now_nanos,
+                trace.previous_tat_nanos,
+                trace.new_tat_nanos
+            ),
+            (0, 0, Some(1_000_000_000))
+        );
+
+        limiter.check_request_explained("client1").unwrap();
+        let (decision, trace) = limiter.check_request_explained("client1").unwrap();
+        let trace = trace.unwrap();
+        assert!(!decision.allowed);
+        assert!(!trace.new_client);
+        assert_eq!(
+            trace.to_string(),
+            "now 0, tat 2000000000 -> unchanged, interval 1000000000, tolerance 1000000000"
+        );
+        // explained checks consume capacity like any other
+        assert_eq!(
+            limiter
+                .client_snapshot("client1")
+                .unwrap()
+                .unwrap()
+                .tat_nanos,
+            2_000_000_000
+        );
+    }
+
+    #[test]
+    fn fallback_decisions_have_no_trace() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let (decision, trace) = limiter.check_request_explained("client1").unwrap();
+        assert_eq!(decision.reason, DecisionReason::Fallback);
+        assert!(trace.is_none());
+    }
+
+    #[test]
+    fn retry_after_renders_as_delta_seconds_or_http_date() {
+        // the RFC 9110 example date, plus a quarter second
+        let clock = TestClock::new(784_111_777.25);
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let allowed = limiter.check_request("client1").unwrap();
+        assert_eq!(allowed.retry_after_delta_seconds(), None);
+        assert_eq!(allowed.retry_after_http_date(), None);
+
+        clock.advance(0.5);
+        let denied = limiter.check_request("client1").unwrap();
+        assert_eq!(denied.retry_after_delta_seconds(), Some(1));
+        // the reset at 08:49:38.25 rounds up, never early
+        assert_eq!(
+            denied.retry_after_http_date().as_deref(),
+            Some("Sun, 06 Nov 1994 08:49:39 GMT")
+        );
+
+        // a leap day, and a date past 2038
+        for (seconds, date) in [
+            (951_782_400.0, "Tue, 29 Feb 2000 00:00:00 GMT"),
+            (4_102_444_799.0, "Thu, 31 Dec 2099 23:59:59 GMT"),
+        ] {
+            let denied = FluxLimiterDecision {
+                reset_time_nanos: (seconds * 1e9) as u64,
+                ..denied
+            };
+            assert_eq!(denied.retry_after_http_date().as_deref(), Some(date));
+        }
+    }
 }
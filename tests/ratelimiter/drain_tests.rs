@@ -0,0 +1,109 @@
+// tests/ratelimiter/drain_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{DecisionReason, DrainMode, FluxLimiter, FluxLimiterConfig};
+
+    // 1 req/sec, burst of 1
+    fn limiter(clock: &TestClock, mode: DrainMode) -> FluxLimiter<String, TestClock> {
+        let config = FluxLimiterConfig::new(1.0, 1.0)
+            .stats(true)
+            .drain_mode(mode);
+        FluxLimiter::with_config(config, clock.clone()).unwrap()
+    }
+
+    #[test]
+    fn draining_denies_new_clients_and_keeps_existing_schedules() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock, DrainMode::NewClients);
+        limiter.check_request("existing".to_string()).unwrap();
+
+        limiter.clone().set_drain(true);
+        assert!(limiter.is_draining());
+
+        let denied = limiter.check_request_ref("new").unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.reason, DecisionReason::Draining);
+        assert_eq!(denied.retry_after_seconds, Some(1.0));
+        assert!(!limiter.client_state.contains_key("new"));
+
+        // the existing client still gets the rest of its burst, then waits
+        assert!(
+            limiter
+                .check_request("existing".to_string())
+                .unwrap()
+                .allowed
+        );
+        let exhausted = limiter.check_request("existing".to_string()).unwrap();
+        assert_eq!(exhausted.reason, DecisionReason::BurstExhausted);
+        clock.advance(1.0);
+        assert!(
+            limiter
+                .check_request("existing".to_string())
+                .unwrap()
+                .allowed
+        );
+
+        let stats = limiter.stats();
+        assert_eq!((stats.allowed, stats.denied), (3, 2));
+
+        limiter.set_drain(false);
+        assert!(limiter.check_request("new".to_string()).unwrap().allowed);
+    }
+
+    #[test]
+    fn existing_clients_mode_turns_away_tracked_clients() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock, DrainMode::ExistingClients);
+        limiter.check_request("existing".to_string()).unwrap();
+        let tat_nanos = limiter
+            .client_snapshot("existing")
+            .unwrap()
+            .unwrap()
+            .tat_nanos;
+
+        limiter.set_drain(true);
+        let denied = limiter.check_request("existing".to_string()).unwrap();
+        assert_eq!(denied.reason, DecisionReason::Draining);
+        assert_eq!(denied.reset_time_nanos, tat_nanos);
+        assert_eq!(
+            limiter
+                .client_snapshot("existing")
+                .unwrap()
+                .unwrap()
+                .tat_nanos,
+            tat_nanos
+        );
+
+        assert!(limiter.check_request("new".to_string()).unwrap().allowed);
+        // once tracked, the new client is drained too
+        assert!(!limiter.check_request("new".to_string()).unwrap().allowed);
+    }
+
+    #[test]
+    fn drained_keys_deny_all_or_nothing_checks() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock, DrainMode::NewClients);
+        limiter.check_request("user".to_string()).unwrap();
+
+        limiter.set_drain(true);
+        let keys = ["user".to_string(), "endpoint".to_string()];
+        let outcome = limiter.check_all(&keys).unwrap();
+        assert!(!outcome.allowed);
+        assert_eq!(outcome.denied_by.as_deref(), Some("endpoint"));
+        assert_eq!(outcome.decisions[1].1.reason, DecisionReason::Draining);
+        // nothing was consumed for the existing key either
+        assert_eq!(
+            limiter.client_snapshot("user").unwrap().unwrap().tat_nanos,
+            1_000_000_000
+        );
+
+        let (decision, trace) = limiter
+            .check_request_explained("other".to_string())
+            .unwrap();
+        assert_eq!(decision.reason, DecisionReason::Draining);
+        assert!(trace.is_none());
+    }
+}
@@ -0,0 +1,116 @@
+// tests/ratelimiter/entry_ttl_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig};
+
+    // one request per 1000s, so a client's TAT stays far ahead of the clock
+    fn slow_config() -> FluxLimiterConfig {
+        FluxLimiterConfig::new(0.001, 0.0)
+    }
+
+    #[test]
+    fn cleanup_removes_idle_clients_before_they_recover() {
+        let clock = TestClock::new(0.0);
+        let with_ttl =
+            FluxLimiter::with_config(slow_config().entry_ttl(60.0), clock.clone()).unwrap();
+        let without_ttl = FluxLimiter::with_config(slow_config(), clock.clone()).unwrap();
+
+        with_ttl.check_request("client1".to_string()).unwrap(); // TAT = t=1000
+        without_ttl.check_request("client1".to_string()).unwrap();
+
+        clock.set_time(61.0);
+        with_ttl.cleanup_stale_clients(0).unwrap();
+        without_ttl.cleanup_stale_clients(0).unwrap();
+
+        assert_eq!(with_ttl.client_state.len(), 0);
+        assert_eq!(without_ttl.client_state.len(), 1);
+    }
+
+    #[test]
+    fn clients_checked_within_the_ttl_are_kept() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(slow_config().entry_ttl(60.0), clock.clone()).unwrap();
+
+        // Denied checks count as access too
+        for _ in 0..5 {
+            limiter.check_request("client1".to_string()).unwrap();
+            clock.advance(30.0);
+        }
+        limiter.cleanup_stale_clients(0).unwrap();
+        assert!(limiter.client_state.contains_key("client1"));
+
+        let entry = limiter.client_state.get("client1").unwrap();
+        assert_eq!(entry.last_access_nanos(), 120_000_000_000);
+    }
+
+    #[test]
+    fn returning_clients_start_over() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(slow_config().entry_ttl(60.0), clock.clone()).unwrap();
+
+        assert!(
+            limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        clock.set_time(61.0);
+        assert!(
+            !limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+
+        clock.set_time(200.0);
+        limiter.cleanup_stale_clients(0).unwrap();
+        assert!(
+            limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn expiry_wheel_removes_clients_when_the_ttl_runs_out() {
+        let clock = TestClock::new(0.0);
+        let config = slow_config().entry_ttl(10.0).expiry_wheel(0.1);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1".to_string()).unwrap();
+        clock.set_time(5.0);
+        limiter.check_request("client2".to_string()).unwrap();
+        assert!(limiter.client_state.contains_key("client1"));
+
+        clock.set_time(11.0);
+        limiter.check_request("client3".to_string()).unwrap();
+        assert!(!limiter.client_state.contains_key("client1"));
+        assert!(limiter.client_state.contains_key("client2"));
+    }
+
+    #[test]
+    fn check_all_counts_as_access_for_every_key() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(slow_config().entry_ttl(60.0), clock.clone()).unwrap();
+        let keys = ["a".to_string(), "b".to_string()];
+
+        assert!(limiter.check_all(&keys).unwrap().allowed);
+        clock.set_time(50.0);
+        assert!(!limiter.check_all(&keys).unwrap().allowed);
+
+        clock.set_time(100.0);
+        limiter.cleanup_stale_clients(0).unwrap();
+        assert_eq!(limiter.client_state.len(), 2);
+
+        clock.set_time(111.0);
+        limiter.cleanup_stale_clients(0).unwrap();
+        assert_eq!(limiter.client_state.len(), 0);
+    }
+}
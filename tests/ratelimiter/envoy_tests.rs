@@ -0,0 +1,210 @@
+// tests/ratelimiter/envoy_tests.rs
+
+#[cfg(all(test, feature = "envoy"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        DescriptorEntry, EnvoyRateLimitService, FluxLimiterConfig, Policy, PolicyRouter,
+        RateLimitCode, RateLimitDescriptor, RateLimitRequest, RateLimitResponse, RateLimitUnit,
+    };
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::client::Grpc;
+    use tonic::codec::ProstCodec;
+    use tonic::codegen::http::uri::PathAndQuery;
+    use tonic::transport::{Channel, Server};
+    use tonic::{Code, Request, Status};
+
+    // serve `router` on a free local port and connect a client to it
+    async fn start(router: PolicyRouter<TestClock>) -> Grpc<Channel> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(EnvoyRateLimitService::new(Arc::new(router)))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let channel = Channel::from_shared(format!("http://{addr}"))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        Grpc::new(channel)
+    }
+
+    async fn should_rate_limit(
+        client: &mut Grpc<Channel>,
+        request: RateLimitRequest,
+    ) -> Result<RateLimitResponse, Status> {
+        client.ready().await.unwrap();
+        let path = PathAndQuery::from_static(
+            "/envoy.service.ratelimit.v3.RateLimitService/ShouldRateLimit",
+        );
+        client
+            .unary(
+                Request::new(request),
+                path,
+                ProstCodec::<RateLimitRequest, RateLimitResponse>::default(),
+            )
+            .await
+            .map(|response| response.into_inner())
+    }
+
+    fn descriptor(entries: &[(&str, &str)]) -> RateLimitDescriptor {
+        RateLimitDescriptor {
+            entries: entries
+                .iter()
+                .map(|(key, value)| DescriptorEntry {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            hits_addend: None,
+        }
+    }
+
+    fn request(descriptors: Vec<RateLimitDescriptor>) -> RateLimitRequest {
+        RateLimitRequest {
+            domain: "edge".to_string(),
+            descriptors,
+            hits_addend: 0,
+        }
+    }
+
+    fn router(clock: &TestClock) -> PolicyRouter<TestClock> {
+        PolicyRouter::with_routes(
+            [
+                // 1 req/sec per client address, burst of 1
+                (
+                    "edge|remote_address=*",
+                    Policy::Limit(FluxLimiterConfig::new(1.0, 1.0)),
+                ),
+                (
+                    "edge|path=/login",
+                    Policy::Limit(FluxLimiterConfig::new(0.5, 0.0)),
+                ),
+            ],
+            clock.clone(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn descriptor_key_joins_domain_and_entries() {
+        let descriptor = descriptor(&[("remote_address", "10.0.0.1"), ("path", "/login")]);
+        assert_eq!(
+            EnvoyRateLimitService::<TestClock>::descriptor_key("edge", &descriptor),
+            "edge|remote_address=10.0.0.1|path=/login"
+        );
+    }
+
+    #[tokio::test]
+    async fn descriptors_are_limited_independently() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(router(&clock)).await;
+        let call = || {
+            request(vec![
+                descriptor(&[("remote_address", "10.0.0.1")]),
+                descriptor(&[("path", "/login")]),
+            ])
+        };
+
+        let allowed = should_rate_limit(&mut client, call()).await.unwrap();
+        assert_eq!(allowed.overall_code, RateLimitCode::Ok as i32);
+        assert_eq!(allowed.statuses.len(), 2);
+        assert_eq!(allowed.statuses[0].limit_remaining, 0);
+        let limit = allowed.statuses[1].current_limit.clone().unwrap();
+        assert_eq!(limit.requests_per_unit, 30);
+        assert_eq!(limit.unit, RateLimitUnit::Minute as i32);
+        assert!(allowed.response_headers_to_add.is_empty());
+
+        // the login path is exhausted, the client address is not
+        let limited = should_rate_limit(&mut client, call()).await.unwrap();
+        assert_eq!(limited.overall_code, RateLimitCode::OverLimit as i32);
+        assert_eq!(limited.statuses[0].code, RateLimitCode::Ok as i32);
+        assert_eq!(limited.statuses[1].code, RateLimitCode::OverLimit as i32);
+        let reset = limited.statuses[1].duration_until_reset.unwrap();
+        assert_eq!((reset.seconds, reset.nanos), (2, 0));
+        assert_eq!(limited.response_headers_to_add[0].key, "retry-after");
+        assert_eq!(limited.response_headers_to_add[0].value, "2");
+    }
+
+    #[tokio::test]
+    async fn hits_addend_is_all_or_nothing() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(router(&clock)).await;
+        let call = |hits_addend| RateLimitRequest {
+            hits_addend,
+            ..request(vec![descriptor(&[("remote_address", "10.0.0.1")])])
+        };
+
+        // three requests do not fit a burst of one
+        let denied = should_rate_limit(&mut client, call(3)).await.unwrap();
+        assert_eq!(denied.overall_code, RateLimitCode::OverLimit as i32);
+
+        // nothing was consumed, so two still fit
+        let allowed = should_rate_limit(&mut client, call(2)).await.unwrap();
+        assert_eq!(allowed.overall_code, RateLimitCode::Ok as i32);
+        assert_eq!(allowed.statuses[0].limit_remaining, 0);
+
+        // a descriptor's own hits_addend overrides the request's
+        clock.advance(2.0);
+        let mut request = call(5);
+        request.descriptors[0].hits_addend = Some(1);
+        let allowed = should_rate_limit(&mut client, request).await.unwrap();
+        assert_eq!(allowed.overall_code, RateLimitCode::Ok as i32);
+    }
+
+    #[tokio::test]
+    async fn unrouted_descriptors_are_unlimited() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(router(&clock)).await;
+
+        for _ in 0..5 {
+            let response =
+                should_rate_limit(&mut client, request(vec![descriptor(&[("user", "alice")])]))
+                    .await
+                    .unwrap();
+            assert_eq!(response.overall_code, RateLimitCode::Ok as i32);
+            assert!(response.statuses[0].current_limit.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn bad_calls_fail_with_status_codes() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(router(&clock)).await;
+
+        let mut no_domain = request(vec![descriptor(&[("path", "/login")])]);
+        no_domain.domain.clear();
+        assert_eq!(
+            should_rate_limit(&mut client, no_domain)
+                .await
+                .unwrap_err()
+                .code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            should_rate_limit(&mut client, request(Vec::new()))
+                .await
+                .unwrap_err()
+                .code(),
+            Code::InvalidArgument
+        );
+
+        clock.fail_next_call();
+        assert_eq!(
+            should_rate_limit(
+                &mut client,
+                request(vec![descriptor(&[("path", "/login")])])
+            )
+            .await
+            .unwrap_err()
+            .code(),
+            Code::Unavailable
+        );
+    }
+}
@@ -3,7 +3,11 @@
 #[cfg(test)]
 mod tests {
     use crate::fixtures::test_clock::TestClock;
-    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+    use flux_limiter::{
+        ClockError, FluxLimiter, FluxLimiterConfig, FluxLimiterError, RateLimitedError, StoreError,
+    };
+    use std::error::Error;
+    use std::time::{Duration, UNIX_EPOCH};
 
     #[test]
     fn clock_error_propagates_in_check_request() {
@@ -183,4 +187,92 @@ mod tests {
         let result = limiter.cleanup_stale_clients(1_000_000_000); // 1 second threshold
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn try_check_returns_denials_as_errors() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let meta = limiter.try_check("client1").unwrap();
+        assert_eq!(meta.burst_capacity, 1.0);
+        assert!(limiter.try_check("client1").is_ok());
+
+        let err = limiter.try_check("client1").unwrap_err();
+        assert!(matches!(err, RateLimitedError::Denied(_)));
+        assert_eq!(err.retry_after_seconds(), Some(1.0));
+        assert_eq!(err.reset_time_nanos(), Some(2_000_000_000));
+        assert_eq!(err.to_string(), "Rate limited; retry after 1.000s");
+
+        clock.advance(1.0);
+        assert!(limiter.try_check("client1").is_ok());
+    }
+
+    #[test]
+    fn try_check_wraps_check_failures() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let err = limiter.try_check("client1").unwrap_err();
+        assert!(matches!(
+            err,
+            RateLimitedError::Failed(FluxLimiterError::ClockError(_))
+        ));
+        assert_eq!(err.retry_after_seconds(), None);
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn clock_errors_expose_their_source() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(10.0, 5.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let err = limiter.check_request("client1").unwrap_err();
+
+        let clock_error = err.source().expect("clock error as source");
+        assert_eq!(clock_error.to_string(), "Clock failed");
+        let cause = clock_error
+            .source()
+            .expect("custom clock failure as source");
+        assert_eq!(cause.to_string(), "test clock failure");
+    }
+
+    #[test]
+    fn clock_error_variants_describe_the_failure() {
+        let before_epoch = UNIX_EPOCH
+            .duration_since(UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap_err();
+
+        let err = ClockError::SystemTimeError(before_epoch);
+        assert_eq!(err.to_string(), "System time is before the Unix epoch");
+        assert!(err.source().is_some());
+
+        let err = ClockError::OutOfRange;
+        assert!(err.to_string().contains("timestamp range"));
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn io_and_store_errors_expose_their_source() {
+        let err = FluxLimiterError::PersistenceError(std::io::Error::other("disk full"));
+        assert_eq!(err.source().unwrap().to_string(), "disk full");
+
+        let err = FluxLimiterError::StoreError(StoreError::new("connection reset"));
+        let store_error = err.source().expect("store error as source");
+        assert_eq!(
+            store_error.source().unwrap().to_string(),
+            "connection reset"
+        );
+    }
+
+    #[cfg(feature = "gossip")]
+    #[test]
+    fn gossip_errors_expose_their_source() {
+        let err = FluxLimiterError::GossipError(std::io::Error::other("peer unreachable"));
+        assert_eq!(err.source().unwrap().to_string(), "peer unreachable");
+    }
 }
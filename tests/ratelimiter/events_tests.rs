@@ -0,0 +1,127 @@
+// tests/ratelimiter/events_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FailurePolicy, FluxLimiter, FluxLimiterConfig};
+
+    #[test]
+    fn subscribers_see_every_check() {
+        let clock = TestClock::new(5.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+        let events = limiter.subscribe();
+
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client2").unwrap();
+
+        let events: Vec<_> = events.try_iter().collect();
+        let seen: Vec<_> = events
+            .iter()
+            .map(|event| (event.client_id, event.decision.allowed))
+            .collect();
+        assert_eq!(
+            seen,
+            vec![("client1", true), ("client1", false), ("client2", true)]
+        );
+        assert!(
+            events
+                .iter()
+                .all(|event| event.timestamp_nanos == 5_000_000_000)
+        );
+    }
+
+    #[test]
+    fn checks_before_subscribing_are_not_replayed() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), clock).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        let events = limiter.subscribe();
+        limiter.check_request("client2").unwrap();
+
+        let keys: Vec<_> = events.try_iter().map(|event| event.client_id).collect();
+        assert_eq!(keys, vec!["client2"]);
+    }
+
+    #[test]
+    fn clones_publish_to_the_same_subscribers() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::<String, _>::with_config(FluxLimiterConfig::new(10.0, 5.0), clock)
+                .unwrap();
+        let first = limiter.subscribe();
+        let second = limiter.clone().subscribe();
+
+        limiter.clone().check_request_ref("client1").unwrap();
+
+        assert_eq!(first.try_iter().count(), 1);
+        assert_eq!(second.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn failure_policy_decisions_are_published_without_a_timestamp() {
+        let clock = TestClock::new(3.0);
+        let config = FluxLimiterConfig::new(10.0, 5.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let events = limiter.subscribe();
+
+        clock.fail_next_call();
+        limiter.check_request("client1").unwrap();
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.client_id, "client1");
+        assert!(!event.decision.allowed);
+        assert_eq!(event.timestamp_nanos, 0);
+    }
+
+    #[test]
+    fn multi_key_checks_publish_each_key() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), clock).unwrap();
+        let events = limiter.subscribe();
+
+        limiter.check_all(&["user", "endpoint"]).unwrap();
+        limiter.check_requests(["user", "ip"]).unwrap();
+
+        // batches are evaluated, and published, in shard order
+        let mut keys: Vec<_> = events.try_iter().map(|event| event.client_id).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["endpoint", "ip", "user", "user"]);
+    }
+
+    #[test]
+    fn slow_and_dropped_subscribers_never_block_checks() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1_000.0, 0.0), clock.clone()).unwrap();
+        let slow = limiter.subscribe();
+        drop(limiter.subscribe());
+
+        for _ in 0..2_000 {
+            limiter.check_request("client1").unwrap();
+            clock.advance(0.001);
+        }
+
+        // the buffer filled up and later events were dropped
+        assert_eq!(slow.try_iter().count(), 1024);
+        limiter.check_request("client1").unwrap();
+        assert_eq!(slow.try_iter().count(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_subscribers_receive_events() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), clock).unwrap();
+        let mut events = limiter.subscribe_async();
+
+        limiter.check_request("client1").unwrap();
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.client_id, "client1");
+        assert!(event.decision.allowed);
+    }
+}
@@ -0,0 +1,156 @@
+// tests/ratelimiter/expiry_wheel_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig};
+
+    #[test]
+    fn recovered_clients_expire_without_cleanup() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .expiry_wheel(0.1)
+            .stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1".to_string()).unwrap(); // TAT = t=1
+        assert_eq!(limiter.client_state.len(), 1);
+
+        // Any later check advances the wheel past client1's deadline
+        clock.set_time(2.0);
+        limiter.check_request("client2".to_string()).unwrap();
+
+        assert!(!limiter.client_state.contains_key("client1"));
+        assert!(limiter.client_state.contains_key("client2"));
+        assert_eq!(limiter.stats().evictions, 1);
+    }
+
+    #[test]
+    fn active_clients_are_rescheduled_instead_of_removed() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).expiry_wheel(0.1);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        // A request every 0.9s keeps the TAT ahead of the clock
+        for _ in 0..20 {
+            limiter.check_request("busy".to_string()).unwrap();
+            clock.advance(0.9);
+        }
+        assert!(limiter.client_state.contains_key("busy"));
+
+        // Once the client goes quiet it is removed
+        clock.advance(30.0);
+        limiter.check_request("other".to_string()).unwrap();
+        assert!(!limiter.client_state.contains_key("busy"));
+    }
+
+    #[test]
+    fn clients_expire_in_deadline_order() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(0.01, 0.0).expiry_wheel(0.01);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        // Client n is first seen at t=n and recovers 100s later
+        for client in 0..50u64 {
+            clock.set_time(client as f64);
+            limiter.check_request(client).unwrap();
+        }
+
+        // 100 ticks per second, so deadlines move down through several levels of the wheel
+        for second in 50..160u64 {
+            clock.set_time(second as f64 + 0.5);
+            limiter.check_request(u64::MAX).unwrap();
+            for client in 0..50u64 {
+                let recovered = client + 100 <= second;
+                assert_eq!(
+                    limiter.client_state.contains_key(&client),
+                    !recovered,
+                    "client {client} at t={second}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn long_idle_periods_expire_every_client_at_once() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(10.0, 5.0).expiry_wheel(0.001);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for client in 0..1_000u64 {
+            limiter.check_request(client).unwrap();
+        }
+
+        clock.advance(1_000_000.0);
+        limiter.check_request(u64::MAX).unwrap();
+        assert_eq!(limiter.client_state.len(), 1);
+    }
+
+    #[test]
+    fn clients_seen_by_check_all_and_check_request_ref_expire() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).expiry_wheel(0.1);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request_ref("ref").unwrap();
+        limiter
+            .check_all(&["all1".to_string(), "all2".to_string()])
+            .unwrap();
+        assert_eq!(limiter.client_state.len(), 3);
+
+        clock.set_time(5.0);
+        limiter.check_request_ref("later").unwrap();
+        assert_eq!(limiter.client_state.len(), 1);
+    }
+
+    #[test]
+    fn restored_clients_are_scheduled() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).expiry_wheel(0.1);
+        let limiter = FluxLimiter::with_config(config.clone(), clock.clone()).unwrap();
+        limiter.check_request("client1".to_string()).unwrap();
+
+        let restored =
+            FluxLimiter::with_state(config, clock.clone(), limiter.export_state()).unwrap();
+        clock.set_time(5.0);
+        restored.check_request("client2".to_string()).unwrap();
+
+        assert!(!restored.client_state.contains_key("client1"));
+    }
+
+    #[test]
+    fn expiry_does_not_change_decisions() {
+        let clock = TestClock::new(0.0);
+        let with_wheel = FluxLimiter::with_config(
+            FluxLimiterConfig::new(2.0, 3.0).expiry_wheel(0.05),
+            clock.clone(),
+        )
+        .unwrap();
+        let without_wheel =
+            FluxLimiter::with_config(FluxLimiterConfig::new(2.0, 3.0), clock.clone()).unwrap();
+
+        for step in 0..2_000u64 {
+            clock.set_time(step as f64 * 0.037);
+            let client = step % 7;
+            let kept = with_wheel.check_request(client).unwrap();
+            let reference = without_wheel.check_request(client).unwrap();
+            assert_eq!(kept.allowed, reference.allowed, "step {step}");
+            assert_eq!(kept.reset_time_nanos, reference.reset_time_nanos);
+        }
+    }
+
+    #[test]
+    fn far_future_schedules_do_not_overflow_deadlines() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 5.0).expiry_wheel(0.1);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        limiter.check_request_at("late", u64::MAX - 10).unwrap();
+        // a huge penalty pushes the TAT to the end of the time range
+        limiter.check_request("penalized").unwrap();
+        limiter.penalize("penalized", 1e12).unwrap();
+        limiter.check_request("penalized").unwrap();
+        assert!(limiter.client_state.contains_key("penalized"));
+    }
+}
@@ -0,0 +1,145 @@
+// tests/ratelimiter/failure_policy_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FailurePolicy, FluxLimiter, FluxLimiterConfig, FluxLimiterError, StateStore,
+        StoreBackedLimiter, StoreError,
+    };
+
+    // store whose backend is always down
+    struct DownStore;
+
+    impl StateStore<String> for DownStore {
+        fn get(&self, _key: &String) -> Result<Option<u64>, StoreError> {
+            Err(StoreError::new("connection refused"))
+        }
+
+        fn compare_and_swap(
+            &self,
+            _key: &String,
+            _expected: Option<u64>,
+            _new: u64,
+        ) -> Result<bool, StoreError> {
+            Err(StoreError::new("connection refused"))
+        }
+    }
+
+    #[test]
+    fn clock_errors_propagate_by_default() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.check_request("client1"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+    }
+
+    #[test]
+    fn fail_open_allows_on_clock_error() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .failure_policy(FailurePolicy::FailOpen)
+            .stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let decision = limiter.check_request("client1").unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining_capacity, None);
+
+        // no state was touched, so the next real check is a first request
+        assert!(limiter.client_state.is_empty());
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert_eq!(limiter.stats().allowed, 2);
+    }
+
+    #[test]
+    fn fail_closed_denies_on_clock_error() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 0.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let decision = limiter.check_request_ref("client1").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(0.5));
+    }
+
+    #[test]
+    fn policy_applies_to_batch_and_all_or_nothing_checks() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .failure_policy(FailurePolicy::FailClosed)
+            .stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let decisions = limiter.check_requests(["a", "b", "c"]).unwrap();
+        assert_eq!(decisions.len(), 3);
+        assert!(decisions.iter().all(|(_, decision)| !decision.allowed));
+        // one decision per key, however the failure is recorded
+        assert_eq!(limiter.stats().total_checks, 3);
+        assert_eq!(limiter.stats().denied, 3);
+
+        clock.fail_next_call();
+        let outcome = limiter.check_all(&["a", "b"]).unwrap();
+        assert!(!outcome.allowed);
+        assert_eq!(outcome.denied_by, Some("a"));
+        assert_eq!(limiter.stats().total_checks, 5);
+
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .failure_policy(FailurePolicy::FailOpen)
+            .stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let decisions = limiter.check_requests(["a", "b"]).unwrap();
+        assert!(decisions.iter().all(|(_, decision)| decision.allowed));
+        assert_eq!(limiter.stats().total_checks, 2);
+        assert_eq!(limiter.stats().allowed, 2);
+
+        // an empty batch records nothing, and does not read the clock
+        clock.fail_next_call();
+        let decisions = limiter.check_requests(Vec::<&str>::new()).unwrap();
+        assert!(decisions.is_empty());
+        assert_eq!(limiter.stats().total_checks, 2);
+        clock.reset_failure();
+    }
+
+    #[test]
+    fn policy_applies_to_store_errors() {
+        let clock = TestClock::new(0.0);
+        let client = "client1".to_string();
+
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = StoreBackedLimiter::with_config(config, clock.clone(), DownStore).unwrap();
+        assert!(matches!(
+            limiter.check_request(&client),
+            Err(FluxLimiterError::StoreError(_))
+        ));
+
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailOpen);
+        let limiter = StoreBackedLimiter::with_config(config, clock.clone(), DownStore).unwrap();
+        assert!(limiter.check_request(&client).unwrap().allowed);
+
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = StoreBackedLimiter::with_config(config, clock, DownStore).unwrap();
+        assert!(!limiter.check_request(&client).unwrap().allowed);
+    }
+
+    #[test]
+    fn non_check_operations_still_return_errors() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailOpen);
+        let limiter = FluxLimiter::<&str, _>::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(limiter.cleanup_stale_clients(0).is_err());
+    }
+}
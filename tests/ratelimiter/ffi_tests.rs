@@ -0,0 +1,85 @@
+// tests/ratelimiter/ffi_tests.rs
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+
+    use flux_limiter::{
+        Classification, FLUX_LIMITER_ERR_LIMITER, FLUX_LIMITER_ERR_NULL, FLUX_LIMITER_OK,
+        FfiDecision, FfiLimiter, FluxLimiter, FluxLimiterConfig, SystemClock, flux_limiter_check,
+        flux_limiter_cleanup, flux_limiter_free, flux_limiter_new,
+    };
+    use std::ffi::CString;
+
+    #[test]
+    fn check_writes_decisions_per_client() {
+        let limiter = flux_limiter_new(1.0, 1.0);
+        assert!(!limiter.is_null());
+        let client1 = CString::new("client1").unwrap();
+        let client2 = CString::new("client2").unwrap();
+        let mut decision = FfiDecision::default();
+
+        let mut check = |client: &CString| unsafe {
+            assert_eq!(
+                flux_limiter_check(limiter, client.as_ptr(), &mut decision),
+                FLUX_LIMITER_OK
+            );
+            decision
+        };
+        assert!(check(&client1).allowed);
+        assert!(check(&client1).allowed);
+        let denied = check(&client1);
+        assert!(!denied.allowed);
+        // the system clock has moved on a little since the first check
+        assert!(denied.retry_after_seconds > 0.9 && denied.retry_after_seconds <= 1.0);
+        assert!(check(&client2).allowed);
+
+        // nothing has recovered yet
+        assert_eq!(unsafe { flux_limiter_cleanup(limiter, 0) }, 0);
+        unsafe { flux_limiter_free(limiter) };
+    }
+
+    #[test]
+    fn invalid_configs_and_null_pointers_are_reported() {
+        assert!(flux_limiter_new(0.0, 1.0).is_null());
+        assert!(flux_limiter_new(1.0, f64::NAN).is_null());
+
+        let limiter = flux_limiter_new(1.0, 0.0);
+        let client = CString::new("client1").unwrap();
+        let mut decision = FfiDecision::default();
+        unsafe {
+            assert_eq!(
+                flux_limiter_check(std::ptr::null(), client.as_ptr(), &mut decision),
+                FLUX_LIMITER_ERR_NULL
+            );
+            assert_eq!(
+                flux_limiter_check(limiter, std::ptr::null(), &mut decision),
+                FLUX_LIMITER_ERR_NULL
+            );
+            assert_eq!(
+                flux_limiter_check(limiter, client.as_ptr(), std::ptr::null_mut()),
+                FLUX_LIMITER_ERR_NULL
+            );
+            assert_eq!(flux_limiter_cleanup(std::ptr::null(), 0), -1);
+            flux_limiter_free(std::ptr::null_mut());
+            flux_limiter_free(limiter);
+        }
+        // a failed call leaves the output untouched
+        assert_eq!(decision, FfiDecision::default());
+    }
+
+    #[test]
+    fn panics_do_not_unwind_into_the_caller() {
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let limiter: FfiLimiter = FluxLimiter::with_config(config, SystemClock)
+            .unwrap()
+            .with_classifier(|_: &Vec<u8>| -> Classification { panic!("classifier failed") });
+        let client = CString::new("client1").unwrap();
+        let mut decision = FfiDecision::default();
+
+        let status = unsafe { flux_limiter_check(&limiter, client.as_ptr(), &mut decision) };
+        assert_eq!(status, FLUX_LIMITER_ERR_LIMITER);
+        assert_eq!(decision, FfiDecision::default());
+        // the limiter is still usable afterwards
+        assert_eq!(unsafe { flux_limiter_cleanup(&limiter, 0) }, 0);
+    }
+}
@@ -0,0 +1,141 @@
+// tests/ratelimiter/fifo_waiter_tests.rs
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+
+    use flux_limiter::{AcquireError, FluxLimiter, FluxLimiterConfig, TokioClock};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn limiter() -> Arc<FluxLimiter<&'static str, TokioClock>> {
+        let config = FluxLimiterConfig::new(10.0, 0.0); // one slot every 100ms
+        let limiter = FluxLimiter::with_config(config, TokioClock::new()).unwrap();
+        Arc::new(limiter.with_fifo_waiters())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiters_are_served_in_arrival_order() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+        let served = Arc::new(Mutex::new(Vec::new()));
+
+        let mut waiters = Vec::new();
+        for waiter in 0..5 {
+            let limiter = Arc::clone(&limiter);
+            let served = Arc::clone(&served);
+            waiters.push(tokio::spawn(async move {
+                let permit = limiter
+                    .acquire_timeout("queue", Duration::from_secs(10))
+                    .await
+                    .unwrap();
+                served.lock().unwrap().push((waiter, Instant::now()));
+                permit.commit();
+            }));
+            // later waiters arrive closer to the next slot than earlier ones
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let start = Instant::now() - Duration::from_millis(50);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+
+        let served = served.lock().unwrap();
+        let order: Vec<_> = served.iter().map(|(waiter, _)| *waiter).collect();
+        assert_eq!(order, [0, 1, 2, 3, 4]);
+        // one slot every 100ms, starting 100ms after the first request
+        for (slot, (_, at)) in served.iter().enumerate() {
+            assert_eq!(*at - start, Duration::from_millis(100 * (slot as u64 + 1)));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn later_waiter_cannot_take_freed_capacity_first() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+        let served = Arc::new(Mutex::new(Vec::new()));
+
+        let spawn_waiter = |name: &'static str| {
+            let limiter = Arc::clone(&limiter);
+            let served = Arc::clone(&served);
+            tokio::spawn(async move {
+                let permit = limiter
+                    .acquire_timeout("queue", Duration::from_secs(1))
+                    .await
+                    .unwrap();
+                served.lock().unwrap().push(name);
+                permit.commit();
+            })
+        };
+
+        // the first waiter sleeps towards the next slot...
+        let first = spawn_waiter("first");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // ...when capacity is given back, and a second waiter arrives
+        limiter.refund("queue", 1.0).unwrap();
+        let second = spawn_waiter("second");
+
+        first.await.unwrap();
+        second.await.unwrap();
+        assert_eq!(*served.lock().unwrap(), ["first", "second"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiter_timing_out_in_line_does_not_hold_it_up() {
+        let limiter = limiter();
+        limiter.acquire("queue").unwrap().commit();
+
+        let head = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move {
+                limiter
+                    .acquire_timeout("queue", Duration::from_secs(1))
+                    .await
+                    .map(|permit| permit.commit())
+            }
+        });
+        tokio::task::yield_now().await;
+
+        // queued behind the head, and gone before the head is served
+        let err = limiter
+            .acquire_timeout("queue", Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        match err {
+            AcquireError::Elapsed(meta) => assert!(meta.retry_after_seconds > 0.0),
+            other => panic!("expected Elapsed, got {other:?}"),
+        }
+
+        head.await.unwrap().unwrap();
+        let next = limiter
+            .acquire_timeout("queue", Duration::from_millis(100))
+            .await;
+        assert!(next.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keys_queue_independently() {
+        let limiter = limiter();
+        limiter.acquire("busy").unwrap().commit();
+
+        let busy = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move {
+                limiter
+                    .acquire_timeout("busy", Duration::from_secs(1))
+                    .await
+                    .map(|permit| permit.commit())
+            }
+        });
+        tokio::task::yield_now().await;
+
+        let start = Instant::now();
+        limiter
+            .acquire_timeout("idle", Duration::from_secs(1))
+            .await
+            .unwrap()
+            .commit();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        busy.await.unwrap().unwrap();
+    }
+}
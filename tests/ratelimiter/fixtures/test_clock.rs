@@ -1,7 +1,7 @@
 // tests/ratelimiter/fixtures/test_clock.rs
 
 // dependencies
-use flux_limiter::{Clock, ClockError};
+use flux_limiter::{Clock, ClockError, Timestamp};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
@@ -47,11 +47,11 @@ impl TestClock {
 }
 
 impl Clock for TestClock {
-    fn now(&self) -> Result<u64, ClockError> {
+    fn now(&self) -> Result<Timestamp, ClockError> {
         if self.should_fail.swap(false, Ordering::Relaxed) {
-            Err(ClockError::SystemTimeError)
+            Err(ClockError::other("test clock failure"))
         } else {
-            Ok(self.time.load(Ordering::Relaxed))
+            Ok(Timestamp::from_nanos(self.time.load(Ordering::Relaxed)))
         }
     }
 }
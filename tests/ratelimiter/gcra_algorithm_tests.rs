@@ -4,7 +4,9 @@
 mod tests {
 
     use crate::fixtures::test_clock::TestClock;
-    use flux_limiter::{FluxLimiter, FluxLimiterConfig};
+    use flux_limiter::gcra::{gcra_decide, intervals};
+    use flux_limiter::{Clock, Decision, FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+    use flux_limiter::{Precision, Rounding};
 
     // GCRA algorithm tests
     #[test]
@@ -180,4 +182,335 @@ mod tests {
                 .allowed
         );
     }
+
+    #[test]
+    fn borrowed_key_checks_share_state_with_owned_checks() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 1 req/sec, burst of 1
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        // First sighting inserts the owned key
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        assert_eq!(limiter.client_state.len(), 1);
+        assert!(limiter.client_state.contains_key("client1"));
+
+        // Owned and borrowed checks consume the same allowance
+        assert!(
+            limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        assert!(!limiter.check_request_ref("client1").unwrap().allowed);
+        assert_eq!(limiter.client_state.len(), 1);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn concurrent_checks_never_exceed_burst() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 9.0); // 10 immediate requests
+        let limiter =
+            std::sync::Arc::new(FluxLimiter::<String, _>::with_config(config, clock).unwrap());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    (0..50)
+                        .filter(|_| limiter.check_request_ref("shared").unwrap().allowed)
+                        .count()
+                })
+            })
+            .collect();
+
+        let allowed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(allowed, 10);
+    }
+
+    #[test]
+    fn denial_storms_leave_entries_untouched() {
+        let clock = TestClock::new(5.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        limiter.check_request(0u64).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    (0..100).all(|_| !limiter.check_request(0).unwrap().allowed)
+                })
+            })
+            .collect();
+        assert!(handles.into_iter().all(|h| h.join().unwrap()));
+
+        let entry = limiter.client_state.get(&0).unwrap();
+        assert_eq!(entry.tat_nanos(), 6_000_000_000);
+        assert_eq!(entry.last_access_nanos(), 5_000_000_000);
+        drop(entry);
+
+        // denials still record the time of the latest check once it moves
+        clock.advance(0.5);
+        assert!(!limiter.check_request(0).unwrap().allowed);
+        let entry = limiter.client_state.get(&0).unwrap();
+        assert_eq!(entry.tat_nanos(), 6_000_000_000);
+        assert_eq!(entry.last_access_nanos(), 5_500_000_000);
+    }
+
+    #[test]
+    fn concurrent_owned_and_borrowed_checks_advance_tat_exactly() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 9.0); // 10 immediate requests
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    (0..50)
+                        .filter(|_| match thread % 2 {
+                            0 => limiter.check_request("hot".to_string()).unwrap().allowed,
+                            _ => limiter.check_request_ref("hot").unwrap().allowed,
+                        })
+                        .count()
+                })
+            })
+            .collect();
+
+        let allowed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(allowed, 10);
+        // every allowed request advanced the TAT by exactly one interval
+        assert_eq!(
+            limiter.client_state.get("hot").unwrap().tat_nanos(),
+            10_000_000_000
+        );
+    }
+
+    #[test]
+    fn check_request_at_uses_supplied_timestamps() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+        let second = 1_000_000_000u64;
+
+        // the injected clock is ignored entirely
+        clock.fail_next_call();
+        assert!(
+            limiter
+                .check_request_at(client, 100 * second)
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            !limiter
+                .check_request_at(client, 100 * second + second / 2)
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            limiter
+                .check_request_at(client, 101 * second)
+                .unwrap()
+                .allowed
+        );
+
+        // an out-of-order (older) event is denied
+        assert!(
+            !limiter
+                .check_request_at(client, 99 * second)
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn check_request_at_replays_a_log() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 1.0); // 2 req/sec, burst of 1
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        // (seconds, expected) pairs from an access log
+        let log = [
+            (0.0, true),
+            (0.0, true),
+            (0.0, false),
+            (0.5, true),
+            (0.6, false),
+            (2.0, true),
+        ];
+        for (seconds, expected) in log {
+            let timestamp = (seconds * 1_000_000_000.0) as u64;
+            let decision = limiter.check_request_at("client1", timestamp).unwrap();
+            assert_eq!(decision.allowed, expected, "event at {}s", seconds);
+        }
+    }
+
+    #[test]
+    fn clones_share_state() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let clone = limiter.clone();
+
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert!(clone.check_request("client1").unwrap().allowed);
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+        assert!(!clone.check_request("client1").unwrap().allowed);
+
+        assert_eq!(clone.stats().allowed, 2);
+        assert_eq!(limiter.stats().denied, 2);
+        assert_eq!(clone.client_state.len(), 1);
+
+        clock.advance(1.0);
+        assert!(clone.check_request("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn clones_can_be_moved_to_threads() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 9.0); // 10 immediate requests
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    (0..10)
+                        .filter(|_| limiter.check_request_ref("shared").unwrap().allowed)
+                        .count()
+                })
+            })
+            .collect();
+
+        let allowed: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(allowed, 10);
+    }
+
+    #[test]
+    fn gcra_decide_matches_the_limiter() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(4.0, 2.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let (emission_interval, tolerance) = intervals(4.0, 2.0).unwrap();
+        assert_eq!((emission_interval, tolerance), (250_000_000, 500_000_000));
+
+        let mut tat = 0;
+        for step in 0..40 {
+            // irregular arrivals, bursty at first and then slower
+            clock.advance(if step < 10 { 0.05 } else { 0.2 });
+            let now = clock.now().unwrap().as_nanos();
+            let expected = limiter.check_request("client1").unwrap();
+            let (decision, new_tat) = gcra_decide(tat, now, emission_interval, tolerance);
+            tat = new_tat;
+
+            assert_eq!(decision.is_allowed(), expected.allowed, "step {step}");
+            match decision {
+                Decision::Allowed(meta) => {
+                    assert_eq!(meta.reset_time_nanos, expected.reset_time_nanos)
+                }
+                Decision::Denied(meta) => {
+                    assert_eq!(Some(meta.retry_after_seconds), expected.retry_after_seconds)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gcra_decide_reports_implied_limits_and_keeps_denied_tats() {
+        let (decision, tat) = gcra_decide(0, 0, 1_000_000_000, 0);
+        let Decision::Allowed(meta) = decision else {
+            panic!("first request denied");
+        };
+        assert_eq!((meta.limit_per_second, meta.burst_capacity), (1.0, 0.0));
+        assert_eq!(tat, 1_000_000_000);
+
+        assert_eq!(gcra_decide(tat, 0, 1_000_000_000, 0).1, tat);
+        // an overflowing TAT is denied rather than wrapping
+        let (decision, tat) = gcra_decide(u64::MAX, u64::MAX, 1, 0);
+        assert!(!decision.is_allowed());
+        assert_eq!(tat, u64::MAX);
+
+        assert!(matches!(
+            intervals(0.0, 1.0),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+    }
+
+    #[test]
+    fn precision_controls_nanosecond_rounding() {
+        // 600M req/s is 1.67ns apart, so truncating makes it 1G req/s
+        let rate = 600_000_000.0;
+        let truncate = Precision::default();
+        let nearest = Precision::default().rounding(Rounding::Nearest);
+        let up = Precision::default().rounding(Rounding::Up);
+        assert_eq!(truncate.intervals(rate, 2.0).unwrap(), (1, 2));
+        assert_eq!(nearest.intervals(rate, 2.0).unwrap(), (2, 4));
+        assert_eq!(
+            up.intervals(4.0 / 3.0, 0.5).unwrap(),
+            (750_000_000, 375_000_000)
+        );
+        assert_eq!(up.intervals(3.0, 0.5).unwrap(), (333_333_334, 166_666_667));
+        assert_eq!(intervals(3.0, 0.5).unwrap(), (333_333_333, 166_666_666));
+
+        let coarse = Precision::default().min_emission_interval_nanos(1_000);
+        assert!(matches!(
+            coarse.intervals(rate, 0.0),
+            Err(FluxLimiterError::RateTooHigh)
+        ));
+        assert_eq!(coarse.intervals(1_000_000.0, 0.0).unwrap(), (1_000, 0));
+    }
+
+    #[test]
+    fn limiters_report_effective_nanosecond_values() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(3.0, 1.0);
+        let truncated =
+            FluxLimiter::<String, _>::with_config(config.clone(), clock.clone()).unwrap();
+        let rounded_up = FluxLimiter::<String, _>::with_config(
+            config.precision(Precision::default().rounding(Rounding::Up)),
+            clock.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(truncated.emission_interval_nanos(), 333_333_333);
+        assert_eq!(truncated.tolerance_nanos(), 333_333_333);
+        assert_eq!(rounded_up.emission_interval_nanos(), 333_333_334);
+        assert_eq!(rounded_up.tolerance_nanos(), 333_333_334);
+
+        // the rounded interval is what the schedule advances by
+        rounded_up.check_request_ref("client1").unwrap();
+        let snapshot = rounded_up.client_snapshot("client1").unwrap().unwrap();
+        assert_eq!(snapshot.tat_nanos, 333_333_334);
+    }
+
+    #[test]
+    fn flux_limiter_matches_gcra_decide() {
+        let (emission_interval, tolerance) = intervals(3.0, 2.0).unwrap();
+        let config = FluxLimiterConfig::new(3.0, 2.0);
+        let limiter = FluxLimiter::with_config(config, TestClock::new(0.0)).unwrap();
+
+        // bursts, denials, idle gaps and a request exactly on the schedule
+        let mut tat = 0;
+        for now in [
+            0,
+            1,
+            2,
+            3,
+            100_000_000,
+            333_333_333,
+            2_000_000_000,
+            2_000_000_001,
+        ] {
+            let (expected, expected_tat) = gcra_decide(tat, now, emission_interval, tolerance);
+            let decision = limiter.check_request_at("client1", now).unwrap();
+            let snapshot = limiter.client_snapshot("client1").unwrap().unwrap();
+            assert_eq!(decision.allowed, expected.is_allowed(), "at {now}");
+            assert_eq!(snapshot.tat_nanos, expected_tat, "at {now}");
+            tat = expected_tat;
+        }
+    }
 }
@@ -0,0 +1,132 @@
+// tests/ratelimiter/gcra_property_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use flux_limiter::{FluxLimiterConfig, simulate};
+    use proptest::prelude::*;
+
+    // a config plus a non-decreasing sequence of arrival times
+    fn scenario() -> impl Strategy<Value = (f64, f64, Vec<u64>)> {
+        (
+            0.5f64..5_000.0,
+            0.0f64..20.0,
+            prop::collection::vec(0u64..3_000_000_000, 1..200),
+        )
+            .prop_map(|(rate, burst, gaps)| {
+                let arrivals = gaps
+                    .iter()
+                    .scan(1_700_000_000_000_000_000u64, |now, gap| {
+                        *now += gap;
+                        Some(*now)
+                    })
+                    .collect();
+                (rate, burst, arrivals)
+            })
+    }
+
+    // the emission interval and burst tolerance the limiter actually uses
+    fn intervals(rate: f64, burst: f64) -> (u64, u64) {
+        let increment = (1_000_000_000.0 / rate) as u64;
+        (increment, (burst * increment as f64) as u64)
+    }
+
+    proptest! {
+        #[test]
+        fn allowed_requests_never_exceed_rate_times_window_plus_burst(
+            (rate, burst, arrivals) in scenario()
+        ) {
+            let decisions = simulate(FluxLimiterConfig::new(rate, burst), arrivals.clone()).unwrap();
+            let allowed: Vec<u64> = arrivals
+                .iter()
+                .zip(&decisions)
+                .filter(|(_, decision)| decision.allowed)
+                .map(|(arrival, _)| *arrival)
+                .collect();
+
+            let (increment, tolerance) = intervals(rate, burst);
+            for (start, &from) in allowed.iter().enumerate() {
+                for (offset, &to) in allowed[start..].iter().enumerate() {
+                    // at most one request per interval over the window, plus the burst
+                    let limit = (to - from + tolerance) / increment + 1;
+                    prop_assert!(
+                        (offset as u64) < limit,
+                        "{} requests allowed in {}ns (limit {})",
+                        offset + 1,
+                        to - from,
+                        limit
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn first_request_and_idle_clients_are_always_allowed(
+            (rate, burst, arrivals) in scenario()
+        ) {
+            let decisions = simulate(FluxLimiterConfig::new(rate, burst), arrivals.clone()).unwrap();
+            prop_assert!(decisions[0].allowed);
+
+            let (increment, tolerance) = intervals(rate, burst);
+            let mut latest_reset = 0;
+            for (arrival, decision) in arrivals.iter().zip(&decisions) {
+                // once the previous schedule has fully drained, the client is idle
+                if *arrival >= latest_reset {
+                    prop_assert!(decision.allowed);
+                }
+                if decision.allowed {
+                    latest_reset = decision.reset_time_nanos;
+                    prop_assert!(decision.reset_time_nanos <= arrival + tolerance + increment);
+                }
+            }
+        }
+
+        #[test]
+        fn decision_metadata_stays_in_bounds(
+            (rate, burst, arrivals) in scenario()
+        ) {
+            let decisions = simulate(FluxLimiterConfig::new(rate, burst), arrivals.clone()).unwrap();
+
+            for (arrival, decision) in arrivals.iter().zip(&decisions) {
+                let remaining = decision.remaining_capacity.unwrap();
+                prop_assert!((0.0..=burst + 1e-9).contains(&remaining));
+                prop_assert!(decision.reset_time_nanos >= *arrival);
+                match decision.allowed {
+                    true => prop_assert!(decision.retry_after_seconds.is_none()),
+                    false => prop_assert!(decision.retry_after_seconds.unwrap() > 0.0),
+                }
+            }
+        }
+
+        #[test]
+        fn retrying_after_the_advised_delay_is_allowed(
+            (rate, burst, arrivals) in scenario()
+        ) {
+            let config = FluxLimiterConfig::new(rate, burst);
+            let decisions = simulate(config.clone(), arrivals.clone()).unwrap();
+
+            for (index, decision) in decisions.iter().enumerate() {
+                let Some(retry_after) = decision.retry_after_seconds else {
+                    continue;
+                };
+                // replay the history up to the denial, then retry after the advised delay
+                let retry_at = arrivals[index] + (retry_after * 1_000_000_000.0).ceil() as u64;
+                let replay = arrivals[..index].iter().copied().chain([retry_at]);
+                let retried = simulate(config.clone(), replay).unwrap();
+                prop_assert!(retried.last().unwrap().allowed);
+            }
+        }
+
+        #[test]
+        fn requests_spaced_one_interval_apart_are_all_allowed(
+            rate in 0.5f64..5_000.0,
+            burst in 0.0f64..20.0,
+            count in 1usize..500,
+        ) {
+            let (increment, _) = intervals(rate, burst);
+            let arrivals = (0..count as u64).map(|n| 1_000_000_000 + n * increment);
+            let decisions = simulate(FluxLimiterConfig::new(rate, burst), arrivals).unwrap();
+            prop_assert!(decisions.iter().all(|decision| decision.allowed));
+        }
+    }
+}
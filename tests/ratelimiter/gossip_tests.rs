@@ -0,0 +1,187 @@
+// tests/ratelimiter/gossip_tests.rs
+
+#[cfg(all(test, feature = "gossip"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        ConsumptionSummary, FluxLimiter, FluxLimiterConfig, FluxLimiterError, GossipLimiter,
+        GossipTransport, UdpTransport,
+    };
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // an in-memory network: every node's inbox, indexed by node position
+    type Inboxes = Arc<Mutex<Vec<Vec<ConsumptionSummary<String>>>>>;
+
+    struct MemoryTransport {
+        inboxes: Inboxes,
+        node: usize,
+    }
+
+    impl GossipTransport<String> for MemoryTransport {
+        fn send(&self, summary: &ConsumptionSummary<String>) -> io::Result<()> {
+            let mut inboxes = self.inboxes.lock().unwrap();
+            for (node, inbox) in inboxes.iter_mut().enumerate() {
+                if node != self.node {
+                    inbox.push(summary.clone());
+                }
+            }
+            Ok(())
+        }
+
+        fn receive(&self) -> io::Result<Vec<ConsumptionSummary<String>>> {
+            Ok(std::mem::take(&mut self.inboxes.lock().unwrap()[self.node]))
+        }
+    }
+
+    fn cluster(nodes: usize, clock: &TestClock) -> Vec<GossipLimiter<String, TestClock>> {
+        let inboxes: Inboxes = Arc::new(Mutex::new(vec![Vec::new(); nodes]));
+        (0..nodes)
+            .map(|node| {
+                let config = FluxLimiterConfig::new(1.0, 4.0); // 5 immediate requests
+                let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+                let transport = MemoryTransport {
+                    inboxes: Arc::clone(&inboxes),
+                    node,
+                };
+                GossipLimiter::new(limiter, transport)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn peers_share_one_budget_per_key() {
+        let clock = TestClock::new(0.0);
+        let nodes = cluster(2, &clock);
+        let key = "user_123".to_string();
+
+        for _ in 0..5 {
+            assert!(nodes[0].check_request(key.clone()).unwrap().allowed);
+        }
+        assert_eq!(nodes[0].sync().unwrap(), 0);
+        assert_eq!(nodes[1].sync().unwrap(), 1);
+
+        // the second node has seen the first node's traffic
+        assert!(!nodes[1].check_request(key.clone()).unwrap().allowed);
+        // other keys are untouched
+        assert!(nodes[1].check_request("other".to_string()).unwrap().allowed);
+    }
+
+    #[test]
+    fn own_duplicate_and_stale_summaries_are_ignored() {
+        let clock = TestClock::new(0.0);
+        let nodes = cluster(2, &clock);
+        let summary = |node_id, sequence| ConsumptionSummary {
+            node_id,
+            sequence,
+            requests: vec![("user_123".to_string(), 1)],
+        };
+
+        assert!(!nodes[0].merge(&summary(nodes[0].node_id(), 1)).unwrap());
+        assert!(nodes[0].merge(&summary(7, 2)).unwrap());
+        assert!(!nodes[0].merge(&summary(7, 2)).unwrap());
+        assert!(!nodes[0].merge(&summary(7, 1)).unwrap());
+        assert!(nodes[0].merge(&summary(8, 1)).unwrap());
+    }
+
+    #[test]
+    fn charges_stop_at_an_exhausted_burst() {
+        let clock = TestClock::new(0.0);
+        let nodes = cluster(2, &clock);
+        let key = "user_123".to_string();
+
+        let flood = ConsumptionSummary {
+            node_id: 7,
+            sequence: 1,
+            requests: vec![(key.clone(), 1_000)],
+        };
+        nodes[0].merge(&flood).unwrap();
+        assert!(!nodes[0].check_request(key.clone()).unwrap().allowed);
+
+        // one emission interval later the key conforms again
+        clock.advance(1.0);
+        assert!(nodes[0].check_request(key).unwrap().allowed);
+    }
+
+    #[test]
+    fn charges_respect_max_clients() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 4.0).max_clients(1);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+        let transport = MemoryTransport {
+            inboxes: Arc::new(Mutex::new(vec![Vec::new()])),
+            node: 0,
+        };
+        let node = GossipLimiter::new(limiter, transport);
+        node.check_request("tracked".to_string()).unwrap();
+
+        let summary = ConsumptionSummary {
+            node_id: 7,
+            sequence: 1,
+            requests: vec![("tracked".to_string(), 1), ("new".to_string(), 1)],
+        };
+        assert!(matches!(
+            node.merge(&summary),
+            Err(FluxLimiterError::CapacityExceeded { current: 1, max: 1 })
+        ));
+        assert_eq!(node.limiter().client_state.len(), 1);
+    }
+
+    #[test]
+    fn summaries_are_split_and_numbered() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), clock).unwrap();
+        let transport = MemoryTransport {
+            inboxes: Arc::new(Mutex::new(vec![Vec::new()])),
+            node: 0,
+        };
+        let node = GossipLimiter::new(limiter, transport)
+            .max_keys_per_summary(2)
+            .with_node_id(42);
+
+        for key in ["a", "b", "c", "d", "e"] {
+            node.check_request(key.to_string()).unwrap();
+        }
+        node.check_request("a".to_string()).unwrap();
+
+        let summaries = node.take_summaries();
+        let sequences: Vec<_> = summaries.iter().map(|summary| summary.sequence).collect();
+        assert_eq!(sequences, [1, 2, 3]);
+        assert!(summaries.iter().all(|summary| summary.node_id == 42));
+        let mut requests: Vec<_> = summaries
+            .into_iter()
+            .flat_map(|summary| summary.requests)
+            .collect();
+        requests.sort();
+        assert_eq!(requests[0], ("a".to_string(), 2));
+        assert_eq!(requests.len(), 5);
+
+        // counts are reset by taking them
+        assert!(node.take_summaries().is_empty());
+    }
+
+    #[test]
+    fn udp_transport_delivers_summaries() {
+        let receiver = UdpTransport::<String>::bind("127.0.0.1:0", Vec::<&str>::new()).unwrap();
+        let sender =
+            UdpTransport::<String>::bind("127.0.0.1:0", [receiver.local_addr().unwrap()]).unwrap();
+        let summary = ConsumptionSummary {
+            node_id: 1,
+            sequence: 1,
+            requests: vec![("user_123".to_string(), 3)],
+        };
+
+        sender.send(&summary).unwrap();
+        let mut received = Vec::new();
+        for _ in 0..100 {
+            received = receiver.receive().unwrap();
+            if !received.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(received, [summary]);
+    }
+}
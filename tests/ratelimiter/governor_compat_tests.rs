@@ -0,0 +1,123 @@
+// tests/ratelimiter/governor_compat_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        DirectRateLimiter, FluxLimiterConfig, InsufficientCapacity, Quota, RateLimiter, Timestamp,
+    };
+    use std::num::NonZeroU32;
+    use std::time::Duration;
+
+    fn n(value: u32) -> NonZeroU32 {
+        NonZeroU32::new(value).unwrap()
+    }
+
+    #[test]
+    fn quotas_match_governor_semantics() {
+        let quota = Quota::per_minute(n(30));
+        assert_eq!(quota.burst_size(), n(30));
+        assert_eq!(quota.replenish_interval(), Duration::from_secs(2));
+        assert_eq!(quota.burst_size_replenished_in(), Duration::from_secs(60));
+
+        let quota = Quota::with_period(Duration::from_millis(250))
+            .unwrap()
+            .allow_burst(n(4));
+        assert_eq!(
+            FluxLimiterConfig::from(quota),
+            FluxLimiterConfig::new(4.0, 3.0)
+        );
+
+        assert!(Quota::with_period(Duration::ZERO).is_none());
+        // more than one request per nanosecond is capped there
+        assert_eq!(
+            Quota::per_second(NonZeroU32::MAX).replenish_interval(),
+            Duration::from_nanos(1)
+        );
+    }
+
+    #[test]
+    fn check_key_allows_the_burst_then_reports_when_to_retry() {
+        let clock = TestClock::new(10.0);
+        let limiter = RateLimiter::keyed_with_clock(Quota::per_second(n(2)), clock.clone());
+
+        assert!(limiter.check_key(&"client1").is_ok());
+        assert!(limiter.check_key(&"client1").is_ok());
+        let negative = limiter.check_key(&"client1").unwrap_err();
+        assert_eq!(negative.wait_time(), Duration::from_millis(500));
+        assert_eq!(
+            negative.earliest_possible(),
+            Timestamp::from_nanos(10_500_000_000)
+        );
+        assert_eq!(
+            negative.wait_time_from(Timestamp::from_nanos(10_200_000_000)),
+            Duration::from_millis(300)
+        );
+        assert_eq!(negative.quota(), limiter.quota());
+
+        // keys are limited separately
+        assert!(limiter.check_key(&"client2").is_ok());
+        assert_eq!(limiter.len(), 2);
+
+        clock.advance(0.5);
+        assert!(limiter.check_key(&"client1").is_ok());
+    }
+
+    #[test]
+    fn check_n_is_all_or_nothing() {
+        let clock = TestClock::new(0.0);
+        let limiter = DirectRateLimiter::direct_with_clock(Quota::per_second(n(3)), clock.clone());
+
+        assert_eq!(limiter.check_n(n(4)), Err(InsufficientCapacity(3)));
+        assert!(limiter.check_n(n(2)).unwrap().is_ok());
+        // only one request is left, so two are refused and none consumed
+        assert!(limiter.check_n(n(2)).unwrap().is_err());
+        assert!(limiter.check().is_ok());
+        assert!(limiter.check().is_err());
+    }
+
+    #[test]
+    fn clock_failures_deny_for_one_interval() {
+        let clock = TestClock::new(0.0);
+        let limiter = RateLimiter::keyed_with_clock(Quota::per_second(n(4)), clock.clone());
+
+        clock.fail_next_call();
+        let negative = limiter.check_key(&"client1").unwrap_err();
+        assert_eq!(negative.wait_time(), Duration::from_millis(250));
+        assert_eq!(negative.earliest_possible(), Timestamp::from_nanos(0));
+    }
+
+    #[test]
+    fn retain_recent_forgets_replenished_keys() {
+        let clock = TestClock::new(0.0);
+        let limiter = RateLimiter::keyed_with_clock(Quota::per_second(n(1)), clock.clone());
+
+        limiter.check_key(&"client1").unwrap();
+        clock.advance(0.5);
+        limiter.check_key(&"client2").unwrap();
+
+        clock.advance(0.5);
+        limiter.retain_recent();
+        assert_eq!(limiter.len(), 1);
+        assert!(!limiter.is_empty());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn until_key_ready_waits_for_capacity() {
+        use flux_limiter::TokioClock;
+
+        let limiter = RateLimiter::keyed_with_clock(Quota::per_second(n(1)), TokioClock::new());
+        let start = tokio::time::Instant::now();
+
+        limiter.until_key_ready(&"client1").await;
+        limiter.until_key_ready(&"client1").await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        assert_eq!(
+            limiter.until_key_n_ready(&"client1", n(2)).await,
+            Err(InsufficientCapacity(1))
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// tests/ratelimiter/grpc_tests.rs
+
+#[cfg(all(test, feature = "tonic"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        BearerTokenHash, FluxLimiter, FluxLimiterConfig, FluxLimiterDecision,
+        FluxLimiterInterceptor, GrpcKey,
+    };
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tonic::service::Interceptor;
+    use tonic::{Code, Request};
+    use tonic_types::StatusExt;
+
+    fn request_with_key(key: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("x-api-key", key.parse().unwrap());
+        request
+    }
+
+    #[test]
+    fn denied_calls_are_resource_exhausted_with_retry_info() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 0.0); // 2 req/sec, no burst
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock).unwrap());
+        let mut interceptor =
+            FluxLimiterInterceptor::new(limiter, GrpcKey::Metadata("x-api-key".into()));
+
+        let allowed = interceptor.call(request_with_key("key-1")).unwrap();
+        let decision = allowed.extensions().get::<FluxLimiterDecision>().unwrap();
+        assert!(decision.allowed);
+
+        let status = interceptor.call(request_with_key("key-1")).unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        let retry_info = status.get_details_retry_info().unwrap();
+        assert_eq!(retry_info.retry_delay, Some(Duration::from_millis(500)));
+
+        // other keys are unaffected
+        assert!(interceptor.call(request_with_key("key-2")).is_ok());
+    }
+
+    #[test]
+    fn calls_without_a_key_pass_through() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock).unwrap());
+        let mut interceptor = FluxLimiterInterceptor::new(limiter.clone(), GrpcKey::PeerAddr);
+
+        // in-process requests have no peer address
+        for _ in 0..3 {
+            let request = interceptor.call(Request::new(())).unwrap();
+            assert!(request.extensions().get::<FluxLimiterDecision>().is_none());
+        }
+        assert!(limiter.client_state.is_empty());
+    }
+
+    #[test]
+    fn custom_extractors_key_calls() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock).unwrap());
+        let mut interceptor =
+            FluxLimiterInterceptor::with_extractor(limiter.clone(), BearerTokenHash);
+
+        let with_token = || {
+            let mut request = Request::new(());
+            request
+                .metadata_mut()
+                .insert("authorization", "Bearer token-1".parse().unwrap());
+            request
+        };
+        assert!(interceptor.call(with_token()).is_ok());
+        assert!(interceptor.call(with_token()).is_err());
+        assert!(
+            !limiter
+                .client_state
+                .iter()
+                .any(|entry| entry.key().contains("token-1"))
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// tests/ratelimiter/hasher_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{BuildHasher, BuildHasherDefault};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // counts how many hashers the limiter's map builds
+    #[derive(Clone, Default)]
+    struct CountingHasher {
+        built: Arc<AtomicUsize>,
+    }
+
+    impl BuildHasher for CountingHasher {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            self.built.fetch_add(1, Ordering::Relaxed);
+            DefaultHasher::new()
+        }
+    }
+
+    #[test]
+    fn custom_hasher_limits_like_the_default() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::<String, _, _>::with_config_and_hasher(
+            FluxLimiterConfig::new(1.0, 1.0),
+            clock.clone(),
+            BuildHasherDefault::<DefaultHasher>::default(),
+        )
+        .unwrap();
+
+        assert!(limiter.check_request("client".to_string()).unwrap().allowed);
+        assert!(limiter.check_request("client".to_string()).unwrap().allowed);
+        assert!(!limiter.check_request("client".to_string()).unwrap().allowed);
+        assert!(limiter.check_request("other".to_string()).unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request("client".to_string()).unwrap().allowed);
+        assert!(limiter.client_state.contains_key("client"));
+    }
+
+    #[test]
+    fn custom_hasher_is_used_for_client_lookups() {
+        let hasher = CountingHasher::default();
+        let built = Arc::clone(&hasher.built);
+        let limiter = FluxLimiter::with_config_and_hasher(
+            FluxLimiterConfig::new(10.0, 5.0),
+            TestClock::new(0.0),
+            hasher,
+        )
+        .unwrap();
+
+        let before = built.load(Ordering::Relaxed);
+        limiter.check_request(7u64).unwrap();
+        limiter.check_request_ref(&7u64).unwrap();
+        assert!(built.load(Ordering::Relaxed) > before);
+    }
+
+    #[test]
+    fn custom_hasher_config_is_validated() {
+        let result = FluxLimiter::<u64, _, _>::with_config_and_hasher(
+            FluxLimiterConfig::new(-1.0, 0.0),
+            TestClock::new(0.0),
+            BuildHasherDefault::<DefaultHasher>::default(),
+        );
+
+        assert!(matches!(result, Err(FluxLimiterError::InvalidRate)));
+    }
+}
@@ -0,0 +1,94 @@
+// tests/ratelimiter/http_headers_tests.rs
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FailurePolicy, FluxLimiter, FluxLimiterConfig};
+    use http::{HeaderMap, StatusCode};
+
+    #[test]
+    fn allowed_decisions_report_limit_remaining_and_reset() {
+        let clock = TestClock::new(100.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(2.0, 3.0), clock).unwrap();
+
+        let mut headers = HeaderMap::new();
+        limiter
+            .check_request("client1")
+            .unwrap()
+            .apply_headers(&mut headers);
+
+        assert_eq!(headers["x-ratelimit-limit"], "2");
+        assert_eq!(headers["x-ratelimit-remaining"], "2");
+        assert_eq!(headers["x-ratelimit-reset"], "101"); // TAT = t=100.5, rounded up
+        assert!(!headers.contains_key("retry-after"));
+    }
+
+    #[test]
+    fn denials_round_retry_after_up() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(0.4, 0.0), clock.clone()).unwrap();
+        limiter.check_request("client1").unwrap();
+        clock.advance(1.0);
+
+        let mut headers = HeaderMap::new();
+        let decision = limiter.check_request("client1").unwrap();
+        decision.apply_headers(&mut headers);
+
+        assert_eq!(decision.retry_after_seconds, Some(1.5));
+        assert_eq!(headers["retry-after"], "2");
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+        assert_eq!(headers["x-ratelimit-limit"], "0.4");
+    }
+
+    #[test]
+    fn existing_headers_are_replaced() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "99".parse().unwrap());
+        limiter
+            .check_request("client1")
+            .unwrap()
+            .apply_headers(&mut headers);
+
+        assert_eq!(headers.get_all("x-ratelimit-remaining").iter().count(), 1);
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+    }
+
+    #[test]
+    fn clock_failures_omit_reset_and_remaining() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let response = limiter
+            .check_request("client1")
+            .unwrap()
+            .to_429_response(|_| ());
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "1");
+        assert!(!response.headers().contains_key("x-ratelimit-reset"));
+        assert!(!response.headers().contains_key("x-ratelimit-remaining"));
+    }
+
+    #[test]
+    fn response_body_is_built_from_the_decision() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+        limiter.check_request("client1").unwrap();
+
+        let response = limiter
+            .check_request("client1")
+            .unwrap()
+            .to_429_response(|decision| decision.retry_after_seconds);
+
+        assert_eq!(*response.body(), Some(1.0));
+    }
+}
@@ -0,0 +1,72 @@
+// tests/ratelimiter/intern_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, KeyInterner, ShardedFluxLimiter};
+    use std::sync::Arc;
+
+    fn limiter(clock: &TestClock) -> FluxLimiter<Arc<str>, TestClock> {
+        FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap()
+    }
+
+    #[test]
+    fn str_checks_allocate_each_key_once() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        assert!(limiter.check_request_str("token-1").unwrap().allowed);
+        assert!(!limiter.check_request_str("token-1").unwrap().allowed);
+        assert!(limiter.check_request_str("token-2").unwrap().allowed);
+
+        // the snapshot shares the stored allocation
+        let stored = limiter
+            .client_state
+            .iter()
+            .find(|entry| &**entry.key() == "token-1")
+            .map(|entry| Arc::clone(entry.key()))
+            .unwrap();
+        let snapshot = limiter.export_state();
+        let exported = snapshot
+            .clients
+            .iter()
+            .find(|(key, _)| &**key == "token-1")
+            .map(|(key, _)| key)
+            .unwrap();
+        assert!(Arc::ptr_eq(&stored, exported));
+    }
+
+    #[test]
+    fn sharded_limiters_accept_str_keys() {
+        let clock = TestClock::new(0.0);
+        let limiter: ShardedFluxLimiter<Arc<str>, _> =
+            ShardedFluxLimiter::new(FluxLimiterConfig::new(1.0, 0.0), 4, clock).unwrap();
+
+        assert!(limiter.check_request_str("token-1").unwrap().allowed);
+        assert!(!limiter.check_request_str("token-1").unwrap().allowed);
+    }
+
+    #[test]
+    fn interned_keys_are_shared_between_limiters() {
+        let clock = TestClock::new(0.0);
+        let first = limiter(&clock);
+        let second = limiter(&clock);
+        let interner = KeyInterner::new();
+
+        let key = interner.intern("token-1");
+        assert!(Arc::ptr_eq(&key, &interner.intern("token-1")));
+        first.check_request(Arc::clone(&key)).unwrap();
+        second.check_request(interner.intern("token-1")).unwrap();
+        assert_eq!(interner.len(), 1);
+        drop(key);
+
+        // keys stay interned while a limiter still tracks them
+        assert_eq!(interner.prune(), 0);
+        clock.advance(10.0);
+        first.cleanup_stale_clients(1_000_000_000).unwrap();
+        second.cleanup_stale_clients(1_000_000_000).unwrap();
+        assert_eq!(interner.prune(), 1);
+        assert!(interner.is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// tests/ratelimiter/ip_prefix_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FluxLimiter, FluxLimiterConfig, FluxLimiterError, IpPrefix, IpPrefixer, KeyExtractor,
+        PeerIp, RequestInfo,
+    };
+    use std::net::IpAddr;
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn default_prefixes_group_ipv4_by_24_and_ipv6_by_64() {
+        let prefixer = IpPrefixer::default();
+
+        assert_eq!(
+            prefixer.prefix(ip("203.0.113.7")).to_string(),
+            "203.0.113.0/24"
+        );
+        assert_eq!(
+            prefixer.prefix(ip("2001:db8:1:2:ffff::1")),
+            prefixer.prefix(ip("2001:db8:1:2::9"))
+        );
+        assert_ne!(
+            prefixer.prefix(ip("2001:db8:1:2::1")),
+            prefixer.prefix(ip("2001:db8:1:3::1"))
+        );
+
+        // IPv4-mapped IPv6 peers are grouped with plain IPv4
+        assert_eq!(
+            prefixer.prefix(ip("::ffff:203.0.113.9")),
+            prefixer.prefix(ip("203.0.113.7"))
+        );
+    }
+
+    #[test]
+    fn prefix_lengths_are_configurable_and_validated() {
+        let prefixer = IpPrefixer::new(32, 48).unwrap();
+        assert_eq!(
+            prefixer.prefix(ip("203.0.113.7")).to_string(),
+            "203.0.113.7/32"
+        );
+        assert_eq!(
+            prefixer.prefix(ip("2001:db8:1:2::1")).to_string(),
+            "2001:db8:1::/48"
+        );
+
+        assert!(matches!(
+            IpPrefixer::new(33, 64),
+            Err(FluxLimiterError::InvalidIpPrefix(_))
+        ));
+        assert!(IpPrefixer::new(24, 129).is_err());
+        assert!(IpPrefix::new(ip("203.0.113.7"), 33).is_err());
+    }
+
+    #[test]
+    fn prefixes_parse_and_contain_addresses() {
+        let prefix: IpPrefix = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(prefix.network(), ip("10.0.0.0"));
+        assert_eq!(prefix.prefix_len(), 8);
+        assert!(prefix.contains(ip("10.200.0.1")));
+        assert!(!prefix.contains(ip("11.0.0.1")));
+        assert!(!prefix.contains(ip("::a00:1")));
+
+        let single: IpPrefix = "2001:db8::1".parse().unwrap();
+        assert_eq!(single, IpPrefix::from(ip("2001:db8::1")));
+        assert_eq!(single.prefix_len(), 128);
+
+        for invalid in ["10.0.0.0/33", "10.0.0.0/x", "nope/8", ""] {
+            assert!(invalid.parse::<IpPrefix>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn rotating_addresses_within_a_prefix_share_a_budget() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::<IpPrefix, _>::with_config(FluxLimiterConfig::new(1.0, 1.0), clock)
+                .unwrap();
+        let prefixer = IpPrefixer::default();
+
+        for host in ["2001:db8::1", "2001:db8::2", "2001:db8::3"] {
+            limiter.check_request(prefixer.prefix(ip(host))).unwrap();
+        }
+        assert!(
+            !limiter
+                .check_request(prefixer.prefix(ip("2001:db8::4")))
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            limiter
+                .check_request(prefixer.prefix(ip("2001:db9::1")))
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn peer_ip_can_key_by_prefix() {
+        struct Peer(IpAddr);
+
+        impl RequestInfo for Peer {
+            fn peer_ip(&self) -> Option<IpAddr> {
+                Some(self.0)
+            }
+
+            fn header(&self, _name: &str) -> Option<&str> {
+                None
+            }
+        }
+
+        let extractor = PeerIp::new().aggregate(IpPrefixer::default());
+        assert_eq!(
+            extractor.extract(&Peer(ip("198.51.100.23"))).as_deref(),
+            Some("198.51.100.0/24")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn prefixes_serialize_as_strings() {
+        let prefix: IpPrefix = "198.51.100.0/24".parse().unwrap();
+        let json = serde_json::to_string(&prefix).unwrap();
+        assert_eq!(json, "\"198.51.100.0/24\"");
+        assert_eq!(serde_json::from_str::<IpPrefix>(&json).unwrap(), prefix);
+        assert!(serde_json::from_str::<IpPrefix>("\"198.51.100.0/40\"").is_err());
+    }
+
+    #[cfg(feature = "ipnet")]
+    #[test]
+    fn prefixes_convert_to_and_from_ipnet() {
+        let net: ipnet::IpNet = "2001:db8::/32".parse().unwrap();
+        let prefix = IpPrefix::from(net);
+        assert_eq!(prefix.to_string(), "2001:db8::/32");
+        assert_eq!(ipnet::IpNet::from(prefix), net);
+    }
+}
@@ -0,0 +1,51 @@
+// tests/ratelimiter/iter_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, IteratorExt, SystemClock};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn throttle_sleeps_until_items_conform() {
+        let config = FluxLimiterConfig::new(20.0, 0.0); // one item every 50ms
+        let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+
+        let start = Instant::now();
+        let items: Vec<_> = (1..=3)
+            .throttle(&limiter, |_| "batch")
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(items, vec![1, 2, 3]);
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[test]
+    fn throttle_keys_are_independent() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        // one item per key never waits, so the frozen test clock is fine
+        let items: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .throttle(&limiter, |key| *key)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(items, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn throttle_yields_limiter_errors() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let mut items = [1, 2].into_iter().throttle(&limiter, |n| *n);
+        assert!(items.next().unwrap().is_err());
+        assert_eq!(items.next().unwrap().unwrap(), 2);
+        assert!(items.next().is_none());
+    }
+}
@@ -0,0 +1,138 @@
+// tests/ratelimiter/jitter_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FailurePolicy, FluxLimiter, FluxLimiterConfig, FluxLimiterError, JitterStrategy,
+        SeededJitter,
+    };
+
+    struct FixedJitter(f64);
+
+    impl JitterStrategy for FixedJitter {
+        fn jitter(&self, _retry_after_seconds: f64, _max_seconds: f64) -> f64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn uniform_jitter_stays_within_bounds() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(0.5);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        limiter.check_request("client").unwrap();
+        let delays: Vec<f64> = (0..200)
+            .map(|_| {
+                limiter
+                    .check_request("client")
+                    .unwrap()
+                    .retry_after_seconds
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(delays.iter().all(|delay| (1.0..=1.5).contains(delay)));
+        // the delays are actually spread out
+        let spread = delays.iter().cloned().fold(f64::MIN, f64::max)
+            - delays.iter().cloned().fold(f64::MAX, f64::min);
+        assert!(spread > 0.25);
+    }
+
+    #[test]
+    fn strategies_are_pluggable_and_clamped() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(0.5);
+
+        let limiter = FluxLimiter::with_config(config.clone(), clock.clone())
+            .unwrap()
+            .with_jitter_strategy(FixedJitter(0.25));
+        limiter.check_request("client").unwrap();
+        let decision = limiter.check_request("client").unwrap();
+        assert_eq!(decision.retry_after_seconds, Some(1.25));
+
+        // out-of-range answers are clamped to the configured bound
+        let limiter = FluxLimiter::with_config(config.clone(), clock.clone())
+            .unwrap()
+            .with_jitter_strategy(FixedJitter(10.0));
+        limiter.check_request("client").unwrap();
+        let decision = limiter.check_request("client").unwrap();
+        assert_eq!(decision.retry_after_seconds, Some(1.5));
+
+        let limiter = FluxLimiter::with_config(config, clock)
+            .unwrap()
+            .with_jitter_strategy(FixedJitter(-3.0));
+        limiter.check_request("client").unwrap();
+        let decision = limiter.check_request("client").unwrap();
+        assert_eq!(decision.retry_after_seconds, Some(1.0));
+    }
+
+    #[test]
+    fn no_jitter_by_default() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock)
+            .unwrap()
+            .with_jitter_strategy(FixedJitter(0.25));
+
+        limiter.check_request("client").unwrap();
+        let decision = limiter.check_request("client").unwrap();
+        assert_eq!(decision.retry_after_seconds, Some(1.0));
+    }
+
+    #[test]
+    fn fail_closed_denials_are_jittered() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .retry_jitter(0.5)
+            .failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone())
+            .unwrap()
+            .with_jitter_strategy(FixedJitter(0.5));
+
+        clock.fail_next_call();
+        let decision = limiter.check_request("client").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(1.5));
+    }
+
+    #[test]
+    fn invalid_jitter_is_rejected() {
+        for max_seconds in [-1.0, f64::NAN, f64::INFINITY] {
+            let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(max_seconds);
+            assert!(matches!(
+                config.validate(),
+                Err(FluxLimiterError::InvalidRetryJitter)
+            ));
+        }
+    }
+
+    #[test]
+    fn seeded_jitter_repeats_across_runs() {
+        let delays = |seed| {
+            let clock = TestClock::new(0.0);
+            let config = FluxLimiterConfig::new(1.0, 0.0).retry_jitter(0.5);
+            let limiter = FluxLimiter::with_config(config, clock)
+                .unwrap()
+                .with_jitter_strategy(SeededJitter::new(seed));
+            limiter.check_request("client").unwrap();
+            (0..50)
+                .map(|_| {
+                    limiter
+                        .check_request("client")
+                        .unwrap()
+                        .retry_after_seconds
+                        .unwrap()
+                })
+                .collect::<Vec<f64>>()
+        };
+
+        let first = delays(7);
+        assert_eq!(first, delays(7));
+        assert_ne!(first, delays(8));
+        assert!(first.iter().all(|delay| (1.0..=1.5).contains(delay)));
+        // the draws are spread out, not stuck on one value
+        assert!(first.iter().any(|delay| *delay < 1.25) && first.iter().any(|delay| *delay > 1.25));
+    }
+}
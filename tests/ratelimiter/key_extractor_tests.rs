@@ -0,0 +1,155 @@
+// tests/ratelimiter/key_extractor_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use flux_limiter::{
+        BearerTokenHash, HeaderKey, KeyExtractor, PeerIp, RequestInfo, RouteTemplate,
+    };
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+
+    // request type with whatever a test needs
+    #[derive(Default)]
+    struct FakeRequest {
+        peer: Option<IpAddr>,
+        headers: HashMap<&'static str, &'static str>,
+        route: Option<&'static str>,
+    }
+
+    impl FakeRequest {
+        fn from_peer(peer: &str) -> Self {
+            Self {
+                peer: Some(peer.parse().unwrap()),
+                ..Self::default()
+            }
+        }
+
+        fn with_header(mut self, name: &'static str, value: &'static str) -> Self {
+            self.headers.insert(name, value);
+            self
+        }
+    }
+
+    impl RequestInfo for FakeRequest {
+        fn peer_ip(&self) -> Option<IpAddr> {
+            self.peer
+        }
+
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers.get(name).copied()
+        }
+
+        fn route_template(&self) -> Option<String> {
+            self.route.map(str::to_owned)
+        }
+    }
+
+    fn proxied() -> PeerIp {
+        PeerIp::new()
+            .trust_proxies("10.0.0.0".parse().unwrap(), 8)
+            .trust_proxies("2001:db8::".parse().unwrap(), 32)
+    }
+
+    #[test]
+    fn peer_ip_ignores_forwarded_for_from_untrusted_peers() {
+        let request =
+            FakeRequest::from_peer("203.0.113.7").with_header("x-forwarded-for", "1.1.1.1");
+
+        assert_eq!(
+            PeerIp::new().extract(&request).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(proxied().extract(&request).as_deref(), Some("203.0.113.7"));
+        assert_eq!(PeerIp::new().extract(&FakeRequest::default()), None);
+    }
+
+    #[test]
+    fn peer_ip_walks_forwarded_for_past_trusted_proxies() {
+        // the leftmost entry is spoofed by the client and must be ignored
+        let request = FakeRequest::from_peer("10.1.2.3")
+            .with_header("x-forwarded-for", "6.6.6.6, 198.51.100.9, 10.9.9.9");
+        assert_eq!(proxied().extract(&request).as_deref(), Some("198.51.100.9"));
+
+        let request =
+            FakeRequest::from_peer("2001:db8::1").with_header("x-forwarded-for", "198.51.100.9");
+        assert_eq!(proxied().extract(&request).as_deref(), Some("198.51.100.9"));
+
+        // a trusted peer without the header is the client
+        let request = FakeRequest::from_peer("10.1.2.3");
+        assert_eq!(proxied().extract(&request).as_deref(), Some("10.1.2.3"));
+
+        // a malformed entry stops at the proxy that reported it
+        let request = FakeRequest::from_peer("10.1.2.3")
+            .with_header("x-forwarded-for", "198.51.100.9, garbage, 10.9.9.9");
+        assert_eq!(proxied().extract(&request).as_deref(), Some("10.9.9.9"));
+    }
+
+    #[test]
+    fn header_and_route_extractors() {
+        let request = FakeRequest {
+            route: Some("GET /users/<id>"),
+            ..FakeRequest::from_peer("203.0.113.7").with_header("x-api-key", "key-1")
+        };
+
+        assert_eq!(
+            HeaderKey::new("x-api-key").extract(&request).as_deref(),
+            Some("key-1")
+        );
+        assert_eq!(HeaderKey::new("x-tenant").extract(&request), None);
+        assert_eq!(
+            (RouteTemplate, PeerIp::new()).extract(&request).as_deref(),
+            Some("GET /users/<id>|203.0.113.7")
+        );
+        assert_eq!(
+            (RouteTemplate, HeaderKey::new("x-tenant")).extract(&request),
+            None
+        );
+    }
+
+    #[test]
+    fn bearer_tokens_are_hashed() {
+        let first = FakeRequest::default().with_header("authorization", "Bearer secret-token");
+        let again = FakeRequest::default().with_header("authorization", "bearer  secret-token ");
+        let other = FakeRequest::default().with_header("authorization", "Bearer other-token");
+
+        let key = BearerTokenHash.extract(&first).unwrap();
+        assert!(key.starts_with("bearer:"));
+        assert!(!key.contains("secret"));
+        assert_eq!(BearerTokenHash.extract(&again), Some(key.clone()));
+        assert_ne!(BearerTokenHash.extract(&other), Some(key));
+
+        let basic = FakeRequest::default().with_header("authorization", "Basic dXNlcjpwYXNz");
+        assert_eq!(BearerTokenHash.extract(&basic), None);
+        assert_eq!(BearerTokenHash.extract(&FakeRequest::default()), None);
+    }
+
+    #[test]
+    fn closures_are_extractors() {
+        let tenant = |request: &FakeRequest| request.header("x-tenant").map(str::to_uppercase);
+        let request = FakeRequest::default().with_header("x-tenant", "acme");
+        assert_eq!(tenant.extract(&request).as_deref(), Some("ACME"));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_requests_read_the_peer_from_extensions() {
+        let mut request = http::Request::builder()
+            .header("x-api-key", "key-1")
+            .body(())
+            .unwrap();
+        assert_eq!(PeerIp::new().extract(&request), None);
+
+        request
+            .extensions_mut()
+            .insert("192.0.2.1:4000".parse::<std::net::SocketAddr>().unwrap());
+        assert_eq!(
+            PeerIp::new().extract(&request).as_deref(),
+            Some("192.0.2.1")
+        );
+        assert_eq!(
+            HeaderKey::new("x-api-key").extract(&request).as_deref(),
+            Some("key-1")
+        );
+    }
+}
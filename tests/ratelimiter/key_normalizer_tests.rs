@@ -0,0 +1,65 @@
+// tests/ratelimiter/key_normalizer_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, KeyExtractor, KeyNormalizer};
+
+    #[test]
+    fn steps_run_in_order() {
+        let normalizer = KeyNormalizer::new().trim().lowercase().strip_prefix("bot/");
+        assert_eq!(normalizer.normalize("  Bot/Crawler-1.0\n"), "crawler-1.0");
+
+        // stripping before lowercasing only matches the exact prefix
+        let normalizer = KeyNormalizer::new().strip_prefix("bot/").lowercase();
+        assert_eq!(normalizer.normalize("Bot/Crawler"), "bot/crawler");
+        assert_eq!(KeyNormalizer::new().normalize(" As Is "), " As Is ");
+    }
+
+    #[test]
+    fn hashing_is_stable_and_fixed_length() {
+        let normalizer = KeyNormalizer::new().trim().lowercase().hash();
+        let short = normalizer.normalize("Mozilla/5.0");
+
+        assert_eq!(short, normalizer.normalize("  MOZILLA/5.0 "));
+        assert_eq!(short.len(), 16);
+        assert_eq!(normalizer.normalize(&"x".repeat(4096)).len(), 16);
+        assert_ne!(short, normalizer.normalize("curl/8.0"));
+    }
+
+    #[test]
+    fn spellings_of_one_client_share_a_budget() {
+        let clock = TestClock::new(0.0);
+        // 1 req/sec, burst of 1
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), clock).unwrap();
+        let normalizer = KeyNormalizer::new().trim().lowercase();
+
+        for spelling in ["API-Key-1", " api-key-1", "Api-Key-1 "] {
+            limiter
+                .check_request(normalizer.normalize(spelling))
+                .unwrap();
+        }
+        assert_eq!(limiter.client_state.len(), 1);
+        assert!(
+            limiter
+                .client_snapshot(normalizer.normalize("API-KEY-1").as_str())
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn normalized_extractor_drops_empty_keys() {
+        let extractor = KeyNormalizer::new()
+            .trim()
+            .lowercase()
+            .extractor(|value: &str| Some(value.to_owned()));
+
+        assert_eq!(
+            extractor.extract("  Curl/8.0 ").as_deref(),
+            Some("curl/8.0")
+        );
+        assert_eq!(extractor.extract("   "), None);
+    }
+}
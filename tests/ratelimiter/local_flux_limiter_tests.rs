@@ -0,0 +1,115 @@
+// tests/ratelimiter/local_flux_limiter_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FailurePolicy, FluxLimiter, FluxLimiterConfig, FluxLimiterError, LocalFluxLimiter,
+    };
+
+    #[test]
+    fn decisions_match_flux_limiter() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(4.0, 2.0);
+        let shared = FluxLimiter::with_config(config.clone(), clock.clone()).unwrap();
+        let local = LocalFluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for step in [0.0, 0.0, 0.0, 0.1, 0.15, 0.65, 0.0, 4.0] {
+            clock.advance(step);
+            for client in ["client1", "client2"] {
+                let expected = shared.check_request(client).unwrap();
+                let decision = local.check_request(client).unwrap();
+                assert_eq!(decision.allowed, expected.allowed);
+                assert_eq!(decision.retry_after_seconds, expected.retry_after_seconds);
+                assert_eq!(decision.remaining_capacity, expected.remaining_capacity);
+                assert_eq!(decision.reset_time_nanos, expected.reset_time_nanos);
+            }
+        }
+        assert_eq!(local.len(), 2);
+    }
+
+    #[test]
+    fn clones_share_client_state() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            LocalFluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock).unwrap();
+        let clone = limiter.clone();
+
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert!(!clone.check_request("client1").unwrap().allowed);
+        assert_eq!(clone.len(), 1);
+    }
+
+    #[test]
+    fn borrowed_keys_reach_the_same_client() {
+        let clock = TestClock::new(0.0);
+        let limiter = LocalFluxLimiter::<String, _>::with_config(
+            FluxLimiterConfig::new(1.0, 0.0),
+            clock.clone(),
+        )
+        .unwrap();
+
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        assert!(
+            !limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        clock.advance(1.0);
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn clock_failures_follow_the_failure_policy() {
+        let clock = TestClock::new(0.0);
+        let propagate =
+            LocalFluxLimiter::with_config(FluxLimiterConfig::new(2.0, 0.0), clock.clone()).unwrap();
+        let closed = LocalFluxLimiter::with_config(
+            FluxLimiterConfig::new(2.0, 0.0).failure_policy(FailurePolicy::FailClosed),
+            clock.clone(),
+        )
+        .unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            propagate.check_request("client1"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+
+        clock.fail_next_call();
+        let decision = closed.check_request("client1").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(0.5));
+        assert!(closed.is_empty());
+    }
+
+    #[test]
+    fn cleanup_reports_removed_clients() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            LocalFluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+        limiter.check_request("client1").unwrap(); // TAT = t=1
+
+        clock.set_time(5.0);
+        limiter.check_request("client2").unwrap(); // TAT = t=6
+
+        let report = limiter.cleanup_stale_clients(1_000_000_000).unwrap();
+        assert_eq!(
+            (report.scanned, report.removed, report.remaining),
+            (2, 1, 1)
+        );
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let result = LocalFluxLimiter::<&str, _>::with_config(
+            FluxLimiterConfig::new(0.0, 1.0),
+            TestClock::new(0.0),
+        );
+        assert!(matches!(result, Err(FluxLimiterError::InvalidRate)));
+    }
+}
@@ -0,0 +1,102 @@
+// tests/ratelimiter/local_limiter_tests.rs
+
+#[cfg(all(test, feature = "hashbrown"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, LocalLimiter, simulate};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    type Hasher = BuildHasherDefault<DefaultHasher>;
+
+    fn limiter(
+        rate: f64,
+        burst: f64,
+        clock: TestClock,
+    ) -> LocalLimiter<&'static str, TestClock, Hasher> {
+        LocalLimiter::new(rate, burst, clock).unwrap()
+    }
+
+    #[test]
+    fn decisions_match_flux_limiter() {
+        let arrivals = [
+            0,
+            0,
+            0,
+            100_000_000,
+            250_000_000,
+            900_000_000,
+            900_000_001,
+            5_000_000_000,
+        ];
+        let expected = simulate(FluxLimiterConfig::new(4.0, 2.0), arrivals).unwrap();
+
+        let mut local = limiter(4.0, 2.0, TestClock::new(0.0));
+        for (arrival, expected) in arrivals.into_iter().zip(expected) {
+            let decision = local.check_request_at("client1", arrival);
+            assert_eq!(decision.allowed, expected.allowed);
+            assert_eq!(decision.retry_after_seconds, expected.retry_after_seconds);
+            assert_eq!(decision.remaining_capacity, expected.remaining_capacity);
+            assert_eq!(decision.reset_time_nanos, expected.reset_time_nanos);
+        }
+    }
+
+    #[test]
+    fn reads_the_injected_clock() {
+        let clock = TestClock::new(0.0);
+        let mut local = limiter(1.0, 0.0, clock.clone());
+
+        assert!(local.check_request("client1").unwrap().allowed);
+        let denied = local.check_request("client1").unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after_seconds, Some(1.0));
+
+        clock.advance(1.0);
+        assert!(local.check_request("client1").unwrap().allowed);
+        assert!(local.check_request("client2").unwrap().allowed);
+        assert_eq!(local.len(), 2);
+    }
+
+    #[test]
+    fn clock_failures_are_reported() {
+        let clock = TestClock::new(0.0);
+        let mut local = limiter(1.0, 0.0, clock.clone());
+
+        clock.fail_next_call();
+        assert!(matches!(
+            local.check_request("client1"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+        assert!(local.is_empty());
+    }
+
+    #[test]
+    fn cleanup_removes_recovered_clients() {
+        let clock = TestClock::new(0.0);
+        let mut local = limiter(1.0, 0.0, clock.clone());
+        local.check_request("client1").unwrap(); // TAT = t=1
+
+        clock.set_time(5.0);
+        local.check_request("client2").unwrap(); // TAT = t=6
+
+        assert_eq!(local.cleanup_stale_clients(1_000_000_000).unwrap(), 1);
+        assert_eq!(local.len(), 1);
+    }
+
+    #[test]
+    fn invalid_rate_and_burst_are_rejected() {
+        let clock = TestClock::new(0.0);
+        let invalid = |rate, burst| {
+            LocalLimiter::<&str, _, Hasher>::new(rate, burst, clock.clone()).unwrap_err()
+        };
+
+        assert!(matches!(invalid(0.0, 1.0), FluxLimiterError::InvalidRate));
+        assert!(matches!(invalid(1.0, -1.0), FluxLimiterError::InvalidBurst));
+        assert!(matches!(
+            invalid(f64::NAN, 1.0),
+            FluxLimiterError::NonFiniteRate
+        ));
+        assert!(matches!(invalid(2e9, 1.0), FluxLimiterError::RateTooHigh));
+    }
+}
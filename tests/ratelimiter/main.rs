@@ -1,14 +1,86 @@
 // tests/ratelimiter/main.rs
 
 // test modules
+mod acquire_timeout_tests;
+mod adaptive_tests;
+mod admin_tests;
+mod anonymize_tests;
+mod async_store_tests;
+mod audit_tests;
+mod batch_tests;
+mod cached_store_tests;
+mod cancellation_tests;
+mod classify_tests;
 mod cleanup_tests;
 mod config_tests;
+mod debt_tests;
 mod decision_metadata_tests;
+mod drain_tests;
+mod entry_ttl_tests;
+mod envoy_tests;
 mod error_tests;
+mod events_tests;
+mod expiry_wheel_tests;
+mod failure_policy_tests;
+mod ffi_tests;
+mod fifo_waiter_tests;
 mod fixtures;
 mod gcra_algorithm_tests;
+mod gcra_property_tests;
+mod gossip_tests;
+mod governor_compat_tests;
+mod grpc_tests;
+mod hasher_tests;
 mod helpers;
+mod http_headers_tests;
+mod intern_tests;
+mod ip_prefix_tests;
+mod iter_tests;
+mod jitter_tests;
+mod key_extractor_tests;
+mod key_normalizer_tests;
+mod local_flux_limiter_tests;
+mod local_limiter_tests;
+mod matrix_tests;
+mod max_clients_tests;
+mod memcached_store_tests;
+mod namespace_tests;
+mod operation_cost_tests;
+mod otel_tests;
+mod pace_tests;
+mod per_key_stats_tests;
 mod performance_tests;
+mod permit_tests;
+mod persistence_tests;
+mod postgres_store_tests;
+mod pressure_tests;
+mod priority_tests;
+mod python_tests;
+mod refund_tests;
+mod registry_tests;
+mod reload_tests;
+mod replay_tests;
+mod replenishment_tests;
+mod reqwest_tests;
+mod rocket_tests;
+mod router_tests;
+mod same_instant_tests;
+mod schedule_tests;
+mod serde_tests;
+mod sharded_tests;
+mod shm_store_tests;
+mod sidecar_tests;
+mod simulation_tests;
+mod sled_store_tests;
+mod snapshot_tests;
+mod state_tests;
+mod stats_tests;
+mod store_tests;
+mod stream_tests;
+mod timestamp_tests;
+mod tokio_clock_tests;
+mod tower_throttle_tests;
+mod two_rate_tests;
 
 // Re-export common test utilities
 pub use fixtures::test_clock::TestClock;
@@ -0,0 +1,95 @@
+// tests/ratelimiter/matrix_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, MatrixLimiter};
+
+    fn matrix(clock: &TestClock) -> MatrixLimiter<&'static str, TestClock> {
+        MatrixLimiter::new()
+            .endpoint("search", 5.0, 2.0)
+            .endpoint("write", 1.0, 0.0)
+            .build(clock.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn each_endpoint_and_client_pair_has_its_own_budget() {
+        let clock = TestClock::new(0.0);
+        let limiter = matrix(&clock);
+
+        assert!(limiter.check("write", "alice").unwrap().allowed);
+        assert!(!limiter.check("write", "alice").unwrap().allowed);
+        assert!(limiter.check("write", "bob").unwrap().allowed);
+
+        // search has its own config: 1 + 2 burst
+        for _ in 0..3 {
+            assert!(limiter.check("search", "alice").unwrap().allowed);
+        }
+        let denied = limiter.check("search", "alice").unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.limit_per_second, 5.0);
+
+        clock.advance(1.0);
+        assert!(limiter.check("write", "alice").unwrap().allowed);
+
+        let mut endpoints: Vec<_> = limiter.endpoints().collect();
+        endpoints.sort_unstable();
+        assert_eq!(endpoints, ["search", "write"]);
+    }
+
+    #[test]
+    fn unknown_endpoints_use_the_fallback_or_fail() {
+        let clock = TestClock::new(0.0);
+        let limiter = matrix(&clock);
+        assert!(limiter.limiter("delete").is_none());
+        assert!(matches!(
+            limiter.check("delete", "alice"),
+            Err(FluxLimiterError::UnknownEndpoint(endpoint)) if endpoint == "delete"
+        ));
+
+        let limiter: MatrixLimiter<&str, TestClock> = MatrixLimiter::new()
+            .endpoint("write", 1.0, 0.0)
+            .fallback(FluxLimiterConfig::new(10.0, 0.0))
+            .build(clock.clone())
+            .unwrap();
+        let decision = limiter.check("delete", "alice").unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.limit_per_second, 10.0);
+    }
+
+    #[test]
+    fn build_validates_every_config() {
+        let built = MatrixLimiter::new()
+            .endpoint("search", 5.0, 2.0)
+            .endpoint("write", 0.0, 0.0)
+            .build::<&str, _>(TestClock::new(0.0));
+        assert!(matches!(built, Err(FluxLimiterError::InvalidRate)));
+
+        // a later group with the same name replaces the earlier one
+        let limiter: MatrixLimiter<&str, TestClock> = MatrixLimiter::new()
+            .endpoint("write", 0.0, 0.0)
+            .endpoint("write", 1.0, 0.0)
+            .build(TestClock::new(0.0))
+            .unwrap();
+        assert_eq!(limiter.endpoints().count(), 1);
+    }
+
+    #[test]
+    fn reset_and_cleanup_cover_every_endpoint() {
+        let clock = TestClock::new(0.0);
+        let limiter = matrix(&clock);
+        limiter.check("write", "alice").unwrap();
+        limiter.check("search", "alice").unwrap();
+        limiter.check("search", "bob").unwrap();
+
+        assert!(limiter.reset_client("alice"));
+        assert!(!limiter.reset_client("alice"));
+        assert!(limiter.check("write", "alice").unwrap().allowed);
+
+        clock.advance(10.0);
+        let report = limiter.cleanup_stale_clients(1_000_000_000).unwrap();
+        assert_eq!(report.removed, 2);
+    }
+}
@@ -0,0 +1,92 @@
+// tests/ratelimiter/max_clients_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        DecisionReason, FailurePolicy, FluxLimiter, FluxLimiterConfig, FluxLimiterError,
+        ShardedFluxLimiter,
+    };
+
+    #[test]
+    fn new_clients_beyond_the_cap_are_refused() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).max_clients(2);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        assert!(
+            limiter
+                .check_request("client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        assert!(limiter.check_request_ref("client2").unwrap().allowed);
+        assert!(matches!(
+            limiter.check_request("client3".to_string()),
+            Err(FluxLimiterError::CapacityExceeded { current: 2, max: 2 })
+        ));
+        assert!(matches!(
+            limiter.check_request_ref("client3"),
+            Err(FluxLimiterError::CapacityExceeded { .. })
+        ));
+
+        // known clients are still rate limited as usual
+        let denied = limiter.check_request_ref("client1").unwrap();
+        assert_eq!(denied.reason, DecisionReason::BurstExhausted);
+
+        // cleanup makes room again
+        clock.advance(10.0);
+        limiter.cleanup_stale_clients(0).unwrap();
+        assert!(limiter.check_request_ref("client3").unwrap().allowed);
+    }
+
+    #[test]
+    fn failure_policy_turns_the_cap_into_a_decision() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .max_clients(1)
+            .failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        let refused = limiter.check_request("client2").unwrap();
+        assert!(!refused.allowed);
+        assert_eq!(refused.reason, DecisionReason::Fallback);
+        assert_eq!(limiter.client_state.len(), 1);
+    }
+
+    #[test]
+    fn check_all_refuses_keys_that_would_not_all_fit() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).max_clients(2);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("user:1").unwrap();
+        assert!(matches!(
+            limiter.check_all(&["user:1", "ip:a", "ip:b"]),
+            Err(FluxLimiterError::CapacityExceeded { current: 1, max: 2 })
+        ));
+        // nothing was consumed or tracked
+        assert_eq!(limiter.client_state.len(), 1);
+        assert!(limiter.check_all(&["user:1", "ip:a"]).unwrap().allowed);
+    }
+
+    #[test]
+    fn sharded_limiters_split_the_cap() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).max_clients(8);
+        let limiter = ShardedFluxLimiter::new(config, 4, clock.clone()).unwrap();
+
+        let refused = (0..100)
+            .filter(|key| limiter.check_request(*key).is_err())
+            .count();
+        // 2 per shard; 100 random keys fill every shard
+        let tracked: usize = limiter
+            .shards()
+            .iter()
+            .map(|shard| shard.client_state.len())
+            .sum();
+        assert_eq!((tracked, refused), (8, 92));
+    }
+}
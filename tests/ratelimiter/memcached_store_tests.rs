@@ -0,0 +1,51 @@
+// tests/ratelimiter/memcached_store_tests.rs
+
+// These tests need a running server; point FLUX_LIMITER_MEMCACHED_URL at it
+// (e.g. memcache://127.0.0.1:11211) and run
+// `cargo test --features memcached -- --ignored`.
+#[cfg(all(test, feature = "memcached"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, MemcachedStore, StateStore, StoreBackedLimiter};
+
+    // connect and clear the keys a test uses
+    fn store(keys: &[&str]) -> MemcachedStore {
+        let url = std::env::var("FLUX_LIMITER_MEMCACHED_URL")
+            .expect("FLUX_LIMITER_MEMCACHED_URL must be set");
+        let client = memcache::Client::connect(url).unwrap();
+        for key in keys {
+            client.delete(key).unwrap();
+        }
+        MemcachedStore::new(client)
+    }
+
+    #[test]
+    #[ignore = "requires a memcached server"]
+    fn memcached_store_applies_gcra() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let store = store(&["flux-gcra"]);
+        let limiter = StoreBackedLimiter::with_config(config, clock.clone(), store).unwrap();
+        let client = "flux-gcra".to_string();
+
+        assert!(limiter.check_request(&client).unwrap().allowed);
+        assert!(!limiter.check_request(&client).unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request(&client).unwrap().allowed);
+    }
+
+    #[test]
+    #[ignore = "requires a memcached server"]
+    fn memcached_compare_and_swap_rejects_stale_expectations() {
+        let store = store(&["flux-cas", "flux-missing"]);
+
+        assert!(store.compare_and_swap("flux-cas", None, 5).unwrap());
+        assert!(!store.compare_and_swap("flux-cas", None, 6).unwrap());
+        assert!(!store.compare_and_swap("flux-cas", Some(4), 6).unwrap());
+        assert!(store.compare_and_swap("flux-cas", Some(5), 6).unwrap());
+        assert_eq!(StateStore::<str>::get(&store, "flux-cas").unwrap(), Some(6));
+        assert!(!store.compare_and_swap("flux-missing", Some(1), 2).unwrap());
+    }
+}
@@ -0,0 +1,101 @@
+// tests/ratelimiter/namespace_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, NamespacedLimiter};
+
+    fn namespaced(clock: &TestClock) -> NamespacedLimiter<&'static str, TestClock> {
+        let limiter =
+            NamespacedLimiter::new(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+        limiter
+            .set_config("enterprise", FluxLimiterConfig::new(1.0, 2.0))
+            .unwrap();
+        limiter
+    }
+
+    #[test]
+    fn namespaces_have_separate_budgets_for_the_same_key() {
+        let clock = TestClock::new(0.0);
+        let limiter = namespaced(&clock);
+
+        assert!(limiter.check("acme", "user").unwrap().allowed);
+        assert!(!limiter.check("acme", "user").unwrap().allowed);
+        assert!(limiter.check("globex", "user").unwrap().allowed);
+
+        // configured namespaces use their own config
+        for _ in 0..3 {
+            assert!(limiter.check("enterprise", "user").unwrap().allowed);
+        }
+        assert!(!limiter.check("enterprise", "user").unwrap().allowed);
+        assert_eq!(
+            limiter.config("enterprise"),
+            FluxLimiterConfig::new(1.0, 2.0)
+        );
+        assert_eq!(limiter.config("acme"), FluxLimiterConfig::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn stats_and_cleanup_are_per_namespace() {
+        let clock = TestClock::new(0.0);
+        let limiter = namespaced(&clock);
+        for key in ["a", "b", "c"] {
+            limiter.check("acme", key).unwrap();
+        }
+        limiter.check("globex", "a").unwrap();
+        assert!(limiter.stats("initech").is_none());
+
+        clock.advance(10.0);
+        let report = limiter.cleanup_namespace("acme", 1_000_000_000).unwrap();
+        assert_eq!(report.removed, 3);
+        assert_eq!(limiter.stats("acme").unwrap().current_clients, 0);
+        assert_eq!(limiter.stats("globex").unwrap().current_clients, 1);
+
+        let report = limiter.cleanup_stale_clients(1_000_000_000).unwrap();
+        assert_eq!(report.removed, 1);
+        assert_eq!(
+            limiter.cleanup_namespace("initech", 0).unwrap(),
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn removing_or_reconfiguring_a_namespace_resets_only_its_state() {
+        let clock = TestClock::new(0.0);
+        let limiter = namespaced(&clock);
+        limiter.check("acme", "user").unwrap();
+        limiter.check("globex", "user").unwrap();
+
+        assert!(limiter.remove_namespace("acme"));
+        assert!(!limiter.remove_namespace("acme"));
+        assert_eq!(limiter.namespaces(), vec!["globex".to_string()]);
+        assert!(limiter.check("acme", "user").unwrap().allowed);
+
+        limiter
+            .set_config("globex", FluxLimiterConfig::new(1.0, 0.0))
+            .unwrap();
+        assert!(limiter.check("globex", "user").unwrap().allowed);
+        assert!(!limiter.check("acme", "user").unwrap().allowed);
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let clock = TestClock::new(0.0);
+        assert!(matches!(
+            NamespacedLimiter::<&str, _>::new(FluxLimiterConfig::new(0.0, 1.0), clock.clone()),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+
+        let limiter = namespaced(&clock);
+        assert!(
+            limiter
+                .set_config("enterprise", FluxLimiterConfig::new(1.0, -1.0))
+                .is_err()
+        );
+        assert_eq!(
+            limiter.config("enterprise"),
+            FluxLimiterConfig::new(1.0, 2.0)
+        );
+    }
+}
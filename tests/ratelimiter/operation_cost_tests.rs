@@ -0,0 +1,90 @@
+// tests/ratelimiter/operation_cost_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError, Precision};
+
+    fn limiter(clock: TestClock) -> FluxLimiter<String, TestClock> {
+        // 1 req/sec, burst of 2; writes cost four reads
+        let config = FluxLimiterConfig::new(1.0, 2.0)
+            .operation_cost("read", 1.0)
+            .operation_cost("write", 4.0);
+        FluxLimiter::with_config(config, clock).unwrap()
+    }
+
+    #[test]
+    fn reads_and_writes_share_one_schedule() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(clock.clone());
+        let client = "client1".to_string();
+
+        // the write is admitted within the burst and then takes four intervals
+        let write = limiter.check_request_op(client.clone(), "write").unwrap();
+        assert!(write.allowed);
+        assert_eq!(write.limit_per_second, 0.25);
+        assert_eq!(write.reset_time_nanos, 4_000_000_000);
+        assert_eq!(limiter.client_state.len(), 1);
+
+        // the schedule is 4s ahead, past the 2s burst, so reads wait too
+        let read = limiter.check_request_op(client.clone(), "read").unwrap();
+        assert!(!read.allowed);
+        assert_eq!(read.retry_after_seconds, Some(2.0));
+
+        clock.advance(2.0);
+        let read = limiter.check_request_op(client.clone(), "read").unwrap();
+        assert!(read.allowed);
+        assert_eq!(read.limit_per_second, 1.0);
+        assert_eq!(read.reset_time_nanos, 5_000_000_000);
+    }
+
+    #[test]
+    fn unknown_operations_touch_no_state() {
+        let limiter = limiter(TestClock::new(0.0));
+
+        match limiter.check_request_op("client1".to_string(), "delete") {
+            Err(FluxLimiterError::UnknownOperation(operation)) => assert_eq!(operation, "delete"),
+            other => panic!("expected UnknownOperation, got {:?}", other),
+        }
+        assert!(limiter.client_state.is_empty());
+        assert_eq!(limiter.operation_cost("write"), Some(4.0));
+        assert_eq!(limiter.operation_cost("delete"), None);
+    }
+
+    #[test]
+    fn non_positive_costs_are_rejected() {
+        for cost in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let config = FluxLimiterConfig::new(1.0, 2.0).operation_cost("write", cost);
+            assert!(matches!(
+                config.validate(),
+                Err(FluxLimiterError::InvalidOperationCost(operation)) if operation == "write"
+            ));
+        }
+    }
+
+    #[test]
+    fn costs_below_the_minimum_interval_are_rejected() {
+        // 1e-300 of a one-second interval rounds to no time at all
+        let config = FluxLimiterConfig::new(1.0, 0.0).operation_cost("ping", 1e-300);
+        assert!(matches!(
+            config.validate(),
+            Err(FluxLimiterError::InvalidOperationCost(operation)) if operation == "ping"
+        ));
+
+        // the same holds against a coarser minimum interval
+        let config = FluxLimiterConfig::new(1.0, 0.0)
+            .precision(Precision::default().min_emission_interval_nanos(1_000))
+            .operation_cost("ping", 1e-7);
+        assert!(matches!(
+            config.validate(),
+            Err(FluxLimiterError::InvalidOperationCost(operation)) if operation == "ping"
+        ));
+
+        // one nanosecond per operation is still charged
+        let config = FluxLimiterConfig::new(1.0, 0.0).operation_cost("ping", 1e-9);
+        let limiter = FluxLimiter::with_config(config, TestClock::new(0.0)).unwrap();
+        assert!(limiter.check_request_op("a", "ping").unwrap().allowed);
+        assert!(!limiter.check_request_op("a", "ping").unwrap().allowed);
+    }
+}
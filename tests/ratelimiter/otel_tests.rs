@@ -0,0 +1,164 @@
+// tests/ratelimiter/otel_tests.rs
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FailurePolicy, FluxLimiter, FluxLimiterConfig};
+    use opentelemetry::metrics::{
+        Counter, Histogram, HistogramBuilder, InstrumentBuilder, InstrumentProvider, Meter,
+        SyncInstrument,
+    };
+    use opentelemetry::{KeyValue, Value};
+    use std::sync::{Arc, Mutex};
+
+    // measurements recorded by one instrument, with their attributes
+    type Measurements<T> = Arc<Mutex<Vec<(T, Vec<KeyValue>)>>>;
+
+    struct Recorder<T>(Measurements<T>);
+
+    impl<T: Send + Sync> SyncInstrument<T> for Recorder<T> {
+        fn measure(&self, measurement: T, attributes: &[KeyValue]) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((measurement, attributes.to_vec()));
+        }
+    }
+
+    // a minimal metrics SDK that keeps every measurement for inspection
+    #[derive(Default)]
+    struct RecordingProvider {
+        counters: Measurements<u64>,
+        histograms: Measurements<f64>,
+    }
+
+    impl InstrumentProvider for RecordingProvider {
+        fn u64_counter(&self, _builder: InstrumentBuilder<'_, Counter<u64>>) -> Counter<u64> {
+            Counter::new(Arc::new(Recorder(self.counters.clone())))
+        }
+
+        fn f64_histogram(&self, _builder: HistogramBuilder<'_, Histogram<f64>>) -> Histogram<f64> {
+            Histogram::new(Arc::new(Recorder(self.histograms.clone())))
+        }
+    }
+
+    fn recording_meter() -> (Meter, Measurements<u64>, Measurements<f64>) {
+        let provider = RecordingProvider::default();
+        let counters = provider.counters.clone();
+        let histograms = provider.histograms.clone();
+        (Meter::new(Arc::new(provider)), counters, histograms)
+    }
+
+    fn allowed_attribute(attributes: &[KeyValue]) -> Option<Value> {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == "rate_limit.allowed")
+            .map(|kv| kv.value.clone())
+    }
+
+    #[test]
+    fn decisions_are_counted_by_outcome() {
+        let (meter, counters, _) = recording_meter();
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), TestClock::new(0.0))
+                .unwrap()
+                .with_otel_meter(&meter);
+
+        for _ in 0..3 {
+            limiter.check_request("client1").unwrap();
+        }
+
+        let counters = counters.lock().unwrap();
+        let outcomes: Vec<_> = counters
+            .iter()
+            .map(|(count, attributes)| (*count, allowed_attribute(attributes)))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (1, Some(Value::Bool(true))),
+                (1, Some(Value::Bool(true))),
+                (1, Some(Value::Bool(false))),
+            ]
+        );
+    }
+
+    #[test]
+    fn denials_record_retry_after() {
+        let (meter, _, histograms) = recording_meter();
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(2.0, 0.0), TestClock::new(0.0))
+                .unwrap()
+                .with_otel_meter(&meter);
+
+        limiter.check_request("client1").unwrap();
+        assert!(histograms.lock().unwrap().is_empty());
+
+        let denied = limiter.check_request("client1").unwrap();
+        let histograms = histograms.lock().unwrap();
+        assert_eq!(histograms.len(), 1);
+        assert_eq!(Some(histograms[0].0), denied.retry_after_seconds);
+    }
+
+    #[test]
+    fn failure_policy_decisions_are_exported() {
+        let (meter, counters, histograms) = recording_meter();
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(4.0, 0.0).failure_policy(FailurePolicy::FailClosed);
+        let limiter = FluxLimiter::with_config(config, clock.clone())
+            .unwrap()
+            .with_otel_meter(&meter);
+
+        clock.fail_next_call();
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+
+        assert_eq!(
+            allowed_attribute(&counters.lock().unwrap()[0].1),
+            Some(Value::Bool(false))
+        );
+        assert_eq!(histograms.lock().unwrap()[0].0, 0.25);
+    }
+
+    #[test]
+    fn multi_key_checks_count_every_key() {
+        let (meter, counters, _) = recording_meter();
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), TestClock::new(0.0))
+                .unwrap()
+                .with_otel_meter(&meter);
+
+        assert!(limiter.check_all(&["user", "endpoint"]).unwrap().allowed);
+        assert!(!limiter.check_all(&["user", "endpoint"]).unwrap().allowed);
+
+        let counters = counters.lock().unwrap();
+        let allowed: Vec<_> = counters
+            .iter()
+            .map(|(_, attributes)| allowed_attribute(attributes))
+            .collect();
+        assert_eq!(
+            allowed,
+            vec![
+                Some(Value::Bool(true)),
+                Some(Value::Bool(true)),
+                Some(Value::Bool(false)),
+                Some(Value::Bool(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn hooks_apply_only_to_the_handle_they_were_attached_to() {
+        let (meter, counters, _) = recording_meter();
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), TestClock::new(0.0))
+                .unwrap();
+        let exported = limiter.clone().with_otel_meter(&meter);
+
+        limiter.check_request("client1").unwrap();
+        assert!(counters.lock().unwrap().is_empty());
+
+        exported.check_request("client1").unwrap();
+        assert_eq!(counters.lock().unwrap().len(), 1);
+    }
+}
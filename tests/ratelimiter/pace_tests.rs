@@ -0,0 +1,103 @@
+// tests/ratelimiter/pace_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+
+    #[test]
+    fn pace_spaces_requests_at_the_rate() {
+        let clock = TestClock::new(10.0);
+        let config = FluxLimiterConfig::new(4.0, 0.0); // one slot every 250ms
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let slots: Vec<u64> = (0..4)
+            .map(|_| limiter.pace("mailer").unwrap().as_nanos())
+            .collect();
+        assert_eq!(
+            slots,
+            [
+                10_000_000_000,
+                10_250_000_000,
+                10_500_000_000,
+                10_750_000_000
+            ]
+        );
+
+        // pacing never rejects, and checks see the schedule it built up
+        assert!(!limiter.check_request("mailer").unwrap().allowed);
+    }
+
+    #[test]
+    fn pace_schedules_burst_immediately() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 2.0); // 3 immediate slots
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.pace("mailer").unwrap().as_nanos(), 0);
+        }
+        assert_eq!(limiter.pace("mailer").unwrap().as_nanos(), 1_000_000_000);
+    }
+
+    #[test]
+    fn pace_restarts_from_now_after_idle() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.pace("mailer").unwrap();
+        limiter.pace("mailer").unwrap();
+        clock.advance(30.0);
+
+        assert_eq!(limiter.pace("mailer").unwrap().as_nanos(), 30_000_000_000);
+        assert_eq!(limiter.pace("mailer").unwrap().as_nanos(), 31_000_000_000);
+    }
+
+    #[test]
+    fn pace_keeps_clients_independent() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.pace_at("a", 0).unwrap();
+        limiter.pace_at("a", 0).unwrap();
+        assert_eq!(limiter.pace_at("b", 0).unwrap().as_nanos(), 0);
+        assert_eq!(limiter.pace_at("a", 0).unwrap().as_nanos(), 2_000_000_000);
+    }
+
+    #[test]
+    fn pace_respects_max_clients() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).max_clients(1);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.pace("a").unwrap();
+        assert!(matches!(
+            limiter.pace("b"),
+            Err(FluxLimiterError::CapacityExceeded { current: 1, max: 1 })
+        ));
+        assert!(matches!(
+            limiter.pace_at("b", 0),
+            Err(FluxLimiterError::CapacityExceeded { .. })
+        ));
+        // tracked clients are still scheduled
+        assert_eq!(limiter.pace("a").unwrap().as_nanos(), 1_000_000_000);
+        assert_eq!(limiter.client_state.len(), 1);
+    }
+
+    #[test]
+    fn pace_propagates_clock_errors() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.pace("mailer"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+        assert!(limiter.client_state.is_empty());
+    }
+}
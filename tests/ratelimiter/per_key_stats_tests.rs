@@ -0,0 +1,140 @@
+// tests/ratelimiter/per_key_stats_tests.rs
+
+#[cfg(all(test, feature = "per-key-stats"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{ClientStats, FluxLimiter, FluxLimiterConfig};
+
+    #[test]
+    fn client_stats_count_each_client_separately() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        for _ in 0..5 {
+            limiter.check_request_ref("client1").unwrap();
+        }
+        limiter.check_request_ref("client2").unwrap();
+
+        assert_eq!(
+            limiter.client_stats("client1"),
+            Some(ClientStats {
+                allowed: 2,
+                denied: 3
+            })
+        );
+        assert_eq!(
+            limiter.client_stats("client2"),
+            Some(ClientStats {
+                allowed: 1,
+                denied: 0
+            })
+        );
+        assert_eq!(limiter.client_stats("unknown"), None);
+    }
+
+    #[test]
+    fn client_stats_count_concurrent_checks() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 4.0); // 5 immediate requests
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = limiter.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        limiter.check_request_ref("hot").unwrap();
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+
+        assert_eq!(
+            limiter.client_stats("hot"),
+            Some(ClientStats {
+                allowed: 5,
+                denied: 95
+            })
+        );
+    }
+
+    #[test]
+    fn observed_rate_tracks_steady_demand() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).observed_rate_window(10.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        // 4 req/s for two minutes against a 1 req/s limit: denials count as demand too
+        for _ in 0..480 {
+            limiter.check_request_ref("client").unwrap();
+            clock.advance(0.25);
+        }
+
+        let rate = limiter.observed_rate("client").unwrap().unwrap();
+        assert!((rate - 4.0).abs() < 0.2, "observed {rate}");
+        assert_eq!(limiter.observed_rate("unknown").unwrap(), None);
+    }
+
+    #[test]
+    fn observed_rate_decays_while_idle() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(100.0, 10.0).observed_rate_window(10.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        for _ in 0..100 {
+            limiter.check_request_ref("client").unwrap();
+            clock.advance(0.1);
+        }
+        let busy = limiter.observed_rate("client").unwrap().unwrap();
+
+        // one window later the estimate has decayed by a factor of e
+        clock.advance(10.0);
+        let idle = limiter.observed_rate("client").unwrap().unwrap();
+        assert!((idle * std::f64::consts::E - busy).abs() < 1e-9);
+    }
+
+    #[test]
+    fn observed_rate_counts_check_all_once_per_occurrence() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(10.0, 10.0).observed_rate_window(1.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        limiter.check_all(&["a", "a", "b"]).unwrap();
+
+        // decayed counts over a 1s window read back as requests per second
+        assert_eq!(limiter.observed_rate("a").unwrap(), Some(2.0));
+        assert_eq!(limiter.observed_rate("b").unwrap(), Some(1.0));
+    }
+
+    #[test]
+    fn check_all_counts_per_key() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        assert!(limiter.check_all(&["a", "b"]).unwrap().allowed);
+        assert!(!limiter.check_all(&["a", "c"]).unwrap().allowed);
+
+        let a = limiter.client_stats("a").unwrap();
+        assert_eq!((a.allowed, a.denied), (1, 1));
+        assert_eq!(limiter.client_stats("b").unwrap().allowed, 1);
+        // the rejected operation never created an entry for "c"
+        assert_eq!(limiter.client_stats("c"), None);
+    }
+
+    #[test]
+    fn client_stats_are_removed_with_stale_clients() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        clock.advance(3600.0);
+        limiter.cleanup_stale_clients(1_000_000_000).unwrap();
+
+        assert_eq!(limiter.client_stats("client1"), None);
+    }
+}
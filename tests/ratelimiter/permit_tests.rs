@@ -0,0 +1,85 @@
+// tests/ratelimiter/permit_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FailurePolicy, FluxLimiter, FluxLimiterConfig, RateLimitedError};
+
+    fn limiter(clock: &TestClock) -> FluxLimiter<&'static str, TestClock> {
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        FluxLimiter::with_config(config, clock.clone()).unwrap()
+    }
+
+    #[test]
+    fn dropped_permit_keeps_capacity_by_default() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        let permit = limiter.acquire("client1").unwrap();
+        assert_eq!(*permit.client_id(), "client1");
+        drop(permit);
+
+        assert!(matches!(
+            limiter.acquire("client1"),
+            Err(RateLimitedError::Denied(_))
+        ));
+    }
+
+    #[test]
+    fn refund_on_drop_returns_capacity_on_early_exit() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        let handler = || -> Result<(), &'static str> {
+            let permit = limiter.acquire("client1").unwrap().refund_on_drop();
+            Err("upstream unavailable")?;
+            permit.commit();
+            Ok(())
+        };
+        assert!(handler().is_err());
+
+        assert!(limiter.acquire("client1").is_ok());
+    }
+
+    #[test]
+    fn committed_permit_is_not_refunded() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        limiter
+            .acquire("client1")
+            .unwrap()
+            .refund_on_drop()
+            .commit();
+
+        assert!(limiter.acquire("client1").is_err());
+    }
+
+    #[test]
+    fn refund_unused_returns_part_of_the_cost() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        drop(limiter.acquire("client1").unwrap().refund_unused(0.5));
+
+        // half a request was given back, so the client conforms 500ms early
+        assert_eq!(
+            limiter.client_state.get("client1").unwrap().tat_nanos(),
+            500_000_000
+        );
+    }
+
+    #[test]
+    fn acquire_reports_clock_failures() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::Propagate);
+        let limiter = FluxLimiter::<&str, _>::with_config(config, clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.acquire("client1"),
+            Err(RateLimitedError::Failed(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+// tests/ratelimiter/persistence_tests.rs
+
+#[cfg(all(test, feature = "persistence"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError, PersistentLimiter};
+    use std::path::PathBuf;
+
+    // unique path per test so tests can run in parallel
+    fn state_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("flux-limiter-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = state_path("round-trip");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+
+        let limiter = FluxLimiter::<String, _>::with_config(config.clone(), clock.clone()).unwrap();
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        limiter.save(&path).unwrap();
+
+        let restored = FluxLimiter::<String, _>::load(config, clock, &path).unwrap();
+        assert!(!restored.check_request_ref("client1").unwrap().allowed);
+        assert!(
+            !path
+                .with_file_name(format!(
+                    "{}.tmp",
+                    path.file_name().unwrap().to_string_lossy()
+                ))
+                .exists()
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_file_starts_fresh() {
+        let path = state_path("missing");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+
+        let limiter = FluxLimiter::<String, _>::load(config, clock, &path).unwrap();
+        assert!(limiter.client_state.is_empty());
+    }
+
+    #[test]
+    fn recovered_clients_are_not_restored() {
+        let path = state_path("stale");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+
+        let limiter = FluxLimiter::<String, _>::with_config(config.clone(), clock.clone()).unwrap();
+        limiter.check_request_ref("idle").unwrap();
+        clock.advance(0.5);
+        limiter.check_request_ref("busy").unwrap();
+        limiter.save(&path).unwrap();
+
+        // by t=1.0 "idle" has fully recovered, "busy" has not
+        clock.set_time(1.0);
+        let restored = FluxLimiter::<String, _>::load(config, clock, &path).unwrap();
+        assert!(!restored.client_state.contains_key("idle"));
+        assert!(restored.client_state.contains_key("busy"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn far_future_schedules_are_restored() {
+        let path = state_path("far-future");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 5.0);
+
+        let limiter = FluxLimiter::<String, _>::with_config(config.clone(), clock.clone()).unwrap();
+        limiter.check_request_ref("penalized").unwrap();
+        // pushes the TAT to the end of the time range
+        limiter.penalize("penalized".to_string(), 1e12).unwrap();
+        limiter.save(&path).unwrap();
+
+        let restored = FluxLimiter::<String, _>::load(config, clock, &path).unwrap();
+        assert!(restored.client_state.contains_key("penalized"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_an_error() {
+        let path = state_path("corrupt");
+        std::fs::write(&path, b"not json").unwrap();
+
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let result = FluxLimiter::<String, _>::load(config, clock, &path);
+        assert!(matches!(result, Err(FluxLimiterError::PersistenceError(_))));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn persistent_limiter_saves_on_drop() {
+        let path = state_path("drop");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+
+        {
+            let limiter =
+                PersistentLimiter::<String, _>::open(config.clone(), clock.clone(), &path).unwrap();
+            assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        }
+        assert!(path.exists());
+
+        let limiter = PersistentLimiter::<String, _>::open(config, clock, &path).unwrap();
+        assert!(!limiter.check_request_ref("client1").unwrap().allowed);
+        drop(limiter);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+// tests/ratelimiter/postgres_store_tests.rs
+
+// These tests need a running server; point FLUX_LIMITER_POSTGRES_URL at it and
+// run `cargo test --features postgres -- --ignored`.
+#[cfg(all(test, feature = "postgres"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, PostgresStore, StateStore, StoreBackedLimiter};
+
+    // connect and start from an empty, test-specific table
+    fn store(table: &str) -> PostgresStore {
+        let url = std::env::var("FLUX_LIMITER_POSTGRES_URL")
+            .expect("FLUX_LIMITER_POSTGRES_URL must be set");
+        let mut client = postgres::Client::connect(&url, postgres::NoTls).unwrap();
+        client
+            .batch_execute(&format!("DROP TABLE IF EXISTS \"{}\"", table))
+            .unwrap();
+
+        let store = PostgresStore::new(client, table);
+        store.create_table().unwrap();
+        store
+    }
+
+    #[test]
+    #[ignore = "requires a PostgreSQL server"]
+    fn postgres_store_applies_gcra() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter =
+            StoreBackedLimiter::with_config(config, clock.clone(), store("flux_gcra")).unwrap();
+        let client = "client1".to_string();
+
+        assert!(limiter.check_request(&client).unwrap().allowed);
+        assert!(!limiter.check_request(&client).unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request(&client).unwrap().allowed);
+    }
+
+    #[test]
+    #[ignore = "requires a PostgreSQL server"]
+    fn postgres_compare_and_swap_rejects_stale_expectations() {
+        let store = store("flux_cas");
+
+        assert!(store.compare_and_swap("client1", None, 5).unwrap());
+        assert!(!store.compare_and_swap("client1", None, 6).unwrap());
+        assert!(!store.compare_and_swap("client1", Some(4), 6).unwrap());
+        assert!(store.compare_and_swap("client1", Some(5), 6).unwrap());
+        assert_eq!(StateStore::<str>::get(&store, "client1").unwrap(), Some(6));
+        assert!(!store.compare_and_swap("missing", Some(1), 2).unwrap());
+    }
+}
@@ -0,0 +1,115 @@
+// tests/ratelimiter/pressure_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{Decision, FluxLimiter, FluxLimiterConfig};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // a pressure gauge the test can move, stored as f64 bits
+    fn gauge() -> (Arc<AtomicU64>, impl Fn() -> f64 + Send + Sync + 'static) {
+        let bits = Arc::new(AtomicU64::new(0.0f64.to_bits()));
+        let reader = bits.clone();
+        (bits, move || f64::from_bits(reader.load(Ordering::Relaxed)))
+    }
+
+    fn set(gauge: &AtomicU64, pressure: f64) {
+        gauge.store(pressure.to_bits(), Ordering::Relaxed);
+    }
+
+    #[test]
+    fn no_pressure_leaves_decisions_unchanged() {
+        let clock = TestClock::new(0.0);
+        let (_gauge, provider) = gauge();
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 0.0), clock)
+            .unwrap()
+            .with_pressure_provider(provider);
+
+        let decision = limiter.check_request("client").unwrap();
+        assert!(decision.allowed);
+        assert!(!decision.shedding);
+        assert_eq!(decision.limit_per_second, 10.0);
+    }
+
+    #[test]
+    fn pressure_scales_the_rate_down_proportionally() {
+        let clock = TestClock::new(0.0);
+        let (gauge, provider) = gauge();
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 0.0), clock.clone())
+            .unwrap()
+            .with_pressure_provider(provider);
+
+        // half pressure: 5 req/s, so the next slot is 0.2s out instead of 0.1s
+        set(&gauge, 0.5);
+        let decision = limiter.check_request("client").unwrap();
+        assert!(decision.allowed);
+        assert!(decision.shedding);
+        assert_eq!(decision.limit_per_second, 5.0);
+
+        clock.advance(0.1);
+        let decision = limiter.check_request("client").unwrap();
+        assert!(!decision.allowed);
+        assert!(decision.shedding);
+
+        clock.advance(0.1);
+        assert!(limiter.check_request("client").unwrap().allowed);
+    }
+
+    #[test]
+    fn full_pressure_sheds_everything_without_touching_state() {
+        let clock = TestClock::new(0.0);
+        let (gauge, provider) = gauge();
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 5.0), clock)
+            .unwrap()
+            .with_pressure_provider(provider);
+
+        set(&gauge, 1.0);
+        let decision = limiter.check_request("client").unwrap();
+        assert!(!decision.allowed);
+        assert!(decision.shedding);
+        assert_eq!(decision.retry_after_seconds, Some(0.1));
+
+        // relief restores the full burst
+        set(&gauge, 0.0);
+        for _ in 0..6 {
+            assert!(limiter.check_request("client").unwrap().allowed);
+        }
+    }
+
+    #[test]
+    fn out_of_range_pressure_is_clamped() {
+        let clock = TestClock::new(0.0);
+        let (gauge, provider) = gauge();
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 0.0), clock)
+            .unwrap()
+            .with_pressure_provider(provider);
+
+        set(&gauge, -3.0);
+        assert!(!limiter.check_request("a").unwrap().shedding);
+
+        set(&gauge, f64::NAN);
+        assert!(!limiter.check_request("b").unwrap().shedding);
+
+        set(&gauge, 7.0);
+        assert!(!limiter.check_request("c").unwrap().allowed);
+    }
+
+    #[test]
+    fn typed_decisions_carry_the_shedding_flag() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(10.0, 0.0), clock)
+            .unwrap()
+            .with_pressure_provider(|| 0.2);
+
+        match limiter.check_request_typed("client").unwrap() {
+            Decision::Allowed(meta) => assert!(meta.shedding),
+            Decision::Denied(_) => panic!("first request should be allowed"),
+        }
+        match limiter.check_request_typed("client").unwrap() {
+            Decision::Denied(meta) => assert!(meta.shedding),
+            Decision::Allowed(_) => panic!("second request should be denied"),
+        }
+    }
+}
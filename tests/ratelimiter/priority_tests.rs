@@ -0,0 +1,128 @@
+// tests/ratelimiter/priority_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError, Priority};
+
+    #[test]
+    fn low_priority_cannot_use_the_reserved_share() {
+        let clock = TestClock::new(0.0);
+        // 5 immediate requests, 2 of them reserved
+        let config = FluxLimiterConfig::new(1.0, 4.0).priority_reserve(0.4);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        for _ in 0..3 {
+            assert!(
+                limiter
+                    .check_request_with_priority("svc", Priority::Low)
+                    .unwrap()
+                    .allowed
+            );
+        }
+        let decision = limiter
+            .check_request_with_priority("svc", Priority::Low)
+            .unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(1.0));
+
+        // high priority still gets the reserve
+        for _ in 0..2 {
+            assert!(
+                limiter
+                    .check_request_with_priority("svc", Priority::High)
+                    .unwrap()
+                    .allowed
+            );
+        }
+        assert!(
+            !limiter
+                .check_request_with_priority("svc", Priority::High)
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn low_priority_keeps_the_sustained_rate() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).priority_reserve(0.5);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        assert!(
+            limiter
+                .check_request_with_priority("svc", Priority::Low)
+                .unwrap()
+                .allowed
+        );
+        for _ in 0..5 {
+            clock.advance(1.0);
+            assert!(
+                limiter
+                    .check_request_with_priority("svc", Priority::Low)
+                    .unwrap()
+                    .allowed
+            );
+            assert!(
+                !limiter
+                    .check_request_with_priority("svc", Priority::Low)
+                    .unwrap()
+                    .allowed
+            );
+        }
+    }
+
+    #[test]
+    fn check_request_behaves_as_high_priority() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).priority_reserve(1.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        // a full reserve locks out low priority entirely while the window is in use
+        assert!(limiter.check_request("svc").unwrap().allowed);
+        assert!(
+            !limiter
+                .check_request_with_priority("svc", Priority::Low)
+                .unwrap()
+                .allowed
+        );
+        assert!(limiter.check_request("svc").unwrap().allowed);
+    }
+
+    #[test]
+    fn no_reserve_treats_classes_equally() {
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 1.0), clock).unwrap();
+
+        assert!(
+            limiter
+                .check_request_with_priority("svc", Priority::Low)
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            limiter
+                .check_request_with_priority("svc", Priority::Low)
+                .unwrap()
+                .allowed
+        );
+        assert!(
+            !limiter
+                .check_request_with_priority("svc", Priority::High)
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn invalid_reserves_are_rejected() {
+        for fraction in [-0.1, 1.5, f64::NAN] {
+            let config = FluxLimiterConfig::new(1.0, 1.0).priority_reserve(fraction);
+            assert!(matches!(
+                config.validate(),
+                Err(FluxLimiterError::InvalidPriorityReserve)
+            ));
+        }
+    }
+}
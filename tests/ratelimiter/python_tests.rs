@@ -0,0 +1,69 @@
+// tests/ratelimiter/python_tests.rs
+
+#[cfg(all(test, feature = "pyo3-tests"))]
+mod tests {
+
+    use flux_limiter::python_module;
+    use pyo3::prelude::*;
+    use pyo3::types::{PyDict, PyModule};
+    use std::ffi::CStr;
+
+    // run `code` with the module imported as `flux_limiter`
+    fn run(code: &CStr) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "flux_limiter")?;
+            python_module(&module)?;
+            let globals = PyDict::new(py);
+            globals.set_item("flux_limiter", module)?;
+            py.run(code, Some(&globals), None)
+        })
+    }
+
+    #[test]
+    fn checks_return_truthy_decisions() {
+        run(c"
+limiter = flux_limiter.FluxLimiter(1.0, 1.0)
+assert (limiter.rate, limiter.burst) == (1.0, 1.0)
+
+assert limiter.check('user_123')
+assert limiter.check('user_123').allowed
+denied = limiter.check('user_123')
+assert not denied
+assert denied.reason == 'burst_exhausted'
+assert 0.9 < denied.retry_after_seconds <= 1.0
+assert limiter.check('user_456')
+assert limiter.reset('user_123')
+assert limiter.check('user_123')
+")
+        .unwrap();
+    }
+
+    #[test]
+    fn event_times_replay_deterministically() {
+        run(c"
+limiter = flux_limiter.FluxLimiter(1.0)
+second = 1_000_000_000
+assert limiter.check_at('user_123', 0)
+assert not limiter.check_at('user_123', second // 2)
+decision = limiter.check_at('user_123', second)
+assert decision and decision.reset_time_nanos == 2 * second
+# long recovered by the system clock's reckoning
+assert limiter.cleanup(0) == 1
+assert limiter.cleanup(0) == 0
+")
+        .unwrap();
+    }
+
+    #[test]
+    fn invalid_configs_raise_value_error() {
+        run(c"
+try:
+    flux_limiter.FluxLimiter(0.0, 1.0)
+except ValueError as err:
+    assert 'positive' in str(err)
+else:
+    raise AssertionError('expected ValueError')
+")
+        .unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+// tests/ratelimiter/refund_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+
+    #[test]
+    fn refund_returns_consumed_capacity() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+
+        assert!(limiter.check_request(client).unwrap().allowed);
+        assert!(!limiter.check_request(client).unwrap().allowed);
+
+        limiter.refund(client, 1.0).unwrap();
+        assert!(limiter.check_request(client).unwrap().allowed);
+    }
+
+    #[test]
+    fn refund_is_clamped_to_now() {
+        let clock = TestClock::new(10.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+
+        assert!(limiter.check_request(client).unwrap().allowed);
+
+        // refunding far more than was consumed only restores the idle state
+        limiter.refund(client, 100.0).unwrap();
+        assert_eq!(
+            limiter.client_state.get(client).unwrap().tat_nanos(),
+            10_000_000_000
+        );
+
+        assert!(limiter.check_request(client).unwrap().allowed);
+        assert!(limiter.check_request(client).unwrap().allowed);
+        assert!(!limiter.check_request(client).unwrap().allowed);
+    }
+
+    #[test]
+    fn refund_ignores_unknown_clients() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        limiter.refund("nobody", 1.0).unwrap();
+        assert_eq!(limiter.client_state.len(), 0);
+    }
+
+    #[test]
+    fn refund_rejects_invalid_cost() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        for cost in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                limiter.refund("client1", cost),
+                Err(FluxLimiterError::InvalidCost)
+            ));
+        }
+    }
+
+    #[test]
+    fn refund_propagates_clock_error() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        assert!(limiter.check_request("client1").unwrap().allowed);
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.refund("client1", 1.0),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+    }
+
+    #[test]
+    fn penalize_locks_a_client_out_beyond_its_burst() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        let client = "client1";
+
+        // unknown clients are tracked from now
+        limiter.penalize(client, 5.0).unwrap();
+        assert_eq!(
+            limiter.client_state.get(client).unwrap().tat_nanos(),
+            5_000_000_000
+        );
+        assert!(!limiter.check_request(client).unwrap().allowed);
+
+        // a request conforms again once the TAT is within the burst
+        clock.advance(4.0);
+        assert!(limiter.check_request(client).unwrap().allowed);
+        assert!(!limiter.check_request(client).unwrap().allowed);
+
+        assert!(matches!(
+            limiter.penalize(client, -1.0),
+            Err(FluxLimiterError::InvalidCost)
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+// tests/ratelimiter/registry_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, LimiterRegistry};
+
+    fn registry(clock: &TestClock) -> LimiterRegistry<&'static str, TestClock> {
+        LimiterRegistry::with_configs(
+            [
+                ("search", FluxLimiterConfig::new(1.0, 1.0)),
+                ("upload", FluxLimiterConfig::new(1.0, 0.0)),
+            ],
+            clock.clone(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn named_limiters_are_independent() {
+        let clock = TestClock::new(0.0);
+        let registry = registry(&clock);
+
+        assert!(registry.check("upload", "user").unwrap().allowed);
+        assert!(!registry.check("upload", "user").unwrap().allowed);
+
+        // the same key has its own budget under another name
+        assert!(registry.check("search", "user").unwrap().allowed);
+        assert!(registry.check("search", "user").unwrap().allowed);
+        assert!(!registry.check("search", "user").unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(registry.check("upload", "user").unwrap().allowed);
+    }
+
+    #[test]
+    fn limiters_are_created_lazily_and_shared() {
+        let clock = TestClock::new(0.0);
+        let registry = registry(&clock);
+
+        let search = registry.get("search").unwrap();
+        assert!(search.client_state.is_empty());
+        registry.check("search", "user").unwrap();
+        assert_eq!(search.client_state.len(), 1);
+        assert!(registry.get("missing").is_none());
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        let clock = TestClock::new(0.0);
+        let registry = registry(&clock);
+
+        let result = registry.check("admin", "user");
+        assert!(matches!(result, Err(FluxLimiterError::UnknownLimiter(name)) if name == "admin"));
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected_up_front() {
+        let clock = TestClock::new(0.0);
+        let result = LimiterRegistry::<&str, _>::with_configs(
+            [("broken", FluxLimiterConfig::new(-1.0, 0.0))],
+            clock,
+        );
+        assert!(matches!(result, Err(FluxLimiterError::InvalidRate)));
+    }
+
+    #[test]
+    fn cleanup_covers_every_limiter() {
+        let clock = TestClock::new(0.0);
+        let registry = registry(&clock);
+
+        registry.check("search", "user").unwrap();
+        registry.check("upload", "user").unwrap();
+        clock.advance(3600.0);
+        registry.cleanup_stale_clients(1_000_000_000).unwrap();
+
+        assert!(registry.get("search").unwrap().client_state.is_empty());
+        assert!(registry.get("upload").unwrap().client_state.is_empty());
+    }
+
+    #[test]
+    fn re_registering_replaces_the_limiter() {
+        let clock = TestClock::new(0.0);
+        let registry = registry(&clock);
+
+        assert!(registry.check("upload", "user").unwrap().allowed);
+        registry
+            .register("upload", FluxLimiterConfig::new(1.0, 5.0))
+            .unwrap();
+        assert!(registry.check("upload", "user").unwrap().allowed);
+        assert_eq!(registry.get("upload").unwrap().burst(), 5.0);
+
+        let mut names = registry.names();
+        names.sort();
+        assert_eq!(names, vec!["search", "upload"]);
+    }
+}
@@ -0,0 +1,148 @@
+// tests/ratelimiter/reload_tests.rs
+
+#[cfg(all(test, feature = "hot-reload"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterError, PolicyRouter, PolicyWatcher};
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    const SEARCH_LIMITED: &str = r#"
+        fallback = { limit = { rate_per_second = 1.0, burst_capacity = 1.0 } }
+
+        [[routes]]
+        pattern = "/search*"
+        policy = { limit = { rate_per_second = 1.0, burst_capacity = 0.0 } }
+
+        [[routes]]
+        pattern = "/health"
+        policy = "unlimited"
+    "#;
+
+    // unique policy file per test, in its own directory so watchers see only its events
+    fn policy_path(name: &str, contents: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "flux-limiter-policies-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("policies.toml");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    // replace the file the way deploy tools do, so watchers never see it half-written
+    fn replace(path: &Path, contents: &str) {
+        let staged = path.with_extension("staged");
+        std::fs::write(&staged, contents).unwrap();
+        std::fs::rename(&staged, path).unwrap();
+    }
+
+    #[test]
+    fn policy_files_configure_routes_and_fallback() {
+        let path = policy_path("load", SEARCH_LIMITED);
+        let router = PolicyRouter::from_policy_file(&path, TestClock::new(0.0)).unwrap();
+
+        assert!(router.check("/search?q=a").unwrap().allowed);
+        assert!(!router.check("/search?q=a").unwrap().allowed);
+        assert!(
+            router
+                .check("/health")
+                .unwrap()
+                .limit_per_second
+                .is_infinite()
+        );
+        assert_eq!(router.check("/other").unwrap().burst_capacity, 1.0);
+    }
+
+    #[test]
+    fn unreadable_and_invalid_files_are_rejected() {
+        let missing = std::env::temp_dir().join("flux-limiter-policies-missing.toml");
+        assert!(matches!(
+            PolicyRouter::from_policy_file(&missing, TestClock::new(0.0)),
+            Err(FluxLimiterError::PolicyFileError(_))
+        ));
+
+        let garbled = policy_path("garbled", "[[routes]]\npattern = 3\n");
+        assert!(matches!(
+            PolicyRouter::from_policy_file(&garbled, TestClock::new(0.0)),
+            Err(FluxLimiterError::PolicyFileError(_))
+        ));
+
+        let invalid = policy_path(
+            "invalid",
+            "fallback = { limit = { rate_per_second = -1.0, burst_capacity = 0.0 } }\n",
+        );
+        assert!(matches!(
+            PolicyRouter::from_policy_file(&invalid, TestClock::new(0.0)),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+    }
+
+    #[test]
+    fn reloads_keep_state_of_unchanged_routes_only() {
+        let path = policy_path("keep-state", SEARCH_LIMITED);
+        let policies = PolicyWatcher::watch(&path, TestClock::new(0.0)).unwrap();
+        assert!(policies.check("/search").unwrap().allowed);
+        assert!(policies.check("/other").unwrap().allowed);
+        assert!(policies.check("/other").unwrap().allowed);
+
+        // the fallback changes, the search route does not
+        replace(
+            &path,
+            &SEARCH_LIMITED.replace("burst_capacity = 1.0", "burst_capacity = 2.0"),
+        );
+        policies.reload().unwrap();
+
+        assert!(!policies.check("/search").unwrap().allowed);
+        let other = policies.check("/other").unwrap();
+        assert!(other.allowed);
+        assert_eq!(other.burst_capacity, 2.0);
+    }
+
+    #[test]
+    fn failed_reloads_keep_the_current_policies() {
+        let path = policy_path("failed", SEARCH_LIMITED);
+        let policies = PolicyWatcher::watch(&path, TestClock::new(0.0)).unwrap();
+        let before = policies.router();
+
+        replace(&path, "fallback = ");
+        assert!(matches!(
+            policies.reload(),
+            Err(FluxLimiterError::PolicyFileError(_))
+        ));
+        assert!(std::sync::Arc::ptr_eq(&before, &policies.router()));
+    }
+
+    #[test]
+    fn changes_on_disk_are_applied() {
+        let path = policy_path("watch", SEARCH_LIMITED);
+        let (sender, reloads) = mpsc::channel();
+        let policies = PolicyWatcher::watch(&path, TestClock::new(0.0))
+            .unwrap()
+            .with_reload_listener(move |result| {
+                let _ = sender.send(result.is_ok());
+            });
+        assert!(
+            !policies
+                .check("/health")
+                .unwrap()
+                .limit_per_second
+                .is_finite()
+        );
+
+        replace(
+            &path,
+            &SEARCH_LIMITED.replace(
+                "\"unlimited\"",
+                "{ limit = { rate_per_second = 4.0, burst_capacity = 0.0 } }",
+            ),
+        );
+
+        assert!(reloads.recv_timeout(Duration::from_secs(5)).unwrap());
+        assert_eq!(policies.check("/health").unwrap().limit_per_second, 4.0);
+    }
+}
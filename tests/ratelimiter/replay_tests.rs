@@ -0,0 +1,67 @@
+// tests/ratelimiter/replay_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, ReplayLoader, simulate};
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn replayed_clients_start_where_the_log_left_them() {
+        let config = FluxLimiterConfig::new(1.0, 2.0);
+        let mut loader = ReplayLoader::new(&config).unwrap();
+        // heavy user: 5 requests in the second before the deploy, 2 of them denied
+        loader.extend((0..5).map(|i| ("heavy", 9 * SECOND + i * SECOND / 5)));
+        // light user: one request long ago
+        loader.record("light", SECOND);
+        assert_eq!(
+            (loader.replayed(), loader.denied(), loader.len()),
+            (6, 2, 2)
+        );
+
+        let clock = TestClock::new(9.8);
+        let limiter = loader.load(config, clock.clone()).unwrap();
+        assert!(!limiter.check_request("heavy").unwrap().allowed);
+        for _ in 0..3 {
+            assert!(limiter.check_request("light").unwrap().allowed);
+        }
+        assert_eq!(limiter.stats().total_checks, 0);
+    }
+
+    #[test]
+    fn replay_matches_the_limiter_decisions() {
+        let config = FluxLimiterConfig::new(2.0, 1.0);
+        let arrivals = [0, 0, 0, SECOND / 4, SECOND / 2, SECOND, SECOND, 3 * SECOND];
+        let expected: Vec<bool> = simulate(config.clone(), arrivals)
+            .unwrap()
+            .iter()
+            .map(|decision| decision.allowed)
+            .collect();
+
+        let mut loader = ReplayLoader::new(&config).unwrap();
+        let replayed: Vec<bool> = arrivals
+            .iter()
+            .map(|&arrival| loader.record((), arrival))
+            .collect();
+        assert_eq!(replayed, expected);
+
+        let snapshot = loader.into_snapshot();
+        assert_eq!(snapshot.clients, [((), 3 * SECOND + SECOND / 2)]);
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let config = FluxLimiterConfig::new(0.0, 1.0);
+        assert!(matches!(
+            ReplayLoader::<String>::new(&config),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+        assert!(
+            ReplayLoader::<String>::new(&FluxLimiterConfig::new(1.0, 0.0))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
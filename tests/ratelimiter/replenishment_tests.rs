@@ -0,0 +1,92 @@
+// tests/ratelimiter/replenishment_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError, Replenishment};
+
+    fn limiter(clock: TestClock, replenishment: Replenishment) -> FluxLimiter<String, TestClock> {
+        // 1 req/sec, burst of 2
+        let config = FluxLimiterConfig::new(1.0, 2.0).replenishment(replenishment);
+        FluxLimiter::with_config(config, clock).unwrap()
+    }
+
+    fn allowed_in_a_row(limiter: &FluxLimiter<String, TestClock>) -> usize {
+        (0..10)
+            .take_while(|_| {
+                limiter
+                    .check_request("client1".to_string())
+                    .unwrap()
+                    .allowed
+            })
+            .count()
+    }
+
+    #[test]
+    fn stepwise_credits_only_whole_steps() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(clock.clone(), Replenishment::Stepwise(4.0));
+        assert_eq!(allowed_in_a_row(&limiter), 3);
+
+        // three seconds would regain the whole burst linearly, but the step is not over
+        clock.advance(3.0);
+        let decision = limiter.check_request("client1".to_string()).unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(1.0));
+
+        clock.advance(1.0);
+        assert_eq!(allowed_in_a_row(&limiter), 3);
+    }
+
+    #[test]
+    fn when_idle_holds_exhausted_clients_until_caught_up() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(clock.clone(), Replenishment::WhenIdle);
+
+        // without a denial capacity comes back linearly
+        for _ in 0..2 {
+            assert!(
+                limiter
+                    .check_request("client1".to_string())
+                    .unwrap()
+                    .allowed
+            );
+        }
+        clock.advance(1.0);
+        assert_eq!(allowed_in_a_row(&limiter), 2);
+
+        // the denial above holds the client until its TAT of 4s
+        clock.advance(1.0);
+        let decision = limiter.check_request("client1".to_string()).unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.retry_after_seconds, Some(2.0));
+
+        clock.advance(2.0);
+        assert_eq!(allowed_in_a_row(&limiter), 3);
+    }
+
+    #[test]
+    fn linear_regains_one_request_per_interval() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(clock.clone(), Replenishment::Linear);
+        assert_eq!(allowed_in_a_row(&limiter), 3);
+
+        clock.advance(1.0);
+        assert_eq!(allowed_in_a_row(&limiter), 1);
+        clock.advance(2.0);
+        assert_eq!(allowed_in_a_row(&limiter), 2);
+    }
+
+    #[test]
+    fn invalid_steps_are_rejected() {
+        for step in [0.0, -1.0, 1e-10, f64::NAN, f64::INFINITY] {
+            let config =
+                FluxLimiterConfig::new(1.0, 2.0).replenishment(Replenishment::Stepwise(step));
+            assert!(matches!(
+                config.validate(),
+                Err(FluxLimiterError::InvalidReplenishmentStep)
+            ));
+        }
+    }
+}
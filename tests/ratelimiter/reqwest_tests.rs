@@ -0,0 +1,81 @@
+// tests/ratelimiter/reqwest_tests.rs
+
+#[cfg(all(test, feature = "reqwest-middleware"))]
+mod tests {
+
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, SystemClock, ThrottleMiddleware};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    // tiny HTTP server answering every request with an empty 200
+    fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn outgoing_requests_are_paced_per_host() {
+        let base = spawn_server();
+        let config = FluxLimiterConfig::new(20.0, 0.0); // one request every 50ms
+        let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(ThrottleMiddleware::per_host(limiter.clone()))
+            .build();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            let response = client.get(&base).send().await.unwrap();
+            assert!(response.status().is_success());
+        }
+
+        // the second and third requests each waited for the next slot
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        assert_eq!(limiter.client_state.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn custom_keys_separate_budgets() {
+        let base = spawn_server();
+        let config = FluxLimiterConfig::new(1.0, 0.0); // one request per second
+        let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+        let middleware = ThrottleMiddleware::with_key(limiter.clone(), |request| {
+            request
+                .headers()
+                .get("x-tenant")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        });
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(middleware)
+            .build();
+
+        // different tenants never wait on each other
+        let start = Instant::now();
+        for tenant in ["a", "b", "c"] {
+            client
+                .get(&base)
+                .header("x-tenant", tenant)
+                .send()
+                .await
+                .unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_millis(900));
+        assert_eq!(limiter.client_state.len(), 3);
+    }
+}
@@ -0,0 +1,86 @@
+// tests/ratelimiter/rocket_tests.rs
+
+#[cfg(all(test, feature = "rocket"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterFairing, RateLimit, RocketKey};
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use std::sync::Arc;
+
+    #[rocket::get("/limited")]
+    fn limited(limit: RateLimit) -> String {
+        let decision = limit.decision.expect("test requests set a client IP");
+        format!("allowed={}", decision.allowed)
+    }
+
+    #[rocket::get("/open")]
+    fn open() -> &'static str {
+        "ok"
+    }
+
+    fn client(key: RocketKey) -> (Client, TestClock) {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0); // 1 req/sec, no burst
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock.clone()).unwrap());
+        let rocket = rocket::build()
+            .attach(FluxLimiterFairing::new(limiter, key))
+            .mount("/", rocket::routes![limited, open]);
+        (Client::tracked(rocket).unwrap(), clock)
+    }
+
+    // local requests have no client IP unless one is set
+    fn get_from<'c>(
+        client: &'c Client,
+        uri: &'static str,
+    ) -> rocket::local::blocking::LocalResponse<'c> {
+        client
+            .get(uri)
+            .remote("10.0.0.1:9000".parse().unwrap())
+            .dispatch()
+    }
+
+    #[test]
+    fn guard_rejects_with_429_and_retry_after() {
+        let (client, clock) = client(RocketKey::ClientIp);
+
+        let response = get_from(&client, "/limited");
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(
+            response.headers().get_one("X-RateLimit-Remaining"),
+            Some("0")
+        );
+        assert_eq!(response.into_string().unwrap(), "allowed=true");
+
+        let response = get_from(&client, "/limited");
+        assert_eq!(response.status(), Status::TooManyRequests);
+        assert_eq!(response.headers().get_one("Retry-After"), Some("1"));
+
+        clock.advance(1.0);
+        assert_eq!(get_from(&client, "/limited").status(), Status::Ok);
+    }
+
+    #[test]
+    fn routes_without_the_guard_are_not_limited() {
+        let (client, _clock) = client(RocketKey::ClientIp);
+
+        for _ in 0..3 {
+            let response = get_from(&client, "/open");
+            assert_eq!(response.status(), Status::Ok);
+            assert!(response.headers().get_one("Retry-After").is_none());
+        }
+    }
+
+    #[test]
+    fn route_keys_are_shared_across_clients() {
+        let (client, _clock) = client(RocketKey::Route);
+
+        assert_eq!(client.get("/limited").dispatch().status(), Status::Ok);
+        let response = client
+            .get("/limited")
+            .remote("10.0.0.2:9000".parse().unwrap())
+            .dispatch();
+        assert_eq!(response.status(), Status::TooManyRequests);
+    }
+}
@@ -0,0 +1,197 @@
+// tests/ratelimiter/router_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, Policy, PolicyRouter};
+
+    #[test]
+    fn exact_routes_beat_patterns() {
+        let clock = TestClock::new(0.0);
+        let router = PolicyRouter::with_routes(
+            [
+                ("/api/*", Policy::Limit(FluxLimiterConfig::new(1.0, 0.0))),
+                ("/api/health", Policy::Unlimited),
+            ],
+            clock,
+        )
+        .unwrap();
+
+        for _ in 0..10 {
+            assert!(router.check("/api/health").unwrap().allowed);
+        }
+        assert!(router.limiter_for("/api/health").is_none());
+
+        assert!(router.check("/api/users").unwrap().allowed);
+        assert!(!router.check("/api/users").unwrap().allowed);
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let clock = TestClock::new(0.0);
+        let router = PolicyRouter::with_routes(
+            [
+                ("/api/*", Policy::Limit(FluxLimiterConfig::new(1.0, 5.0))),
+                (
+                    "/api/v1/search*",
+                    Policy::Limit(FluxLimiterConfig::new(5.0, 0.0)),
+                ),
+            ],
+            clock,
+        )
+        .unwrap();
+
+        let decision = router.check("/api/v1/search?q=rust").unwrap();
+        assert_eq!(decision.limit_per_second, 5.0);
+        assert_eq!(decision.burst_capacity, 0.0);
+
+        let decision = router.check("/api/v1/users").unwrap();
+        assert_eq!(decision.limit_per_second, 1.0);
+    }
+
+    #[test]
+    fn globs_match_wildcards_in_order() {
+        let clock = TestClock::new(0.0);
+        let router = PolicyRouter::with_routes(
+            [
+                (
+                    "/api/*/upload",
+                    Policy::Limit(FluxLimiterConfig::new(1.0, 0.0)),
+                ),
+                ("/api/v?/*", Policy::Limit(FluxLimiterConfig::new(2.0, 0.0))),
+            ],
+            clock,
+        )
+        .unwrap();
+
+        // both globs match; the first one added applies
+        assert_eq!(
+            router.check("/api/v1/upload").unwrap().limit_per_second,
+            1.0
+        );
+        assert_eq!(
+            router.check("/api/v2/download").unwrap().limit_per_second,
+            2.0
+        );
+        assert!(router.limiter_for("/api/v10/download").is_none());
+        assert!(router.limiter_for("/api/upload").is_none());
+    }
+
+    #[test]
+    fn unmatched_keys_use_fallback() {
+        let clock = TestClock::new(0.0);
+        let mut router = PolicyRouter::new(clock.clone());
+
+        // unlimited by default
+        let decision = router.check("anything").unwrap();
+        assert!(decision.allowed);
+        assert!(decision.limit_per_second.is_infinite());
+
+        router.fallback(FluxLimiterConfig::new(1.0, 0.0)).unwrap();
+        assert!(router.check("anything").unwrap().allowed);
+        assert!(!router.check("anything").unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(router.check("anything").unwrap().allowed);
+    }
+
+    #[test]
+    fn keys_are_tracked_individually_within_a_route() {
+        let clock = TestClock::new(0.0);
+        let router = PolicyRouter::with_routes(
+            [("user:*", Policy::Limit(FluxLimiterConfig::new(1.0, 0.0)))],
+            clock,
+        )
+        .unwrap();
+
+        assert!(router.check("user:alice").unwrap().allowed);
+        assert!(router.check("user:bob").unwrap().allowed);
+        assert!(!router.check("user:alice").unwrap().allowed);
+    }
+
+    #[test]
+    fn replacing_a_route_resets_its_state() {
+        let clock = TestClock::new(0.0);
+        let mut router = PolicyRouter::new(clock);
+        router
+            .route("user:*", FluxLimiterConfig::new(1.0, 0.0))
+            .unwrap();
+
+        assert!(router.check("user:alice").unwrap().allowed);
+        assert!(!router.check("user:alice").unwrap().allowed);
+
+        router.route("user:*", Policy::Unlimited).unwrap();
+        assert!(router.check("user:alice").unwrap().allowed);
+        assert!(router.check("user:alice").unwrap().allowed);
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let clock = TestClock::new(0.0);
+        let mut router = PolicyRouter::new(clock);
+
+        assert!(
+            router
+                .route("user:*", FluxLimiterConfig::new(0.0, 1.0))
+                .is_err()
+        );
+        assert!(router.limiter_for("user:alice").is_none());
+    }
+
+    #[test]
+    fn cleanup_reaches_every_route() {
+        let clock = TestClock::new(0.0);
+        let mut router = PolicyRouter::with_routes(
+            [
+                ("/a", Policy::Limit(FluxLimiterConfig::new(1.0, 0.0))),
+                ("/b*", Policy::Limit(FluxLimiterConfig::new(1.0, 0.0))),
+            ],
+            clock.clone(),
+        )
+        .unwrap();
+        router.fallback(FluxLimiterConfig::new(1.0, 0.0)).unwrap();
+
+        router.check("/a").unwrap();
+        router.check("/b1").unwrap();
+        router.check("/other").unwrap();
+
+        clock.advance(10.0);
+        router.cleanup_stale_clients(1_000_000_000).unwrap();
+
+        for key in ["/a", "/b1", "/other"] {
+            assert!(router.limiter_for(key).unwrap().client_state.is_empty());
+        }
+    }
+
+    #[test]
+    fn effective_config_reports_the_route_and_its_rate_changes() {
+        let clock = TestClock::new(0.0);
+        let mut router = PolicyRouter::with_routes(
+            [
+                ("/api/*", Policy::Limit(FluxLimiterConfig::new(1.0, 2.0))),
+                ("/api/health", Policy::Unlimited),
+            ],
+            clock,
+        )
+        .unwrap();
+        router.fallback(FluxLimiterConfig::new(50.0, 0.0)).unwrap();
+
+        let api = router.effective_config("/api/users").unwrap();
+        assert_eq!((api.rate_per_second(), api.burst_capacity()), (1.0, 2.0));
+        assert_eq!(
+            router.effective_config("/other").unwrap().rate_per_second(),
+            50.0
+        );
+        assert!(router.effective_config("/api/health").is_none());
+
+        router
+            .limiter_for("/api/users")
+            .unwrap()
+            .operator("ops")
+            .set_rate(4.0)
+            .unwrap();
+        let api = router.effective_config("/api/users").unwrap();
+        assert_eq!((api.rate_per_second(), api.burst_capacity()), (4.0, 2.0));
+    }
+}
@@ -0,0 +1,78 @@
+// tests/ratelimiter/same_instant_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{Clock, ClockError, FluxLimiter, FluxLimiterConfig, Timestamp};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // a test clock that counts how often it is read
+    #[derive(Debug, Clone)]
+    struct CountingClock {
+        clock: TestClock,
+        reads: Arc<AtomicUsize>,
+    }
+
+    impl Clock for CountingClock {
+        fn now(&self) -> Result<Timestamp, ClockError> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            self.clock.now()
+        }
+    }
+
+    fn counting_clock() -> CountingClock {
+        CountingClock {
+            clock: TestClock::new(0.0),
+            reads: Arc::default(),
+        }
+    }
+
+    #[test]
+    fn check_many_same_instant_reads_the_clock_once() {
+        let clock = counting_clock();
+        // 1 req/sec, burst of 1
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let decisions = limiter
+            .check_many_same_instant(["user:1", "ip:a", "user:1", "user:1"])
+            .unwrap();
+        assert_eq!(clock.reads.load(Ordering::Relaxed), 1);
+        // in input order, each occurrence consuming capacity
+        let allowed: Vec<bool> = decisions.iter().map(|decision| decision.allowed).collect();
+        assert_eq!(allowed, [true, true, true, false]);
+        assert_eq!(decisions[0].reset_time_nanos, decisions[1].reset_time_nanos);
+    }
+
+    #[test]
+    fn same_instant_checks_use_the_reading_taken_up_front() {
+        let clock = counting_clock();
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock.clone()).unwrap();
+
+        let instant = limiter.same_instant();
+        assert!(instant.check_request_ref("user:1").unwrap().allowed);
+        clock.clock.advance(10.0);
+        let ip = instant.check_request("ip:a".to_string()).unwrap();
+        assert_eq!(ip.reset_time_nanos, 1_000_000_000);
+        assert_eq!(instant.timestamp(), Some(Timestamp::from_nanos(0)));
+        assert_eq!(clock.reads.load(Ordering::Relaxed), 1);
+
+        // a fresh reading sees the time that has passed
+        assert!(limiter.check_request_ref("user:1").unwrap().allowed);
+    }
+
+    #[test]
+    fn a_failed_reading_falls_back_to_reading_per_check() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), clock.clone()).unwrap();
+
+        clock.fail_next_call();
+        let instant = limiter.same_instant();
+        assert_eq!(instant.timestamp(), None);
+        assert!(instant.check_request("user:1").unwrap().allowed);
+    }
+}
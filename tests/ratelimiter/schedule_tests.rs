@@ -0,0 +1,163 @@
+// tests/ratelimiter/schedule_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FluxLimiterConfig, FluxLimiterError, ScheduledLimiter, TimeWindow, Weekday,
+    };
+
+    const HOUR: f64 = 3600.0;
+    const DAY: f64 = 24.0 * HOUR;
+
+    // the Unix epoch (t = 0) was Thursday 00:00 UTC
+    fn peak_limiter(clock: &TestClock) -> ScheduledLimiter<&'static str, TestClock> {
+        ScheduledLimiter::new(FluxLimiterConfig::new(100.0, 0.0), clock.clone())
+            .unwrap()
+            .profile(
+                TimeWindow::daily((9, 0), (17, 0)).unwrap().weekdays(),
+                FluxLimiterConfig::new(20.0, 0.0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn profile_applies_inside_its_window() {
+        let clock = TestClock::new(0.0);
+        let limiter = peak_limiter(&clock);
+
+        // Thursday 08:59 is off-peak
+        clock.set_time(8.0 * HOUR + 59.0 * 60.0);
+        let decision = limiter.check_request("user").unwrap();
+        assert_eq!(decision.limit_per_second, 100.0);
+
+        // Thursday 09:00 is peak
+        clock.set_time(9.0 * HOUR);
+        let decision = limiter.check_request("user").unwrap();
+        assert_eq!(decision.limit_per_second, 20.0);
+
+        // the window end is exclusive
+        clock.set_time(17.0 * HOUR);
+        let decision = limiter.check_request("user").unwrap();
+        assert_eq!(decision.limit_per_second, 100.0);
+    }
+
+    #[test]
+    fn day_restrictions_are_honoured() {
+        let clock = TestClock::new(0.0);
+        let limiter = peak_limiter(&clock);
+
+        // Saturday 10:00
+        clock.set_time(2.0 * DAY + 10.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 100.0);
+
+        // Monday 10:00
+        clock.set_time(4.0 * DAY + 10.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 20.0);
+    }
+
+    #[test]
+    fn wrapping_windows_belong_to_their_start_day() {
+        let clock = TestClock::new(0.0);
+        let limiter =
+            ScheduledLimiter::<&str, _>::new(FluxLimiterConfig::new(10.0, 0.0), clock.clone())
+                .unwrap()
+                .profile(
+                    TimeWindow::daily((22, 0), (6, 0))
+                        .unwrap()
+                        .on(&[Weekday::Friday]),
+                    FluxLimiterConfig::new(1.0, 0.0),
+                )
+                .unwrap();
+
+        // Friday 23:00 and Saturday 05:00 are in the window
+        clock.set_time(DAY + 23.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 1.0);
+        clock.set_time(2.0 * DAY + 5.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 1.0);
+
+        // Friday 05:00 belongs to Thursday's window, which is not enabled
+        clock.set_time(DAY + 5.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 10.0);
+    }
+
+    #[test]
+    fn utc_offset_shifts_the_schedule() {
+        let clock = TestClock::new(0.0);
+        // UTC-5: Thursday 14:00 UTC is 09:00 local
+        let limiter = peak_limiter(&clock).utc_offset_minutes(-300);
+
+        clock.set_time(14.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 20.0);
+        clock.set_time(9.0 * HOUR);
+        assert_eq!(limiter.active_limiter().unwrap().rate(), 100.0);
+    }
+
+    #[test]
+    fn profiles_switch_automatically_as_time_passes() {
+        let clock = TestClock::new(9.0 * HOUR - 1.0);
+        let limiter = ScheduledLimiter::new(FluxLimiterConfig::new(1.0, 1.0), clock.clone())
+            .unwrap()
+            .profile(
+                TimeWindow::daily((9, 0), (17, 0)).unwrap(),
+                FluxLimiterConfig::new(1.0, 0.0),
+            )
+            .unwrap();
+
+        assert!(limiter.check_request("user").unwrap().allowed);
+        assert!(limiter.check_request("user").unwrap().allowed);
+        assert!(!limiter.check_request("user").unwrap().allowed);
+
+        // the peak profile has its own, tighter state
+        clock.advance(1.0);
+        assert!(limiter.check_request("user").unwrap().allowed);
+        assert!(!limiter.check_request("user").unwrap().allowed);
+    }
+
+    #[test]
+    fn clock_failures_follow_the_default_failure_policy() {
+        let clock = TestClock::new(0.0);
+        let limiter = peak_limiter(&clock);
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.check_request("user"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_windows_are_rejected() {
+        assert!(matches!(
+            TimeWindow::daily((24, 1), (1, 0)),
+            Err(FluxLimiterError::InvalidTimeWindow)
+        ));
+        assert!(matches!(
+            TimeWindow::daily((9, 60), (10, 0)),
+            Err(FluxLimiterError::InvalidTimeWindow)
+        ));
+        assert!(matches!(
+            TimeWindow::daily((9, 0), (9, 0)),
+            Err(FluxLimiterError::InvalidTimeWindow)
+        ));
+        assert!(TimeWindow::daily((0, 0), (24, 0)).is_ok());
+    }
+
+    #[test]
+    fn effective_config_is_the_active_profile() {
+        let clock = TestClock::new(0.0);
+        let limiter = peak_limiter(&clock);
+
+        assert_eq!(limiter.effective_config().unwrap().rate_per_second(), 100.0);
+        // Thursday 09:00 is peak
+        clock.set_time(9.0 * HOUR);
+        assert_eq!(limiter.effective_config().unwrap().rate_per_second(), 20.0);
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.effective_config(),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+    }
+}
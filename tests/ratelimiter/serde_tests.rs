@@ -0,0 +1,116 @@
+// tests/ratelimiter/serde_tests.rs
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        AuditEvent, Classification, Decision, FluxLimiter, FluxLimiterConfig, FluxLimiterDecision,
+        StateSnapshot,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn decision_round_trips_through_json() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        let denied = limiter.check_request("client1").unwrap();
+
+        let json = serde_json::to_string(&denied).unwrap();
+        assert!(json.contains("\"allowed\":false"));
+        assert!(json.contains("retry_after_seconds"));
+
+        let parsed: FluxLimiterDecision = serde_json::from_str(&json).unwrap();
+        assert!(!parsed.allowed);
+        assert_eq!(parsed.retry_after_seconds, denied.retry_after_seconds);
+        assert_eq!(parsed.reset_time_nanos, denied.reset_time_nanos);
+    }
+
+    #[test]
+    fn exempt_decisions_round_trip_through_json() {
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, TestClock::new(0.0))
+            .unwrap()
+            .with_classifier(|_: &String| Classification::Bypass);
+
+        let exempt = limiter.check_request("internal".to_string()).unwrap();
+        assert_eq!(exempt.limit_per_second, f64::INFINITY);
+
+        // JSON has no infinity, so the unlimited rate and burst are written as null
+        let json = serde_json::to_string(&exempt).unwrap();
+        assert!(json.contains("\"limit_per_second\":null"));
+        assert!(json.contains("\"burst_capacity\":null"));
+        let parsed: FluxLimiterDecision = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, exempt);
+
+        let typed = Decision::from(exempt);
+        let json = serde_json::to_string(&typed).unwrap();
+        assert_eq!(serde_json::from_str::<Decision>(&json).unwrap(), typed);
+    }
+
+    #[test]
+    fn config_deserializes_from_json() {
+        let json = r#"{ "rate_per_second": 25.0, "burst_capacity": 5.0 }"#;
+        let config: FluxLimiterConfig = serde_json::from_str(json).unwrap();
+        assert!(config.validate().is_ok());
+
+        let clock = TestClock::new(0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+        assert_eq!(limiter.rate(), 25.0);
+        assert_eq!(limiter.burst(), 5.0);
+    }
+
+    #[test]
+    fn deserialized_config_is_still_validated() {
+        let json = r#"{ "rate_per_second": 0.0, "burst_capacity": 5.0 }"#;
+        let config: FluxLimiterConfig = serde_json::from_str(json).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn state_snapshot_survives_a_restart_through_json() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config.clone(), clock.clone()).unwrap();
+        limiter.check_request_ref("client1").unwrap();
+
+        let json = serde_json::to_string(&limiter.export_state()).unwrap();
+        assert!(json.contains("\"version\":1"));
+
+        let snapshot: StateSnapshot<String> = serde_json::from_str(&json).unwrap();
+        let restored = FluxLimiter::with_state(config, clock, snapshot).unwrap();
+        assert!(!restored.check_request_ref("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn audit_events_serialize_as_flat_records() {
+        let trail = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&trail);
+        let limiter =
+            FluxLimiter::with_config(FluxLimiterConfig::new(1.0, 0.0), TestClock::new(2.0))
+                .unwrap()
+                .with_audit_sink(move |event: AuditEvent<String>| {
+                    sink.lock()
+                        .unwrap()
+                        .push(serde_json::to_value(event).unwrap())
+                });
+
+        limiter
+            .operator("alice")
+            .penalize("bot".to_string(), 4.0)
+            .unwrap();
+        assert_eq!(
+            trail.lock().unwrap()[0],
+            serde_json::json!({
+                "actor": "alice",
+                "action": "penalize",
+                "client_id": "bot",
+                "cost": 4.0,
+                "timestamp_nanos": 2_000_000_000u64,
+            })
+        );
+    }
+}
@@ -0,0 +1,141 @@
+// tests/ratelimiter/sharded_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, FluxLimiterError, ShardedFluxLimiter};
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    #[test]
+    fn sharded_limiter_rejects_invalid_shard_counts() {
+        for shards in [0, 1, 6] {
+            let result = ShardedFluxLimiter::<String, _>::new(
+                FluxLimiterConfig::new(10.0, 5.0),
+                shards,
+                TestClock::new(0.0),
+            );
+            assert!(matches!(
+                result.unwrap_err(),
+                FluxLimiterError::InvalidShardAmount(n) if n == shards
+            ));
+        }
+    }
+
+    #[test]
+    fn sharded_limiter_validates_config() {
+        let result = ShardedFluxLimiter::<String, _>::new(
+            FluxLimiterConfig::new(0.0, 5.0),
+            4,
+            TestClock::new(0.0),
+        );
+        assert!(matches!(result.unwrap_err(), FluxLimiterError::InvalidRate));
+    }
+
+    #[test]
+    fn each_key_is_limited_like_a_single_limiter() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate requests
+        let limiter = ShardedFluxLimiter::<String, _>::new(config, 4, clock.clone()).unwrap();
+
+        for client in ["a", "b", "c", "d", "e"] {
+            assert!(limiter.check_request(client.to_string()).unwrap().allowed);
+            assert!(limiter.check_request_ref(client).unwrap().allowed);
+            assert!(!limiter.check_request(client.to_string()).unwrap().allowed);
+        }
+
+        clock.advance(1.0);
+        assert!(limiter.check_request_ref("a").unwrap().allowed);
+        assert_eq!(limiter.rate(), 1.0);
+        assert_eq!(limiter.burst(), 1.0);
+    }
+
+    #[test]
+    fn keys_are_spread_across_shards() {
+        let limiter =
+            ShardedFluxLimiter::new(FluxLimiterConfig::new(10.0, 5.0), 8, TestClock::new(0.0))
+                .unwrap();
+
+        for client in 0..1_000u64 {
+            limiter.check_request(client).unwrap();
+        }
+
+        let sizes: Vec<usize> = limiter
+            .shards()
+            .iter()
+            .map(|shard| shard.client_state.len())
+            .collect();
+        assert_eq!(sizes.iter().sum::<usize>(), 1_000);
+        assert!(sizes.iter().all(|&size| size > 0));
+        assert!(limiter.shard_for(&7).client_state.contains_key(&7));
+    }
+
+    #[test]
+    fn stats_are_combined_across_shards() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = ShardedFluxLimiter::new(config, 4, clock.clone()).unwrap();
+
+        for client in 0..20u64 {
+            limiter.check_request(client).unwrap();
+            limiter.check_request(client).unwrap();
+        }
+
+        let stats = limiter.stats();
+        assert_eq!((stats.allowed, stats.denied), (20, 20));
+        assert_eq!(stats.total_checks, 40);
+        assert_eq!(stats.current_clients, 20);
+
+        clock.advance(10.0);
+        let dry_run = limiter
+            .cleanup_stale_clients_dry_run(1_000_000_000)
+            .unwrap();
+        assert_eq!((dry_run.scanned, dry_run.removed), (20, 20));
+        assert_eq!(limiter.stats().current_clients, 20);
+
+        let report = limiter.cleanup_stale_clients(1_000_000_000).unwrap();
+        assert_eq!(report, dry_run);
+        let stats = limiter.stats();
+        assert_eq!((stats.evictions, stats.current_clients), (20, 0));
+
+        limiter.reset_stats();
+        assert_eq!(limiter.stats().total_checks, 0);
+    }
+
+    #[test]
+    fn clones_share_shards() {
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = ShardedFluxLimiter::<String, _>::new(config, 2, TestClock::new(0.0)).unwrap();
+        let clone = limiter.clone();
+
+        assert!(limiter.check_request_ref("client").unwrap().allowed);
+        assert!(!clone.check_request_ref("client").unwrap().allowed);
+    }
+
+    #[test]
+    fn fixed_hashers_assign_shards_reproducibly() {
+        let shard_counts = || {
+            let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+            let limiter = ShardedFluxLimiter::<String, _, _>::with_hasher(
+                config,
+                4,
+                TestClock::new(0.0),
+                BuildHasherDefault::<DefaultHasher>::default(),
+            )
+            .unwrap();
+            for client in 0..40 {
+                limiter.check_request(format!("client{client}")).unwrap();
+            }
+            limiter
+                .shards()
+                .iter()
+                .map(|shard| shard.stats().current_clients)
+                .collect::<Vec<_>>()
+        };
+
+        let counts = shard_counts();
+        assert_eq!(counts, shard_counts());
+        assert_eq!(counts.iter().sum::<usize>(), 40);
+    }
+}
@@ -0,0 +1,124 @@
+// tests/ratelimiter/shm_store_tests.rs
+
+#[cfg(all(test, feature = "shm"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FluxLimiterConfig, FluxLimiterError, SharedMemoryStore, StateStore, StoreBackedLimiter,
+    };
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    // a fresh file per test, so parallel tests do not share tables
+    fn shm_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("flux-limiter-shm-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn stores_over_the_same_file_share_one_limit() {
+        let path = shm_path("shared");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let first = StoreBackedLimiter::with_config(
+            config.clone(),
+            clock.clone(),
+            SharedMemoryStore::open(&path, 64).unwrap(),
+        )
+        .unwrap();
+        let second = StoreBackedLimiter::with_config(
+            config,
+            clock.clone(),
+            SharedMemoryStore::open(&path, 64).unwrap(),
+        )
+        .unwrap();
+
+        assert!(first.check_request(&"client1").unwrap().allowed);
+        assert!(second.check_request(&"client1").unwrap().allowed);
+        assert!(!first.check_request(&"client1").unwrap().allowed);
+        assert!(!second.check_request(&"client1").unwrap().allowed);
+        assert!(second.check_request(&"client2").unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(second.check_request(&"client1").unwrap().allowed);
+        assert_eq!(
+            StateStore::<str>::get(first.store(), "client1").unwrap(),
+            Some(3_000_000_000)
+        );
+        assert_eq!(first.store().len(), 2);
+    }
+
+    #[test]
+    fn concurrent_workers_admit_the_burst_once() {
+        let path = shm_path("concurrent");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 9.0);
+        let allowed = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = StoreBackedLimiter::with_config(
+                    config.clone(),
+                    clock.clone(),
+                    SharedMemoryStore::open(&path, 16).unwrap(),
+                )
+                .unwrap();
+                let allowed = Arc::clone(&allowed);
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        if limiter.check_request(&"client1").unwrap().allowed {
+                            allowed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(allowed.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn full_table_rejects_new_clients() {
+        let path = shm_path("full");
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0);
+        let limiter = StoreBackedLimiter::with_config(
+            config,
+            clock,
+            SharedMemoryStore::open(&path, 1).unwrap(),
+        )
+        .unwrap();
+
+        assert!(limiter.check_request(&"client1").unwrap().allowed);
+        assert!(matches!(
+            limiter.check_request(&"client2"),
+            Err(FluxLimiterError::StoreError(_))
+        ));
+        // the client holding the slot is unaffected
+        assert!(limiter.check_request(&"client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn reopening_checks_the_file() {
+        let path = shm_path("reopen");
+        drop(SharedMemoryStore::open(&path, 8).unwrap());
+
+        let err = SharedMemoryStore::open(&path, 16).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        std::fs::write(&path, vec![0xff; 256]).unwrap();
+        let err = SharedMemoryStore::open(&path, 8).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let err = SharedMemoryStore::open(&path, 0).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,171 @@
+// tests/ratelimiter/sidecar_tests.rs
+
+#[cfg(all(test, feature = "sidecar"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        CheckRequest, CheckResponse, FluxLimiter, FluxLimiterConfig, PeekRequest, PeekResponse,
+        RateLimiterService, ResetRequest, ResetResponse, StatsRequest, StatsResponse,
+    };
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::client::Grpc;
+    use tonic::codec::ProstCodec;
+    use tonic::codegen::http::uri::PathAndQuery;
+    use tonic::transport::{Channel, Server};
+    use tonic::{Code, Request, Status};
+
+    // serve `limiter` on a free local port and connect a client to it
+    async fn start(limiter: Arc<FluxLimiter<String, TestClock>>) -> Grpc<Channel> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(RateLimiterService::new(limiter))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let channel = Channel::from_shared(format!("http://{addr}"))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        Grpc::new(channel)
+    }
+
+    async fn call<M, R>(
+        client: &mut Grpc<Channel>,
+        method: &'static str,
+        message: M,
+    ) -> Result<R, Status>
+    where
+        M: prost::Message + Send + Sync + 'static,
+        R: prost::Message + Default + Send + Sync + 'static,
+    {
+        client.ready().await.unwrap();
+        let path = PathAndQuery::from_static(method);
+        client
+            .unary(Request::new(message), path, ProstCodec::<M, R>::default())
+            .await
+            .map(|response| response.into_inner())
+    }
+
+    async fn check(client: &mut Grpc<Channel>, client_id: &str) -> Result<CheckResponse, Status> {
+        let request = CheckRequest {
+            client_id: client_id.to_string(),
+        };
+        call(client, "/flux_limiter.v1.RateLimiter/Check", request).await
+    }
+
+    fn limiter(clock: &TestClock) -> Arc<FluxLimiter<String, TestClock>> {
+        // 1 req/sec, burst of 1
+        let config = FluxLimiterConfig::new(1.0, 1.0).stats(true);
+        Arc::new(FluxLimiter::with_config(config, clock.clone()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn check_returns_the_decision() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(limiter(&clock)).await;
+
+        let allowed = check(&mut client, "client1").await.unwrap();
+        assert!(allowed.allowed);
+        assert_eq!(allowed.reason, "conforming");
+        assert_eq!(allowed.limit_per_second, 1.0);
+        assert!(check(&mut client, "client1").await.unwrap().allowed);
+
+        let denied = check(&mut client, "client1").await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.reason, "burst_exhausted");
+        assert_eq!(denied.retry_after_seconds, Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn peek_and_reset_share_state_with_the_limiter() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        let mut client = start(Arc::clone(&limiter)).await;
+
+        let request = PeekRequest {
+            client_id: "client1".to_string(),
+        };
+        let untracked: PeekResponse = call(
+            &mut client,
+            "/flux_limiter.v1.RateLimiter/Peek",
+            request.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(!untracked.tracked);
+
+        // a check made in-process shows through the service
+        limiter.check_request("client1".to_string()).unwrap();
+        let tracked: PeekResponse = call(&mut client, "/flux_limiter.v1.RateLimiter/Peek", request)
+            .await
+            .unwrap();
+        assert!(tracked.tracked);
+        assert_eq!(tracked.tat_nanos, 1_000_000_000);
+
+        let request = ResetRequest {
+            client_id: "client1".to_string(),
+        };
+        let reset: ResetResponse = call(&mut client, "/flux_limiter.v1.RateLimiter/Reset", request)
+            .await
+            .unwrap();
+        assert!(reset.removed);
+        assert!(limiter.client_snapshot("client1").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn stats_counts_checks() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(limiter(&clock)).await;
+
+        for _ in 0..3 {
+            check(&mut client, "client1").await.unwrap();
+        }
+        let stats: StatsResponse = call(
+            &mut client,
+            "/flux_limiter.v1.RateLimiter/Stats",
+            StatsRequest {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            (
+                stats.total_checks,
+                stats.allowed,
+                stats.denied,
+                stats.current_clients
+            ),
+            (3, 2, 1, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn bad_calls_fail_with_status_codes() {
+        let clock = TestClock::new(0.0);
+        let mut client = start(limiter(&clock)).await;
+
+        assert_eq!(
+            check(&mut client, "").await.unwrap_err().code(),
+            Code::InvalidArgument
+        );
+
+        clock.fail_next_call();
+        assert_eq!(
+            check(&mut client, "client1").await.unwrap_err().code(),
+            Code::Unavailable
+        );
+
+        let unknown: Result<StatsResponse, Status> = call(
+            &mut client,
+            "/flux_limiter.v1.RateLimiter/Drain",
+            StatsRequest {},
+        )
+        .await;
+        assert_eq!(unknown.unwrap_err().code(), Code::Unimplemented);
+    }
+}
@@ -0,0 +1,133 @@
+// tests/ratelimiter/simulation_tests.rs
+
+#[cfg(all(test, feature = "simulation"))]
+mod tests {
+
+    use flux_limiter::{ArrivalPattern, FluxLimiterConfig, FluxLimiterError, Simulator};
+
+    #[test]
+    fn demand_within_the_limit_is_never_denied() {
+        let simulator = Simulator::new(FluxLimiterConfig::new(10.0, 0.0)).unwrap();
+        let report = simulator
+            .run(
+                ArrivalPattern::Constant {
+                    rate_per_second: 10.0,
+                },
+                10.0,
+            )
+            .unwrap();
+
+        assert_eq!((report.total, report.allowed, report.denied), (100, 100, 0));
+        assert_eq!(report.retry_after_p50, None);
+        assert_eq!(report.retry_after_max, None);
+    }
+
+    #[test]
+    fn constant_overload_is_capped_at_the_rate() {
+        let simulator = Simulator::new(FluxLimiterConfig::new(10.0, 0.0)).unwrap();
+        let report = simulator
+            .run(
+                ArrivalPattern::Constant {
+                    rate_per_second: 40.0,
+                },
+                10.0,
+            )
+            .unwrap();
+
+        assert_eq!(report.total, 400);
+        assert_eq!(report.allowed, 100);
+        assert_eq!(report.denied, 300);
+        // denied requests land 25ms, 50ms or 75ms after the last allowed one
+        assert!((report.retry_after_p50.unwrap() - 0.05).abs() < 1e-6);
+        assert!((report.retry_after_max.unwrap() - 0.075).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bursts_beyond_the_burst_capacity_are_denied() {
+        // 5 immediate requests, refilled fully between bursts
+        let simulator = Simulator::new(FluxLimiterConfig::new(10.0, 4.0)).unwrap();
+        let report = simulator
+            .run(
+                ArrivalPattern::Bursty {
+                    burst_size: 8,
+                    period_seconds: 1.0,
+                },
+                10.0,
+            )
+            .unwrap();
+
+        assert_eq!(report.total, 80);
+        assert_eq!((report.allowed, report.denied), (50, 30));
+        assert!(report.retry_after_p50.unwrap() <= report.retry_after_p90.unwrap());
+        assert!(report.retry_after_p99.unwrap() <= report.retry_after_max.unwrap());
+    }
+
+    #[test]
+    fn poisson_runs_are_reproducible_per_seed() {
+        let config = FluxLimiterConfig::new(5.0, 2.0);
+        let pattern = ArrivalPattern::Poisson {
+            rate_per_second: 10.0,
+        };
+
+        let first = Simulator::new(config.clone())
+            .unwrap()
+            .seed(7)
+            .run(pattern, 100.0);
+        let again = Simulator::new(config.clone())
+            .unwrap()
+            .seed(7)
+            .run(pattern, 100.0);
+        let other = Simulator::new(config).unwrap().seed(8).run(pattern, 100.0);
+
+        let first = first.unwrap();
+        assert_eq!(first, again.unwrap());
+        assert_ne!(first, other.unwrap());
+        // about 1000 arrivals at twice the limit
+        assert!((800..1_200).contains(&first.total));
+        assert!(first.allowed <= 5 * 100 + 3);
+        assert_eq!(first.allowed + first.denied, first.total);
+    }
+
+    #[test]
+    fn invalid_runs_are_rejected() {
+        let simulator = Simulator::new(FluxLimiterConfig::new(10.0, 0.0)).unwrap();
+        let invalid = [
+            (
+                ArrivalPattern::Constant {
+                    rate_per_second: 0.0,
+                },
+                1.0,
+            ),
+            (
+                ArrivalPattern::Poisson {
+                    rate_per_second: f64::NAN,
+                },
+                1.0,
+            ),
+            (
+                ArrivalPattern::Bursty {
+                    burst_size: 0,
+                    period_seconds: 1.0,
+                },
+                1.0,
+            ),
+            (
+                ArrivalPattern::Constant {
+                    rate_per_second: 1.0,
+                },
+                -1.0,
+            ),
+        ];
+
+        for (pattern, duration) in invalid {
+            assert!(matches!(
+                simulator.run(pattern, duration),
+                Err(FluxLimiterError::InvalidSimulation)
+            ));
+        }
+        assert!(matches!(
+            Simulator::new(FluxLimiterConfig::new(-1.0, 0.0)),
+            Err(FluxLimiterError::InvalidRate)
+        ));
+    }
+}
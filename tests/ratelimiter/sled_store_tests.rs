@@ -0,0 +1,78 @@
+// tests/ratelimiter/sled_store_tests.rs
+
+#[cfg(all(test, feature = "sled"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiterConfig, SledStore, StateStore, StoreBackedLimiter};
+
+    fn temporary_tree() -> (sled::Db, sled::Tree) {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let tree = db.open_tree("rate-limits").unwrap();
+        (db, tree)
+    }
+
+    #[test]
+    fn write_through_persists_every_allowed_request() {
+        let (_db, tree) = temporary_tree();
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter =
+            StoreBackedLimiter::with_config(config, clock, SledStore::new(tree.clone())).unwrap();
+
+        assert!(limiter.check_request(&"client1").unwrap().allowed);
+        assert!(!limiter.check_request(&"client1").unwrap().allowed);
+        assert_eq!(
+            tree.get("client1").unwrap().unwrap().as_ref(),
+            &1_000_000_000u64.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn state_survives_a_new_store_over_the_same_tree() {
+        let (_db, tree) = temporary_tree();
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+
+        let first = StoreBackedLimiter::with_config(
+            config.clone(),
+            clock.clone(),
+            SledStore::new(tree.clone()),
+        )
+        .unwrap();
+        assert!(first.check_request(&"client1").unwrap().allowed);
+        drop(first);
+
+        let second = StoreBackedLimiter::with_config(config, clock, SledStore::new(tree)).unwrap();
+        assert!(!second.check_request(&"client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn write_behind_defers_writes_until_flush() {
+        let (_db, tree) = temporary_tree();
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter =
+            StoreBackedLimiter::with_config(config, clock, SledStore::write_behind(tree.clone()))
+                .unwrap();
+
+        assert!(limiter.check_request(&"client1").unwrap().allowed);
+        assert!(!limiter.check_request(&"client1").unwrap().allowed);
+        assert!(tree.get("client1").unwrap().is_none());
+
+        limiter.store().flush().unwrap();
+        assert!(tree.get("client1").unwrap().is_some());
+    }
+
+    #[test]
+    fn write_behind_reads_through_to_existing_state() {
+        let (_db, tree) = temporary_tree();
+        tree.insert("client1", &5u64.to_be_bytes()).unwrap();
+
+        let store = SledStore::write_behind(tree);
+        assert_eq!(StateStore::<str>::get(&store, "client1").unwrap(), Some(5));
+        assert!(!store.compare_and_swap("client1", None, 7).unwrap());
+        assert!(store.compare_and_swap("client1", Some(5), 7).unwrap());
+        assert_eq!(StateStore::<str>::get(&store, "client1").unwrap(), Some(7));
+    }
+}
@@ -0,0 +1,84 @@
+// tests/ratelimiter/snapshot_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError};
+
+    #[test]
+    fn snapshot_of_unknown_client_is_none() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        assert!(limiter.client_snapshot("nobody").unwrap().is_none());
+        assert_eq!(limiter.client_state.len(), 0);
+    }
+
+    #[test]
+    fn snapshot_reports_time_until_conforming() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(2.0, 0.0); // 2 req/sec, no burst
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        assert!(limiter.check_request("client1").unwrap().allowed);
+
+        let snapshot = limiter.client_snapshot("client1").unwrap().unwrap();
+        assert_eq!(snapshot.tat_nanos, 500_000_000);
+        assert!(!snapshot.is_conforming());
+        assert_eq!(snapshot.time_until_conforming_nanos, 500_000_000);
+        assert_eq!(snapshot.remaining_capacity, 0.0);
+
+        clock.advance(0.5);
+        let snapshot = limiter.client_snapshot("client1").unwrap().unwrap();
+        assert!(snapshot.is_conforming());
+        assert_eq!(snapshot.time_until_conforming_seconds(), 0.0);
+    }
+
+    #[test]
+    fn snapshot_does_not_mutate_state() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 2.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        let decision = limiter.check_request("client1").unwrap();
+        for _ in 0..10 {
+            let snapshot = limiter.client_snapshot("client1").unwrap().unwrap();
+            assert_eq!(snapshot.tat_nanos, decision.reset_time_nanos);
+            assert_eq!(
+                Some(snapshot.remaining_capacity),
+                decision.remaining_capacity
+            );
+        }
+        assert!(limiter.check_request("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn snapshot_propagates_clock_error() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+        limiter.check_request("client1").unwrap();
+
+        clock.fail_next_call();
+        assert!(matches!(
+            limiter.client_snapshot("client1"),
+            Err(FluxLimiterError::ClockError(_))
+        ));
+    }
+
+    #[test]
+    fn reset_client_restores_the_burst() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::<String, _>::with_config(config, clock).unwrap();
+
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+        assert!(!limiter.check_request_ref("client1").unwrap().allowed);
+
+        assert!(limiter.reset_client("client1"));
+        assert!(!limiter.reset_client("client1"));
+        assert!(limiter.check_request_ref("client1").unwrap().allowed);
+    }
+}
@@ -0,0 +1,58 @@
+// tests/ratelimiter/state_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterError, StateSnapshot};
+
+    #[test]
+    fn restored_limiter_keeps_clients_throttled() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 1 req/sec, burst of 1
+        let limiter = FluxLimiter::with_config(config.clone(), clock.clone()).unwrap();
+
+        // exhaust client1's burst before the "restart"
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        limiter.check_request("client2").unwrap();
+
+        let snapshot = limiter.export_state();
+        assert_eq!(snapshot.version, StateSnapshot::<&str>::VERSION);
+        assert_eq!(snapshot.clients.len(), 2);
+
+        let restored = FluxLimiter::with_state(config, clock.clone(), snapshot).unwrap();
+        assert!(!restored.check_request("client1").unwrap().allowed);
+        assert!(restored.check_request("client2").unwrap().allowed);
+
+        // unknown clients still start fresh
+        assert!(restored.check_request("client3").unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(restored.check_request("client1").unwrap().allowed);
+    }
+
+    #[test]
+    fn unsupported_snapshot_version_is_rejected() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let mut snapshot = StateSnapshot::new(vec![("client1", 0)]);
+        snapshot.version = 99;
+
+        let result = FluxLimiter::with_state(config, clock, snapshot);
+        assert!(matches!(
+            result,
+            Err(FluxLimiterError::UnsupportedStateVersion(99))
+        ));
+    }
+
+    #[test]
+    fn with_state_still_validates_config() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(0.0, 0.0);
+        let snapshot = StateSnapshot::<&str>::new(Vec::new());
+
+        let result = FluxLimiter::with_state(config, clock, snapshot);
+        assert!(matches!(result, Err(FluxLimiterError::InvalidRate)));
+    }
+}
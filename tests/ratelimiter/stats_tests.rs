@@ -0,0 +1,95 @@
+// tests/ratelimiter/stats_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{FluxLimiter, FluxLimiterConfig, FluxLimiterStats};
+
+    #[test]
+    fn stats_are_disabled_by_default() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client1").unwrap();
+
+        let stats = limiter.stats();
+        assert_eq!(stats.total_checks, 0);
+        assert_eq!(stats.current_clients, 1);
+    }
+
+    #[test]
+    fn stats_count_allows_and_denials() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        for _ in 0..5 {
+            limiter.check_request("client1").unwrap();
+        }
+        limiter.check_request("client2").unwrap();
+        limiter.check_requests(["client3", "client3"]).unwrap();
+
+        assert_eq!(
+            limiter.stats(),
+            FluxLimiterStats {
+                total_checks: 8,
+                allowed: 5,
+                denied: 3,
+                evictions: 0,
+                current_clients: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn stats_count_multi_key_checks_per_key() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        assert!(limiter.check_all(&["a", "b"]).unwrap().allowed);
+        assert!(!limiter.check_all(&["a", "c"]).unwrap().allowed);
+
+        let stats = limiter.stats();
+        assert_eq!(stats.allowed, 2);
+        assert_eq!(stats.denied, 2);
+    }
+
+    #[test]
+    fn stats_count_cleanup_evictions() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock.clone()).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client2").unwrap();
+        clock.set_time(10.0);
+        limiter.check_request("client3").unwrap();
+
+        limiter.cleanup_stale_clients(0).unwrap();
+        let stats = limiter.stats();
+        assert_eq!(stats.evictions, 2);
+        assert_eq!(stats.current_clients, 1);
+    }
+
+    #[test]
+    fn reset_stats_zeroes_counters() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0).stats(true);
+        let limiter = FluxLimiter::with_config(config, clock).unwrap();
+
+        limiter.check_request("client1").unwrap();
+        limiter.check_request("client1").unwrap();
+        limiter.reset_stats();
+
+        let stats = limiter.stats();
+        assert_eq!(stats.total_checks, 0);
+        assert_eq!(stats.allowed, 0);
+        assert_eq!(stats.denied, 0);
+        // the tracked clients are not affected
+        assert_eq!(stats.current_clients, 1);
+    }
+}
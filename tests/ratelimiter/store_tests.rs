@@ -0,0 +1,109 @@
+// tests/ratelimiter/store_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FluxLimiterConfig, FluxLimiterError, StateStore, StoreBackedLimiter, StoreError,
+    };
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    // minimal store that can lose a race or fail on demand
+    #[derive(Default)]
+    struct MapStore {
+        tats: Mutex<HashMap<String, u64>>,
+        lose_next_swap: AtomicBool,
+        fail: AtomicBool,
+        swaps: AtomicUsize,
+    }
+
+    impl StateStore<String> for MapStore {
+        fn get(&self, key: &String) -> Result<Option<u64>, StoreError> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(StoreError::new("backend unavailable"));
+            }
+            Ok(self.tats.lock().unwrap().get(key).copied())
+        }
+
+        fn compare_and_swap(
+            &self,
+            key: &String,
+            expected: Option<u64>,
+            new: u64,
+        ) -> Result<bool, StoreError> {
+            self.swaps.fetch_add(1, Ordering::Relaxed);
+            if self.lose_next_swap.swap(false, Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let mut tats = self.tats.lock().unwrap();
+            if tats.get(key).copied() != expected {
+                return Ok(false);
+            }
+            tats.insert(key.clone(), new);
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn store_backed_limiter_applies_gcra() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 1 req/sec, burst of 1
+        let limiter =
+            StoreBackedLimiter::with_config(config, clock.clone(), MapStore::default()).unwrap();
+        let client = "client1".to_string();
+
+        assert!(limiter.check_request(&client).unwrap().allowed);
+        assert!(limiter.check_request(&client).unwrap().allowed);
+        assert!(!limiter.check_request(&client).unwrap().allowed);
+
+        clock.advance(1.0);
+        assert!(limiter.check_request(&client).unwrap().allowed);
+        assert_eq!(limiter.store().tats.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn denied_requests_do_not_write() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = StoreBackedLimiter::with_config(config, clock, MapStore::default()).unwrap();
+        let client = "client1".to_string();
+
+        limiter.check_request(&client).unwrap();
+        limiter.check_request(&client).unwrap();
+        limiter.check_request(&client).unwrap();
+        assert_eq!(limiter.store().swaps.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn lost_swap_is_retried() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = StoreBackedLimiter::with_config(config, clock, MapStore::default()).unwrap();
+        limiter
+            .store()
+            .lose_next_swap
+            .store(true, Ordering::Relaxed);
+
+        assert!(
+            limiter
+                .check_request(&"client1".to_string())
+                .unwrap()
+                .allowed
+        );
+        assert_eq!(limiter.store().swaps.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn store_errors_propagate() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = StoreBackedLimiter::with_config(config, clock, MapStore::default()).unwrap();
+        limiter.store().fail.store(true, Ordering::Relaxed);
+
+        let result = limiter.check_request(&"client1".to_string());
+        assert!(matches!(result, Err(FluxLimiterError::StoreError(_))));
+    }
+}
@@ -0,0 +1,58 @@
+// tests/ratelimiter/stream_tests.rs
+
+#[cfg(all(test, feature = "stream"))]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        FluxLimitStreamExt, FluxLimiter, FluxLimiterConfig, StreamMode, SystemClock,
+    };
+    use futures::StreamExt;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn shed_mode_drops_items_over_the_limit() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 1.0); // 2 immediate items per key
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock).unwrap());
+        let items = futures::stream::iter(vec![("a", 1), ("a", 2), ("b", 3), ("a", 4), ("b", 5)]);
+
+        let delivered: Vec<_> = items
+            .flux_limit(limiter, |(key, _)| *key)
+            .mode(StreamMode::Shed)
+            .map(|item| item.unwrap().1)
+            .collect()
+            .await;
+        assert_eq!(delivered, vec![1, 2, 3, 5]);
+    }
+
+    #[tokio::test]
+    async fn pace_mode_delays_but_keeps_every_item() {
+        let config = FluxLimiterConfig::new(20.0, 0.0); // one item every 50ms
+        let limiter = Arc::new(FluxLimiter::with_config(config, SystemClock).unwrap());
+        let items = futures::stream::iter(1..=3);
+
+        let start = Instant::now();
+        let delivered: Vec<_> = items
+            .flux_limit(limiter, |_| "pipeline")
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(delivered, vec![1, 2, 3]);
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn limiter_errors_are_yielded() {
+        let clock = TestClock::new(0.0);
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = Arc::new(FluxLimiter::with_config(config, clock.clone()).unwrap());
+
+        clock.fail_next_call();
+        let mut stream = futures::stream::iter(vec![1, 2]).flux_limit(limiter, |n| *n);
+        assert!(stream.next().await.unwrap().is_err());
+        assert_eq!(stream.next().await.unwrap().unwrap(), 2);
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,124 @@
+// tests/ratelimiter/timestamp_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use flux_limiter::{
+        Clock, ClockError, FluxLimiter, FluxLimiterConfig, Priority, Replenishment, SystemClock,
+        Timestamp,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn timestamp_arithmetic_is_checked() {
+        let start = Timestamp::from_nanos(1_000);
+
+        assert_eq!(
+            start.checked_add(Duration::from_secs(1)),
+            Some(Timestamp::from_nanos(1_000_001_000))
+        );
+        assert_eq!(
+            start.checked_sub(Duration::from_nanos(1_000)),
+            Some(Timestamp::default())
+        );
+        assert_eq!(start.checked_sub(Duration::from_nanos(1_001)), None);
+        assert_eq!(Timestamp::MAX.checked_add(Duration::from_nanos(1)), None);
+        assert_eq!(start.checked_add(Duration::MAX), None);
+        assert_eq!(
+            Timestamp::MAX.checked_duration_since(start),
+            Some(Duration::from_nanos(u64::MAX - 1_000))
+        );
+        assert_eq!(start.checked_duration_since(Timestamp::MAX), None);
+    }
+
+    #[test]
+    fn timestamps_from_durations_reject_overflow() {
+        assert_eq!(
+            Timestamp::from_duration(Duration::from_secs(5)).map(Timestamp::as_nanos),
+            Some(5_000_000_000)
+        );
+        assert_eq!(
+            Timestamp::from_duration(Duration::from_nanos(u64::MAX)),
+            Some(Timestamp::MAX)
+        );
+        assert_eq!(Timestamp::from_duration(Duration::MAX), None);
+        assert_eq!(u64::from(Timestamp::from_nanos(42)), 42);
+    }
+
+    #[test]
+    fn system_clock_reads_nanoseconds_since_the_unix_epoch() {
+        let now = SystemClock.now().unwrap();
+        // later than 2023-11-14 and earlier than the end of the range
+        assert!(now > Timestamp::from_nanos(1_700_000_000_000_000_000));
+        assert!(now < Timestamp::MAX);
+    }
+
+    #[test]
+    fn requests_that_would_overflow_the_tat_are_denied() {
+        let config = FluxLimiterConfig::new(1.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, SystemClock).unwrap();
+
+        // one emission interval (1s) still fits
+        let last_slot = u64::MAX - 1_000_000_000;
+        assert!(limiter.check_request_at("near", last_slot).unwrap().allowed);
+        assert_eq!(
+            limiter.client_snapshot("near").unwrap().unwrap().tat_nanos,
+            u64::MAX
+        );
+
+        // the next TAT would pass the end of the range
+        let decision = limiter.check_request_at("far", u64::MAX - 10).unwrap();
+        assert!(!decision.allowed);
+        assert!(!limiter.check_request_at("far", u64::MAX).unwrap().allowed);
+    }
+
+    // A clock parked a few nanoseconds before the end of the range
+    #[derive(Clone)]
+    struct EndOfTime;
+
+    impl Clock for EndOfTime {
+        fn now(&self) -> Result<Timestamp, ClockError> {
+            Ok(Timestamp::from_nanos(u64::MAX - 10))
+        }
+    }
+
+    #[test]
+    fn checks_at_the_end_of_the_time_range_do_not_overflow() {
+        // every optional path that adds to a TAT or a deadline
+        let config = FluxLimiterConfig::new(1.0, 2.0)
+            .debt_ceiling(3.0)
+            .priority_reserve(0.5)
+            .expiry_wheel(1.0)
+            .entry_ttl(60.0)
+            .operation_cost("write", 2.0);
+        let limiter = FluxLimiter::with_config(config, EndOfTime).unwrap();
+
+        for now in [u64::MAX - 10, u64::MAX - 1, u64::MAX] {
+            let decision = limiter.check_request_at("a", now).unwrap();
+            assert!(!decision.allowed);
+        }
+        assert!(
+            !limiter
+                .check_request_with_priority("a", Priority::Low)
+                .unwrap()
+                .allowed
+        );
+        assert!(!limiter.check_request_op("a", "write").unwrap().allowed);
+        let (decision, _) = limiter.check_request_explained("a").unwrap();
+        assert!(!decision.allowed);
+
+        // the state left behind can still be swept
+        limiter.cleanup_stale_clients(0).unwrap();
+
+        // stepped credit rounds the clock down, so only the panics matter here
+        for replenishment in [Replenishment::Stepwise(2.0), Replenishment::WhenIdle] {
+            let config = FluxLimiterConfig::new(1.0, 2.0)
+                .debt_ceiling(3.0)
+                .replenishment(replenishment);
+            let limiter = FluxLimiter::with_config(config, EndOfTime).unwrap();
+            for now in [u64::MAX - 10, u64::MAX - 1, u64::MAX] {
+                limiter.check_request_at("a", now).unwrap();
+            }
+        }
+    }
+}
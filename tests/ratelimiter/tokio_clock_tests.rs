@@ -0,0 +1,59 @@
+// tests/ratelimiter/tokio_clock_tests.rs
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+
+    use flux_limiter::{Clock, FluxLimiter, FluxLimiterConfig, Timestamp, TokioClock};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn paused_time_stands_still() {
+        let clock = TokioClock::starting_at(Timestamp::from_nanos(1_000));
+        assert_eq!(clock.now().unwrap(), Timestamp::from_nanos(1_000));
+
+        tokio::time::advance(Duration::from_millis(250)).await;
+        assert_eq!(clock.now().unwrap(), Timestamp::from_nanos(250_001_000));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn limiter_follows_advanced_time() {
+        let config = FluxLimiterConfig::new(2.0, 0.0);
+        let limiter = FluxLimiter::with_config(config, TokioClock::new()).unwrap();
+
+        assert!(limiter.check_request("client1").unwrap().allowed);
+        let denied = limiter.check_request("client1").unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after_seconds, Some(0.5));
+
+        tokio::time::advance(Duration::from_millis(499)).await;
+        assert!(!limiter.check_request("client1").unwrap().allowed);
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert!(limiter.check_request("client1").unwrap().allowed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sleeping_moves_the_clock() {
+        let clock = TokioClock::new();
+        let before = clock.now().unwrap();
+
+        // with time paused, sleeps complete by auto-advancing the clock
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        let slept = clock.now().unwrap().checked_duration_since(before);
+        assert_eq!(slept, Some(Duration::from_secs(60)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clones_share_the_starting_point() {
+        let clock = TokioClock::new();
+        let clone = clock;
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(clock.now().unwrap(), clone.now().unwrap());
+    }
+
+    #[test]
+    fn starts_at_the_system_time() {
+        let clock = TokioClock::new();
+        assert!(clock.now().unwrap() > Timestamp::from_nanos(1_700_000_000_000_000_000));
+    }
+}
@@ -0,0 +1,116 @@
+// tests/ratelimiter/tower_throttle_tests.rs
+
+#[cfg(all(test, feature = "tower"))]
+mod tests {
+
+    use flux_limiter::{
+        Authority, FluxLimiter, FluxLimiterConfig, KeyExtractor, ThrottleLayer, TokioClock,
+    };
+    use std::convert::Infallible;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time::Instant;
+    use tower::{Layer, ServiceExt, service_fn};
+
+    fn limiter() -> Arc<FluxLimiter<String, TokioClock>> {
+        let config = FluxLimiterConfig::new(1.0, 0.0); // one call per second
+        Arc::new(FluxLimiter::with_config(config, TokioClock::new()).unwrap())
+    }
+
+    fn request(uri: &str) -> http::Request<()> {
+        http::Request::builder().uri(uri).body(()).unwrap()
+    }
+
+    fn echo()
+    -> impl tower::Service<
+        http::Request<()>,
+        Response = String,
+        Error = Infallible,
+        Future = impl Send,
+    > + Clone
+    + Send
+    + 'static {
+        service_fn(|request: http::Request<()>| async move {
+            Ok::<_, Infallible>(request.uri().to_string())
+        })
+    }
+
+    #[test]
+    fn authority_keys_by_host_and_port() {
+        assert_eq!(
+            Authority.extract(&request("https://api.example.com/v1/users")),
+            Some("api.example.com".to_string())
+        );
+        assert_eq!(
+            Authority.extract(&request("http://localhost:8080/")),
+            Some("localhost:8080".to_string())
+        );
+
+        let relative = http::Request::builder()
+            .uri("/v1/users")
+            .header("host", "api.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            Authority.extract(&relative),
+            Some("api.example.com".to_string())
+        );
+        assert_eq!(Authority.extract(&request("/v1/users")), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn calls_over_the_limit_wait_instead_of_failing() {
+        let service = ThrottleLayer::per_authority(limiter()).layer(echo());
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            let response = service
+                .clone()
+                .oneshot(request("https://api.example.com/v1"))
+                .await
+                .unwrap();
+            assert_eq!(response, "https://api.example.com/v1");
+        }
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn authorities_and_keyless_calls_are_paced_separately() {
+        let limiter = limiter();
+        let service = ThrottleLayer::per_authority(Arc::clone(&limiter)).layer(echo());
+        let start = Instant::now();
+
+        for uri in ["https://a.example.com/", "https://b.example.com/", "/local"] {
+            service.clone().oneshot(request(uri)).await.unwrap();
+        }
+        service.clone().oneshot(request("/local")).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // the limiter is shared with in-process callers
+        assert!(!limiter.check_request_ref("a.example.com").unwrap().allowed);
+        assert!(limiter.client_snapshot("/local").unwrap().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn custom_keys_and_inner_errors() {
+        let token = |request: &http::Request<()>| {
+            request
+                .headers()
+                .get("x-api-token")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let failing = service_fn(|_request: http::Request<()>| async {
+            Err::<(), _>(std::io::Error::other("upstream down"))
+        });
+        let service = ThrottleLayer::with_key(limiter(), token).layer(failing);
+
+        let call = http::Request::builder()
+            .uri("https://api.example.com/")
+            .header("x-api-token", "token1")
+            .body(())
+            .unwrap();
+        let err = service.oneshot(call).await.unwrap_err();
+        assert_eq!(err.to_string(), "upstream down");
+    }
+}
@@ -0,0 +1,123 @@
+// tests/ratelimiter/two_rate_tests.rs
+
+#[cfg(test)]
+mod tests {
+
+    use crate::fixtures::test_clock::TestClock;
+    use flux_limiter::{
+        DecisionReason, FailurePolicy, FluxLimiterConfig, FluxLimiterError, TwoRateLimiter,
+    };
+
+    // sustained 1/s with 4 extra, peak 10/s with 1 extra
+    fn limiter(clock: &TestClock) -> TwoRateLimiter<&'static str, TestClock> {
+        TwoRateLimiter::new(
+            FluxLimiterConfig::new(1.0, 4.0),
+            FluxLimiterConfig::new(10.0, 1.0),
+            clock.clone(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn peak_rate_limits_how_fast_the_burst_is_spent() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        assert!(limiter.check_request("trunk").unwrap().allowed);
+        assert!(limiter.check_request("trunk").unwrap().allowed);
+        let denied = limiter.check_request("trunk").unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.reason, DecisionReason::PeakExceeded);
+        assert!((denied.retry_after_seconds.unwrap() - 0.1).abs() < 1e-9);
+
+        // a denied request advances neither schedule
+        clock.advance(0.1);
+        assert!(limiter.check_request("trunk").unwrap().allowed);
+    }
+
+    #[test]
+    fn sustained_rate_bounds_the_total_burst() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+
+        // spaced at the peak rate, the sustained burst of 1 + 4 runs out
+        for _ in 0..5 {
+            assert!(limiter.check_request("trunk").unwrap().allowed);
+            clock.advance(0.1);
+        }
+        let denied = limiter.check_request("trunk").unwrap();
+        assert_eq!(denied.reason, DecisionReason::BurstExhausted);
+        assert!((denied.retry_after_seconds.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(denied.limit_per_second, 1.0);
+    }
+
+    #[test]
+    fn remaining_capacity_is_the_tighter_of_both_rates() {
+        let clock = TestClock::new(0.0);
+        let limiter = TwoRateLimiter::new(
+            FluxLimiterConfig::new(1.0, 4.0),
+            FluxLimiterConfig::new(10.0, 3.0),
+            clock.clone(),
+        )
+        .unwrap();
+
+        // peak: 3 - 1 left, sustained: 4 - 1 left
+        let first = limiter.check_request("trunk").unwrap();
+        assert_eq!(first.reason, DecisionReason::Conforming);
+        assert!((first.remaining_capacity.unwrap() - 2.0).abs() < 1e-9);
+
+        // back-to-back requests spend the small peak burst first: 3 - 2 vs 4 - 2
+        clock.advance(1.0);
+        limiter.check_request("trunk").unwrap();
+        let third = limiter.check_request("trunk").unwrap();
+        assert!((third.remaining_capacity.unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn peak_rates_below_the_sustained_rate_are_rejected() {
+        let result = TwoRateLimiter::<&str, _>::new(
+            FluxLimiterConfig::new(10.0, 1.0),
+            FluxLimiterConfig::new(5.0, 1.0),
+            TestClock::new(0.0),
+        );
+        assert!(matches!(result, Err(FluxLimiterError::InvalidPeakRate)));
+
+        let result = TwoRateLimiter::<&str, _>::new(
+            FluxLimiterConfig::new(10.0, 1.0),
+            FluxLimiterConfig::new(20.0, -1.0),
+            TestClock::new(0.0),
+        );
+        assert!(matches!(result, Err(FluxLimiterError::InvalidBurst)));
+    }
+
+    #[test]
+    fn cleanup_waits_for_both_rates_to_recover() {
+        let clock = TestClock::new(0.0);
+        let limiter = limiter(&clock);
+        for _ in 0..3 {
+            limiter.check_request("a").unwrap();
+            clock.advance(0.1);
+        }
+
+        assert_eq!(limiter.cleanup_stale_clients(0).unwrap().removed, 0);
+        clock.advance(10.0);
+        assert_eq!(limiter.cleanup_stale_clients(0).unwrap().removed, 1);
+        assert!(limiter.is_empty());
+    }
+
+    #[test]
+    fn clock_failures_follow_the_sustained_failure_policy() {
+        let clock = TestClock::new(0.0);
+        let limiter = TwoRateLimiter::new(
+            FluxLimiterConfig::new(1.0, 0.0).failure_policy(FailurePolicy::FailClosed),
+            FluxLimiterConfig::new(2.0, 0.0),
+            clock.clone(),
+        )
+        .unwrap();
+
+        clock.fail_next_call();
+        let decision = limiter.check_request("trunk").unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.reason, DecisionReason::Fallback);
+    }
+}